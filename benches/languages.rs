@@ -94,6 +94,7 @@ fn benchmark_parse_json(c: &mut Criterion) {
                   inputs = ["in"]
                   version = "2"
                   search_dirs = ["benches/lua_deps"]
+                  sandbox.stdlib = "full"
                   source = """
                   local json = require "json"
 
@@ -200,6 +201,7 @@ fn benchmark_multifaceted(c: &mut Criterion) {
                   type = "lua"
                   inputs = ["in"]
                   version = "2"
+                  sandbox.stdlib = "full"
                   source = """
                   local severities = { "emerg", "alert", "crit", "error", "warning", "notice", "info", "debug" }
                   local facilities = {
@@ -42,7 +42,12 @@ fn dedupe(c: &mut Criterion) {
                 fields: Some(FieldMatchConfig::IgnoreFields(vec![String::from(
                     "message",
                 )])),
-                cache: CacheConfig { num_events: 4 },
+                key: None,
+                cache: CacheConfig {
+                    num_events: 4,
+                    ttl_secs: None,
+                },
+                persist_state: false,
             },
         },
         // Modification of previous where field "message" is matched.
@@ -51,7 +56,12 @@ fn dedupe(c: &mut Criterion) {
             input: fixed_stream.clone(),
             dedupe_config: DedupeConfig {
                 fields: Some(FieldMatchConfig::MatchFields(vec![String::from("message")])),
-                cache: CacheConfig { num_events: 4 },
+                key: None,
+                cache: CacheConfig {
+                    num_events: 4,
+                    ttl_secs: None,
+                },
+                persist_state: false,
             },
         },
         // Measurement where ignore fields do not exist in the event.
@@ -59,7 +69,10 @@ fn dedupe(c: &mut Criterion) {
             slug: "field_ignore_dne",
             input: fixed_stream.clone(),
             dedupe_config: DedupeConfig {
-                cache: CacheConfig { num_events: 4 },
+                cache: CacheConfig {
+                    num_events: 4,
+                    ttl_secs: None,
+                },
                 fields: Some(FieldMatchConfig::IgnoreFields(vec![
                     String::from("abcde"),
                     String::from("eabcd"),
@@ -67,6 +80,8 @@ fn dedupe(c: &mut Criterion) {
                     String::from("cdeab"),
                     String::from("bcdea"),
                 ])),
+                key: None,
+                persist_state: false,
             },
         },
         // Modification of previous where match fields do not exist in the
@@ -75,7 +90,10 @@ fn dedupe(c: &mut Criterion) {
             slug: "field_match_dne",
             input: fixed_stream.clone(),
             dedupe_config: DedupeConfig {
-                cache: CacheConfig { num_events: 4 },
+                cache: CacheConfig {
+                    num_events: 4,
+                    ttl_secs: None,
+                },
                 fields: Some(FieldMatchConfig::MatchFields(vec![
                     String::from("abcde"),
                     String::from("eabcd"),
@@ -83,6 +101,8 @@ fn dedupe(c: &mut Criterion) {
                     String::from("cdeab"),
                     String::from("bcdea"),
                 ])),
+                key: None,
+                persist_state: false,
             },
         },
     ] {
@@ -90,8 +110,10 @@ fn dedupe(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("transform", param), &param, |b, param| {
             b.iter_batched(
                 || {
-                    let dedupe =
-                        Transform::task(Dedupe::new(param.dedupe_config.clone())).into_task();
+                    let dedupe = Transform::task(
+                        Dedupe::new(param.dedupe_config.clone(), &Default::default()).unwrap(),
+                    )
+                    .into_task();
                     (Box::new(dedupe), Box::pin(param.input.clone()))
                 },
                 |(dedupe, input)| {
@@ -0,0 +1,172 @@
+use crate::{
+    vrl_util::{
+        add_index, compile_condition, evaluate_condition, evaluate_select, ConditionExpr, Error,
+    },
+    IndexHandle, TableRegistry, TableSearch,
+};
+use std::collections::BTreeMap;
+use vrl_core::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct FindEnrichmentTableRecords;
+impl Function for FindEnrichmentTableRecords {
+    fn identifier(&self) -> &'static str {
+        "find_enrichment_table_records"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "table",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "condition",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "select",
+                kind: kind::ARRAY,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    fn compile(&self, state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let registry = state
+            .get_external_context::<TableRegistry>()
+            .ok_or(Box::new(Error::TablesNotLoaded) as Box<dyn DiagnosticError>)?;
+
+        let tables = registry
+            .table_ids()
+            .into_iter()
+            .map(Value::from)
+            .collect::<Vec<_>>();
+
+        let table = arguments
+            .required_enum("table", &tables)?
+            .try_bytes_utf8_lossy()
+            .expect("table is not valid utf8")
+            .into_owned();
+        let condition = compile_condition(arguments.required_object("condition")?)
+            .map_err(|err| Box::new(err) as Box<dyn DiagnosticError>)?;
+        let select = arguments.optional("select");
+
+        Ok(Box::new(FindEnrichmentTableRecordsFn {
+            table,
+            condition,
+            select,
+            index: None,
+            enrichment_tables: registry.as_readonly(),
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FindEnrichmentTableRecordsFn {
+    table: String,
+    condition: BTreeMap<String, ConditionExpr>,
+    select: Option<Box<dyn Expression>>,
+    index: Option<IndexHandle>,
+    enrichment_tables: TableSearch,
+}
+
+impl Expression for FindEnrichmentTableRecordsFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let condition = evaluate_condition(&self.condition, ctx)?;
+        let select = evaluate_select(self.select.as_deref(), ctx)?;
+
+        let data = self
+            .enrichment_tables
+            .find_table_rows(&self.table, &condition, select.as_deref(), self.index)?
+            .into_iter()
+            .map(Value::Object)
+            .collect::<Vec<_>>();
+
+        Ok(Value::Array(data))
+    }
+
+    fn update_state(
+        &mut self,
+        state: &mut state::Compiler,
+    ) -> std::result::Result<(), ExpressionError> {
+        let mut registry = state.get_external_context_mut::<TableRegistry>();
+
+        match registry {
+            Some(ref mut table) => {
+                // Store the index to use while searching.
+                self.index = add_index(table, &self.table, &self.condition)?;
+
+                Ok(())
+            }
+            // We shouldn't reach this point since the type checker will ensure the table exists before this function is called.
+            None => unreachable!("enrichment tables aren't loaded"),
+        }
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new()
+            .fallible()
+            .array_mapped::<(), Kind>(map! { (): Kind::Object })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::get_table_registry;
+
+    use super::*;
+    use shared::{btreemap, TimeZone};
+
+    #[test]
+    fn find_table_rows() {
+        let registry = get_table_registry();
+        let func = FindEnrichmentTableRecordsFn {
+            table: "dummy1".to_string(),
+            condition: btreemap! {
+                "field" => ConditionExpr::Equals(expression::Literal::from("value").into()),
+            },
+            select: None,
+            index: Some(IndexHandle(999)),
+            enrichment_tables: registry.as_readonly(),
+        };
+
+        let tz = TimeZone::default();
+        let mut object: Value = BTreeMap::new().into();
+        let mut runtime_state = vrl_core::state::Runtime::default();
+        let mut ctx = Context::new(&mut object, &mut runtime_state, &tz);
+
+        registry.finish_load();
+
+        let got = func.resolve(&mut ctx);
+
+        assert_eq!(Ok(value![[{ "field": "result" }]]), got);
+    }
+
+    #[test]
+    fn add_indexes() {
+        let registry = get_table_registry();
+
+        let mut func = FindEnrichmentTableRecordsFn {
+            table: "dummy1".to_string(),
+            condition: btreemap! {
+                "field" => ConditionExpr::Equals(expression::Literal::from("value").into()),
+            },
+            select: None,
+            index: None,
+            enrichment_tables: registry.as_readonly(),
+        };
+
+        let mut compiler = state::Compiler::new();
+        compiler.set_external_context(Some(Box::new(registry)));
+
+        assert_eq!(Ok(()), func.update_state(&mut compiler));
+        assert_eq!(Some(IndexHandle(0)), func.index);
+    }
+}
@@ -1,41 +1,11 @@
-use crate::{Condition, IndexHandle, TableRegistry, TableSearch};
-use std::collections::BTreeMap;
-use vrl_core::{
-    diagnostic::{Label, Span},
-    prelude::*,
+use crate::{
+    vrl_util::{
+        add_index, compile_condition, evaluate_condition, evaluate_select, ConditionExpr, Error,
+    },
+    IndexHandle, TableRegistry, TableSearch,
 };
-
-#[derive(Debug)]
-pub enum Error {
-    TablesNotLoaded,
-}
-
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Error::TablesNotLoaded => write!(f, "enrichment tables not loaded"),
-        }
-    }
-}
-
-impl std::error::Error for Error {}
-
-impl DiagnosticError for Error {
-    fn code(&self) -> usize {
-        111
-    }
-
-    fn labels(&self) -> Vec<Label> {
-        match self {
-            Error::TablesNotLoaded => {
-                vec![Label::primary(
-                    "enrichment table error: tables not loaded".to_string(),
-                    Span::default(),
-                )]
-            }
-        }
-    }
-}
+use std::collections::BTreeMap;
+use vrl_core::prelude::*;
 
 #[derive(Clone, Copy, Debug)]
 pub struct GetEnrichmentTableRecord;
@@ -56,6 +26,11 @@ impl Function for GetEnrichmentTableRecord {
                 kind: kind::OBJECT,
                 required: true,
             },
+            Parameter {
+                keyword: "select",
+                kind: kind::ARRAY,
+                required: false,
+            },
         ]
     }
 
@@ -79,11 +54,14 @@ impl Function for GetEnrichmentTableRecord {
             .try_bytes_utf8_lossy()
             .expect("table is not valid utf8")
             .into_owned();
-        let condition = arguments.required_object("condition")?;
+        let condition = compile_condition(arguments.required_object("condition")?)
+            .map_err(|err| Box::new(err) as Box<dyn DiagnosticError>)?;
+        let select = arguments.optional("select");
 
         Ok(Box::new(GetEnrichmentTableRecordFn {
             table,
             condition,
+            select,
             index: None,
             enrichment_tables: registry.as_readonly(),
         }))
@@ -93,27 +71,23 @@ impl Function for GetEnrichmentTableRecord {
 #[derive(Debug, Clone)]
 pub struct GetEnrichmentTableRecordFn {
     table: String,
-    condition: BTreeMap<String, expression::Expr>,
+    condition: BTreeMap<String, ConditionExpr>,
+    select: Option<Box<dyn Expression>>,
     index: Option<IndexHandle>,
     enrichment_tables: TableSearch,
 }
 
 impl Expression for GetEnrichmentTableRecordFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
-        let condition = self
-            .condition
-            .iter()
-            .map(|(key, value)| {
-                Ok(Condition::Equals {
-                    field: key,
-                    value: value.resolve(ctx)?,
-                })
-            })
-            .collect::<Result<Vec<Condition>>>()?;
-
-        let data = self
-            .enrichment_tables
-            .find_table_row(&self.table, &condition, self.index)?;
+        let condition = evaluate_condition(&self.condition, ctx)?;
+        let select = evaluate_select(self.select.as_deref(), ctx)?;
+
+        let data = self.enrichment_tables.find_table_row(
+            &self.table,
+            &condition,
+            select.as_deref(),
+            self.index,
+        )?;
 
         Ok(Value::Object(data))
     }
@@ -126,15 +100,8 @@ impl Expression for GetEnrichmentTableRecordFn {
 
         match registry {
             Some(ref mut table) => {
-                let fields = self
-                    .condition
-                    .iter()
-                    .map(|(field, _)| field.as_ref())
-                    .collect::<Vec<_>>();
-                let index = table.add_index(&self.table, &fields)?;
-
                 // Store the index to use while searching.
-                self.index = Some(index);
+                self.index = add_index(table, &self.table, &self.condition)?;
 
                 Ok(())
             }
@@ -163,8 +130,9 @@ mod tests {
         let func = GetEnrichmentTableRecordFn {
             table: "dummy1".to_string(),
             condition: btreemap! {
-                "field" =>  expression::Literal::from("value"),
+                "field" => ConditionExpr::Equals(expression::Literal::from("value").into()),
             },
+            select: None,
             index: Some(IndexHandle(999)),
             enrichment_tables: registry.as_readonly(),
         };
@@ -188,8 +156,9 @@ mod tests {
         let mut func = GetEnrichmentTableRecordFn {
             table: "dummy1".to_string(),
             condition: btreemap! {
-                "field" =>  expression::Literal::from("value"),
+                "field" => ConditionExpr::Equals(expression::Literal::from("value").into()),
             },
+            select: None,
             index: None,
             enrichment_tables: registry.as_readonly(),
         };
@@ -1,10 +1,13 @@
+pub mod find_enrichment_table_records;
 pub mod get_enrichment_table_record;
 pub mod tables;
 
 #[cfg(test)]
 mod test_util;
+mod vrl_util;
 use dyn_clone::DynClone;
 use std::collections::BTreeMap;
+use std::net::IpAddr;
 use vrl_core::Value;
 
 pub use tables::{TableRegistry, TableSearch};
@@ -22,6 +25,16 @@ pub enum Condition<'a> {
         from: chrono::DateTime<chrono::Utc>,
         to: chrono::DateTime<chrono::Utc>,
     },
+    /// The number or timestamp is between the values in the two fields (inclusive).
+    BetweenFields {
+        from_field: &'a str,
+        to_field: &'a str,
+        value: Value,
+    },
+    /// The IP address is within the network, in CIDR notation, in the field.
+    IpInCidr { field: &'a str, value: IpAddr },
+    /// The value in the field is a prefix of the value.
+    PrefixOf { field: &'a str, value: Value },
 }
 
 /// Enrichment tables represent additional data sources that can be used to enrich the event data
@@ -29,21 +42,47 @@ pub enum Condition<'a> {
 pub trait Table: DynClone {
     /// Search the enrichment table data with the given condition.
     /// All conditions must match (AND).
+    /// If `select` is passed, only the listed fields are returned.
     ///
     /// # Errors
     /// Errors if no rows, or more than 1 row is found.
     fn find_table_row<'a>(
         &self,
         condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
         index: Option<IndexHandle>,
     ) -> Result<BTreeMap<String, vrl_core::Value>, String>;
 
+    /// Search the enrichment table data with the given condition.
+    /// All conditions must match (AND).
+    /// If `select` is passed, only the listed fields are returned.
+    ///
+    /// Returns every row that matches, which may be none.
+    fn find_table_rows<'a>(
+        &self,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, vrl_core::Value>>, String>;
+
     /// Hints to the enrichment table what data is going to be searched to allow it to index the
     /// data in advance.
     ///
     /// # Errors
     /// Errors if the fields are not in the table.
     fn add_index(&mut self, fields: &[&str]) -> Result<IndexHandle, String>;
+
+    /// Returns the fields of the indexes that have been added to the table, in
+    /// the order they were added. This allows the indexes to be recreated when
+    /// the table is reloaded.
+    fn index_fields(&self) -> Vec<Vec<String>> {
+        Vec::new()
+    }
+
+    /// Returns the number of rows in the table, if it is known.
+    fn row_count(&self) -> Option<usize> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(Table);
@@ -52,5 +91,7 @@ pub fn vrl_functions() -> Vec<Box<dyn vrl_core::Function>> {
     vec![
         Box::new(get_enrichment_table_record::GetEnrichmentTableRecord)
             as Box<dyn vrl_core::Function>,
+        Box::new(find_enrichment_table_records::FindEnrichmentTableRecords)
+            as Box<dyn vrl_core::Function>,
     ]
 }
@@ -28,6 +28,14 @@
 //! `TableRegistry::as_readonly`. This returns a cheaply clonable struct that
 //! implements `vrl:EnrichmentTableSearch` through with the enrichment tables
 //! can be searched.
+//!
+//! ## Reloading
+//!
+//! Whilst in the reading stage, a single table can be replaced with a freshly
+//! loaded copy of its data by calling `TableRegistry::reload_table`. The
+//! indexes of the current table are recreated on the new table, so the
+//! `IndexHandle`s held by the transforms remain valid, before the table is
+//! swapped in.
 
 use super::{Condition, IndexHandle, Table};
 use arc_swap::ArcSwap;
@@ -37,10 +45,15 @@ use std::sync::{Arc, Mutex};
 /// A hashmap of name => implementation of an enrichment table.
 type TableMap = HashMap<String, Box<dyn Table + Send + Sync>>;
 
+/// A hashmap of name => implementation of an enrichment table in the reading
+/// stage. The tables are shared so a single table can be swapped out without
+/// copying the others.
+type ReadonlyTableMap = HashMap<String, Arc<dyn Table + Send + Sync>>;
+
 #[derive(Clone, Default)]
 pub struct TableRegistry {
     loading: Arc<Mutex<Option<TableMap>>>,
-    tables: Arc<ArcSwap<Option<TableMap>>>,
+    tables: Arc<ArcSwap<Option<ReadonlyTableMap>>>,
 }
 
 impl TableRegistry {
@@ -67,8 +80,8 @@ impl TableRegistry {
     /// Once loading is complete, the data is swapped out of `loading` and we
     /// return to a single copy of the tables.
     ///
-    /// TODO This function currently does nothing to reload the the underlying
-    /// data should it have changed in the enrichment source.
+    /// Tables that are passed in replace the existing tables with the same
+    /// name, as their configuration has changed.
     ///
     /// # Panics
     ///
@@ -78,11 +91,12 @@ impl TableRegistry {
         let existing = self.tables.load();
         if let Some(existing) = &**existing {
             // We already have some tables
-            tables.extend(
-                existing
-                    .iter()
-                    .map(|(key, value)| (key.clone(), value.clone())),
-            );
+            let existing = existing
+                .iter()
+                .filter(|(key, _)| !tables.contains_key(*key))
+                .map(|(key, value)| (key.clone(), dyn_clone::clone_box(&**value)))
+                .collect::<Vec<_>>();
+            tables.extend(existing);
         }
         match *loading {
             None => *loading = Some(tables),
@@ -100,10 +114,73 @@ impl TableRegistry {
     /// Panics if the Mutex is poisoned.
     pub fn finish_load(&self) {
         let mut tables_lock = self.loading.lock().unwrap();
-        let tables = tables_lock.take();
+        let tables = tables_lock.take().map(|tables| {
+            tables
+                .into_iter()
+                .map(|(key, table)| (key, Arc::from(table)))
+                .collect()
+        });
         self.tables.swap(Arc::new(tables));
     }
 
+    /// Replaces the given table with a freshly loaded copy whilst in the
+    /// reading stage.
+    ///
+    /// The indexes of the current table are added to the new table in the same
+    /// order, so the `IndexHandle`s handed out whilst loading keep pointing to
+    /// the same fields. The table is then atomically swapped in, searches that
+    /// are in flight complete against the previous data.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the tables are being loaded, the table isn't loaded, or the
+    /// indexes can't be recreated on the new table. The current table is kept
+    /// in that case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Mutex is poisoned.
+    pub fn reload_table(
+        &self,
+        table: &str,
+        mut data: Box<dyn Table + Send + Sync>,
+    ) -> Result<(), String> {
+        // Holding the lock prevents a load from starting whilst we swap the
+        // table in.
+        let loading = self.loading.lock().unwrap();
+        if loading.is_some() {
+            return Err("tables are being loaded".to_string());
+        }
+
+        let tables = self.tables.load();
+        let tables = match **tables {
+            Some(ref tables) => tables,
+            None => return Err("finish_load not called".to_string()),
+        };
+        let existing = tables
+            .get(table)
+            .ok_or_else(|| format!("table '{}' not loaded", table))?;
+
+        for (handle, fields) in existing.index_fields().iter().enumerate() {
+            let fields = fields
+                .iter()
+                .map(|field| field.as_ref())
+                .collect::<Vec<_>>();
+            if data.add_index(&fields)? != IndexHandle(handle) {
+                return Err(format!(
+                    "unable to recreate the index on fields {:?}",
+                    fields
+                ));
+            }
+        }
+
+        let mut tables = tables.clone();
+        tables.insert(table.to_string(), Arc::from(data));
+        self.tables.store(Arc::new(Some(tables)));
+
+        Ok(())
+    }
+
     /// Return a list of the available tables that we can write to.
     ///
     /// This only works in the writing stage and will acquire a lock to retrieve
@@ -156,7 +233,7 @@ impl std::fmt::Debug for TableRegistry {
 /// `vrl::EnrichmentTableSearch` trait. Cloning this object is designed to be
 /// cheap. The underlying data will be shared by all clones.
 #[derive(Clone, Default)]
-pub struct TableSearch(Arc<ArcSwap<Option<TableMap>>>);
+pub struct TableSearch(Arc<ArcSwap<Option<ReadonlyTableMap>>>);
 
 impl TableSearch {
     /// Search the given table to find the data.
@@ -166,13 +243,35 @@ impl TableSearch {
         &self,
         table: &str,
         condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
         index: Option<IndexHandle>,
     ) -> Result<BTreeMap<String, vrl_core::Value>, String> {
         let tables = self.0.load();
         if let Some(ref tables) = **tables {
             match tables.get(table) {
                 None => Err(format!("table {} not loaded", table)),
-                Some(table) => table.find_table_row(condition, index),
+                Some(table) => table.find_table_row(condition, select, index),
+            }
+        } else {
+            Err("finish_load not called".to_string())
+        }
+    }
+
+    /// Search the given table to find all the rows matching the condition.
+    ///
+    /// If we are in the writing stage, this function will return an error.
+    pub fn find_table_rows<'a>(
+        &self,
+        table: &str,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, vrl_core::Value>>, String> {
+        let tables = self.0.load();
+        if let Some(ref tables) = **tables {
+            match tables.get(table) {
+                None => Err(format!("table {} not loaded", table)),
+                Some(table) => table.find_table_rows(condition, select, index),
             }
         } else {
             Err("finish_load not called".to_string())
@@ -190,7 +289,7 @@ impl std::fmt::Debug for TableSearch {
 fn fmt_enrichment_table(
     f: &mut std::fmt::Formatter<'_>,
     name: &'static str,
-    tables: &Arc<ArcSwap<Option<TableMap>>>,
+    tables: &Arc<ArcSwap<Option<ReadonlyTableMap>>>,
 ) -> std::fmt::Result {
     let tables = tables.load();
     match **tables {
@@ -261,6 +360,7 @@ mod tests {
                     field: "thing",
                     value: Value::from("thang"),
                 }],
+                None,
                 None
             )
         );
@@ -302,6 +402,7 @@ mod tests {
                     field: "thing",
                     value: Value::from("thang"),
                 }],
+                None,
                 None
             )
         );
@@ -332,4 +433,99 @@ mod tests {
 
         assert_eq!(vec!["dummy1".to_string(), "dummy2".to_string()], table_ids,);
     }
+
+    #[test]
+    fn load_replaces_existing_tables() {
+        let mut tables: TableMap = HashMap::new();
+        tables.insert("dummy1".to_string(), Box::new(DummyEnrichmentTable::new()));
+
+        let registry = super::TableRegistry::default();
+        registry.load(tables);
+        registry.finish_load();
+
+        let mut tables: TableMap = HashMap::new();
+        tables.insert(
+            "dummy1".to_string(),
+            Box::new(DummyEnrichmentTable::new_with_data(btreemap! {
+                "field" => "reloaded",
+            })),
+        );
+        registry.load(tables);
+        registry.finish_load();
+
+        assert_eq!(
+            Ok(btreemap! {
+                "field" => "reloaded",
+            }),
+            registry
+                .as_readonly()
+                .find_table_row("dummy1", &[], None, None)
+        );
+    }
+
+    #[test]
+    fn can_reload_table() {
+        let mut tables: TableMap = HashMap::new();
+        tables.insert("dummy1".to_string(), Box::new(DummyEnrichmentTable::new()));
+
+        let mut registry = super::TableRegistry::default();
+        registry.load(tables);
+        assert_eq!(Ok(IndexHandle(0)), registry.add_index("dummy1", &["erk"]));
+        assert_eq!(
+            Ok(IndexHandle(1)),
+            registry.add_index("dummy1", &["erk", "ork"])
+        );
+        let tables_search = registry.as_readonly();
+        registry.finish_load();
+
+        let reloaded = DummyEnrichmentTable::new_with_data(btreemap! {
+            "field" => "reloaded",
+        });
+
+        assert_eq!(
+            Ok(()),
+            registry.reload_table("dummy1", Box::new(reloaded.clone()))
+        );
+        assert_eq!(
+            Ok(btreemap! {
+                "field" => "reloaded",
+            }),
+            tables_search.find_table_row("dummy1", &[], None, Some(IndexHandle(1)))
+        );
+
+        // The indexes are recreated in the same order.
+        assert_eq!(
+            vec![
+                vec!["erk".to_string()],
+                vec!["erk".to_string(), "ork".to_string()]
+            ],
+            reloaded.index_fields()
+        );
+    }
+
+    #[test]
+    fn can_not_reload_table_whilst_loading() {
+        let mut tables: TableMap = HashMap::new();
+        tables.insert("dummy1".to_string(), Box::new(DummyEnrichmentTable::new()));
+
+        let registry = super::TableRegistry::default();
+        registry.load(tables);
+
+        assert_eq!(
+            Err("tables are being loaded".to_string()),
+            registry.reload_table("dummy1", Box::new(DummyEnrichmentTable::new()))
+        );
+    }
+
+    #[test]
+    fn can_not_reload_unknown_table() {
+        let registry = super::TableRegistry::default();
+        registry.load(HashMap::new());
+        registry.finish_load();
+
+        assert_eq!(
+            Err("table 'dummy1' not loaded".to_string()),
+            registry.reload_table("dummy1", Box::new(DummyEnrichmentTable::new()))
+        );
+    }
 }
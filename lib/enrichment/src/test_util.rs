@@ -25,22 +25,43 @@ impl DummyEnrichmentTable {
             indexes,
         }
     }
+
+    pub(crate) fn new_with_data(data: BTreeMap<String, Value>) -> Self {
+        Self {
+            data,
+            indexes: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
 }
 
 impl Table for DummyEnrichmentTable {
     fn find_table_row(
         &self,
         _condition: &[Condition],
+        _select: Option<&[String]>,
         _index: Option<IndexHandle>,
     ) -> Result<BTreeMap<String, Value>, String> {
         Ok(self.data.clone())
     }
 
+    fn find_table_rows(
+        &self,
+        _condition: &[Condition],
+        _select: Option<&[String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        Ok(vec![self.data.clone()])
+    }
+
     fn add_index(&mut self, fields: &[&str]) -> Result<IndexHandle, String> {
         let mut indexes = self.indexes.lock().unwrap();
         indexes.push(fields.iter().map(|s| (*s).to_string()).collect());
         Ok(IndexHandle(indexes.len() - 1))
     }
+
+    fn index_fields(&self) -> Vec<Vec<String>> {
+        self.indexes.lock().unwrap().clone()
+    }
 }
 
 /// Create a table registry with dummy data
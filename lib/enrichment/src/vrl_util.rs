@@ -0,0 +1,294 @@
+//! Utilities shared between the enrichment table VRL functions.
+
+use crate::{Condition, IndexHandle, TableRegistry};
+use std::collections::BTreeMap;
+use vrl_core::{
+    diagnostic::{Label, Span},
+    prelude::*,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    TablesNotLoaded,
+    InvalidCondition { field: String, reason: &'static str },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TablesNotLoaded => write!(f, "enrichment tables not loaded"),
+            Error::InvalidCondition { .. } => write!(f, "invalid enrichment table condition"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl DiagnosticError for Error {
+    fn code(&self) -> usize {
+        111
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        match self {
+            Error::TablesNotLoaded => {
+                vec![Label::primary(
+                    "enrichment table error: tables not loaded".to_string(),
+                    Span::default(),
+                )]
+            }
+            Error::InvalidCondition { field, reason } => {
+                vec![Label::primary(
+                    format!(
+                        "enrichment table error: invalid condition for field {}: {}",
+                        field, reason
+                    ),
+                    Span::default(),
+                )]
+            }
+        }
+    }
+}
+
+/// A condition for a field in the condition object.
+///
+/// Fields are searched for the value given unless the value is one of these
+/// object literals:
+///
+/// - `{ "from": <timestamp>, "to": <timestamp> }`: the date in the field is
+///   between the two timestamps.
+/// - `{ "to_field": "<field>", "value": <number or timestamp> }`: the value is
+///   between the values in the field and `to_field`.
+/// - `{ "contains_ip": <ip> }`: the network in the field contains the IP address.
+/// - `{ "prefix_of": <string> }`: the value in the field is a prefix of the string.
+#[derive(Debug, Clone)]
+pub(crate) enum ConditionExpr {
+    Equals(expression::Expr),
+    BetweenDates {
+        from: expression::Expr,
+        to: expression::Expr,
+    },
+    BetweenFields {
+        to_field: String,
+        value: expression::Expr,
+    },
+    IpInCidr(expression::Expr),
+    PrefixOf(expression::Expr),
+}
+
+/// Parses the conditions for each field in the condition object.
+pub(crate) fn compile_condition(
+    condition: BTreeMap<String, expression::Expr>,
+) -> std::result::Result<BTreeMap<String, ConditionExpr>, Error> {
+    condition
+        .into_iter()
+        .map(|(field, expr)| {
+            let object = match expr {
+                expression::Expr::Container(expression::Container {
+                    variant: expression::Variant::Object(object),
+                }) => object,
+                expr => return Ok((field, ConditionExpr::Equals(expr))),
+            };
+
+            let keys = object.keys().map(String::as_str).collect::<Vec<_>>();
+            let condition = match keys.as_slice() {
+                ["from", "to"] => ConditionExpr::BetweenDates {
+                    from: object["from"].clone(),
+                    to: object["to"].clone(),
+                },
+                ["to_field", "value"] => match object["to_field"].as_value() {
+                    Some(Value::Bytes(to_field)) => ConditionExpr::BetweenFields {
+                        to_field: String::from_utf8_lossy(&to_field).into_owned(),
+                        value: object["value"].clone(),
+                    },
+                    _ => {
+                        return Err(Error::InvalidCondition {
+                            field,
+                            reason: "to_field must be a string literal",
+                        })
+                    }
+                },
+                ["contains_ip"] => ConditionExpr::IpInCidr(object["contains_ip"].clone()),
+                ["prefix_of"] => ConditionExpr::PrefixOf(object["prefix_of"].clone()),
+                _ => {
+                    return Err(Error::InvalidCondition {
+                        field,
+                        reason: "expected a value, or an object with from and to, to_field and value, contains_ip or prefix_of",
+                    })
+                }
+            };
+
+            Ok((field, condition))
+        })
+        .collect()
+}
+
+/// Evaluates the condition object to search the enrichment tables with.
+pub(crate) fn evaluate_condition<'a>(
+    condition: &'a BTreeMap<String, ConditionExpr>,
+    ctx: &mut Context,
+) -> Result<Vec<Condition<'a>>> {
+    condition
+        .iter()
+        .map(|(field, condition)| {
+            Ok(match condition {
+                ConditionExpr::Equals(value) => Condition::Equals {
+                    field,
+                    value: value.resolve(ctx)?,
+                },
+                ConditionExpr::BetweenDates { from, to } => Condition::BetweenDates {
+                    field,
+                    from: from.resolve(ctx)?.try_timestamp()?,
+                    to: to.resolve(ctx)?.try_timestamp()?,
+                },
+                ConditionExpr::BetweenFields { to_field, value } => Condition::BetweenFields {
+                    from_field: field,
+                    to_field,
+                    value: value.resolve(ctx)?,
+                },
+                ConditionExpr::IpInCidr(value) => {
+                    let value = value.resolve(ctx)?;
+                    let ip = value
+                        .try_bytes_utf8_lossy()?
+                        .parse()
+                        .map_err(|_| format!("unable to parse IP address: {}", value))?;
+
+                    Condition::IpInCidr { field, value: ip }
+                }
+                ConditionExpr::PrefixOf(value) => Condition::PrefixOf {
+                    field,
+                    value: value.resolve(ctx)?,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Evaluates the optional list of fields to return from the enrichment tables.
+pub(crate) fn evaluate_select(
+    select: Option<&dyn Expression>,
+    ctx: &mut Context,
+) -> Result<Option<Vec<String>>> {
+    select
+        .map(|select| {
+            select
+                .resolve(ctx)?
+                .try_array()?
+                .into_iter()
+                .map(|field| Ok(field.try_bytes_utf8_lossy()?.into_owned()))
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()
+}
+
+/// Adds an index to the table for the fields in the condition that are
+/// searched for with an equality check. Other fields are compared against the
+/// rows found with the index, so can't be part of it.
+///
+/// Returns `None` if there are no such fields.
+pub(crate) fn add_index(
+    registry: &mut TableRegistry,
+    table: &str,
+    condition: &BTreeMap<String, ConditionExpr>,
+) -> std::result::Result<Option<IndexHandle>, ExpressionError> {
+    let fields = condition
+        .iter()
+        .filter_map(|(field, condition)| match condition {
+            ConditionExpr::Equals(_) => Some(field.as_ref()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if fields.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(registry.add_index(table, &fields)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::{btreemap, TimeZone};
+
+    fn object(fields: BTreeMap<String, expression::Expr>) -> expression::Expr {
+        expression::Container::new(expression::Variant::Object(expression::Object::new(fields)))
+            .into()
+    }
+
+    fn literal(value: impl Into<expression::Literal>) -> expression::Expr {
+        value.into().into()
+    }
+
+    #[test]
+    fn evaluates_conditions() {
+        let condition = compile_condition(btreemap! {
+            "id" => literal("value"),
+            "network" => object(btreemap! { "contains_ip" => literal("10.1.2.3") }),
+            "prefix" => object(btreemap! { "prefix_of" => literal("+4420") }),
+            "start" => object(btreemap! {
+                "to_field" => literal("end"),
+                "value" => literal(5),
+            }),
+        })
+        .unwrap();
+
+        let tz = TimeZone::default();
+        let mut object: Value = BTreeMap::new().into();
+        let mut runtime_state = vrl_core::state::Runtime::default();
+        let mut ctx = Context::new(&mut object, &mut runtime_state, &tz);
+
+        assert_eq!(
+            Ok(vec![
+                Condition::Equals {
+                    field: "id",
+                    value: "value".into(),
+                },
+                Condition::IpInCidr {
+                    field: "network",
+                    value: "10.1.2.3".parse().unwrap(),
+                },
+                Condition::PrefixOf {
+                    field: "prefix",
+                    value: "+4420".into(),
+                },
+                Condition::BetweenFields {
+                    from_field: "start",
+                    to_field: "end",
+                    value: 5.into(),
+                },
+            ]),
+            evaluate_condition(&condition, &mut ctx)
+        );
+    }
+
+    #[test]
+    fn invalid_condition() {
+        assert!(matches!(
+            compile_condition(btreemap! {
+                "id" => object(btreemap! { "starts_with" => literal("value") }),
+            }),
+            Err(Error::InvalidCondition { .. })
+        ));
+        assert!(matches!(
+            compile_condition(btreemap! {
+                "start" => object(btreemap! {
+                    "to_field" => literal(1),
+                    "value" => literal(5),
+                }),
+            }),
+            Err(Error::InvalidCondition { .. })
+        ));
+    }
+
+    #[test]
+    fn only_equality_fields_are_indexed() {
+        let mut registry = crate::test_util::get_table_registry();
+        let condition = compile_condition(btreemap! {
+            "network" => object(btreemap! { "contains_ip" => literal("10.1.2.3") }),
+        })
+        .unwrap();
+
+        assert_eq!(Ok(None), add_index(&mut registry, "dummy1", &condition));
+    }
+}
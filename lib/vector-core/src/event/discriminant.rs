@@ -30,6 +30,12 @@ impl Discriminant {
             .collect();
         Self { values }
     }
+
+    /// Returns the values of the fields the discriminant was created from,
+    /// in the order the fields were given.
+    pub fn values(&self) -> &[Option<Value>] {
+        &self.values
+    }
 }
 
 impl PartialEq for Discriminant {
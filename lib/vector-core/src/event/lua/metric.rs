@@ -1,5 +1,8 @@
 use super::util::{table_to_timestamp, timestamp_to_table};
-use crate::event::{metric, Metric, MetricKind, MetricValue, StatisticKind};
+use crate::event::{
+    metric::{self, DDSketch, SketchBin},
+    Metric, MetricKind, MetricValue, StatisticKind,
+};
 use mlua::prelude::*;
 use std::collections::BTreeMap;
 
@@ -126,6 +129,18 @@ impl<'a> ToLua<'a> for Metric {
                 aggregated_summary.raw_set("sum", sum)?;
                 tbl.raw_set("aggregated_summary", aggregated_summary)?;
             }
+            MetricValue::Sketch { sketch } => {
+                let table = lua.create_table()?;
+                let keys: Vec<_> = sketch.bins().iter().map(|b| b.key).collect();
+                let counts: Vec<_> = sketch.bins().iter().map(|b| b.count).collect();
+                table.raw_set("keys", keys)?;
+                table.raw_set("counts", counts)?;
+                table.raw_set("count", sketch.count())?;
+                table.raw_set("sum", sketch.sum())?;
+                table.raw_set("min", sketch.min())?;
+                table.raw_set("max", sketch.max())?;
+                tbl.raw_set("sketch", table)?;
+            }
         }
 
         Ok(LuaValue::Table(tbl))
@@ -196,11 +211,26 @@ impl<'a> FromLua<'a> for Metric {
                 count: aggregated_summary.raw_get("count")?,
                 sum: aggregated_summary.raw_get("sum")?,
             }
+        } else if let Some(sketch) = table.raw_get::<_, Option<LuaTable>>("sketch")? {
+            let keys: Vec<i32> = sketch.raw_get("keys")?;
+            let counts: Vec<u32> = sketch.raw_get("counts")?;
+            let bins = keys
+                .into_iter()
+                .zip(counts)
+                .map(|(key, count)| SketchBin { key, count })
+                .collect();
+            let sketch = DDSketch::from_parts(
+                bins,
+                sketch.raw_get("sum")?,
+                sketch.raw_get::<_, Option<f64>>("min")?.unwrap_or_default(),
+                sketch.raw_get::<_, Option<f64>>("max")?.unwrap_or_default(),
+            );
+            MetricValue::Sketch { sketch }
         } else {
             return Err(LuaError::FromLuaConversionError {
                 from: value.type_name(),
                 to: "Metric",
-                message: Some("Cannot find metric value, expected presence one of \"counter\", \"gauge\", \"set\", \"distribution\", \"aggregated_histogram\", \"aggregated_summary\"".to_string()),
+                message: Some("Cannot find metric value, expected presence one of \"counter\", \"gauge\", \"set\", \"distribution\", \"aggregated_histogram\", \"aggregated_summary\", \"sketch\"".to_string()),
             });
         };
 
@@ -385,6 +415,30 @@ mod test {
         assert_metric(metric, assertions);
     }
 
+    #[test]
+    fn to_lua_sketch() {
+        let mut sketch = DDSketch::new();
+        sketch.insert_n(2.0, 3);
+        sketch.insert(-5.0);
+        let metric = Metric::new(
+            "example sketch",
+            MetricKind::Incremental,
+            MetricValue::Sketch { sketch },
+        );
+        let assertions = vec![
+            "type(metric.sketch) == 'table'",
+            "#metric.sketch.keys == 2",
+            "metric.sketch.keys[1] < 0",
+            "#metric.sketch.counts == 2",
+            "metric.sketch.counts[2] == 3",
+            "metric.sketch.count == 4",
+            "metric.sketch.sum == 1",
+            "metric.sketch.min == -5",
+            "metric.sketch.max == 2",
+        ];
+        assert_metric(metric, assertions);
+    }
+
     #[test]
     fn from_lua_counter_minimal() {
         let value = r#"{
@@ -542,4 +596,39 @@ mod test {
         );
         assert_event_data_eq!(Lua::new().load(value).eval::<Metric>().unwrap(), expected);
     }
+
+    #[test]
+    fn from_lua_sketch() {
+        let value = r#"{
+            name = "example sketch",
+            sketch = {
+                keys = { -1200, 1072 },
+                counts = { 1, 3 },
+                sum = 1.0,
+                min = -5.0,
+                max = 2.0
+            }
+        }"#;
+        let sketch = DDSketch::from_parts(
+            vec![
+                SketchBin {
+                    key: -1200,
+                    count: 1,
+                },
+                SketchBin {
+                    key: 1072,
+                    count: 3,
+                },
+            ],
+            1.0,
+            -5.0,
+            2.0,
+        );
+        let expected = Metric::new(
+            "example sketch",
+            MetricKind::Absolute,
+            MetricValue::Sketch { sketch },
+        );
+        assert_event_data_eq!(Lua::new().load(value).eval::<Metric>().unwrap(), expected);
+    }
 }
@@ -1,5 +1,6 @@
 use super::{BatchNotifier, EventFinalizer, EventMetadata};
 use crate::metrics::Handle;
+pub use crate::metrics::{DDSketch, SketchBin};
 use crate::ByteSizeOf;
 use chrono::{DateTime, Utc};
 use getset::{Getters, MutGetters};
@@ -166,6 +167,10 @@ pub enum MetricValue {
         count: u32,
         sum: f64,
     },
+    /// A Sketch contains a `DDSketch` of a set of observations, which
+    /// estimates their quantiles within a fixed relative error. Unlike
+    /// summaries, sketches from different sources can be merged.
+    Sketch { sketch: DDSketch },
 }
 
 impl ByteSizeOf for MetricValue {
@@ -176,6 +181,7 @@ impl ByteSizeOf for MetricValue {
             Self::Distribution { samples, .. } => samples.allocated_bytes(),
             Self::AggregatedHistogram { buckets, .. } => buckets.allocated_bytes(),
             Self::AggregatedSummary { quantiles, .. } => quantiles.allocated_bytes(),
+            Self::Sketch { sketch } => sketch.allocated_bytes(),
         }
     }
 }
@@ -294,6 +300,7 @@ impl From<MetricValue> for vrl_core::Value {
             MetricValue::Distribution { .. } => "distribution",
             MetricValue::AggregatedHistogram { .. } => "aggregated histogram",
             MetricValue::AggregatedSummary { .. } => "aggregated summary",
+            MetricValue::Sketch { .. } => "sketch",
         }
         .into()
     }
@@ -375,6 +382,20 @@ impl Metric {
         self
     }
 
+    /// Replaces a sketch with an aggregated summary of the given quantiles,
+    /// for sinks that can't encode sketches. Other values are kept as is.
+    #[must_use]
+    pub fn with_sketch_as_summary(mut self, quantiles: &[f64]) -> Self {
+        if let MetricValue::Sketch { sketch } = &self.data.value {
+            self.data.value = MetricValue::AggregatedSummary {
+                quantiles: sketch.to_quantiles(quantiles),
+                count: sketch.count(),
+                sum: sketch.sum(),
+            };
+        }
+        self
+    }
+
     #[inline]
     pub fn into_parts(self) -> (MetricSeries, MetricData, EventMetadata) {
         (self.series, self.data, self.metadata)
@@ -644,7 +665,7 @@ impl MetricValue {
     /// Zero out all the values contained in this. This keeps all the
     /// bucket/value vectors for the histogram and summary metric types
     /// intact while zeroing the counts. Distribution metrics are
-    /// emptied of all their values.
+    /// emptied of all their values, as are sketches.
     pub fn zero(&mut self) {
         match self {
             Self::Counter { value } | Self::Gauge { value } => *value = 0.0,
@@ -672,6 +693,7 @@ impl MetricValue {
                 *count = 0;
                 *sum = 0.0;
             }
+            Self::Sketch { sketch } => sketch.clear(),
         }
     }
 
@@ -725,7 +747,10 @@ impl MetricValue {
                 *sum += sum2;
                 true
             }
-
+            (Self::Sketch { ref mut sketch }, Self::Sketch { sketch: sketch2 }) => {
+                sketch.merge(sketch2);
+                true
+            }
             _ => false,
         }
     }
@@ -865,6 +890,13 @@ impl Display for Metric {
                     write!(fmt, "{}@{}", quantile.upper_limit, quantile.value)
                 })
             }
+            MetricValue::Sketch { sketch } => {
+                write!(fmt, "count={} sum={}", sketch.count(), sketch.sum())?;
+                if let (Some(min), Some(max)) = (sketch.min(), sketch.max()) {
+                    write!(fmt, " min={} max={}", min, max)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -1057,6 +1089,25 @@ mod test {
         assert_eq!(dist, expected);
     }
 
+    #[test]
+    fn merge_sketches() {
+        let sketch = |values: &[f64]| {
+            let mut sketch = DDSketch::new();
+            for value in values {
+                sketch.insert(*value);
+            }
+            MetricValue::Sketch { sketch }
+        };
+
+        let mut metric = Metric::new("sketch", MetricKind::Incremental, sketch(&[1.0, 2.0]));
+        let delta = Metric::new("sketch", MetricKind::Incremental, sketch(&[2.0, 30.0]));
+        let expected = metric.clone().with_value(sketch(&[1.0, 2.0, 2.0, 30.0]));
+
+        assert!(metric.data.add(&delta.data));
+        assert_eq!(metric, expected);
+        assert!(!metric.data.subtract(&delta.data));
+    }
+
     #[test]
     // `too_many_lines` is mostly just useful for production code but we're not
     // able to flag the lint on only for non-test.
@@ -1173,5 +1224,20 @@ mod test {
             ),
             r#"six{} = count=2 sum=127 1@63 2@64"#
         );
+
+        let mut sketch = DDSketch::new();
+        sketch.insert(1.0);
+        sketch.insert(3.0);
+        assert_eq!(
+            format!(
+                "{}",
+                Metric::new(
+                    "seven",
+                    MetricKind::Absolute,
+                    MetricValue::Sketch { sketch }
+                )
+            ),
+            r#"seven{} = count=2 sum=4 min=1 max=3"#
+        );
     }
 }
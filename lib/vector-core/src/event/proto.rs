@@ -96,6 +96,19 @@ impl From<Metric> for event::Metric {
                 count: summary.count,
                 sum: summary.sum,
             },
+            MetricValue::Sketch(sketch) => event::MetricValue::Sketch {
+                sketch: event::DDSketch::from_parts(
+                    sketch
+                        .keys
+                        .into_iter()
+                        .zip(sketch.counts)
+                        .map(|(key, count)| event::metric::SketchBin { key, count })
+                        .collect(),
+                    sketch.sum,
+                    sketch.min,
+                    sketch.max,
+                ),
+            },
         };
 
         Self::new(name, kind, value)
@@ -194,6 +207,13 @@ impl From<event::Metric> for WithMetadata<Metric> {
                 count,
                 sum,
             }),
+            event::MetricValue::Sketch { sketch } => MetricValue::Sketch(Sketch {
+                keys: sketch.bins().iter().map(|bin| bin.key).collect(),
+                counts: sketch.bins().iter().map(|bin| bin.count).collect(),
+                sum: sketch.sum(),
+                min: sketch.min().unwrap_or_default(),
+                max: sketch.max().unwrap_or_default(),
+            }),
         };
 
         let data = Metric {
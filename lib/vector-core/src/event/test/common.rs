@@ -1,5 +1,5 @@
 use crate::event::{
-    metric::{Bucket, MetricData, MetricName, MetricSeries, Quantile, Sample},
+    metric::{Bucket, DDSketch, MetricData, MetricName, MetricSeries, Quantile, Sample},
     Event, EventMetadata, LogEvent, Metric, MetricKind, MetricValue, StatisticKind, Value,
 };
 use bytes::Bytes;
@@ -151,7 +151,7 @@ impl Arbitrary for MetricValue {
         // constant here are the number of fields in `MetricValue`. Because the
         // field total is not a power of two we introduce a bias into choice
         // here toward `MetricValue::Counter` and `MetricValue::Gauge`.
-        match u8::arbitrary(g) % 6 {
+        match u8::arbitrary(g) % 7 {
             0 => MetricValue::Counter {
                 value: f64::arbitrary(g) % MAX_F64_SIZE,
             },
@@ -175,6 +175,13 @@ impl Arbitrary for MetricValue {
                 count: u32::arbitrary(g),
                 sum: f64::arbitrary(g) % MAX_F64_SIZE,
             },
+            6 => {
+                let mut sketch = DDSketch::new();
+                for sample in Vec::<Sample>::arbitrary(g) {
+                    sketch.insert_n(sample.value, sample.rate);
+                }
+                MetricValue::Sketch { sketch }
+            }
             _ => unreachable!(),
         }
     }
@@ -302,6 +309,17 @@ impl Arbitrary for MetricValue {
                         }),
                 )
             }
+            MetricValue::Sketch { sketch } => {
+                // The bins of a sketch can't be shrunk independently of
+                // its statistics, so only try the empty sketch.
+                if sketch.is_empty() {
+                    empty_shrinker()
+                } else {
+                    Box::new(std::iter::once(MetricValue::Sketch {
+                        sketch: DDSketch::new(),
+                    }))
+                }
+            }
         }
     }
 }
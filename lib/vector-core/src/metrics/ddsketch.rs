@@ -0,0 +1,468 @@
+//! A quantile sketch with relative error guarantees, based on
+//! [DDSketch](https://arxiv.org/abs/1908.10693).
+//!
+//! All sketches share the same relative accuracy and bin mapping, so any two
+//! sketches can be merged without losing accuracy, unlike aggregated
+//! summaries.
+
+use crate::event::metric::{Bucket, Quantile};
+use crate::ByteSizeOf;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// The relative accuracy of the quantiles estimated by every sketch.
+pub const RELATIVE_ACCURACY: f64 = 0.01;
+
+/// The maximum number of bins kept by a sketch. Past that, the bins holding
+/// the smallest values are collapsed together, so the lowest quantiles lose
+/// their accuracy first.
+pub const MAX_BINS: usize = 4096;
+
+/// Values closer to zero than this are counted in the zero bin.
+const MIN_VALUE: f64 = 1.0e-9;
+
+fn gamma() -> f64 {
+    (1.0 + RELATIVE_ACCURACY) / (1.0 - RELATIVE_ACCURACY)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn index(value: f64) -> i32 {
+    (value.ln() / gamma().ln()).ceil() as i32
+}
+
+/// Shifts the bin indexes so that every key of a non-zero value is at least
+/// one, leaving zero for the zero bin.
+fn offset() -> i32 {
+    1 - index(MIN_VALUE)
+}
+
+fn key(value: f64) -> i32 {
+    if value.abs() < MIN_VALUE {
+        0
+    } else if value > 0.0 {
+        index(value) + offset()
+    } else {
+        -(index(-value) + offset())
+    }
+}
+
+/// The value representing every value counted in the bin with the given key,
+/// which is within the relative accuracy of all of them.
+fn value(key: i32) -> f64 {
+    if key == 0 {
+        return 0.0;
+    }
+    let gamma = gamma();
+    let value = 2.0 * gamma.powi(key.abs() - offset()) / (gamma + 1.0);
+    if key > 0 {
+        value
+    } else {
+        -value
+    }
+}
+
+/// A bin of a `DDSketch`, counting the values mapped to its key.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
+pub struct SketchBin {
+    pub key: i32,
+    pub count: u32,
+}
+
+/// A mergeable sketch of a distribution of values, estimating its quantiles
+/// within `RELATIVE_ACCURACY` of their actual value.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, PartialOrd, Serialize)]
+pub struct DDSketch {
+    /// Bins sorted by key, without duplicate keys.
+    bins: Vec<SketchBin>,
+    count: u32,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl DDSketch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuild a sketch from its bins and summary statistics, as exposed by
+    /// the accessors. The bins are sorted and bins with the same key are
+    /// combined, and the total count is derived from them.
+    pub fn from_parts(mut bins: Vec<SketchBin>, sum: f64, min: f64, max: f64) -> Self {
+        bins.sort_by_key(|bin| bin.key);
+        bins.dedup_by(|bin, previous| {
+            let same = bin.key == previous.key;
+            if same {
+                previous.count = previous.count.saturating_add(bin.count);
+            }
+            same
+        });
+        bins.retain(|bin| bin.count > 0);
+        let count = bins
+            .iter()
+            .fold(0u32, |count, bin| count.saturating_add(bin.count));
+
+        let mut sketch = Self {
+            bins,
+            count,
+            sum,
+            min,
+            max,
+        };
+        if count == 0 {
+            sketch.clear();
+        }
+        sketch.collapse();
+        sketch
+    }
+
+    pub fn bins(&self) -> &[SketchBin] {
+        &self.bins
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// The smallest value inserted, if any.
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.min)
+    }
+
+    /// The largest value inserted, if any.
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.max)
+    }
+
+    /// The mean of the values inserted, if any.
+    pub fn avg(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / f64::from(self.count))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        self.insert_n(value, 1);
+    }
+
+    /// Insert `n` occurrences of the same value. Non-finite values are
+    /// ignored.
+    pub fn insert_n(&mut self, value: f64, n: u32) {
+        if n == 0 || !value.is_finite() {
+            return;
+        }
+
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count = self.count.saturating_add(n);
+        self.sum += value * f64::from(n);
+
+        let key = key(value);
+        match self.bins.binary_search_by_key(&key, |bin| bin.key) {
+            Ok(index) => {
+                let bin = &mut self.bins[index];
+                bin.count = bin.count.saturating_add(n);
+            }
+            Err(index) => {
+                self.bins.insert(index, SketchBin { key, count: n });
+                self.collapse();
+            }
+        }
+    }
+
+    /// Merge the values of another sketch into this one.
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+
+        self.count = self.count.saturating_add(other.count);
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+
+        let (ours, theirs) = (&self.bins, &other.bins);
+        let mut bins = Vec::with_capacity(ours.len() + theirs.len());
+        let (mut i, mut j) = (0, 0);
+        while i < ours.len() && j < theirs.len() {
+            match ours[i].key.cmp(&theirs[j].key) {
+                Ordering::Less => {
+                    bins.push(ours[i]);
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    bins.push(theirs[j]);
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    bins.push(SketchBin {
+                        key: ours[i].key,
+                        count: ours[i].count.saturating_add(theirs[j].count),
+                    });
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        bins.extend_from_slice(&ours[i..]);
+        bins.extend_from_slice(&theirs[j..]);
+        self.bins = bins;
+        self.collapse();
+    }
+
+    /// Remove all the values from this sketch.
+    pub fn clear(&mut self) {
+        self.bins.clear();
+        self.count = 0;
+        self.sum = 0.0;
+        self.min = 0.0;
+        self.max = 0.0;
+    }
+
+    /// Estimate the value at the given quantile (0 <= q <= 1), if the sketch
+    /// has any values.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        if q == 0.0 {
+            return Some(self.min);
+        }
+        if q == 1.0 {
+            return Some(self.max);
+        }
+
+        let rank = q * f64::from(self.count - 1);
+        let mut seen = 0.0;
+        for bin in &self.bins {
+            seen += f64::from(bin.count);
+            if seen > rank {
+                return Some(value(bin.key).max(self.min).min(self.max));
+            }
+        }
+        Some(self.max)
+    }
+
+    /// The value representing each bin, paired with the number of values
+    /// counted in it, in increasing order of value.
+    pub fn bin_values(&self) -> impl Iterator<Item = (f64, u32)> + '_ {
+        self.bins
+            .iter()
+            .map(move |bin| (value(bin.key).max(self.min).min(self.max), bin.count))
+    }
+
+    /// Estimate the values at the given quantiles, as used by aggregated
+    /// summaries.
+    pub fn to_quantiles(&self, quantiles: &[f64]) -> Vec<Quantile> {
+        quantiles
+            .iter()
+            .filter_map(|&upper_limit| {
+                self.quantile(upper_limit)
+                    .map(|value| Quantile { upper_limit, value })
+            })
+            .collect()
+    }
+
+    /// Count the values into buckets with the given upper limits, as used by
+    /// aggregated histograms. Each bucket counts the values above the
+    /// previous limit, and values above the last limit are not counted in
+    /// any bucket.
+    pub fn to_buckets(&self, upper_limits: &[f64]) -> Vec<Bucket> {
+        let mut buckets = upper_limits
+            .iter()
+            .map(|&upper_limit| Bucket {
+                upper_limit,
+                count: 0,
+            })
+            .collect::<Vec<_>>();
+        for (value, count) in self.bin_values() {
+            if let Some(bucket) = buckets
+                .iter_mut()
+                .find(|bucket| value <= bucket.upper_limit)
+            {
+                bucket.count = bucket.count.saturating_add(count);
+            }
+        }
+        buckets
+    }
+
+    /// Fold the lowest bins together until there are at most `MAX_BINS`.
+    fn collapse(&mut self) {
+        if self.bins.len() <= MAX_BINS {
+            return;
+        }
+        let excess = self.bins.len() - MAX_BINS;
+        let count = self.bins[..=excess]
+            .iter()
+            .fold(0u32, |count, bin| count.saturating_add(bin.count));
+        self.bins.drain(..excess);
+        self.bins[0].count = count;
+    }
+}
+
+impl ByteSizeOf for DDSketch {
+    fn allocated_bytes(&self) -> usize {
+        self.bins.capacity() * std::mem::size_of::<SketchBin>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_accurate(estimate: f64, actual: f64) {
+        assert!(
+            (estimate - actual).abs() <= actual.abs() * RELATIVE_ACCURACY + 1e-9,
+            "estimate {} is not within {} of {}",
+            estimate,
+            RELATIVE_ACCURACY,
+            actual
+        );
+    }
+
+    #[test]
+    fn quantiles_are_accurate() {
+        let mut sketch = DDSketch::new();
+        for i in 1..=1000 {
+            sketch.insert(f64::from(i));
+        }
+
+        assert_eq!(sketch.count(), 1000);
+        assert_eq!(sketch.sum(), 500_500.0);
+        assert_eq!(sketch.min(), Some(1.0));
+        assert_eq!(sketch.max(), Some(1000.0));
+        assert_eq!(sketch.quantile(0.0), Some(1.0));
+        assert_eq!(sketch.quantile(1.0), Some(1000.0));
+        for &(q, actual) in &[(0.5, 500.0), (0.9, 900.0), (0.99, 990.0)] {
+            assert_accurate(sketch.quantile(q).unwrap(), actual);
+        }
+    }
+
+    #[test]
+    fn negative_and_zero_values() {
+        let mut sketch = DDSketch::new();
+        sketch.insert_n(-10.0, 10);
+        sketch.insert_n(0.0, 10);
+        sketch.insert_n(10.0, 10);
+
+        assert_accurate(sketch.quantile(0.1).unwrap(), -10.0);
+        assert_eq!(sketch.quantile(0.5), Some(0.0));
+        assert_accurate(sketch.quantile(0.9).unwrap(), 10.0);
+        assert_eq!(sketch.sum(), 0.0);
+    }
+
+    #[test]
+    fn merge_matches_single_sketch() {
+        let mut all = DDSketch::new();
+        let mut even = DDSketch::new();
+        let mut odd = DDSketch::new();
+        for i in 1..=500 {
+            let value = f64::from(i) * 1.5;
+            all.insert(value);
+            if i % 2 == 0 {
+                even.insert(value);
+            } else {
+                odd.insert(value);
+            }
+        }
+
+        even.merge(&odd);
+        assert_eq!(even, all);
+
+        let mut empty = DDSketch::new();
+        empty.merge(&all);
+        assert_eq!(empty, all);
+    }
+
+    #[test]
+    fn empty_sketch() {
+        let sketch = DDSketch::new();
+        assert!(sketch.is_empty());
+        assert_eq!(sketch.quantile(0.5), None);
+        assert_eq!(sketch.min(), None);
+        assert_eq!(sketch.avg(), None);
+        assert!(sketch.to_quantiles(&[0.5]).is_empty());
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn collapses_lowest_bins() {
+        let mut sketch = DDSketch::new();
+        let gamma = gamma();
+        for i in 0..(MAX_BINS as i32 + 10) {
+            sketch.insert(gamma.powf(f64::from(i) + 0.5));
+        }
+
+        assert_eq!(sketch.bins().len(), MAX_BINS);
+        assert_eq!(sketch.bins()[0].count, 11);
+        assert_eq!(sketch.count(), MAX_BINS as u32 + 10);
+        assert_accurate(sketch.quantile(0.99).unwrap(), gamma.powf(4063.5));
+    }
+
+    #[test]
+    fn converts_to_buckets_and_quantiles() {
+        let mut sketch = DDSketch::new();
+        for &value in &[1.0, 2.0, 3.0, 10.0, 100.0] {
+            sketch.insert(value);
+        }
+
+        let buckets = sketch.to_buckets(&[2.5, 50.0]);
+        assert_eq!(
+            buckets,
+            vec![
+                Bucket {
+                    upper_limit: 2.5,
+                    count: 2
+                },
+                Bucket {
+                    upper_limit: 50.0,
+                    count: 2
+                },
+            ]
+        );
+
+        let quantiles = sketch.to_quantiles(&[0.0, 0.5, 1.0]);
+        assert_eq!(quantiles.len(), 3);
+        assert_eq!(quantiles[0].value, 1.0);
+        assert_accurate(quantiles[1].value, 3.0);
+        assert_eq!(quantiles[2].value, 100.0);
+    }
+
+    #[test]
+    fn rebuilds_from_parts() {
+        let mut sketch = DDSketch::new();
+        sketch.insert_n(4.0, 3);
+        sketch.insert(-2.0);
+
+        let rebuilt = DDSketch::from_parts(
+            sketch.bins().to_vec(),
+            sketch.sum(),
+            sketch.min().unwrap(),
+            sketch.max().unwrap(),
+        );
+        assert_eq!(rebuilt, sketch);
+
+        let mut bins = sketch.bins().to_vec();
+        bins.reverse();
+        bins.push(bins[0]);
+        let rebuilt = DDSketch::from_parts(bins, 10.0, -2.0, 4.0);
+        assert_eq!(rebuilt.count(), 7);
+        assert_eq!(rebuilt.bins()[0], sketch.bins()[0]);
+        assert_eq!(rebuilt.bins()[1].count, 6);
+    }
+}
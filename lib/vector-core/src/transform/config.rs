@@ -18,6 +18,9 @@ pub enum ExpandType {
 #[cfg(feature = "vrl")]
 #[derive(Debug, Default)]
 pub struct TransformContext {
+    /// The key of the transform being built, used to name the state it keeps
+    /// across reloads and restarts. This is `None` outside of a topology.
+    pub key: Option<String>,
     pub globals: GlobalOptions,
     pub enrichment_tables: enrichment::TableRegistry,
 }
@@ -25,6 +28,9 @@ pub struct TransformContext {
 #[cfg(not(feature = "vrl"))]
 #[derive(Debug, Default)]
 pub struct TransformContext {
+    /// The key of the transform being built, used to name the state it keeps
+    /// across reloads and restarts. This is `None` outside of a topology.
+    pub key: Option<String>,
     pub globals: GlobalOptions,
 }
 
@@ -12,10 +12,12 @@ criterion_group!(
     targets = assert,
               assert_eq,
               ceil,
+              community_id,
               compact,
               contains,
               decode_base64,
               decode_percent,
+              decrypt,
               // TODO: Cannot pass a Path to bench_function
               //del,
               downcase,
@@ -24,6 +26,7 @@ criterion_group!(
               encode_json,
               encode_logfmt,
               encode_percent,
+              encrypt,
               ends_with,
               // TODO: Cannot pass a Path to bench_function
               //exists
@@ -34,12 +37,15 @@ criterion_group!(
               format_timestamp,
               get_env_var,
               get_hostname,
+              hmac,
               includes,
+              ip_anonymize,
               ip_aton,
               ip_cidr_contains,
               ip_ntoa,
               ip_subnet,
               ip_to_ipv6,
+              ip_version,
               ipv6_to_ipv4,
               is_array,
               is_boolean,
@@ -48,6 +54,7 @@ criterion_group!(
               is_null,
               is_nullish,
               is_object,
+              is_private_ip,
               is_regex,
               is_string,
               is_timestamp,
@@ -66,6 +73,7 @@ criterion_group!(
               parse_aws_alb_log,
               parse_aws_cloudwatch_log_subscription_message,
               parse_aws_vpc_flow_log,
+              parse_cef,
               parse_common_log,
               parse_csv,
               parse_duration,
@@ -73,6 +81,7 @@ criterion_group!(
               parse_grok,
               parse_key_value,
               parse_klog,
+              parse_leef,
               parse_int,
               parse_json,
               parse_nginx_log,
@@ -87,6 +96,8 @@ criterion_group!(
               parse_user_agent,
               parse_xml,
               push,
+              // TODO: value is dynamic so we cannot assert equality
+              //random_bytes,
               redact,
               replace,
               round,
@@ -98,6 +109,9 @@ criterion_group!(
               starts_with,
               strip_ansi_escape_codes,
               strip_whitespace,
+              timestamp_add,
+              timestamp_diff,
+              timestamp_part,
               to_bool,
               to_float,
               to_int,
@@ -109,12 +123,14 @@ criterion_group!(
               to_timestamp,
               to_unix_timestamp,
               truncate,
+              truncate_timestamp,
               unique,
               // TODO: Cannot pass a Path to bench_function
               //unnest
               // TODO: value is dynamic so we cannot assert equality
               //uuidv4,
-              upcase
+              upcase,
+              validate_json_schema
 );
 criterion_main!(benches);
 
@@ -154,6 +170,20 @@ bench_function! {
     }
 }
 
+bench_function! {
+    community_id => vrl_stdlib::CommunityId;
+
+    tcp {
+        args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 1122, destination_port: 3344],
+        want: Ok("1:wCb3OG7yAFWelaUydu0D+125CLM="),
+    }
+
+    icmp {
+        args: func_args![source_ip: "192.168.0.89", destination_ip: "192.168.0.1", protocol: 1, source_port: 8, destination_port: 0],
+        want: Ok("1:X0snYXpgwiv9TZtqg64sgzUn6Dk="),
+    }
+}
+
 bench_function! {
     compact => vrl_stdlib::Compact;
 
@@ -204,6 +234,26 @@ bench_function! {
     }
 }
 
+bench_function! {
+    decrypt => vrl_stdlib::Decrypt;
+
+    aes_256_gcm {
+        args: func_args![ciphertext: Bytes::from_static(b"\xbd\x05\xab\x1b\x60\xdc\xe0\xb7\x7c\x1f\x56\xdb\x7f\x57\x22\xe0\x59\x77\xad\x9a\xe8\x8e\xd5\xce\xa9\x7e\x26\x8d\x77\x6f\xf4\x92\x94\xb4\xfa\xda"),
+                         algorithm: "AES-256-GCM",
+                         key: "01234567890123456789012345678912",
+                         iv: "123456789012"],
+        want: Ok("morethan1blockofdata"),
+    }
+
+    aes_128_cbc_pkcs7 {
+        args: func_args![ciphertext: Bytes::from_static(b"\xb0\x49\x61\xf1\xae\x22\xc0\xd1\x52\x9a\x84\x3e\xbc\x37\xf5\x06\xd1\x12\xdc\x00\x4c\x8e\x40\x36\x6b\xfe\x39\xd8\x85\xd8\x16\x73"),
+                         algorithm: "AES-128-CBC-PKCS7",
+                         key: "0123456789012345",
+                         iv: "1234567890123456"],
+        want: Ok("morethan1blockofdata"),
+    }
+}
+
 bench_function! {
     downcase => vrl_stdlib::Downcase;
 
@@ -323,6 +373,26 @@ bench_function! {
     }
 }
 
+bench_function! {
+    encrypt => vrl_stdlib::Encrypt;
+
+    aes_256_gcm {
+        args: func_args![plaintext: "morethan1blockofdata",
+                         algorithm: "AES-256-GCM",
+                         key: "01234567890123456789012345678912",
+                         iv: "123456789012"],
+        want: Ok(Bytes::from_static(b"\xbd\x05\xab\x1b\x60\xdc\xe0\xb7\x7c\x1f\x56\xdb\x7f\x57\x22\xe0\x59\x77\xad\x9a\xe8\x8e\xd5\xce\xa9\x7e\x26\x8d\x77\x6f\xf4\x92\x94\xb4\xfa\xda")),
+    }
+
+    aes_128_cbc_pkcs7 {
+        args: func_args![plaintext: "morethan1blockofdata",
+                         algorithm: "AES-128-CBC-PKCS7",
+                         key: "0123456789012345",
+                         iv: "1234567890123456"],
+        want: Ok(Bytes::from_static(b"\xb0\x49\x61\xf1\xae\x22\xc0\xd1\x52\x9a\x84\x3e\xbc\x37\xf5\x06\xd1\x12\xdc\x00\x4c\x8e\x40\x36\x6b\xfe\x39\xd8\x85\xd8\x16\x73")),
+    }
+}
+
 bench_function! {
     ends_with => vrl_stdlib::EndsWith;
 
@@ -411,6 +481,11 @@ bench_function! {
         args: func_args![value: Utc.timestamp(10, 0), format: "%+"],
         want: Ok("1970-01-01T00:00:10+00:00"),
     }
+
+    timezone {
+        args: func_args![value: Utc.timestamp(10, 0), format: "%+", timezone: "Asia/Kolkata"],
+        want: Ok("1970-01-01T05:30:10+05:30"),
+    }
 }
 
 bench_function! {
@@ -432,6 +507,20 @@ bench_function! {
     }
 }
 
+bench_function! {
+    hmac => vrl_stdlib::Hmac;
+
+    default {
+        args: func_args![value: "john.doe@example.com", key: "my_secret_key"],
+        want: Ok("219b6bb37e964bb8feb1519160c717db497906fbfa598e750af8ba89f9cfa11f"),
+    }
+
+    sha1 {
+        args: func_args![value: "foo", key: "my_secret", algorithm: "SHA1"],
+        want: Ok("9275a36e53d7d4755e99a57d003470bbb2d09951"),
+    }
+}
+
 bench_function! {
     includes => vrl_stdlib::Includes;
 
@@ -441,6 +530,20 @@ bench_function! {
     }
 }
 
+bench_function! {
+    ip_anonymize => vrl_stdlib::IpAnonymize;
+
+    truncate {
+        args: func_args![value: "192.168.10.23"],
+        want: Ok("192.168.10.0"),
+    }
+
+    prefix_preserving_ipv4 {
+        args: func_args![value: "192.168.10.23", mode: "prefix_preserving", key: "0123456789abcdef0123456789abcdef"],
+        want: Ok("177.88.10.28"),
+    }
+}
+
 bench_function! {
     ip_aton => vrl_stdlib::IpAton;
 
@@ -511,6 +614,20 @@ bench_function! {
     }
 }
 
+bench_function! {
+    ip_version => vrl_stdlib::IpVersion;
+
+    ipv4 {
+        args: func_args![value: "192.168.0.1"],
+        want: Ok("IPv4"),
+    }
+
+    ipv6 {
+        args: func_args![value: "2001:db8::1"],
+        want: Ok("IPv6"),
+    }
+}
+
 bench_function! {
     ipv6_to_ipv4 => vrl_stdlib::Ipv6ToIpV4;
 
@@ -633,6 +750,20 @@ bench_function! {
     }
 }
 
+bench_function! {
+    is_private_ip => vrl_stdlib::IsPrivateIp;
+
+    ipv4 {
+        args: func_args![value: "192.168.0.1"],
+        want: Ok(true),
+    }
+
+    ipv6 {
+        args: func_args![value: "2001:4860:4860::8888"],
+        want: Ok(false),
+    }
+}
+
 bench_function! {
     is_regex => vrl_stdlib::IsRegex;
 
@@ -1097,6 +1228,27 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_cef => vrl_stdlib::ParseCef;
+
+    literal {
+        args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232 msg=Worm stopped on host"],
+        want: Ok(value!({
+            "cefVersion": "0",
+            "deviceVendor": "Security",
+            "deviceProduct": "threatmanager",
+            "deviceVersion": "1.0",
+            "deviceEventClassId": "100",
+            "name": "worm successfully stopped",
+            "severity": "10",
+            "src": "10.0.0.1",
+            "dst": "2.1.2.2",
+            "spt": "1232",
+            "msg": "Worm stopped on host",
+        })),
+    }
+}
+
 bench_function! {
     parse_common_log => vrl_stdlib::ParseCommonLog;
 
@@ -1243,6 +1395,26 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_leef => vrl_stdlib::ParseLeef;
+
+    literal {
+        args: func_args![value: "LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^sev=5^srcPort=81^dstPort=21"],
+        want: Ok(value!({
+            "leefVersion": "2.0",
+            "vendor": "Lancope",
+            "product": "StealthWatch",
+            "productVersion": "1.0",
+            "eventId": "41",
+            "src": "10.0.1.8",
+            "dst": "10.0.0.5",
+            "sev": "5",
+            "srcPort": "81",
+            "dstPort": "21",
+        })),
+    }
+}
+
 bench_function! {
     parse_nginx_log => vrl_stdlib::ParseNginxLog;
 
@@ -1885,6 +2057,43 @@ bench_function! {
     }
 }
 
+bench_function! {
+    timestamp_add => vrl_stdlib::TimestampAdd;
+
+    integer {
+        args: func_args![value: Utc.timestamp(10, 0), duration: 90],
+        want: Ok(Utc.timestamp(100, 0)),
+    }
+
+    float {
+        args: func_args![value: Utc.timestamp(10, 0), duration: 1.5, unit: "m"],
+        want: Ok(Utc.timestamp(100, 0)),
+    }
+}
+
+bench_function! {
+    timestamp_diff => vrl_stdlib::TimestampDiff;
+
+    seconds {
+        args: func_args![start: Utc.timestamp(10, 0), end: Utc.timestamp(100, 500_000_000)],
+        want: Ok(90.5),
+    }
+}
+
+bench_function! {
+    timestamp_part => vrl_stdlib::TimestampPart;
+
+    utc {
+        args: func_args![value: Utc.timestamp(10, 0), part: "week"],
+        want: Ok(1),
+    }
+
+    timezone {
+        args: func_args![value: Utc.timestamp(10, 0), part: "hour", timezone: "Asia/Kolkata"],
+        want: Ok(5),
+    }
+}
+
 bench_function! {
     to_bool => vrl_stdlib::ToBool;
 
@@ -2067,6 +2276,20 @@ bench_function! {
     }
 }
 
+bench_function! {
+    truncate_timestamp => vrl_stdlib::TruncateTimestamp;
+
+    utc {
+        args: func_args![value: Utc.timestamp(100, 0), unit: "minute"],
+        want: Ok(Utc.timestamp(60, 0)),
+    }
+
+    timezone {
+        args: func_args![value: Utc.timestamp(100, 0), unit: "day", timezone: "Asia/Kolkata"],
+        want: Ok(Utc.timestamp(-19_800, 0)),
+    }
+}
+
 bench_function! {
     unique => vrl_stdlib::Unique;
 
@@ -2093,3 +2316,27 @@ bench_function! {
         want: Ok("FOO")
     }
 }
+
+bench_function! {
+    validate_json_schema => vrl_stdlib::ValidateJsonSchema;
+
+    valid {
+        args: func_args![
+            value: value!({"name": "Vector", "age": 3, "tags": ["logs", "metrics"]}),
+            schema_file: "../tests/data/json_schema/person.schema.json",
+        ],
+        want: Ok(value!([])),
+    }
+
+    invalid {
+        args: func_args![
+            value: value!({"name": "Vector", "tags": ["logs", 1]}),
+            schema_file: "../tests/data/json_schema/person.schema.json",
+        ],
+        want: Ok(value!([{
+            "instance_path": "/tags/1",
+            "message": r#"1 is not of type "string""#,
+            "schema_path": "/properties/tags/items/type",
+        }])),
+    }
+}
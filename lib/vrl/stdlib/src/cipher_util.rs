@@ -0,0 +1,175 @@
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::{
+    aead::{Aead, NewAead},
+    Nonce,
+};
+use block_modes::{block_padding::Pkcs7, BlockMode, Cbc};
+use std::str::FromStr;
+use vrl::prelude::*;
+
+/// The symmetric ciphers supported by the `encrypt` and `decrypt` functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Algorithm {
+    Aes128Gcm,
+    Aes256Gcm,
+    Aes128CbcPkcs7,
+    Aes192CbcPkcs7,
+    Aes256CbcPkcs7,
+}
+
+impl Algorithm {
+    pub(crate) fn all_value() -> Vec<Value> {
+        use Algorithm::*;
+
+        vec![
+            Aes128Gcm,
+            Aes256Gcm,
+            Aes128CbcPkcs7,
+            Aes192CbcPkcs7,
+            Aes256CbcPkcs7,
+        ]
+        .into_iter()
+        .map(|a| a.as_str().into())
+        .collect()
+    }
+
+    pub(crate) const fn as_str(self) -> &'static str {
+        use Algorithm::*;
+
+        match self {
+            Aes128Gcm => "AES-128-GCM",
+            Aes256Gcm => "AES-256-GCM",
+            Aes128CbcPkcs7 => "AES-128-CBC-PKCS7",
+            Aes192CbcPkcs7 => "AES-192-CBC-PKCS7",
+            Aes256CbcPkcs7 => "AES-256-CBC-PKCS7",
+        }
+    }
+
+    /// The required length of the key, in bytes.
+    const fn key_len(self) -> usize {
+        use Algorithm::*;
+
+        match self {
+            Aes128Gcm | Aes128CbcPkcs7 => 16,
+            Aes192CbcPkcs7 => 24,
+            Aes256Gcm | Aes256CbcPkcs7 => 32,
+        }
+    }
+
+    /// The required length of the initialization vector (the nonce, for GCM),
+    /// in bytes.
+    const fn iv_len(self) -> usize {
+        use Algorithm::*;
+
+        match self {
+            Aes128Gcm | Aes256Gcm => 12,
+            Aes128CbcPkcs7 | Aes192CbcPkcs7 | Aes256CbcPkcs7 => 16,
+        }
+    }
+
+    fn validate(self, key: &[u8], iv: &[u8]) -> std::result::Result<(), String> {
+        if key.len() != self.key_len() {
+            return Err(format!(
+                "key must be {} bytes for {}, got {}",
+                self.key_len(),
+                self.as_str(),
+                key.len()
+            ));
+        }
+
+        if iv.len() != self.iv_len() {
+            return Err(format!(
+                "iv must be {} bytes for {}, got {}",
+                self.iv_len(),
+                self.as_str(),
+                iv.len()
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn encrypt(
+        self,
+        plaintext: &[u8],
+        key: &[u8],
+        iv: &[u8],
+    ) -> std::result::Result<Vec<u8>, String> {
+        self.validate(key, iv)?;
+
+        // Key and IV lengths are validated above, so constructing the ciphers
+        // can't fail.
+        let ciphertext = match self {
+            Self::Aes128Gcm => aes_gcm::Aes128Gcm::new_from_slice(key)
+                .expect("valid key length")
+                .encrypt(Nonce::from_slice(iv), plaintext),
+            Self::Aes256Gcm => aes_gcm::Aes256Gcm::new_from_slice(key)
+                .expect("valid key length")
+                .encrypt(Nonce::from_slice(iv), plaintext),
+            Self::Aes128CbcPkcs7 => Ok(Cbc::<Aes128, Pkcs7>::new_from_slices(key, iv)
+                .expect("valid key and iv length")
+                .encrypt_vec(plaintext)),
+            Self::Aes192CbcPkcs7 => Ok(Cbc::<Aes192, Pkcs7>::new_from_slices(key, iv)
+                .expect("valid key and iv length")
+                .encrypt_vec(plaintext)),
+            Self::Aes256CbcPkcs7 => Ok(Cbc::<Aes256, Pkcs7>::new_from_slices(key, iv)
+                .expect("valid key and iv length")
+                .encrypt_vec(plaintext)),
+        };
+
+        ciphertext.map_err(|_| "unable to encrypt value".to_owned())
+    }
+
+    pub(crate) fn decrypt(
+        self,
+        ciphertext: &[u8],
+        key: &[u8],
+        iv: &[u8],
+    ) -> std::result::Result<Vec<u8>, String> {
+        self.validate(key, iv)?;
+
+        let plaintext = match self {
+            Self::Aes128Gcm => aes_gcm::Aes128Gcm::new_from_slice(key)
+                .expect("valid key length")
+                .decrypt(Nonce::from_slice(iv), ciphertext)
+                .map_err(|_| ()),
+            Self::Aes256Gcm => aes_gcm::Aes256Gcm::new_from_slice(key)
+                .expect("valid key length")
+                .decrypt(Nonce::from_slice(iv), ciphertext)
+                .map_err(|_| ()),
+            Self::Aes128CbcPkcs7 => Cbc::<Aes128, Pkcs7>::new_from_slices(key, iv)
+                .expect("valid key and iv length")
+                .decrypt_vec(ciphertext)
+                .map_err(|_| ()),
+            Self::Aes192CbcPkcs7 => Cbc::<Aes192, Pkcs7>::new_from_slices(key, iv)
+                .expect("valid key and iv length")
+                .decrypt_vec(ciphertext)
+                .map_err(|_| ()),
+            Self::Aes256CbcPkcs7 => Cbc::<Aes256, Pkcs7>::new_from_slices(key, iv)
+                .expect("valid key and iv length")
+                .decrypt_vec(ciphertext)
+                .map_err(|_| ()),
+        };
+
+        // Don't leak why decryption failed, an invalid tag and invalid padding
+        // are reported the same way.
+        plaintext.map_err(|_| "unable to decrypt value".to_owned())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use Algorithm::*;
+
+        match s {
+            "AES-128-GCM" => Ok(Aes128Gcm),
+            "AES-256-GCM" => Ok(Aes256Gcm),
+            "AES-128-CBC-PKCS7" => Ok(Aes128CbcPkcs7),
+            "AES-192-CBC-PKCS7" => Ok(Aes192CbcPkcs7),
+            "AES-256-CBC-PKCS7" => Ok(Aes256CbcPkcs7),
+            _ => Err("unknown algorithm"),
+        }
+    }
+}
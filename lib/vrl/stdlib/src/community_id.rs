@@ -0,0 +1,415 @@
+use ::sha1::{Digest, Sha1};
+use std::{convert::TryFrom, net::IpAddr};
+use vrl::prelude::*;
+
+const PROTOCOL_ICMP: u8 = 1;
+const PROTOCOL_TCP: u8 = 6;
+const PROTOCOL_UDP: u8 = 17;
+const PROTOCOL_ICMPV6: u8 = 58;
+const PROTOCOL_SCTP: u8 = 132;
+
+#[derive(Clone, Copy, Debug)]
+pub struct CommunityId;
+
+impl Function for CommunityId {
+    fn identifier(&self) -> &'static str {
+        "community_id"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "source_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "destination_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "protocol",
+                kind: kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "source_port",
+                kind: kind::INTEGER | kind::NULL,
+                required: false,
+            },
+            Parameter {
+                keyword: "destination_port",
+                kind: kind::INTEGER | kind::NULL,
+                required: false,
+            },
+            Parameter {
+                keyword: "seed",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "TCP",
+                source: r#"community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", source_port: 1122, destination_port: 3344, protocol: 6)"#,
+                result: Ok("1:wCb3OG7yAFWelaUydu0D+125CLM="),
+            },
+            Example {
+                title: "ICMP",
+                source: r#"community_id!(source_ip: "192.168.0.89", destination_ip: "192.168.0.1", source_port: 8, destination_port: 0, protocol: 1)"#,
+                result: Ok("1:X0snYXpgwiv9TZtqg64sgzUn6Dk="),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let source_ip = arguments.required("source_ip");
+        let destination_ip = arguments.required("destination_ip");
+        let protocol = arguments.required("protocol");
+        let source_port = arguments.optional("source_port");
+        let destination_port = arguments.optional("destination_port");
+        let seed = arguments.optional("seed");
+
+        Ok(Box::new(CommunityIdFn {
+            source_ip,
+            destination_ip,
+            protocol,
+            source_port,
+            destination_port,
+            seed,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CommunityIdFn {
+    source_ip: Box<dyn Expression>,
+    destination_ip: Box<dyn Expression>,
+    protocol: Box<dyn Expression>,
+    source_port: Option<Box<dyn Expression>>,
+    destination_port: Option<Box<dyn Expression>>,
+    seed: Option<Box<dyn Expression>>,
+}
+
+impl Expression for CommunityIdFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let source_ip = parse_ip(self.source_ip.resolve(ctx)?, "source_ip")?;
+        let destination_ip = parse_ip(self.destination_ip.resolve(ctx)?, "destination_ip")?;
+
+        let protocol = self.protocol.resolve(ctx)?.try_integer()?;
+        let protocol = u8::try_from(protocol)
+            .map_err(|_| format!("protocol must be between 0 and 255, got {}", protocol))?;
+
+        let source_port = optional_u16(&self.source_port, ctx, "source_port")?;
+        let destination_port = optional_u16(&self.destination_port, ctx, "destination_port")?;
+        let seed = optional_u16(&self.seed, ctx, "seed")?.unwrap_or(0);
+
+        let ports = match (source_port, destination_port) {
+            (Some(source), Some(destination)) => Some((source, destination)),
+            (None, None) => None,
+            _ => {
+                return Err(
+                    "source_port and destination_port must either both be set or both be unset"
+                        .into(),
+                )
+            }
+        };
+
+        let flow = Flow {
+            source_ip,
+            destination_ip,
+            protocol,
+            ports,
+        };
+
+        Ok(flow.community_id(seed)?.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+fn parse_ip(value: Value, name: &str) -> std::result::Result<IpAddr, ExpressionError> {
+    value
+        .try_bytes_utf8_lossy()?
+        .parse()
+        .map_err(|err| format!("unable to parse {}: {}", name, err).into())
+}
+
+fn optional_u16(
+    expr: &Option<Box<dyn Expression>>,
+    ctx: &mut Context,
+    name: &str,
+) -> std::result::Result<Option<u16>, ExpressionError> {
+    let value = match expr {
+        Some(expr) => expr.resolve(ctx)?,
+        None => return Ok(None),
+    };
+
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    let value = value.try_integer()?;
+    u16::try_from(value)
+        .map(Some)
+        .map_err(|_| format!("{} must be between 0 and 65535, got {}", name, value).into())
+}
+
+/// The 5-tuple (or 3-tuple, for protocols without ports) identifying a flow.
+struct Flow {
+    source_ip: IpAddr,
+    destination_ip: IpAddr,
+    protocol: u8,
+    ports: Option<(u16, u16)>,
+}
+
+impl Flow {
+    /// Computes the version 1 [Community ID][spec] of the flow.
+    ///
+    /// [spec]: https://github.com/corelight/community-id-spec
+    fn community_id(&self, seed: u16) -> std::result::Result<String, String> {
+        let (mut source_ip, mut destination_ip) = match (self.source_ip, self.destination_ip) {
+            (IpAddr::V4(source), IpAddr::V4(destination)) => {
+                (source.octets().to_vec(), destination.octets().to_vec())
+            }
+            (IpAddr::V6(source), IpAddr::V6(destination)) => {
+                (source.octets().to_vec(), destination.octets().to_vec())
+            }
+            _ => {
+                return Err(
+                    "source_ip and destination_ip must be of the same IP version".to_owned(),
+                )
+            }
+        };
+
+        let mut ports = match self.protocol {
+            PROTOCOL_TCP | PROTOCOL_UDP | PROTOCOL_SCTP | PROTOCOL_ICMP | PROTOCOL_ICMPV6 => {
+                Some(self.ports.ok_or_else(|| {
+                    format!(
+                        "source_port and destination_port are required for protocol {}",
+                        self.protocol
+                    )
+                })?)
+            }
+            // Other protocols have no notion of ports, so they are left out of
+            // the hash even when given.
+            _ => None,
+        };
+
+        // ICMP has no ports, the message type and code are passed in their
+        // place. Request/response pairs are mapped onto each other so both
+        // directions of an exchange share the same ID; every other message is
+        // treated as one-way.
+        let mut one_way = false;
+        if let Some((message_type, code)) = ports {
+            let counterpart = match self.protocol {
+                PROTOCOL_ICMP => icmp_counterpart(message_type),
+                PROTOCOL_ICMPV6 => icmpv6_counterpart(message_type),
+                _ => Some(code),
+            };
+
+            match counterpart {
+                Some(counterpart) => ports = Some((message_type, counterpart)),
+                None => one_way = true,
+            }
+        }
+
+        // Order the endpoints so both directions of the flow hash identically.
+        let (source_port, destination_port) = ports.unwrap_or((0, 0));
+        if !one_way && (&source_ip, source_port) > (&destination_ip, destination_port) {
+            std::mem::swap(&mut source_ip, &mut destination_ip);
+            ports = ports.map(|(source, destination)| (destination, source));
+        }
+
+        let mut hasher = Sha1::new();
+        hasher.update(seed.to_be_bytes());
+        hasher.update(&source_ip);
+        hasher.update(&destination_ip);
+        hasher.update([self.protocol, 0]);
+        if let Some((source_port, destination_port)) = ports {
+            hasher.update(source_port.to_be_bytes());
+            hasher.update(destination_port.to_be_bytes());
+        }
+
+        Ok(format!("1:{}", base64::encode(hasher.finalize())))
+    }
+}
+
+/// Returns the message type answering the given ICMP message type, if any.
+const fn icmp_counterpart(message_type: u16) -> Option<u16> {
+    match message_type {
+        // Echo Reply / Echo Request
+        0 => Some(8),
+        8 => Some(0),
+        // Router Advertisement / Router Solicitation
+        9 => Some(10),
+        10 => Some(9),
+        // Timestamp / Timestamp Reply
+        13 => Some(14),
+        14 => Some(13),
+        // Information Request / Information Reply
+        15 => Some(16),
+        16 => Some(15),
+        // Address Mask Request / Address Mask Reply
+        17 => Some(18),
+        18 => Some(17),
+        _ => None,
+    }
+}
+
+/// Returns the message type answering the given ICMPv6 message type, if any.
+const fn icmpv6_counterpart(message_type: u16) -> Option<u16> {
+    match message_type {
+        // Echo Request / Echo Reply
+        128 => Some(129),
+        129 => Some(128),
+        // Multicast Listener Query / Multicast Listener Report
+        130 => Some(131),
+        131 => Some(130),
+        // Router Solicitation / Router Advertisement
+        133 => Some(134),
+        134 => Some(133),
+        // Neighbor Solicitation / Neighbor Advertisement
+        135 => Some(136),
+        136 => Some(135),
+        // Node Information Query / Node Information Response
+        139 => Some(140),
+        140 => Some(139),
+        // Home Agent Address Discovery Request / Reply
+        144 => Some(145),
+        145 => Some(144),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        community_id => CommunityId;
+
+        tcp {
+            args: func_args![source_ip: "128.232.110.120",
+                             destination_ip: "66.35.250.204",
+                             protocol: 6,
+                             source_port: 34855,
+                             destination_port: 80],
+            want: Ok("1:LQU9qZlK+B5F3KDmev6m5PMibrg="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        tcp_reverse {
+            args: func_args![source_ip: "66.35.250.204",
+                             destination_ip: "128.232.110.120",
+                             protocol: 6,
+                             source_port: 80,
+                             destination_port: 34855],
+            want: Ok("1:LQU9qZlK+B5F3KDmev6m5PMibrg="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        tcp_seed {
+            args: func_args![source_ip: "128.232.110.120",
+                             destination_ip: "66.35.250.204",
+                             protocol: 6,
+                             source_port: 34855,
+                             destination_port: 80,
+                             seed: 1],
+            want: Ok("1:3V71V58M3Ksw/yuFALMcW0LAHvc="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        udp {
+            args: func_args![source_ip: "192.168.1.52",
+                             destination_ip: "8.8.8.8",
+                             protocol: 17,
+                             source_port: 54585,
+                             destination_port: 53],
+            want: Ok("1:d/FP5EW3wiY1vCndhwleRRKHowQ="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp_request {
+            args: func_args![source_ip: "192.168.0.89",
+                             destination_ip: "192.168.0.1",
+                             protocol: 1,
+                             source_port: 8,
+                             destination_port: 0],
+            want: Ok("1:X0snYXpgwiv9TZtqg64sgzUn6Dk="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp_reply {
+            args: func_args![source_ip: "192.168.0.1",
+                             destination_ip: "192.168.0.89",
+                             protocol: 1,
+                             source_port: 0,
+                             destination_port: 0],
+            want: Ok("1:X0snYXpgwiv9TZtqg64sgzUn6Dk="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmpv6 {
+            args: func_args![source_ip: "fe80::200:86ff:fe05:80da",
+                             destination_ip: "fe80::260:97ff:fe07:69ea",
+                             protocol: 58,
+                             source_port: 135,
+                             destination_port: 0],
+            want: Ok("1:dGHyGvjMfljg6Bppwm3bg0LO8TY="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        no_ports {
+            args: func_args![source_ip: "10.0.0.1",
+                             destination_ip: "10.0.0.2",
+                             protocol: 47],
+            want: Ok("1:+KlEHDT0vJgzs/eNmzHq0aSpRYw="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        missing_ports {
+            args: func_args![source_ip: "10.0.0.1",
+                             destination_ip: "10.0.0.2",
+                             protocol: 6],
+            want: Err("source_port and destination_port are required for protocol 6"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        mixed_ip_versions {
+            args: func_args![source_ip: "10.0.0.1",
+                             destination_ip: "::1",
+                             protocol: 6,
+                             source_port: 1,
+                             destination_port: 2],
+            want: Err("source_ip and destination_ip must be of the same IP version"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_port {
+            args: func_args![source_ip: "10.0.0.1",
+                             destination_ip: "10.0.0.2",
+                             protocol: 6,
+                             source_port: 70000,
+                             destination_port: 2],
+            want: Err("source_port must be between 0 and 65535, got 70000"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_ip {
+            args: func_args![source_ip: "not an ip",
+                             destination_ip: "10.0.0.2",
+                             protocol: 6,
+                             source_port: 1,
+                             destination_port: 2],
+            want: Err("unable to parse source_ip: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
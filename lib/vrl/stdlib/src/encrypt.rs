@@ -0,0 +1,221 @@
+use crate::cipher_util::Algorithm;
+use std::str::FromStr;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Encrypt;
+
+impl Function for Encrypt {
+    fn identifier(&self) -> &'static str {
+        "encrypt"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "plaintext",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "iv",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key_file",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "AES-256-GCM",
+                source: r#"encode_base64(encrypt!("data", "AES-256-GCM", key_file: "data/keys/aes-256.key", iv: "123456789012"))"#,
+                result: Ok("tAutH6N8KnTNP3fy5ZKNPboN3Fw="),
+            },
+            Example {
+                title: "AES-128-CBC-PKCS7",
+                source: r#"encode_base64(encrypt!("data", "AES-128-CBC-PKCS7", key_file: "data/keys/aes-128.key", iv: "1234567890123456"))"#,
+                result: Ok("8WQ/GYvOfml1YRTA/3MI+Q=="),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let plaintext = arguments.required("plaintext");
+        let algorithm = arguments
+            .required_enum("algorithm", &Algorithm::all_value())?
+            .try_bytes_utf8_lossy()
+            .map(|s| Algorithm::from_str(&s).expect("validated enum"))
+            .expect("algorithm not bytes");
+        let key = crate::util::key_argument("encrypt", &mut arguments)?;
+        let iv = arguments.required("iv");
+
+        Ok(Box::new(EncryptFn {
+            plaintext,
+            algorithm,
+            key,
+            iv,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncryptFn {
+    plaintext: Box<dyn Expression>,
+    algorithm: Algorithm,
+    key: Box<dyn Expression>,
+    iv: Box<dyn Expression>,
+}
+
+impl Expression for EncryptFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let plaintext = self.plaintext.resolve(ctx)?.try_bytes()?;
+        let key = self.key.resolve(ctx)?.try_bytes()?;
+        let iv = self.iv.resolve(ctx)?.try_bytes()?;
+
+        let ciphertext = self.algorithm.encrypt(&plaintext, &key, &iv)?;
+
+        Ok(ciphertext.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AES_256_KEY_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../tests/data/keys/aes-256.key"
+    );
+
+    test_function![
+        encrypt => Encrypt;
+
+        aes_128_gcm {
+            args: func_args![plaintext: "morethan1blockofdata",
+                             algorithm: "AES-128-GCM",
+                             key: "0123456789012345",
+                             iv: "123456789012"],
+            want: Ok(value!(Bytes::from_static(b"\xa6\xe9\x93\x49\xf8\x1d\x24\x44\xa1\x73\xce\x0f\x14\x38\xa6\xec\xf4\x8a\x2f\xcd\x4a\x8a\x5c\x84\xf5\x00\x30\x10\x3a\x17\x95\x04\xff\xea\x90\x07"))),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        aes_256_gcm {
+            args: func_args![plaintext: "morethan1blockofdata",
+                             algorithm: "AES-256-GCM",
+                             key: "01234567890123456789012345678912",
+                             iv: "123456789012"],
+            want: Ok(value!(Bytes::from_static(b"\xbd\x05\xab\x1b\x60\xdc\xe0\xb7\x7c\x1f\x56\xdb\x7f\x57\x22\xe0\x59\x77\xad\x9a\xe8\x8e\xd5\xce\xa9\x7e\x26\x8d\x77\x6f\xf4\x92\x94\xb4\xfa\xda"))),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        aes_128_cbc_pkcs7 {
+            args: func_args![plaintext: "morethan1blockofdata",
+                             algorithm: "AES-128-CBC-PKCS7",
+                             key: "0123456789012345",
+                             iv: "1234567890123456"],
+            want: Ok(value!(Bytes::from_static(b"\xb0\x49\x61\xf1\xae\x22\xc0\xd1\x52\x9a\x84\x3e\xbc\x37\xf5\x06\xd1\x12\xdc\x00\x4c\x8e\x40\x36\x6b\xfe\x39\xd8\x85\xd8\x16\x73"))),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        aes_192_cbc_pkcs7 {
+            args: func_args![plaintext: "morethan1blockofdata",
+                             algorithm: "AES-192-CBC-PKCS7",
+                             key: "012345678901234567890123",
+                             iv: "1234567890123456"],
+            want: Ok(value!(Bytes::from_static(b"\x8c\xed\xda\x5c\x3a\xbf\xa3\x58\xbb\xba\x3a\x73\x1c\x56\x95\xbd\xac\x80\xfc\x83\xc4\xbe\x82\xd3\xc5\x8e\x25\xb1\xda\x1a\x62\xd8"))),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        aes_256_cbc_pkcs7 {
+            args: func_args![plaintext: "morethan1blockofdata",
+                             algorithm: "AES-256-CBC-PKCS7",
+                             key: "01234567890123456789012345678912",
+                             iv: "1234567890123456"],
+            want: Ok(value!(Bytes::from_static(b"\xc5\xd1\xf1\x34\x12\x8a\x70\x4f\x42\x68\x7b\x53\x7f\xe6\x12\x0a\x22\xb1\xaf\xea\x86\xda\xc3\x24\xe8\x0e\x64\xbd\x0e\x42\x3f\x80"))),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_key_length {
+            args: func_args![plaintext: "data",
+                             algorithm: "AES-256-GCM",
+                             key: "tooshort",
+                             iv: "123456789012"],
+            want: Err("key must be 32 bytes for AES-256-GCM, got 8"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_iv_length {
+            args: func_args![plaintext: "data",
+                             algorithm: "AES-128-CBC-PKCS7",
+                             key: "0123456789012345",
+                             iv: "123456789012"],
+            want: Err("iv must be 16 bytes for AES-128-CBC-PKCS7, got 12"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        unknown_algorithm {
+            args: func_args![plaintext: "data",
+                             algorithm: "ROT13",
+                             key: "0123456789012345",
+                             iv: "1234567890123456"],
+            want: Err(r#"invalid enum variant""#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        key_file {
+            args: func_args![plaintext: "morethan1blockofdata",
+                             algorithm: "AES-256-GCM",
+                             key_file: AES_256_KEY_FILE,
+                             iv: "123456789012"],
+            want: Ok(value!(Bytes::from_static(b"\xbd\x05\xab\x1b\x60\xdc\xe0\xb7\x7c\x1f\x56\xdb\x7f\x57\x22\xe0\x59\x77\xad\x9a\xe8\x8e\xd5\xce\xa9\x7e\x26\x8d\x77\x6f\xf4\x92\x94\xb4\xfa\xda"))),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        missing_key_file {
+            args: func_args![plaintext: "data",
+                             algorithm: "AES-256-GCM",
+                             key_file: "/nonexistent/aes-256.key",
+                             iv: "123456789012"],
+            want: Err("invalid argument"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        key_and_key_file {
+            args: func_args![plaintext: "data",
+                             algorithm: "AES-256-GCM",
+                             key: "01234567890123456789012345678912",
+                             key_file: AES_256_KEY_FILE,
+                             iv: "123456789012"],
+            want: Err("invalid argument"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        no_key {
+            args: func_args![plaintext: "data",
+                             algorithm: "AES-256-GCM",
+                             iv: "123456789012"],
+            want: Err("invalid argument"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
@@ -1,5 +1,6 @@
+use crate::timestamp_util::{resolve_timezone, to_local};
 use chrono::format::{strftime::StrftimeItems, Item};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset};
 use vrl::prelude::*;
 
 #[derive(Clone, Copy, Debug)]
@@ -22,22 +23,39 @@ impl Function for FormatTimestamp {
                 kind: kind::BYTES,
                 required: true,
             },
+            Parameter {
+                keyword: "timezone",
+                kind: kind::BYTES,
+                required: false,
+            },
         ]
     }
 
     fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
         let value = arguments.required("value");
         let format = arguments.required("format");
+        let timezone = arguments.optional("timezone");
 
-        Ok(Box::new(FormatTimestampFn { value, format }))
+        Ok(Box::new(FormatTimestampFn {
+            value,
+            format,
+            timezone,
+        }))
     }
 
     fn examples(&self) -> &'static [Example] {
-        &[Example {
-            title: "format timestamp",
-            source: r#"format_timestamp!(t'2021-02-10T23:32:00+00:00', "%d %B %Y %H:%M")"#,
-            result: Ok("10 February 2021 23:32"),
-        }]
+        &[
+            Example {
+                title: "format timestamp",
+                source: r#"format_timestamp!(t'2021-02-10T23:32:00+00:00', "%d %B %Y %H:%M")"#,
+                result: Ok("10 February 2021 23:32"),
+            },
+            Example {
+                title: "format timestamp in timezone",
+                source: r#"format_timestamp!(t'2021-02-10T23:32:00+00:00', "%d %B %Y %H:%M %:z", timezone: "America/New_York")"#,
+                result: Ok("10 February 2021 18:32 -05:00"),
+            },
+        ]
     }
 }
 
@@ -45,6 +63,7 @@ impl Function for FormatTimestamp {
 struct FormatTimestampFn {
     value: Box<dyn Expression>,
     format: Box<dyn Expression>,
+    timezone: Option<Box<dyn Expression>>,
 }
 
 impl Expression for FormatTimestampFn {
@@ -52,8 +71,9 @@ impl Expression for FormatTimestampFn {
         let bytes = self.format.resolve(ctx)?.try_bytes()?;
         let format = String::from_utf8_lossy(&bytes);
         let ts = self.value.resolve(ctx)?.try_timestamp()?;
+        let timezone = resolve_timezone(&self.timezone, ctx)?;
 
-        try_format(&ts, &format).map(Into::into)
+        try_format(&to_local(ts, timezone), &format).map(Into::into)
     }
 
     fn type_def(&self, _: &state::Compiler) -> TypeDef {
@@ -61,7 +81,7 @@ impl Expression for FormatTimestampFn {
     }
 }
 
-fn try_format(dt: &DateTime<Utc>, format: &str) -> Result<String> {
+fn try_format(dt: &DateTime<FixedOffset>, format: &str) -> Result<String> {
     let items = StrftimeItems::new(format)
         .map(|item| match item {
             Item::Error => Err("invalid format".into()),
@@ -75,7 +95,7 @@ fn try_format(dt: &DateTime<Utc>, format: &str) -> Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::TimeZone;
+    use chrono::{TimeZone, Utc};
 
     test_function![
         format_timestamp => FormatTimestamp;
@@ -100,5 +120,29 @@ mod tests {
             want: Ok(value!("1970-01-01T00:00:10+00:00")),
             tdef: TypeDef::new().fallible().bytes(),
         }
+
+        timezone {
+            args: func_args![value: Utc.timestamp(10, 0),
+                             format: "%+",
+                             timezone: "Asia/Kolkata"],
+            want: Ok(value!("1970-01-01T05:30:10+05:30")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        timezone_dst {
+            args: func_args![value: Utc.ymd(2021, 7, 1).and_hms(12, 0, 0),
+                             format: "%H:%M %Z",
+                             timezone: "Europe/Paris"],
+            want: Ok(value!("14:00 +02:00")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_timezone {
+            args: func_args![value: Utc.timestamp(10, 0),
+                             format: "%+",
+                             timezone: "Mars/Olympus_Mons"],
+            want: Err("unable to parse timezone: Mars/Olympus_Mons"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
     ];
 }
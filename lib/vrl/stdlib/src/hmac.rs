@@ -0,0 +1,183 @@
+use ::sha1::Sha1;
+use crypto_hmac::{Mac, NewMac};
+use sha_2::{Sha224, Sha256, Sha384, Sha512};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Hmac;
+
+impl Function for Hmac {
+    fn identifier(&self) -> &'static str {
+        "hmac"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "key_file",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default algorithm",
+                source: r#"hmac("john.doe@example.com", key_file: "data/keys/hmac.key")"#,
+                result: Ok("219b6bb37e964bb8feb1519160c717db497906fbfa598e750af8ba89f9cfa11f"),
+            },
+            Example {
+                title: "custom algorithm",
+                source: r#"hmac("foo", key_file: "data/keys/hmac.key", algorithm: "SHA1")"#,
+                result: Ok("155ce81b88766ccd779d515af9ab6081586f077f"),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let algorithms = vec![
+            value!("SHA1"),
+            value!("SHA-224"),
+            value!("SHA-256"),
+            value!("SHA-384"),
+            value!("SHA-512"),
+        ];
+
+        let value = arguments.required("value");
+        let key = crate::util::key_argument("hmac", &mut arguments)?;
+        let algorithm = arguments
+            .optional_enum("algorithm", &algorithms)?
+            .unwrap_or_else(|| value!("SHA-256"))
+            .try_bytes()
+            .expect("algorithm not bytes");
+
+        Ok(Box::new(HmacFn {
+            value,
+            key,
+            algorithm,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HmacFn {
+    value: Box<dyn Expression>,
+    key: Box<dyn Expression>,
+    algorithm: Bytes,
+}
+
+impl Expression for HmacFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+        let key = self.key.resolve(ctx)?.try_bytes()?;
+
+        let hash = match self.algorithm.as_ref() {
+            b"SHA1" => encode::<crypto_hmac::Hmac<Sha1>>(&value, &key),
+            b"SHA-224" => encode::<crypto_hmac::Hmac<Sha224>>(&value, &key),
+            b"SHA-256" => encode::<crypto_hmac::Hmac<Sha256>>(&value, &key),
+            b"SHA-384" => encode::<crypto_hmac::Hmac<Sha384>>(&value, &key),
+            b"SHA-512" => encode::<crypto_hmac::Hmac<Sha512>>(&value, &key),
+            _ => unreachable!("enum invariant"),
+        };
+
+        Ok(hash.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().bytes()
+    }
+}
+
+#[inline]
+fn encode<T: Mac + NewMac>(value: &[u8], key: &[u8]) -> String {
+    let mut mac = T::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(value);
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/data/keys/hmac.key");
+    const NEWLINE_KEY_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../tests/data/keys/hmac-newline.key"
+    );
+
+    test_function![
+        hmac => Hmac;
+
+        hmac {
+            args: func_args![value: "foo", key: "my_secret"],
+            want: Ok("73acc630e44e86918911e47d0a5996fd4d194f19e847ccd0920060823b2c46aa"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        hmac_sha1 {
+            args: func_args![value: "foo", key: "my_secret", algorithm: "SHA1"],
+            want: Ok("9275a36e53d7d4755e99a57d003470bbb2d09951"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        hmac_sha224 {
+            args: func_args![value: "foo", key: "my_secret", algorithm: "SHA-224"],
+            want: Ok("cc86dec514eae9c947b5c747278878f57daf66228ad1688fd43d8786"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        hmac_sha256 {
+            args: func_args![value: "foo", key: "my_secret", algorithm: "SHA-256"],
+            want: Ok("73acc630e44e86918911e47d0a5996fd4d194f19e847ccd0920060823b2c46aa"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        hmac_sha384 {
+            args: func_args![value: "foo", key: "my_secret", algorithm: "SHA-384"],
+            want: Ok("f6bcc50cec3ac198f7741aa890e8e00f31c086177f3709b6412b29e6b4180ccc26d62699075010a0da05808d9b1fdd07"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        hmac_sha512 {
+            args: func_args![value: "foo", key: "my_secret", algorithm: "SHA-512"],
+            want: Ok("5db8dbf2abe69c051e3c474a123dfc379b0c2b019666f88ad02941bdf24485ba7aa9fd162b1467427f25ca691888126ee81c09bd9315b4b9a9014b1eabea31a8"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        key_file {
+            args: func_args![value: "foo", key_file: KEY_FILE],
+            want: Ok("aac2ca464b2eeaf06af042c950a144d52fabfe0dcc47eeb886a9831743c0b9f8"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        key_file_trailing_newline {
+            args: func_args![value: "foo", key_file: NEWLINE_KEY_FILE],
+            want: Ok("aac2ca464b2eeaf06af042c950a144d52fabfe0dcc47eeb886a9831743c0b9f8"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        no_key {
+            args: func_args![value: "foo"],
+            want: Err("invalid argument"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+    ];
+}
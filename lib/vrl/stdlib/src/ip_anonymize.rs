@@ -0,0 +1,380 @@
+use aes::{Aes128, Block, BlockEncrypt, NewBlockCipher};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use vrl::prelude::*;
+
+/// The length of the key used in `prefix_preserving` mode. The first half is
+/// the AES key, the second half is encrypted to derive the padding.
+const KEY_LEN: usize = 32;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpAnonymize;
+
+impl Function for IpAnonymize {
+    fn identifier(&self) -> &'static str {
+        "ip_anonymize"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "mode",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "ipv4_prefix",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "ipv6_prefix",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "truncate ipv4",
+                source: r#"ip_anonymize!("192.168.10.23")"#,
+                result: Ok("192.168.10.0"),
+            },
+            Example {
+                title: "truncate ipv6",
+                source: r#"ip_anonymize!("2001:db8:85a3:8d3:1319:8a2e:370:7348", ipv6_prefix: 32)"#,
+                result: Ok("2001:db8::"),
+            },
+            Example {
+                title: "prefix preserving",
+                source: r#"ip_anonymize!("192.168.10.23", mode: "prefix_preserving", key: "0123456789abcdef0123456789abcdef")"#,
+                result: Ok("177.88.10.28"),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let modes = vec![value!("truncate"), value!("prefix_preserving")];
+
+        let value = arguments.required("value");
+        let mode = arguments
+            .optional_enum("mode", &modes)?
+            .unwrap_or_else(|| value!("truncate"));
+        let ipv4_prefix = arguments.optional("ipv4_prefix");
+        let ipv6_prefix = arguments.optional("ipv6_prefix");
+        let key = arguments.optional("key");
+
+        let mode = match mode.clone().try_bytes().expect("mode not bytes").as_ref() {
+            b"truncate" => Mode::Truncate {
+                ipv4_prefix,
+                ipv6_prefix,
+            },
+            b"prefix_preserving" => Mode::PrefixPreserving {
+                key: key.ok_or(vrl::function::Error::InvalidArgument {
+                    keyword: "mode",
+                    value: mode,
+                    error: "prefix_preserving mode requires a key",
+                })?,
+            },
+            _ => unreachable!("enum invariant"),
+        };
+
+        Ok(Box::new(IpAnonymizeFn { value, mode }))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Mode {
+    Truncate {
+        ipv4_prefix: Option<Box<dyn Expression>>,
+        ipv6_prefix: Option<Box<dyn Expression>>,
+    },
+    PrefixPreserving {
+        key: Box<dyn Expression>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct IpAnonymizeFn {
+    value: Box<dyn Expression>,
+    mode: Mode,
+}
+
+impl Expression for IpAnonymizeFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        let anonymized: IpAddr = match &self.mode {
+            Mode::Truncate {
+                ipv4_prefix,
+                ipv6_prefix,
+            } => match value {
+                IpAddr::V4(addr) => {
+                    let prefix = prefix_len(ipv4_prefix, ctx, 24, 32, "ipv4_prefix")?;
+                    let bits = u32::from(addr) as u128;
+                    Ipv4Addr::from(truncate(bits, 32, prefix) as u32).into()
+                }
+                IpAddr::V6(addr) => {
+                    let prefix = prefix_len(ipv6_prefix, ctx, 48, 128, "ipv6_prefix")?;
+                    let bits = u128::from(addr);
+                    Ipv6Addr::from(truncate(bits, 128, prefix)).into()
+                }
+            },
+            Mode::PrefixPreserving { key } => {
+                let key = key.resolve(ctx)?.try_bytes()?;
+                let cryptopan = CryptoPan::new(&key)?;
+
+                match value {
+                    IpAddr::V4(addr) => {
+                        let bits = u32::from(addr) as u128;
+                        Ipv4Addr::from(cryptopan.anonymize(bits, 32) as u32).into()
+                    }
+                    IpAddr::V6(addr) => {
+                        Ipv6Addr::from(cryptopan.anonymize(u128::from(addr), 128)).into()
+                    }
+                }
+            }
+        };
+
+        Ok(anonymized.to_string().into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+fn prefix_len(
+    expr: &Option<Box<dyn Expression>>,
+    ctx: &mut Context,
+    default: u32,
+    max: u32,
+    name: &str,
+) -> std::result::Result<u32, ExpressionError> {
+    let prefix = match expr {
+        Some(expr) => expr.resolve(ctx)?.try_integer()?,
+        None => return Ok(default),
+    };
+
+    if !(0..=max as i64).contains(&prefix) {
+        return Err(format!("{} must be between 0 and {}, got {}", name, max, prefix).into());
+    }
+
+    Ok(prefix as u32)
+}
+
+/// Zeroes all but the first `prefix` bits of a `len` bit address.
+fn truncate(addr: u128, len: u32, prefix: u32) -> u128 {
+    if prefix == 0 {
+        return 0;
+    }
+
+    let mask = !0u128 >> (128 - len) << (len - prefix);
+    addr & mask
+}
+
+/// Prefix-preserving anonymization as described in [Crypto-PAn][paper]: two
+/// addresses sharing their first `n` bits are anonymized to two addresses that
+/// also share their first `n` bits, and nothing more.
+///
+/// IPv6 addresses are handled by extending the algorithm to the full 128 bits
+/// of the padding block.
+///
+/// [paper]: https://www.cc.gatech.edu/computing/Networking/projects/cryptopan/
+struct CryptoPan {
+    cipher: Aes128,
+    pad: u128,
+}
+
+impl CryptoPan {
+    fn new(key: &[u8]) -> std::result::Result<Self, String> {
+        if key.len() != KEY_LEN {
+            return Err(format!(
+                "key must be {} bytes for prefix_preserving mode, got {}",
+                KEY_LEN,
+                key.len()
+            ));
+        }
+
+        let cipher = Aes128::new(Block::from_slice(&key[..16]));
+
+        let mut pad = Block::clone_from_slice(&key[16..]);
+        cipher.encrypt_block(&mut pad);
+        let pad = u128::from_be_bytes(pad.into());
+
+        Ok(Self { cipher, pad })
+    }
+
+    /// Anonymizes the `len` bit address `addr`.
+    fn anonymize(&self, addr: u128, len: u32) -> u128 {
+        // Work on the address aligned to the most significant bits of the
+        // block, as the original algorithm does for IPv4.
+        let aligned = addr << (128 - len);
+        let mut otp = 0u128;
+
+        for pos in 0..len {
+            let prefix_mask = if pos == 0 { 0 } else { !0u128 << (128 - pos) };
+            let input = (aligned & prefix_mask) | (self.pad & !prefix_mask);
+
+            let mut block = Block::from(input.to_be_bytes());
+            self.cipher.encrypt_block(&mut block);
+
+            otp |= u128::from(block[0] >> 7) << (len - 1 - pos);
+        }
+
+        addr ^ otp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The reference key and addresses from the Crypto-PAn sample data.
+    const CRYPTOPAN_KEY: [u8; 32] = [
+        21, 34, 23, 141, 51, 164, 207, 128, 19, 10, 91, 22, 73, 144, 125, 16, 216, 152, 143, 131,
+        121, 121, 101, 39, 98, 87, 76, 45, 42, 132, 34, 2,
+    ];
+
+    test_function![
+        ip_anonymize => IpAnonymize;
+
+        ipv4_default {
+            args: func_args![value: "192.168.10.23"],
+            want: Ok("192.168.10.0"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        ipv4_prefix {
+            args: func_args![value: "192.168.10.23", ipv4_prefix: 12],
+            want: Ok("192.160.0.0"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        ipv4_prefix_zero {
+            args: func_args![value: "192.168.10.23", ipv4_prefix: 0],
+            want: Ok("0.0.0.0"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        ipv4_prefix_full {
+            args: func_args![value: "192.168.10.23", ipv4_prefix: 32],
+            want: Ok("192.168.10.23"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        ipv6_default {
+            args: func_args![value: "2001:db8:85a3:8d3:1319:8a2e:370:7348"],
+            want: Ok("2001:db8:85a3::"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        ipv6_prefix {
+            args: func_args![value: "2001:db8:85a3:8d3:1319:8a2e:370:7348", ipv6_prefix: 64],
+            want: Ok("2001:db8:85a3:8d3::"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_prefix {
+            args: func_args![value: "192.168.10.23", ipv4_prefix: 33],
+            want: Err("ipv4_prefix must be between 0 and 32, got 33"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_ip {
+            args: func_args![value: "foo"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        prefix_preserving {
+            args: func_args![value: "128.11.68.132",
+                             mode: "prefix_preserving",
+                             key: Bytes::copy_from_slice(&CRYPTOPAN_KEY)],
+            want: Ok("135.242.180.132"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        prefix_preserving_other {
+            args: func_args![value: "130.132.252.244",
+                             mode: "prefix_preserving",
+                             key: Bytes::copy_from_slice(&CRYPTOPAN_KEY)],
+            want: Ok("133.68.164.234"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        prefix_preserving_invalid_key {
+            args: func_args![value: "128.11.68.132",
+                             mode: "prefix_preserving",
+                             key: "too short"],
+            want: Err("key must be 32 bytes for prefix_preserving mode, got 9"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        prefix_preserving_missing_key {
+            args: func_args![value: "128.11.68.132", mode: "prefix_preserving"],
+            want: Err("invalid argument"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_mode {
+            args: func_args![value: "128.11.68.132", mode: "hash"],
+            want: Err(r#"invalid enum variant""#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+
+    #[test]
+    fn prefix_preserving_sample_data() {
+        let cryptopan = CryptoPan::new(&CRYPTOPAN_KEY).unwrap();
+
+        for (addr, want) in &[
+            ("128.11.68.132", "135.242.180.132"),
+            ("129.118.74.4", "134.136.186.123"),
+            ("130.132.252.244", "133.68.164.234"),
+            ("141.223.7.43", "141.167.8.160"),
+            ("141.233.145.108", "141.129.237.235"),
+            ("152.163.225.39", "151.140.114.167"),
+        ] {
+            let addr: Ipv4Addr = addr.parse().unwrap();
+            let got = Ipv4Addr::from(cryptopan.anonymize(u32::from(addr) as u128, 32) as u32);
+            assert_eq!(got.to_string(), *want);
+        }
+    }
+
+    #[test]
+    fn prefix_preserving_ipv6() {
+        let cryptopan = CryptoPan::new(&CRYPTOPAN_KEY).unwrap();
+        let anonymize =
+            |addr: &str| cryptopan.anonymize(u128::from(addr.parse::<Ipv6Addr>().unwrap()), 128);
+
+        let a = anonymize("2001:db8:85a3:8d3:1319:8a2e:370:7348");
+        let b = anonymize("2001:db8:85a3:8d3:ffff::1");
+        let c = anonymize("2001:db8:85a3:8d4::1");
+
+        // The first 64 bits are shared, the 65th differs.
+        assert_eq!(a >> 64, b >> 64);
+        assert_ne!(a >> 63, b >> 63);
+
+        // 0x8d3 and 0x8d4 differ from their 14th bit on, so only the first 61
+        // bits are shared.
+        assert_eq!(a >> 67, c >> 67);
+        assert_ne!(a >> 66, c >> 66);
+    }
+}
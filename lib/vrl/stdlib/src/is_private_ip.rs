@@ -0,0 +1,182 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IsPrivateIp;
+
+impl Function for IsPrivateIp {
+    fn identifier(&self) -> &'static str {
+        "is_private_ip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "private IPv4",
+                source: r#"is_private_ip!("192.168.0.1")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "public IPv4",
+                source: r#"is_private_ip!("8.8.8.8")"#,
+                result: Ok("false"),
+            },
+            Example {
+                title: "private IPv6",
+                source: r#"is_private_ip!("fd12:3456:789a:1::1")"#,
+                result: Ok("true"),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IsPrivateIpFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IsPrivateIpFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IsPrivateIpFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ip: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        let private = match ip {
+            IpAddr::V4(addr) => is_private_ipv4(addr),
+            IpAddr::V6(addr) => is_private_ipv6(addr),
+        };
+
+        Ok(private.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().boolean()
+    }
+}
+
+/// Private (RFC 1918), loopback and link-local addresses.
+fn is_private_ipv4(addr: Ipv4Addr) -> bool {
+    addr.is_private() || addr.is_loopback() || addr.is_link_local()
+}
+
+/// Unique local (RFC 4193), loopback and link-local addresses. IPv4-mapped
+/// addresses are classified by the IPv4 address they map.
+fn is_private_ipv6(addr: Ipv6Addr) -> bool {
+    if let [0, 0, 0, 0, 0, 0xffff, ..] = addr.segments() {
+        let [.., a, b, c, d] = addr.octets();
+        return is_private_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+
+    let first = addr.segments()[0];
+
+    addr.is_loopback()
+        // fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // fe80::/10
+        || (first & 0xffc0) == 0xfe80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        is_private_ip => IsPrivateIp;
+
+        ipv4_private {
+            args: func_args![value: "10.1.2.3"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_private_172 {
+            args: func_args![value: "172.31.255.255"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_public_172 {
+            args: func_args![value: "172.32.0.1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_loopback {
+            args: func_args![value: "127.0.0.1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_link_local {
+            args: func_args![value: "169.254.10.1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_public {
+            args: func_args![value: "8.8.8.8"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_unique_local {
+            args: func_args![value: "fd12:3456:789a:1::1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_link_local {
+            args: func_args![value: "fe80::1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_loopback {
+            args: func_args![value: "::1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_public {
+            args: func_args![value: "2001:4860:4860::8888"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_mapped_private {
+            args: func_args![value: "::ffff:192.168.0.1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_mapped_public {
+            args: func_args![value: "::ffff:8.8.8.8"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        invalid {
+            args: func_args![value: "i am not an ipaddress"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+    ];
+}
@@ -12,6 +12,10 @@ mod assert_eq;
 mod boolean;
 #[cfg(feature = "ceil")]
 mod ceil;
+#[cfg(any(feature = "decrypt", feature = "encrypt"))]
+mod cipher_util;
+#[cfg(feature = "community_id")]
+mod community_id;
 #[cfg(feature = "compact")]
 mod compact;
 #[cfg(feature = "contains")]
@@ -20,6 +24,8 @@ mod contains;
 mod decode_base64;
 #[cfg(feature = "decode_percent")]
 mod decode_percent;
+#[cfg(feature = "decrypt")]
+mod decrypt;
 #[cfg(feature = "del")]
 mod del;
 #[cfg(feature = "downcase")]
@@ -34,6 +40,8 @@ mod encode_key_value;
 mod encode_logfmt;
 #[cfg(feature = "encode_percent")]
 mod encode_percent;
+#[cfg(feature = "encrypt")]
+mod encrypt;
 #[cfg(feature = "ends_with")]
 mod ends_with;
 #[cfg(feature = "exists")]
@@ -54,10 +62,14 @@ mod format_timestamp;
 mod get_env_var;
 #[cfg(feature = "get_hostname")]
 mod get_hostname;
+#[cfg(feature = "hmac")]
+mod hmac;
 #[cfg(feature = "includes")]
 mod includes;
 #[cfg(feature = "integer")]
 mod integer;
+#[cfg(feature = "ip_anonymize")]
+mod ip_anonymize;
 #[cfg(feature = "ip_aton")]
 mod ip_aton;
 #[cfg(feature = "ip_cidr_contains")]
@@ -68,6 +80,8 @@ mod ip_ntoa;
 mod ip_subnet;
 #[cfg(feature = "ip_to_ipv6")]
 mod ip_to_ipv6;
+#[cfg(feature = "ip_version")]
+mod ip_version;
 #[cfg(feature = "ipv6_to_ipv4")]
 mod ipv6_to_ipv4;
 #[cfg(feature = "is_array")]
@@ -84,6 +98,8 @@ mod is_null;
 mod is_nullish;
 #[cfg(feature = "is_object")]
 mod is_object;
+#[cfg(feature = "is_private_ip")]
+mod is_private_ip;
 #[cfg(feature = "is_regex")]
 mod is_regex;
 #[cfg(feature = "is_string")]
@@ -128,6 +144,8 @@ mod parse_aws_alb_log;
 mod parse_aws_cloudwatch_log_subscription_message;
 #[cfg(feature = "parse_aws_vpc_flow_log")]
 mod parse_aws_vpc_flow_log;
+#[cfg(feature = "parse_cef")]
+mod parse_cef;
 #[cfg(feature = "parse_common_log")]
 mod parse_common_log;
 #[cfg(feature = "parse_csv")]
@@ -146,6 +164,8 @@ mod parse_json;
 mod parse_key_value;
 #[cfg(feature = "parse_klog")]
 mod parse_klog;
+#[cfg(feature = "parse_leef")]
+mod parse_leef;
 #[cfg(feature = "parse_linux_authorization")]
 mod parse_linux_authorization;
 #[cfg(feature = "parse_logfmt")]
@@ -174,6 +194,8 @@ mod parse_user_agent;
 mod parse_xml;
 #[cfg(feature = "push")]
 mod push;
+#[cfg(feature = "random_bytes")]
+mod random_bytes;
 #[cfg(feature = "redact")]
 mod redact;
 #[cfg(feature = "replace")]
@@ -202,6 +224,20 @@ mod strip_whitespace;
 mod tag_types_externally;
 #[cfg(feature = "timestamp")]
 mod timestamp;
+#[cfg(feature = "timestamp_add")]
+mod timestamp_add;
+#[cfg(feature = "timestamp_diff")]
+mod timestamp_diff;
+#[cfg(feature = "timestamp_part")]
+mod timestamp_part;
+#[cfg(any(
+    feature = "format_timestamp",
+    feature = "timestamp_add",
+    feature = "timestamp_diff",
+    feature = "timestamp_part",
+    feature = "truncate_timestamp"
+))]
+mod timestamp_util;
 #[cfg(feature = "to_bool")]
 mod to_bool;
 #[cfg(feature = "to_float")]
@@ -224,6 +260,8 @@ mod to_timestamp;
 mod to_unix_timestamp;
 #[cfg(feature = "truncate")]
 mod truncate;
+#[cfg(feature = "truncate_timestamp")]
+mod truncate_timestamp;
 #[cfg(feature = "unique")]
 mod unique;
 #[cfg(feature = "unnest")]
@@ -232,6 +270,8 @@ mod unnest;
 mod upcase;
 #[cfg(feature = "uuid_v4")]
 mod uuid_v4;
+#[cfg(feature = "validate_json_schema")]
+mod validate_json_schema;
 
 // -----------------------------------------------------------------------------
 
@@ -251,6 +291,8 @@ pub use assert_eq::AssertEq;
 pub use boolean::Boolean;
 #[cfg(feature = "ceil")]
 pub use ceil::Ceil;
+#[cfg(feature = "community_id")]
+pub use community_id::CommunityId;
 #[cfg(feature = "compact")]
 pub use compact::Compact;
 #[cfg(feature = "contains")]
@@ -259,6 +301,8 @@ pub use contains::Contains;
 pub use decode_base64::DecodeBase64;
 #[cfg(feature = "decode_percent")]
 pub use decode_percent::DecodePercent;
+#[cfg(feature = "decrypt")]
+pub use decrypt::Decrypt;
 #[cfg(feature = "del")]
 pub use del::Del;
 #[cfg(feature = "downcase")]
@@ -273,6 +317,8 @@ pub use encode_key_value::EncodeKeyValue;
 pub use encode_logfmt::EncodeLogfmt;
 #[cfg(feature = "encode_percent")]
 pub use encode_percent::EncodePercent;
+#[cfg(feature = "encrypt")]
+pub use encrypt::Encrypt;
 #[cfg(feature = "ends_with")]
 pub use ends_with::EndsWith;
 #[cfg(feature = "exists")]
@@ -293,10 +339,14 @@ pub use format_timestamp::FormatTimestamp;
 pub use get_env_var::GetEnvVar;
 #[cfg(feature = "get_hostname")]
 pub use get_hostname::GetHostname;
+#[cfg(feature = "hmac")]
+pub use hmac::Hmac;
 #[cfg(feature = "includes")]
 pub use includes::Includes;
 #[cfg(feature = "integer")]
 pub use integer::Integer;
+#[cfg(feature = "ip_anonymize")]
+pub use ip_anonymize::IpAnonymize;
 #[cfg(feature = "ip_aton")]
 pub use ip_aton::IpAton;
 #[cfg(feature = "ip_cidr_contains")]
@@ -307,6 +357,8 @@ pub use ip_ntoa::IpNtoa;
 pub use ip_subnet::IpSubnet;
 #[cfg(feature = "ip_to_ipv6")]
 pub use ip_to_ipv6::IpToIpv6;
+#[cfg(feature = "ip_version")]
+pub use ip_version::IpVersion;
 #[cfg(feature = "ipv6_to_ipv4")]
 pub use ipv6_to_ipv4::Ipv6ToIpV4;
 #[cfg(feature = "is_array")]
@@ -323,6 +375,8 @@ pub use is_null::IsNull;
 pub use is_nullish::IsNullish;
 #[cfg(feature = "is_object")]
 pub use is_object::IsObject;
+#[cfg(feature = "is_private_ip")]
+pub use is_private_ip::IsPrivateIp;
 #[cfg(feature = "is_regex")]
 pub use is_regex::IsRegex;
 #[cfg(feature = "is_string")]
@@ -357,6 +411,8 @@ pub use parse_aws_alb_log::ParseAwsAlbLog;
 pub use parse_aws_cloudwatch_log_subscription_message::ParseAwsCloudWatchLogSubscriptionMessage;
 #[cfg(feature = "parse_aws_vpc_flow_log")]
 pub use parse_aws_vpc_flow_log::ParseAwsVpcFlowLog;
+#[cfg(feature = "parse_cef")]
+pub use parse_cef::ParseCef;
 #[cfg(feature = "parse_common_log")]
 pub use parse_common_log::ParseCommonLog;
 #[cfg(feature = "parse_csv")]
@@ -375,6 +431,8 @@ pub use parse_json::ParseJson;
 pub use parse_key_value::ParseKeyValue;
 #[cfg(feature = "parse_klog")]
 pub use parse_klog::ParseKlog;
+#[cfg(feature = "parse_leef")]
+pub use parse_leef::ParseLeef;
 #[cfg(feature = "parse_linux_authorization")]
 pub use parse_linux_authorization::ParseLinuxAuthorization;
 #[cfg(feature = "parse_logfmt")]
@@ -405,6 +463,8 @@ pub use parse_xml::ParseXml;
 pub use push::Push;
 #[cfg(feature = "match")]
 pub use r#match::Match;
+#[cfg(feature = "random_bytes")]
+pub use random_bytes::RandomBytes;
 #[cfg(feature = "redact")]
 pub use redact::Redact;
 #[cfg(feature = "replace")]
@@ -431,6 +491,12 @@ pub use strip_whitespace::StripWhitespace;
 pub use tag_types_externally::TagTypesExternally;
 #[cfg(feature = "timestamp")]
 pub use timestamp::Timestamp;
+#[cfg(feature = "timestamp_add")]
+pub use timestamp_add::TimestampAdd;
+#[cfg(feature = "timestamp_diff")]
+pub use timestamp_diff::TimestampDiff;
+#[cfg(feature = "timestamp_part")]
+pub use timestamp_part::TimestampPart;
 #[cfg(feature = "to_bool")]
 pub use to_bool::ToBool;
 #[cfg(feature = "to_float")]
@@ -453,6 +519,8 @@ pub use to_timestamp::ToTimestamp;
 pub use to_unix_timestamp::ToUnixTimestamp;
 #[cfg(feature = "truncate")]
 pub use truncate::Truncate;
+#[cfg(feature = "truncate_timestamp")]
+pub use truncate_timestamp::TruncateTimestamp;
 #[cfg(feature = "unique")]
 pub use unique::Unique;
 #[cfg(feature = "unnest")]
@@ -461,6 +529,8 @@ pub use unnest::Unnest;
 pub use upcase::Upcase;
 #[cfg(feature = "uuid_v4")]
 pub use uuid_v4::UuidV4;
+#[cfg(feature = "validate_json_schema")]
+pub use validate_json_schema::ValidateJsonSchema;
 
 pub fn all() -> Vec<Box<dyn vrl::Function>> {
     vec![
@@ -476,6 +546,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(Boolean),
         #[cfg(feature = "ceil")]
         Box::new(Ceil),
+        #[cfg(feature = "community_id")]
+        Box::new(CommunityId),
         #[cfg(feature = "compact")]
         Box::new(Compact),
         #[cfg(feature = "contains")]
@@ -484,6 +556,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(DecodeBase64),
         #[cfg(feature = "decode_percent")]
         Box::new(DecodePercent),
+        #[cfg(feature = "decrypt")]
+        Box::new(Decrypt),
         #[cfg(feature = "del")]
         Box::new(Del),
         #[cfg(feature = "downcase")]
@@ -498,6 +572,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(EncodeLogfmt),
         #[cfg(feature = "encode_percent")]
         Box::new(EncodePercent),
+        #[cfg(feature = "encrypt")]
+        Box::new(Encrypt),
         #[cfg(feature = "ends_with")]
         Box::new(EndsWith),
         #[cfg(feature = "exists")]
@@ -518,10 +594,14 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(GetEnvVar),
         #[cfg(feature = "get_hostname")]
         Box::new(GetHostname),
+        #[cfg(feature = "hmac")]
+        Box::new(Hmac),
         #[cfg(feature = "includes")]
         Box::new(Includes),
         #[cfg(feature = "integer")]
         Box::new(Integer),
+        #[cfg(feature = "ip_anonymize")]
+        Box::new(IpAnonymize),
         #[cfg(feature = "ip_aton")]
         Box::new(IpAton),
         #[cfg(feature = "ip_cidr_contains")]
@@ -532,6 +612,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(IpSubnet),
         #[cfg(feature = "ip_to_ipv6")]
         Box::new(IpToIpv6),
+        #[cfg(feature = "ip_version")]
+        Box::new(IpVersion),
         #[cfg(feature = "ipv6_to_ipv4")]
         Box::new(Ipv6ToIpV4),
         #[cfg(feature = "is_array")]
@@ -548,6 +630,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(IsNullish),
         #[cfg(feature = "is_object")]
         Box::new(IsObject),
+        #[cfg(feature = "is_private_ip")]
+        Box::new(IsPrivateIp),
         #[cfg(feature = "is_regex")]
         Box::new(IsRegex),
         #[cfg(feature = "is_string")]
@@ -588,6 +672,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ParseAwsCloudWatchLogSubscriptionMessage),
         #[cfg(feature = "parse_aws_vpc_flow_log")]
         Box::new(ParseAwsVpcFlowLog),
+        #[cfg(feature = "parse_cef")]
+        Box::new(ParseCef),
         #[cfg(feature = "parse_common_log")]
         Box::new(ParseCommonLog),
         #[cfg(feature = "parse_csv")]
@@ -606,6 +692,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ParseKeyValue),
         #[cfg(feature = "parse_klog")]
         Box::new(ParseKlog),
+        #[cfg(feature = "parse_leef")]
+        Box::new(ParseLeef),
         #[cfg(feature = "parse_linux_authorization")]
         Box::new(ParseLinuxAuthorization),
         #[cfg(feature = "parse_logfmt")]
@@ -634,6 +722,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ParseXml),
         #[cfg(feature = "push")]
         Box::new(Push),
+        #[cfg(feature = "random_bytes")]
+        Box::new(RandomBytes),
         #[cfg(feature = "redact")]
         Box::new(Redact),
         #[cfg(feature = "replace")]
@@ -662,6 +752,12 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(TagTypesExternally),
         #[cfg(feature = "timestamp")]
         Box::new(Timestamp),
+        #[cfg(feature = "timestamp_add")]
+        Box::new(TimestampAdd),
+        #[cfg(feature = "timestamp_diff")]
+        Box::new(TimestampDiff),
+        #[cfg(feature = "timestamp_part")]
+        Box::new(TimestampPart),
         #[cfg(feature = "to_bool")]
         Box::new(ToBool),
         #[cfg(feature = "to_float")]
@@ -684,6 +780,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ToUnixTimestamp),
         #[cfg(feature = "truncate")]
         Box::new(Truncate),
+        #[cfg(feature = "truncate_timestamp")]
+        Box::new(TruncateTimestamp),
         #[cfg(feature = "unique")]
         Box::new(Unique),
         #[cfg(feature = "unnest")]
@@ -692,5 +790,7 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(Upcase),
         #[cfg(feature = "uuid_v4")]
         Box::new(UuidV4),
+        #[cfg(feature = "validate_json_schema")]
+        Box::new(ValidateJsonSchema),
     ]
 }
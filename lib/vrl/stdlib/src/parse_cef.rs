@@ -0,0 +1,456 @@
+use crate::util;
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+/// The names of the seven pipe-delimited CEF header fields, in order.
+const HEADER_FIELDS: [&str; 7] = [
+    "cefVersion",
+    "deviceVendor",
+    "deviceProduct",
+    "deviceVersion",
+    "deviceEventClassId",
+    "name",
+    "severity",
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseCef;
+
+impl Function for ParseCef {
+    fn identifier(&self) -> &'static str {
+        "parse_cef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "translate_custom_fields",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "parse CEF",
+                source: r#"parse_cef!(s'CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232')"#,
+                result: Ok(indoc! {r#"{
+                    "cefVersion": "0",
+                    "deviceVendor": "Security",
+                    "deviceProduct": "threatmanager",
+                    "deviceVersion": "1.0",
+                    "deviceEventClassId": "100",
+                    "name": "worm successfully stopped",
+                    "severity": "10",
+                    "src": "10.0.0.1",
+                    "dst": "2.1.2.2",
+                    "spt": "1232"
+                }"#}),
+            },
+            Example {
+                title: "parse CEF wrapped in syslog",
+                source: r#"parse_cef!(s'Sep 29 08:26:10 host CEF:1|Security|threatmanager|1.0|100|detected a \| in message|10|act=blocked a \= dst=1.1.1.1')"#,
+                result: Ok(indoc! {r#"{
+                    "cefVersion": "1",
+                    "deviceVendor": "Security",
+                    "deviceProduct": "threatmanager",
+                    "deviceVersion": "1.0",
+                    "deviceEventClassId": "100",
+                    "name": "detected a | in message",
+                    "severity": "10",
+                    "act": "blocked a =",
+                    "dst": "1.1.1.1"
+                }"#}),
+            },
+            Example {
+                title: "translate custom fields",
+                source: r#"parse_cef!(s'CEF:0|Dev|firewall|2.2|1|Connection denied|5|c6a1=2345:0425:2CA1:0000:0000:0567:5673:23b5 c6a1Label=Device IPv6 Address', translate_custom_fields: true)"#,
+                result: Ok(indoc! {r#"{
+                    "cefVersion": "0",
+                    "deviceVendor": "Dev",
+                    "deviceProduct": "firewall",
+                    "deviceVersion": "2.2",
+                    "deviceEventClassId": "1",
+                    "name": "Connection denied",
+                    "severity": "5",
+                    "Device IPv6 Address": "2345:0425:2CA1:0000:0000:0567:5673:23b5"
+                }"#}),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+        let translate_custom_fields = arguments
+            .optional("translate_custom_fields")
+            .unwrap_or_else(|| expr!(false));
+
+        Ok(Box::new(ParseCefFn {
+            value,
+            translate_custom_fields,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseCefFn {
+    value: Box<dyn Expression>,
+    translate_custom_fields: Box<dyn Expression>,
+}
+
+impl Expression for ParseCefFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let message = value.try_bytes_utf8_lossy()?;
+        let translate_custom_fields = self.translate_custom_fields.resolve(ctx)?.try_boolean()?;
+
+        let mut result = parse(&message)?;
+
+        if translate_custom_fields {
+            translate_custom_fields_in(&mut result);
+        }
+
+        Ok(result.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().object::<(), Kind>(map! {
+            (): Kind::Bytes,
+        })
+    }
+}
+
+/// Parse a CEF message into its header fields and extension key/value pairs.
+///
+/// Anything preceding the `CEF:` marker, such as a syslog header, is ignored.
+fn parse(input: &str) -> std::result::Result<BTreeMap<String, Value>, String> {
+    let start = input
+        .find("CEF:")
+        .ok_or_else(|| "unable to find CEF header in message".to_owned())?;
+    let input = &input[start + "CEF:".len()..];
+
+    let (header, extension) = util::split_header(input, HEADER_FIELDS.len());
+    if header.len() != HEADER_FIELDS.len() {
+        return Err(format!(
+            "CEF header must contain {} fields, found {}",
+            HEADER_FIELDS.len(),
+            header.len()
+        ));
+    }
+
+    let mut result = HEADER_FIELDS
+        .iter()
+        .zip(header)
+        .map(|(key, value)| ((*key).to_owned(), value.trim().to_owned().into()))
+        .collect::<BTreeMap<String, Value>>();
+
+    for (key, value) in parse_extension(extension.unwrap_or_default())? {
+        result.insert(key, value.into());
+    }
+
+    Ok(result)
+}
+
+/// Parse the space-delimited `key=value` extension of a CEF message.
+///
+/// Values can contain spaces, so a value only ends where the next key starts.
+/// An `=` that is not escaped marks the end of a key, unless the word in front
+/// of it isn't a valid key, in which case it's treated as part of the value.
+fn parse_extension(input: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    // (key start, key end) for every key found in the extension. The key end is
+    // the position of the `=`.
+    let mut keys: Vec<(usize, usize)> = Vec::new();
+    let mut escaped = false;
+
+    for (pos, c) in input.char_indices() {
+        match c {
+            '\\' if !escaped => {
+                escaped = true;
+                continue;
+            }
+            '=' if !escaped => {
+                let key_start = input[..pos]
+                    .rfind(|c: char| c.is_ascii_whitespace())
+                    .map(|ws| ws + 1)
+                    .unwrap_or(0);
+
+                let after_previous_key = keys.last().map_or(true, |(_, end)| key_start > *end);
+                let key = &input[key_start..pos];
+
+                if after_previous_key && is_valid_key(key) {
+                    keys.push((key_start, pos));
+                }
+            }
+            _ => {}
+        }
+
+        escaped = false;
+    }
+
+    match keys.first() {
+        Some((start, _)) if !input[..*start].trim().is_empty() => {
+            return Err(format!(
+                "CEF extension value without a key: `{}`",
+                input[..*start].trim()
+            ))
+        }
+        None if !input.trim().is_empty() => {
+            return Err(format!("CEF extension has no key/value pairs: `{}`", input))
+        }
+        _ => {}
+    }
+
+    Ok(keys
+        .iter()
+        .enumerate()
+        .map(|(i, (start, end))| {
+            let value_end = keys.get(i + 1).map_or(input.len(), |(next, _)| *next);
+            let key = input[*start..*end].to_owned();
+            let value = unescape_value(input[end + 1..value_end].trim_end());
+
+            (key, value)
+        })
+        .collect())
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '[' | ']'))
+}
+
+/// Unescape `\=`, `\\`, `\n` and `\r` in an extension value. Any other escape
+/// sequence is kept as-is.
+fn unescape_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('=') => result.push('='),
+            Some('\\') => result.push('\\'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+/// Replace custom extension fields, such as `cs1`, `cn2` or `flexString1`, by
+/// their associated label, as set in the matching `<key>Label` field.
+fn translate_custom_fields_in(result: &mut BTreeMap<String, Value>) {
+    let labels = result
+        .keys()
+        .filter_map(|key| {
+            let field = key.strip_suffix("Label")?;
+            result
+                .contains_key(field)
+                .then(|| (key.to_owned(), field.to_owned()))
+        })
+        .collect::<Vec<_>>();
+
+    for (label_key, field) in labels {
+        let label = result.remove(&label_key).expect("label exists");
+        let value = result.remove(&field).expect("field exists");
+
+        match label.as_bytes() {
+            Some(label) => {
+                result.insert(String::from_utf8_lossy(label).into_owned(), value);
+            }
+            None => {
+                result.insert(label_key, label);
+                result.insert(field, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_header_escapes() {
+        let (header, rest) = util::split_header(r#"0|a\|b|c\\|d\e|rest"#, 4);
+
+        assert_eq!(header, vec!["0", "a|b", r#"c\"#, r#"d\e"#]);
+        assert_eq!(rest, Some("rest"));
+    }
+
+    #[test]
+    fn split_header_too_short() {
+        let (header, rest) = util::split_header("0|a|b", 4);
+
+        assert_eq!(header, vec!["0", "a", "b"]);
+        assert_eq!(rest, None);
+    }
+
+    #[test]
+    fn extension_with_equals_in_value() {
+        assert_eq!(
+            parse_extension(r#"request=http://example.com/?a=b&c\=d act=allowed"#).unwrap(),
+            vec![
+                (
+                    "request".to_owned(),
+                    "http://example.com/?a=b&c=d".to_owned()
+                ),
+                ("act".to_owned(), "allowed".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn extension_without_key() {
+        assert!(parse_extension("dangling value").is_err());
+        assert!(parse_extension("dangling src=1.2.3.4").is_err());
+    }
+
+    test_function![
+        parse_cef => ParseCef;
+
+        header_only {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|"],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "Security",
+                "deviceProduct": "threatmanager",
+                "deviceVersion": "1.0",
+                "deviceEventClassId": "100",
+                "name": "worm successfully stopped",
+                "severity": "10",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        extension {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232"],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "Security",
+                "deviceProduct": "threatmanager",
+                "deviceVersion": "1.0",
+                "deviceEventClassId": "100",
+                "name": "worm successfully stopped",
+                "severity": "10",
+                "src": "10.0.0.1",
+                "dst": "2.1.2.2",
+                "spt": "1232",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        syslog_wrapped {
+            args: func_args![value: "<134>Sep 29 08:26:10 fw01 CEF:0|Palo Alto Networks|PAN-OS|8.0.0|general|SYSTEM|3|rt=Sep 29 2021 08:26:10 GMT msg=Failed authentication for user admin"],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "Palo Alto Networks",
+                "deviceProduct": "PAN-OS",
+                "deviceVersion": "8.0.0",
+                "deviceEventClassId": "general",
+                "name": "SYSTEM",
+                "severity": "3",
+                "rt": "Sep 29 2021 08:26:10 GMT",
+                "msg": "Failed authentication for user admin",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        escapes {
+            args: func_args![value: r#"CEF:0|security|threatmanager|1.0|100|detected a \| in message|10|src=10.0.0.1 act=blocked a \= msg=line\nbreak path=C:\\Windows"#],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "security",
+                "deviceProduct": "threatmanager",
+                "deviceVersion": "1.0",
+                "deviceEventClassId": "100",
+                "name": "detected a | in message",
+                "severity": "10",
+                "src": "10.0.0.1",
+                "act": "blocked a =",
+                "msg": "line\nbreak",
+                "path": r#"C:\Windows"#,
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        pipe_in_extension {
+            args: func_args![value: "CEF:0|security|threatmanager|1.0|100|name|10|msg=a|b"],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "security",
+                "deviceProduct": "threatmanager",
+                "deviceVersion": "1.0",
+                "deviceEventClassId": "100",
+                "name": "name",
+                "severity": "10",
+                "msg": "a|b",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        custom_fields {
+            args: func_args![value: "CEF:0|Dev|firewall|2.2|1|Connection denied|5|cs1=web cs1Label=Policy cn1=42 cn1Label=Rule ID cs2=orphan",
+                             translate_custom_fields: true],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "Dev",
+                "deviceProduct": "firewall",
+                "deviceVersion": "2.2",
+                "deviceEventClassId": "1",
+                "name": "Connection denied",
+                "severity": "5",
+                "Policy": "web",
+                "Rule ID": "42",
+                "cs2": "orphan",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        custom_fields_untranslated {
+            args: func_args![value: "CEF:0|Dev|firewall|2.2|1|Connection denied|5|cs1=web cs1Label=Policy"],
+            want: Ok(value!({
+                "cefVersion": "0",
+                "deviceVendor": "Dev",
+                "deviceProduct": "firewall",
+                "deviceVersion": "2.2",
+                "deviceEventClassId": "1",
+                "name": "Connection denied",
+                "severity": "5",
+                "cs1": "web",
+                "cs1Label": "Policy",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        missing_header {
+            args: func_args![value: "<134>Sep 29 08:26:10 fw01 not a cef message"],
+            want: Err("unable to find CEF header in message"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        incomplete_header {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0"],
+            want: Err("CEF header must contain 7 fields, found 4"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+    ];
+}
@@ -0,0 +1,473 @@
+use crate::util;
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+/// The names of the pipe-delimited LEEF header fields that follow the version,
+/// in order.
+const HEADER_FIELDS: [&str; 4] = ["vendor", "product", "productVersion", "eventId"];
+
+/// The attribute delimiter used when the message doesn't define one.
+const DEFAULT_DELIMITER: char = '\t';
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseLeef;
+
+impl Function for ParseLeef {
+    fn identifier(&self) -> &'static str {
+        "parse_leef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "delimiter",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "parse LEEF 1.0",
+                source: r#"parse_leef!("LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tusrName=joe.black")"#,
+                result: Ok(indoc! {r#"{
+                    "leefVersion": "1.0",
+                    "vendor": "Microsoft",
+                    "product": "MSExchange",
+                    "productVersion": "4.0 SP1",
+                    "eventId": "15345",
+                    "src": "192.0.2.0",
+                    "dst": "172.50.123.1",
+                    "sev": "5",
+                    "usrName": "joe.black"
+                }"#}),
+            },
+            Example {
+                title: "parse LEEF 2.0 with a custom delimiter",
+                source: r#"parse_leef!(s'LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^sev=5')"#,
+                result: Ok(indoc! {r#"{
+                    "leefVersion": "2.0",
+                    "vendor": "Lancope",
+                    "product": "StealthWatch",
+                    "productVersion": "1.0",
+                    "eventId": "41",
+                    "src": "10.0.1.8",
+                    "dst": "10.0.0.5",
+                    "sev": "5"
+                }"#}),
+            },
+            Example {
+                title: "parse LEEF 1.0 with a space delimiter",
+                source: r#"parse_leef!(s'<13>Sep 29 08:26:10 host LEEF:1.0|Vendor|Product|1.0|login|usrName=admin src=10.0.0.1', delimiter: " ")"#,
+                result: Ok(indoc! {r#"{
+                    "leefVersion": "1.0",
+                    "vendor": "Vendor",
+                    "product": "Product",
+                    "productVersion": "1.0",
+                    "eventId": "login",
+                    "usrName": "admin",
+                    "src": "10.0.0.1"
+                }"#}),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+        let delimiter = arguments.optional("delimiter");
+
+        Ok(Box::new(ParseLeefFn { value, delimiter }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseLeefFn {
+    value: Box<dyn Expression>,
+    delimiter: Option<Box<dyn Expression>>,
+}
+
+impl Expression for ParseLeefFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let message = value.try_bytes_utf8_lossy()?;
+
+        let delimiter = match &self.delimiter {
+            Some(expr) => {
+                let delimiter = expr.resolve(ctx)?;
+                let delimiter = delimiter.try_bytes_utf8_lossy()?;
+                let mut chars = delimiter.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err("delimiter must be a single character".into()),
+                }
+            }
+            None => DEFAULT_DELIMITER,
+        };
+
+        Ok(parse(&message, delimiter)?.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().object::<(), Kind>(map! {
+            (): Kind::Bytes,
+        })
+    }
+}
+
+/// Parse a LEEF 1.0 or 2.0 message into its header fields and attributes.
+///
+/// Anything preceding the `LEEF:` marker, such as a syslog header, is ignored.
+/// The `default_delimiter` separates attributes, unless the message is LEEF 2.0
+/// and defines its own delimiter in the header.
+fn parse(
+    input: &str,
+    default_delimiter: char,
+) -> std::result::Result<BTreeMap<String, Value>, String> {
+    let start = input
+        .find("LEEF:")
+        .ok_or_else(|| "unable to find LEEF header in message".to_owned())?;
+    let input = input[start + "LEEF:".len()..].trim_end_matches(&['\r', '\n'][..]);
+
+    let (header, attributes) = util::split_header(input, HEADER_FIELDS.len() + 1);
+    let attributes = match attributes {
+        Some(attributes) => attributes,
+        None => {
+            return Err(format!(
+                "LEEF header must contain {} fields, found {}",
+                HEADER_FIELDS.len() + 1,
+                header.len()
+            ))
+        }
+    };
+
+    let mut header = header.into_iter();
+    let version = header.next().expect("version field").trim().to_owned();
+
+    let (delimiter, attributes) = match version.as_str() {
+        "1.0" | "1" => (default_delimiter, attributes),
+        "2.0" | "2" => match attributes.split_once('|') {
+            Some((field, rest)) => match parse_delimiter(field, default_delimiter) {
+                Some(delimiter) => (delimiter, rest),
+                None => (default_delimiter, attributes),
+            },
+            None => (default_delimiter, attributes),
+        },
+        version => return Err(format!("unsupported LEEF version: `{}`", version)),
+    };
+
+    let mut result = HEADER_FIELDS
+        .iter()
+        .zip(header)
+        .map(|(key, value)| ((*key).to_owned(), value.trim().to_owned().into()))
+        .collect::<BTreeMap<String, Value>>();
+    result.insert("leefVersion".to_owned(), version.into());
+
+    for (key, value) in parse_attributes(attributes, delimiter)? {
+        result.insert(key, value.into());
+    }
+
+    Ok(result)
+}
+
+/// Parse the LEEF 2.0 delimiter header field.
+///
+/// The delimiter is either a single character, or its hex code prefixed by `x`
+/// or `0x`. An empty field means the default delimiter is used. Returns `None`
+/// if the field is neither, in which case it isn't a delimiter field, but part
+/// of the attributes of a message that omitted the delimiter.
+fn parse_delimiter(field: &str, default: char) -> Option<char> {
+    let mut chars = field.chars();
+
+    match (chars.next(), chars.next()) {
+        (None, _) => return Some(default),
+        (Some(c), None) => return Some(c),
+        _ => {}
+    }
+
+    let hex = field
+        .strip_prefix("0x")
+        .or_else(|| field.strip_prefix("0X"))
+        .or_else(|| field.strip_prefix('x'))
+        .or_else(|| field.strip_prefix('X'))
+        .filter(|hex| !hex.is_empty() && hex.len() <= 4)?;
+
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .and_then(std::char::from_u32)
+}
+
+/// Parse the `key=value` attributes of a LEEF message, separated by
+/// `delimiter`.
+///
+/// A segment without an unescaped `=` is assumed to be part of the previous
+/// value, which happens when a value contains the delimiter.
+fn parse_attributes(
+    input: &str,
+    delimiter: char,
+) -> std::result::Result<Vec<(String, String)>, String> {
+    let mut attributes: Vec<(String, String)> = Vec::new();
+
+    for segment in input.split(delimiter) {
+        match find_unescaped_equals(segment) {
+            Some(pos) => {
+                let key = segment[..pos].trim();
+                if key.is_empty() {
+                    return Err(format!("LEEF attribute without a key: `{}`", segment));
+                }
+
+                attributes.push((key.to_owned(), unescape_value(&segment[pos + 1..])));
+            }
+            None => match attributes.last_mut() {
+                Some((_, value)) => {
+                    value.push(delimiter);
+                    value.push_str(&unescape_value(segment));
+                }
+                None if segment.trim().is_empty() => {}
+                None => return Err(format!("LEEF attribute without a key: `{}`", segment)),
+            },
+        }
+    }
+
+    // A trailing delimiter doesn't belong to the last value.
+    if let Some((_, value)) = attributes.last_mut() {
+        let trimmed = value.trim_end_matches(delimiter).len();
+        value.truncate(trimmed);
+    }
+
+    Ok(attributes)
+}
+
+fn find_unescaped_equals(segment: &str) -> Option<usize> {
+    let mut escaped = false;
+
+    for (pos, c) in segment.char_indices() {
+        match c {
+            '\\' if !escaped => {
+                escaped = true;
+                continue;
+            }
+            '=' if !escaped => return Some(pos),
+            _ => {}
+        }
+
+        escaped = false;
+    }
+
+    None
+}
+
+/// Unescape `\=` and `\\` in an attribute value. Any other escape sequence is
+/// kept as-is.
+fn unescape_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(escaped @ '=') | Some(escaped @ '\\') => result.push(escaped),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delimiter_formats() {
+        assert_eq!(parse_delimiter("", '\t'), Some('\t'));
+        assert_eq!(parse_delimiter("^", '\t'), Some('^'));
+        assert_eq!(parse_delimiter("x5E", '\t'), Some('^'));
+        assert_eq!(parse_delimiter("0x5e", '\t'), Some('^'));
+        assert_eq!(parse_delimiter("0x09", '\t'), Some('\t'));
+        assert_eq!(parse_delimiter("0xZZ", '\t'), None);
+        assert_eq!(parse_delimiter("src=10.0.0.1", '\t'), None);
+    }
+
+    #[test]
+    fn attributes_containing_delimiter() {
+        assert_eq!(
+            parse_attributes("devTime=Sep 29 2021 usrName=admin", ' ').unwrap(),
+            vec![
+                ("devTime".to_owned(), "Sep 29 2021".to_owned()),
+                ("usrName".to_owned(), "admin".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_without_key() {
+        assert!(parse_attributes("dangling\tsrc=10.0.0.1", '\t').is_err());
+        assert!(parse_attributes("=value", '\t').is_err());
+    }
+
+    test_function![
+        parse_leef => ParseLeef;
+
+        leef_1 {
+            args: func_args![value: "LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tcat=anomaly\tsrcPort=81\tdstPort=21\tusrName=joe.black"],
+            want: Ok(value!({
+                "leefVersion": "1.0",
+                "vendor": "Microsoft",
+                "product": "MSExchange",
+                "productVersion": "4.0 SP1",
+                "eventId": "15345",
+                "src": "192.0.2.0",
+                "dst": "172.50.123.1",
+                "sev": "5",
+                "cat": "anomaly",
+                "srcPort": "81",
+                "dstPort": "21",
+                "usrName": "joe.black",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        leef_1_syslog_wrapped {
+            args: func_args![value: "<13>Sep 29 08:26:10 qradar LEEF:1.0|IBM|QRadar|7.4|login|usrName=admin\tsrc=10.0.0.1\n"],
+            want: Ok(value!({
+                "leefVersion": "1.0",
+                "vendor": "IBM",
+                "product": "QRadar",
+                "productVersion": "7.4",
+                "eventId": "login",
+                "usrName": "admin",
+                "src": "10.0.0.1",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        leef_2_character_delimiter {
+            args: func_args![value: "LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^sev=5^srcPort=81^dstPort=21"],
+            want: Ok(value!({
+                "leefVersion": "2.0",
+                "vendor": "Lancope",
+                "product": "StealthWatch",
+                "productVersion": "1.0",
+                "eventId": "41",
+                "src": "10.0.1.8",
+                "dst": "10.0.0.5",
+                "sev": "5",
+                "srcPort": "81",
+                "dstPort": "21",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        leef_2_hex_delimiter {
+            args: func_args![value: "LEEF:2.0|Vendor|Product|1.0|41|x7C|src=10.0.1.8|dst=10.0.0.5"],
+            want: Ok(value!({
+                "leefVersion": "2.0",
+                "vendor": "Vendor",
+                "product": "Product",
+                "productVersion": "1.0",
+                "eventId": "41",
+                "src": "10.0.1.8",
+                "dst": "10.0.0.5",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        leef_2_empty_delimiter {
+            args: func_args![value: "LEEF:2.0|Vendor|Product|1.0|41||src=10.0.1.8\tdst=10.0.0.5"],
+            want: Ok(value!({
+                "leefVersion": "2.0",
+                "vendor": "Vendor",
+                "product": "Product",
+                "productVersion": "1.0",
+                "eventId": "41",
+                "src": "10.0.1.8",
+                "dst": "10.0.0.5",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        leef_2_without_delimiter {
+            args: func_args![value: "LEEF:2.0|Vendor|Product|1.0|41|src=10.0.1.8\tmsg=a|b"],
+            want: Ok(value!({
+                "leefVersion": "2.0",
+                "vendor": "Vendor",
+                "product": "Product",
+                "productVersion": "1.0",
+                "eventId": "41",
+                "src": "10.0.1.8",
+                "msg": "a|b",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        escaped_values {
+            args: func_args![value: "LEEF:1.0|Vendor|Pro\\|duct|1.0|41|query=a\\=b\tpath=C:\\\\Temp"],
+            want: Ok(value!({
+                "leefVersion": "1.0",
+                "vendor": "Vendor",
+                "product": "Pro|duct",
+                "productVersion": "1.0",
+                "eventId": "41",
+                "query": "a=b",
+                "path": r#"C:\Temp"#,
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        custom_delimiter_argument {
+            args: func_args![value: "LEEF:1.0|Vendor|Product|1.0|41|src=10.0.1.8;dst=10.0.0.5",
+                             delimiter: ";"],
+            want: Ok(value!({
+                "leefVersion": "1.0",
+                "vendor": "Vendor",
+                "product": "Product",
+                "productVersion": "1.0",
+                "eventId": "41",
+                "src": "10.0.1.8",
+                "dst": "10.0.0.5",
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        invalid_delimiter_argument {
+            args: func_args![value: "LEEF:1.0|Vendor|Product|1.0|41|src=10.0.1.8",
+                             delimiter: ";;"],
+            want: Err("delimiter must be a single character"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        missing_header {
+            args: func_args![value: "not a leef message"],
+            want: Err("unable to find LEEF header in message"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        incomplete_header {
+            args: func_args![value: "LEEF:1.0|Vendor|Product"],
+            want: Err("LEEF header must contain 5 fields, found 3"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+
+        unsupported_version {
+            args: func_args![value: "LEEF:3.0|Vendor|Product|1.0|41|src=10.0.1.8"],
+            want: Err("unsupported LEEF version: `3.0`"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::Bytes }),
+        }
+    ];
+}
@@ -0,0 +1,104 @@
+use rand::RngCore;
+use vrl::prelude::*;
+
+/// The maximum number of bytes that can be generated in a single call.
+const MAX_LENGTH: i64 = 1024 * 64;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RandomBytes;
+
+impl Function for RandomBytes {
+    fn identifier(&self) -> &'static str {
+        "random_bytes"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "length",
+            kind: kind::INTEGER,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "generate random bytes",
+            source: r#"length(random_bytes!(16))"#,
+            result: Ok("16"),
+        }]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let length = arguments.required("length");
+
+        Ok(Box::new(RandomBytesFn { length }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RandomBytesFn {
+    length: Box<dyn Expression>,
+}
+
+impl Expression for RandomBytesFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let length = self.length.resolve(ctx)?.try_integer()?;
+
+        if !(0..=MAX_LENGTH).contains(&length) {
+            return Err(format!("length must be between 0 and {}", MAX_LENGTH).into());
+        }
+
+        let mut buf = vec![0; length as usize];
+        rand::thread_rng().fill_bytes(&mut buf);
+
+        Ok(buf.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::TimeZone;
+
+    fn resolve(length: i64) -> Resolved {
+        let mut state = vrl::state::Runtime::default();
+        let mut object: Value = map![].into();
+        let tz = TimeZone::default();
+        let mut ctx = Context::new(&mut object, &mut state, &tz);
+
+        RandomBytesFn {
+            length: expr!(length),
+        }
+        .resolve(&mut ctx)
+    }
+
+    test_type_def![default {
+        expr: |_| { RandomBytesFn { length: expr!(16) } },
+        want: TypeDef::new().fallible().bytes(),
+    }];
+
+    #[test]
+    fn random_bytes() {
+        let first = resolve(16).unwrap().try_bytes().unwrap();
+        let second = resolve(16).unwrap().try_bytes().unwrap();
+
+        assert_eq!(first.len(), 16);
+        assert_eq!(second.len(), 16);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn random_bytes_empty() {
+        assert_eq!(resolve(0).unwrap().try_bytes().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn random_bytes_invalid_length() {
+        assert!(resolve(-1).is_err());
+        assert!(resolve(MAX_LENGTH + 1).is_err());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::timestamp_util::{duration_unit_nanos, duration_units};
+use chrono::Duration;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct TimestampAdd;
+
+impl Function for TimestampAdd {
+    fn identifier(&self) -> &'static str {
+        "timestamp_add"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::TIMESTAMP,
+                required: true,
+            },
+            Parameter {
+                keyword: "duration",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "unit",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "add seconds",
+                source: r#"timestamp_add!(t'2021-02-10T23:32:00Z', 90)"#,
+                result: Ok("t'2021-02-10T23:33:30Z'"),
+            },
+            Example {
+                title: "subtract hours",
+                source: r#"timestamp_add!(t'2021-02-10T23:32:00Z', -1.5, unit: "h")"#,
+                result: Ok("t'2021-02-10T22:02:00Z'"),
+            },
+            Example {
+                title: "parsed duration",
+                source: r#"timestamp_add!(t'2021-02-10T23:32:00Z', parse_duration!("1005ms", unit: "ms"), unit: "ms")"#,
+                result: Ok("t'2021-02-10T23:32:01.005Z'"),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+        let duration = arguments.required("duration");
+        let unit = arguments
+            .optional_enum("unit", &duration_units())?
+            .unwrap_or_else(|| value!("s"))
+            .try_bytes()
+            .expect("unit not bytes");
+
+        Ok(Box::new(TimestampAddFn {
+            value,
+            duration,
+            unit_nanos: duration_unit_nanos(&unit),
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TimestampAddFn {
+    value: Box<dyn Expression>,
+    duration: Box<dyn Expression>,
+    unit_nanos: i64,
+}
+
+impl Expression for TimestampAddFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ts = self.value.resolve(ctx)?.try_timestamp()?;
+
+        let nanos = match self.duration.resolve(ctx)? {
+            Value::Integer(duration) => duration.checked_mul(self.unit_nanos),
+            Value::Float(duration) => {
+                let nanos = (duration.into_inner() * self.unit_nanos as f64).round();
+
+                // The upper bound is exclusive as `i64::MAX` isn't exactly
+                // representable, and rounds up when cast to a float.
+                if nanos >= i64::MIN as f64 && nanos < i64::MAX as f64 {
+                    Some(nanos as i64)
+                } else {
+                    None
+                }
+            }
+            value => {
+                return Err(value::Error::Expected {
+                    got: value.kind(),
+                    expected: Kind::Integer | Kind::Float,
+                }
+                .into())
+            }
+        }
+        .ok_or("duration is out of range")?;
+
+        ts.checked_add_signed(Duration::nanoseconds(nanos))
+            .map(Into::into)
+            .ok_or_else(|| "resulting timestamp is out of range".into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().timestamp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    test_function![
+        timestamp_add => TimestampAdd;
+
+        seconds {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             duration: 90],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms(23, 33, 30))),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        negative {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             duration: -1,
+                             unit: "d"],
+            want: Ok(value!(Utc.ymd(2021, 2, 9).and_hms(23, 32, 0))),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        float {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             duration: 1.5,
+                             unit: "h"],
+            want: Ok(value!(Utc.ymd(2021, 2, 11).and_hms(1, 2, 0))),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        milliseconds {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             duration: 1005,
+                             unit: "ms"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms_milli(23, 32, 1, 5))),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        nanoseconds {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             duration: 1,
+                             unit: "ns"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms_nano(23, 32, 0, 1))),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        duration_out_of_range {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             duration: i64::MAX,
+                             unit: "d"],
+            want: Err("duration is out of range"),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        float_out_of_range {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             duration: 1e300],
+            want: Err("duration is out of range"),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        invalid_unit {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             duration: 1,
+                             unit: "w"],
+            want: Err(r#"invalid enum variant""#),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+    ];
+}
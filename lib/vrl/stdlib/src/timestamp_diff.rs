@@ -0,0 +1,144 @@
+use crate::timestamp_util::{duration_unit_nanos, duration_units};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct TimestampDiff;
+
+impl Function for TimestampDiff {
+    fn identifier(&self) -> &'static str {
+        "timestamp_diff"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "start",
+                kind: kind::TIMESTAMP,
+                required: true,
+            },
+            Parameter {
+                keyword: "end",
+                kind: kind::TIMESTAMP,
+                required: true,
+            },
+            Parameter {
+                keyword: "unit",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "seconds",
+                source: r#"timestamp_diff(t'2021-02-10T23:32:00Z', t'2021-02-10T23:33:30.5Z')"#,
+                result: Ok("90.5"),
+            },
+            Example {
+                title: "milliseconds",
+                source: r#"timestamp_diff(t'2021-02-10T23:32:00Z', t'2021-02-10T23:31:00Z', unit: "ms")"#,
+                result: Ok("-60000.0"),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let start = arguments.required("start");
+        let end = arguments.required("end");
+        let unit = arguments
+            .optional_enum("unit", &duration_units())?
+            .unwrap_or_else(|| value!("s"))
+            .try_bytes()
+            .expect("unit not bytes");
+
+        Ok(Box::new(TimestampDiffFn {
+            start,
+            end,
+            unit_nanos: duration_unit_nanos(&unit),
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TimestampDiffFn {
+    start: Box<dyn Expression>,
+    end: Box<dyn Expression>,
+    unit_nanos: i64,
+}
+
+impl Expression for TimestampDiffFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let start = self.start.resolve(ctx)?.try_timestamp()?;
+        let end = self.end.resolve(ctx)?.try_timestamp()?;
+
+        // `chrono::Duration::num_nanoseconds` overflows for spans over roughly
+        // 292 years, so the difference is computed in whole seconds and the
+        // remaining nanoseconds.
+        let diff = end - start;
+        let seconds = diff.num_seconds();
+        let nanos = (diff - chrono::Duration::seconds(seconds))
+            .num_nanoseconds()
+            .expect("less than a second");
+
+        let nanos = i128::from(seconds) * 1_000_000_000 + i128::from(nanos);
+        let unit_nanos = i128::from(self.unit_nanos);
+        let diff = (nanos / unit_nanos) as f64 + (nanos % unit_nanos) as f64 / unit_nanos as f64;
+
+        Ok(diff.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().float()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    test_function![
+        timestamp_diff => TimestampDiff;
+
+        seconds {
+            args: func_args![start: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             end: Utc.ymd(2021, 2, 10).and_hms_milli(23, 33, 30, 500)],
+            want: Ok(90.5),
+            tdef: TypeDef::new().infallible().float(),
+        }
+
+        negative {
+            args: func_args![start: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             end: Utc.ymd(2021, 2, 10).and_hms(23, 31, 0),
+                             unit: "ms"],
+            want: Ok(-60000.0),
+            tdef: TypeDef::new().infallible().float(),
+        }
+
+        days {
+            args: func_args![start: Utc.ymd(2021, 2, 10).and_hms(0, 0, 0),
+                             end: Utc.ymd(2021, 2, 13).and_hms(12, 0, 0),
+                             unit: "d"],
+            want: Ok(3.5),
+            tdef: TypeDef::new().infallible().float(),
+        }
+
+        nanoseconds {
+            args: func_args![start: Utc.ymd(2021, 2, 10).and_hms(0, 0, 0),
+                             end: Utc.ymd(2021, 2, 10).and_hms_nano(0, 0, 1, 1),
+                             unit: "ns"],
+            want: Ok(1_000_000_001.0),
+            tdef: TypeDef::new().infallible().float(),
+        }
+
+        large_span {
+            args: func_args![start: Utc.ymd(1000, 1, 1).and_hms(0, 0, 0),
+                             end: Utc.ymd(2000, 1, 1).and_hms(0, 0, 0),
+                             unit: "d"],
+            want: Ok(365_242.0),
+            tdef: TypeDef::new().infallible().float(),
+        }
+    ];
+}
@@ -0,0 +1,266 @@
+use crate::timestamp_util::{resolve_timezone, to_local};
+use chrono::{Datelike, Timelike};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct TimestampPart;
+
+impl Function for TimestampPart {
+    fn identifier(&self) -> &'static str {
+        "timestamp_part"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::TIMESTAMP,
+                required: true,
+            },
+            Parameter {
+                keyword: "part",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "timezone",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "year",
+                source: r#"timestamp_part(t'2021-02-10T23:32:00Z', "year")"#,
+                result: Ok("2021"),
+            },
+            Example {
+                title: "ISO week",
+                source: r#"timestamp_part(t'2021-01-03T23:32:00Z', "week")"#,
+                result: Ok("53"),
+            },
+            Example {
+                title: "weekday in timezone",
+                source: r#"timestamp_part!(t'2021-02-10T23:32:00Z', "weekday", timezone: "Asia/Tokyo")"#,
+                result: Ok("4"),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let parts = vec![
+            value!("year"),
+            value!("iso_year"),
+            value!("quarter"),
+            value!("month"),
+            value!("week"),
+            value!("day"),
+            value!("day_of_year"),
+            value!("weekday"),
+            value!("hour"),
+            value!("minute"),
+            value!("second"),
+            value!("millisecond"),
+            value!("microsecond"),
+            value!("nanosecond"),
+        ];
+
+        let value = arguments.required("value");
+        let part = arguments
+            .required_enum("part", &parts)?
+            .try_bytes()
+            .expect("part not bytes");
+        let timezone = arguments.optional("timezone");
+
+        Ok(Box::new(TimestampPartFn {
+            value,
+            part,
+            timezone,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TimestampPartFn {
+    value: Box<dyn Expression>,
+    part: Bytes,
+    timezone: Option<Box<dyn Expression>>,
+}
+
+impl Expression for TimestampPartFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ts = self.value.resolve(ctx)?.try_timestamp()?;
+        let timezone = resolve_timezone(&self.timezone, ctx)?;
+        let ts = to_local(ts, timezone);
+
+        let part = match self.part.as_ref() {
+            b"year" => ts.year() as i64,
+            b"iso_year" => ts.iso_week().year() as i64,
+            b"quarter" => (ts.month0() / 3 + 1) as i64,
+            b"month" => ts.month() as i64,
+            b"week" => ts.iso_week().week() as i64,
+            b"day" => ts.day() as i64,
+            b"day_of_year" => ts.ordinal() as i64,
+            b"weekday" => ts.weekday().number_from_monday() as i64,
+            b"hour" => ts.hour() as i64,
+            b"minute" => ts.minute() as i64,
+            b"second" => ts.second() as i64,
+            // Leap seconds are represented as a nanosecond part over one
+            // second, they're folded into the last millisecond of the second.
+            b"millisecond" => (ts.nanosecond() / 1_000_000).min(999) as i64,
+            b"microsecond" => (ts.nanosecond() / 1_000).min(999_999) as i64,
+            b"nanosecond" => ts.nanosecond().min(999_999_999) as i64,
+            _ => unreachable!("enum invariant"),
+        };
+
+        Ok(part.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new()
+            .with_fallibility(self.timezone.is_some())
+            .integer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    test_function![
+        timestamp_part => TimestampPart;
+
+        year {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "year"],
+            want: Ok(2021),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        iso_year {
+            args: func_args![value: Utc.ymd(2021, 1, 3).and_hms(23, 32, 0),
+                             part: "iso_year"],
+            want: Ok(2020),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        quarter {
+            args: func_args![value: Utc.ymd(2021, 7, 1).and_hms(0, 0, 0),
+                             part: "quarter"],
+            want: Ok(3),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        month {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "month"],
+            want: Ok(2),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        week {
+            args: func_args![value: Utc.ymd(2021, 1, 3).and_hms(23, 32, 0),
+                             part: "week"],
+            want: Ok(53),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        day {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "day"],
+            want: Ok(10),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        day_of_year {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "day_of_year"],
+            want: Ok(41),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        weekday {
+            args: func_args![value: Utc.ymd(2021, 2, 14).and_hms(23, 32, 0),
+                             part: "weekday"],
+            want: Ok(7),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        hour {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "hour"],
+            want: Ok(23),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        minute {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "minute"],
+            want: Ok(32),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        second {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 15),
+                             part: "second"],
+            want: Ok(15),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        millisecond {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_nano(23, 32, 15, 123_456_789),
+                             part: "millisecond"],
+            want: Ok(123),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        microsecond {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_nano(23, 32, 15, 123_456_789),
+                             part: "microsecond"],
+            want: Ok(123_456),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        nanosecond {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_nano(23, 32, 15, 123_456_789),
+                             part: "nanosecond"],
+            want: Ok(123_456_789),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        timezone {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "day",
+                             timezone: "Asia/Tokyo"],
+            want: Ok(11),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        timezone_half_hour_offset {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "minute",
+                             timezone: "Asia/Kolkata"],
+            want: Ok(2),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        invalid_timezone {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "day",
+                             timezone: "Mars/Olympus_Mons"],
+            want: Err("unable to parse timezone: Mars/Olympus_Mons"),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        invalid_part {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 0),
+                             part: "fortnight"],
+            want: Err(r#"invalid enum variant""#),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+    ];
+}
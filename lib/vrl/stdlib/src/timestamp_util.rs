@@ -0,0 +1,112 @@
+#[cfg(any(
+    feature = "format_timestamp",
+    feature = "timestamp_part",
+    feature = "truncate_timestamp"
+))]
+use chrono::{DateTime, FixedOffset, Local, Offset, TimeZone as _, Utc};
+#[cfg(any(
+    feature = "format_timestamp",
+    feature = "timestamp_part",
+    feature = "truncate_timestamp"
+))]
+use shared::TimeZone;
+use vrl::prelude::*;
+
+/// The units accepted by the duration based timestamp functions, matching the
+/// ones understood by `parse_duration`, along with their length in
+/// nanoseconds.
+#[cfg(any(feature = "timestamp_add", feature = "timestamp_diff"))]
+const DURATION_UNITS: &[(&str, i64)] = &[
+    ("ns", 1),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ms", 1_000_000),
+    ("cs", 10_000_000),
+    ("ds", 100_000_000),
+    ("s", 1_000_000_000),
+    ("m", 60_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("d", 86_400_000_000_000),
+];
+
+#[cfg(any(feature = "timestamp_add", feature = "timestamp_diff"))]
+pub(crate) fn duration_units() -> Vec<Value> {
+    DURATION_UNITS
+        .iter()
+        .map(|(unit, _)| (*unit).into())
+        .collect()
+}
+
+/// Returns the length of the given duration unit in nanoseconds.
+///
+/// The unit is expected to be one of the `duration_units` variants, validated
+/// at compile time.
+#[cfg(any(feature = "timestamp_add", feature = "timestamp_diff"))]
+pub(crate) fn duration_unit_nanos(unit: &[u8]) -> i64 {
+    DURATION_UNITS
+        .iter()
+        .find(|(name, _)| name.as_bytes() == unit)
+        .map(|(_, nanos)| *nanos)
+        .expect("enum invariant")
+}
+
+/// Resolves the optional `timezone` argument of a function. `None` stands for
+/// UTC, the timezone timestamps are represented in.
+#[cfg(any(
+    feature = "format_timestamp",
+    feature = "timestamp_part",
+    feature = "truncate_timestamp"
+))]
+pub(crate) fn resolve_timezone(
+    timezone: &Option<Box<dyn Expression>>,
+    ctx: &mut Context,
+) -> std::result::Result<Option<TimeZone>, ExpressionError> {
+    let timezone = match timezone {
+        Some(timezone) => timezone.resolve(ctx)?,
+        None => return Ok(None),
+    };
+    let timezone = timezone.try_bytes_utf8_lossy()?;
+
+    TimeZone::parse(&timezone)
+        .map(Some)
+        .ok_or_else(|| format!("unable to parse timezone: {}", timezone).into())
+}
+
+/// Converts the timestamp to the local time of the given timezone.
+#[cfg(any(
+    feature = "format_timestamp",
+    feature = "timestamp_part",
+    feature = "truncate_timestamp"
+))]
+pub(crate) fn to_local(ts: DateTime<Utc>, timezone: Option<TimeZone>) -> DateTime<FixedOffset> {
+    let offset = match timezone {
+        None => Utc.fix(),
+        Some(TimeZone::Local) => Local.offset_from_utc_datetime(&ts.naive_utc()).fix(),
+        Some(TimeZone::Named(tz)) => tz.offset_from_utc_datetime(&ts.naive_utc()).fix(),
+    };
+
+    ts.with_timezone(&offset)
+}
+
+/// Converts a local time of the given timezone back to UTC.
+///
+/// Returns `None` if the local time doesn't exist in the timezone, for example
+/// because it falls in a daylight saving time gap. Ambiguous local times
+/// resolve to their earliest instant.
+#[cfg(feature = "truncate_timestamp")]
+pub(crate) fn from_local(
+    local: chrono::NaiveDateTime,
+    timezone: Option<TimeZone>,
+) -> Option<DateTime<Utc>> {
+    match timezone {
+        None => Some(Utc.from_utc_datetime(&local)),
+        Some(TimeZone::Local) => Local
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|ts| ts.with_timezone(&Utc)),
+        Some(TimeZone::Named(tz)) => tz
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|ts| ts.with_timezone(&Utc)),
+    }
+}
@@ -0,0 +1,263 @@
+use crate::timestamp_util::{from_local, resolve_timezone, to_local};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct TruncateTimestamp;
+
+impl Function for TruncateTimestamp {
+    fn identifier(&self) -> &'static str {
+        "truncate_timestamp"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::TIMESTAMP,
+                required: true,
+            },
+            Parameter {
+                keyword: "unit",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "timezone",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "hour",
+                source: r#"truncate_timestamp(t'2021-02-10T23:32:10.123Z', "hour")"#,
+                result: Ok("t'2021-02-10T23:00:00Z'"),
+            },
+            Example {
+                title: "week",
+                source: r#"truncate_timestamp(t'2021-02-10T23:32:10.123Z', "week")"#,
+                result: Ok("t'2021-02-08T00:00:00Z'"),
+            },
+            Example {
+                title: "day in timezone",
+                source: r#"truncate_timestamp!(t'2021-02-10T23:32:10.123Z', "day", timezone: "Asia/Tokyo")"#,
+                result: Ok("t'2021-02-10T15:00:00Z'"),
+            },
+        ]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let units = vec![
+            value!("year"),
+            value!("quarter"),
+            value!("month"),
+            value!("week"),
+            value!("day"),
+            value!("hour"),
+            value!("minute"),
+            value!("second"),
+            value!("millisecond"),
+            value!("microsecond"),
+        ];
+
+        let value = arguments.required("value");
+        let unit = arguments
+            .required_enum("unit", &units)?
+            .try_bytes()
+            .expect("unit not bytes");
+        let timezone = arguments.optional("timezone");
+
+        Ok(Box::new(TruncateTimestampFn {
+            value,
+            unit,
+            timezone,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TruncateTimestampFn {
+    value: Box<dyn Expression>,
+    unit: Bytes,
+    timezone: Option<Box<dyn Expression>>,
+}
+
+impl Expression for TruncateTimestampFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ts = self.value.resolve(ctx)?.try_timestamp()?;
+        let timezone = resolve_timezone(&self.timezone, ctx)?;
+        let local = to_local(ts, timezone).naive_local();
+
+        let truncated = truncate(local, &self.unit);
+
+        from_local(truncated, timezone)
+            .map(Into::into)
+            .ok_or_else(|| format!("truncated time {} doesn't exist in timezone", truncated).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new()
+            .with_fallibility(self.timezone.is_some())
+            .timestamp()
+    }
+}
+
+fn truncate(local: NaiveDateTime, unit: &[u8]) -> NaiveDateTime {
+    let date = local.date();
+    let time = local.time();
+
+    match unit {
+        b"year" => NaiveDate::from_ymd(date.year(), 1, 1).and_hms(0, 0, 0),
+        b"quarter" => {
+            NaiveDate::from_ymd(date.year(), date.month0() / 3 * 3 + 1, 1).and_hms(0, 0, 0)
+        }
+        b"month" => NaiveDate::from_ymd(date.year(), date.month(), 1).and_hms(0, 0, 0),
+        b"week" => {
+            let days = date.weekday().num_days_from_monday();
+            (date - chrono::Duration::days(days as i64)).and_hms(0, 0, 0)
+        }
+        b"day" => date.and_hms(0, 0, 0),
+        b"hour" => date.and_hms(time.hour(), 0, 0),
+        b"minute" => date.and_hms(time.hour(), time.minute(), 0),
+        b"second" => date.and_hms(time.hour(), time.minute(), time.second()),
+        // Leap seconds are represented as a nanosecond part over one second,
+        // keep them so the result stays within the same second.
+        b"millisecond" => date.and_hms_nano(
+            time.hour(),
+            time.minute(),
+            time.second(),
+            time.nanosecond() / 1_000_000 * 1_000_000,
+        ),
+        b"microsecond" => date.and_hms_nano(
+            time.hour(),
+            time.minute(),
+            time.second(),
+            time.nanosecond() / 1_000 * 1_000,
+        ),
+        _ => unreachable!("enum invariant"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    test_function![
+        truncate_timestamp => TruncateTimestamp;
+
+        year {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_milli(23, 32, 10, 123),
+                             unit: "year"],
+            want: Ok(value!(Utc.ymd(2021, 1, 1).and_hms(0, 0, 0))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        quarter {
+            args: func_args![value: Utc.ymd(2021, 8, 10).and_hms_milli(23, 32, 10, 123),
+                             unit: "quarter"],
+            want: Ok(value!(Utc.ymd(2021, 7, 1).and_hms(0, 0, 0))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        month {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_milli(23, 32, 10, 123),
+                             unit: "month"],
+            want: Ok(value!(Utc.ymd(2021, 2, 1).and_hms(0, 0, 0))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        week {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_milli(23, 32, 10, 123),
+                             unit: "week"],
+            want: Ok(value!(Utc.ymd(2021, 2, 8).and_hms(0, 0, 0))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        week_across_year {
+            args: func_args![value: Utc.ymd(2021, 1, 3).and_hms(12, 0, 0),
+                             unit: "week"],
+            want: Ok(value!(Utc.ymd(2020, 12, 28).and_hms(0, 0, 0))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        day {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_milli(23, 32, 10, 123),
+                             unit: "day"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms(0, 0, 0))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        hour {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_milli(23, 32, 10, 123),
+                             unit: "hour"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms(23, 0, 0))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        minute {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_milli(23, 32, 10, 123),
+                             unit: "minute"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms(23, 32, 0))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        second {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_milli(23, 32, 10, 123),
+                             unit: "second"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms(23, 32, 10))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        millisecond {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_nano(23, 32, 10, 123_456_789),
+                             unit: "millisecond"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms_milli(23, 32, 10, 123))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        microsecond {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms_nano(23, 32, 10, 123_456_789),
+                             unit: "microsecond"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms_micro(23, 32, 10, 123_456))),
+            tdef: TypeDef::new().infallible().timestamp(),
+        }
+
+        day_in_timezone {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 10),
+                             unit: "day",
+                             timezone: "Asia/Tokyo"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms(15, 0, 0))),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        hour_in_half_hour_timezone {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 10),
+                             unit: "hour",
+                             timezone: "Asia/Kolkata"],
+            want: Ok(value!(Utc.ymd(2021, 2, 10).and_hms(23, 30, 0))),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        day_across_dst {
+            // 2021-03-28 is the day Europe/Paris moves from UTC+1 to UTC+2.
+            args: func_args![value: Utc.ymd(2021, 3, 28).and_hms(20, 0, 0),
+                             unit: "day",
+                             timezone: "Europe/Paris"],
+            want: Ok(value!(Utc.ymd(2021, 3, 27).and_hms(23, 0, 0))),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+
+        invalid_timezone {
+            args: func_args![value: Utc.ymd(2021, 2, 10).and_hms(23, 32, 10),
+                             unit: "day",
+                             timezone: "Mars/Olympus_Mons"],
+            want: Err("unable to parse timezone: Mars/Olympus_Mons"),
+            tdef: TypeDef::new().fallible().timestamp(),
+        }
+    ];
+}
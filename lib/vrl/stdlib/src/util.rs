@@ -122,3 +122,96 @@ impl std::str::FromStr for Base64Charset {
         }
     }
 }
+
+/// Split the pipe-delimited header into at most `count` fields, unescaping `\|`
+/// and `\\`. The remainder after the last header field, if any, is returned as
+/// well.
+#[cfg(any(feature = "parse_cef", feature = "parse_leef"))]
+pub(crate) fn split_header(input: &str, count: usize) -> (Vec<String>, Option<&str>) {
+    let mut fields = Vec::with_capacity(count);
+    let mut field = String::new();
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped @ '|') | Some(escaped @ '\\') => field.push(escaped),
+                Some(other) => {
+                    field.push('\\');
+                    field.push(other);
+                }
+                None => field.push('\\'),
+            },
+            '|' => {
+                fields.push(std::mem::take(&mut field));
+
+                if fields.len() == count {
+                    let rest = chars.as_str();
+                    return (fields, Some(rest));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+
+    fields.push(field);
+    (fields, None)
+}
+
+/// Takes the key of a cryptographic function from either its `key` argument,
+/// or the file named by its `key_file` argument, which is read when the
+/// program is compiled so that the key doesn't have to be part of it.
+///
+/// Literal keys are still accepted, but warned about, as they end up in the
+/// configuration in plain text.
+#[cfg(any(feature = "decrypt", feature = "encrypt", feature = "hmac"))]
+pub(crate) fn key_argument(
+    function: &'static str,
+    arguments: &mut vrl::prelude::ArgumentList,
+) -> Result<Box<dyn vrl::prelude::Expression>, vrl::function::Error> {
+    use vrl::{function::Error, Value};
+
+    match (
+        arguments.optional("key"),
+        arguments.optional_literal("key_file")?,
+    ) {
+        (Some(key), None) => {
+            if key.as_value().is_some() {
+                tracing::warn!(
+                    message = "Key is written in plain text in the program, use `key_file` to read it from a file instead.",
+                    function,
+                );
+            }
+            Ok(key)
+        }
+        (None, Some(path)) => {
+            let path = path.to_value();
+            let mut key = std::fs::read(&*path.try_bytes_utf8_lossy().expect("key file not bytes"))
+                .map_err(|_| Error::InvalidArgument {
+                    keyword: "key_file",
+                    value: path.clone(),
+                    error: "unable to read key file",
+                })?;
+
+            // Editors and `echo` end files with a newline, which isn't part
+            // of the key.
+            if key.ends_with(b"\r\n") {
+                key.truncate(key.len() - 2);
+            } else if key.ends_with(b"\n") {
+                key.truncate(key.len() - 1);
+            }
+
+            Ok(Value::from(key).into_expression())
+        }
+        (Some(_), Some(path)) => Err(Error::InvalidArgument {
+            keyword: "key_file",
+            value: path.to_value(),
+            error: "key and key_file can't both be given",
+        }),
+        (None, None) => Err(Error::InvalidArgument {
+            keyword: "key",
+            value: Value::Null,
+            error: "either key or key_file is required",
+        }),
+    }
+}
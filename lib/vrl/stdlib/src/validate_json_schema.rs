@@ -0,0 +1,232 @@
+use jsonschema::JSONSchema;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use vrl::{
+    diagnostic::{Label, Span},
+    prelude::*,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    ReadSchemaFile(String, std::io::Error),
+    ParseSchema(String, serde_json::Error),
+    InvalidSchema(String, String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid argument")
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl DiagnosticError for Error {
+    fn code(&self) -> usize {
+        403
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        let message = match self {
+            Error::ReadSchemaFile(path, err) => {
+                format!("unable to read schema file {}: {}", path, err)
+            }
+            Error::ParseSchema(path, err) => {
+                format!("schema file {} isn't valid JSON: {}", path, err)
+            }
+            Error::InvalidSchema(path, err) => {
+                format!("schema file {} isn't a valid JSON schema: {}", path, err)
+            }
+        };
+
+        vec![Label::primary(message, Span::default())]
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ValidateJsonSchema;
+
+impl Function for ValidateJsonSchema {
+    fn identifier(&self) -> &'static str {
+        "validate_json_schema"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::ANY,
+                required: true,
+            },
+            Parameter {
+                keyword: "schema_file",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "validate against schema",
+            source: r#"validate_json_schema({ "name": "Vector", "age": -1 }, "data/json_schema/person.schema.json")"#,
+            result: Ok(indoc! {r#"
+                [{
+                    "instance_path": "/age",
+                    "message": "-1 is less than the minimum of 0",
+                    "schema_path": "/properties/age/minimum"
+                }]
+            "#}),
+        }]
+    }
+
+    fn compile(&self, _state: &state::Compiler, mut arguments: ArgumentList) -> Compiled {
+        let value = arguments.required("value");
+
+        let path = arguments
+            .required_literal("schema_file")?
+            .to_value()
+            .try_bytes_utf8_lossy()
+            .expect("schema file not bytes")
+            .into_owned();
+
+        let schema = std::fs::read_to_string(&path)
+            .map_err(|err| Error::ReadSchemaFile(path.clone(), err))
+            .and_then(|schema| {
+                serde_json::from_str(&schema).map_err(|err| Error::ParseSchema(path.clone(), err))
+            })
+            .and_then(|schema| {
+                JSONSchema::compile(&schema)
+                    .map_err(|err| Error::InvalidSchema(path.clone(), err.to_string()))
+            })
+            .map_err(|err| Box::new(err) as Box<dyn DiagnosticError>)?;
+
+        Ok(Box::new(ValidateJsonSchemaFn {
+            value,
+            schema: Arc::new(schema),
+        }))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ValidateJsonSchemaFn {
+    value: Box<dyn Expression>,
+
+    // Wrapping the schema in an Arc, as it isn't cloneable and is compiled
+    // only once.
+    schema: Arc<JSONSchema>,
+}
+
+impl Expression for ValidateJsonSchemaFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let instance = serde_json::to_value(&value).map_err(|err| err.to_string())?;
+
+        let violations = match self.schema.validate(&instance) {
+            Ok(()) => vec![],
+            Err(errors) => errors
+                .map(|error| {
+                    let mut violation = BTreeMap::new();
+                    violation.insert(
+                        "instance_path".to_owned(),
+                        error.instance_path.to_string().into(),
+                    );
+                    violation.insert(
+                        "schema_path".to_owned(),
+                        error.schema_path.to_string().into(),
+                    );
+                    violation.insert("message".to_owned(), error.to_string().into());
+
+                    Value::from(violation)
+                })
+                .collect(),
+        };
+
+        Ok(violations.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().array_mapped::<(), Kind>(map! {
+            (): Kind::Object,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../tests/data/json_schema/person.schema.json"
+    );
+
+    fn tdef() -> TypeDef {
+        TypeDef::new().infallible().array_mapped::<(), Kind>(map! {
+            (): Kind::Object,
+        })
+    }
+
+    test_function![
+        validate_json_schema => ValidateJsonSchema;
+
+        valid {
+            args: func_args![value: value!({"name": "Vector", "age": 3, "tags": ["logs", "metrics"]}),
+                             schema_file: SCHEMA_FILE],
+            want: Ok(value!([])),
+            tdef: tdef(),
+        }
+
+        invalid_field {
+            args: func_args![value: value!({"name": "Vector", "age": (-1)}),
+                             schema_file: SCHEMA_FILE],
+            want: Ok(value!([{
+                "instance_path": "/age",
+                "message": "-1 is less than the minimum of 0",
+                "schema_path": "/properties/age/minimum",
+            }])),
+            tdef: tdef(),
+        }
+
+        missing_field {
+            args: func_args![value: value!({"age": 3}),
+                             schema_file: SCHEMA_FILE],
+            want: Ok(value!([{
+                "instance_path": "",
+                "message": r#""name" is a required property"#,
+                "schema_path": "/required",
+            }])),
+            tdef: tdef(),
+        }
+
+        nested_array_item {
+            args: func_args![value: value!({"name": "Vector", "tags": ["logs", 1]}),
+                             schema_file: SCHEMA_FILE],
+            want: Ok(value!([{
+                "instance_path": "/tags/1",
+                "message": r#"1 is not of type "string""#,
+                "schema_path": "/properties/tags/items/type",
+            }])),
+            tdef: tdef(),
+        }
+
+        not_an_object {
+            args: func_args![value: "Vector",
+                             schema_file: SCHEMA_FILE],
+            want: Ok(value!([{
+                "instance_path": "",
+                "message": r#""Vector" is not of type "object""#,
+                "schema_path": "/type",
+            }])),
+            tdef: tdef(),
+        }
+
+        missing_schema_file {
+            args: func_args![value: value!({}),
+                             schema_file: "/does/not/exist.json"],
+            want: Err("invalid argument"),
+            tdef: tdef(),
+        }
+    ];
+}
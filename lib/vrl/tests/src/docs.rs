@@ -15,6 +15,8 @@ const SKIP_FUNCTION_EXAMPLES: &[&str] = &[
     "get_hostname",
     "now",
     "get_env_var",
+    "random_bytes",
+    "validate_json_schema",
 ];
 
 #[derive(Debug, Deserialize)]
@@ -406,25 +406,45 @@ inventory::collect!(TransformDescription);
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct EnrichmentTableOuter {
+    /// When set, the files the table is loaded from are checked for changes
+    /// at this interval, and the table is reloaded in the background when they
+    /// have changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reload_interval_secs: Option<u64>,
     #[serde(flatten)]
     pub inner: Box<dyn EnrichmentTableConfig>,
 }
 
 impl EnrichmentTableOuter {
     pub fn new(inner: Box<dyn EnrichmentTableConfig>) -> Self {
-        EnrichmentTableOuter { inner }
+        EnrichmentTableOuter {
+            reload_interval_secs: None,
+            inner,
+        }
     }
 }
 
 #[async_trait]
 #[typetag::serde(tag = "type")]
 pub trait EnrichmentTableConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone {
+    /// Builds the table with the given name, which is the name it is searched
+    /// for with in the `TableRegistry`.
     async fn build(
         &self,
+        name: &str,
         globals: &GlobalOptions,
     ) -> crate::Result<Box<dyn enrichment::Table + Send + Sync>>;
+
+    /// The files the table is loaded from. When reloading is enabled, the
+    /// table is only reloaded once one of them has changed. Tables that don't
+    /// return any files are reloaded at every interval.
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
 }
 
+dyn_clone::clone_trait_object!(EnrichmentTableConfig);
+
 pub type EnrichmentTableDescription = ComponentDescription<Box<dyn EnrichmentTableConfig>>;
 
 inventory::collect!(EnrichmentTableDescription);
@@ -1,10 +1,13 @@
 use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription};
 use bytes::Bytes;
+use cidr_utils::cidr::IpCidr;
 use enrichment::{Condition, IndexHandle, Table};
 use serde::{Deserialize, Serialize};
 use shared::{conversion::Conversion, datetime::TimeZone};
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap};
 use std::hash::Hasher;
+use std::net::IpAddr;
 use std::path::PathBuf;
 use tracing::trace;
 use vrl::Value;
@@ -44,6 +47,7 @@ enum SchemaType {
     Integer,
     Float,
     Boolean,
+    Cidr,
 }
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
@@ -91,6 +95,11 @@ impl FileConfig {
             Some(SchemaType::Boolean) => Conversion::Boolean
                 .convert(Bytes::copy_from_slice(value.as_bytes()))
                 .map_err(|_| format!("unable to parse integer {} found in row {}", value, row))?,
+            Some(SchemaType::Cidr) => {
+                IpCidr::from_str(value)
+                    .map_err(|_| format!("unable to parse CIDR {} found in row {}", value, row))?;
+                value.into()
+            }
             Some(SchemaType::String) | None => value.into(),
         })
     }
@@ -101,6 +110,7 @@ impl FileConfig {
 impl EnrichmentTableConfig for FileConfig {
     async fn build(
         &self,
+        _: &str,
         globals: &crate::config::GlobalOptions,
     ) -> crate::Result<Box<dyn Table + Send + Sync>> {
         let Encoding::Csv {
@@ -145,7 +155,20 @@ impl EnrichmentTableConfig for FileConfig {
             headers
         );
 
-        Ok(Box::new(File::new(data, headers)))
+        let mut file = File::new(data, headers);
+        for (column, _) in self
+            .schema
+            .iter()
+            .filter(|(_, schema)| **schema == SchemaType::Cidr)
+        {
+            file.add_cidr_index(column)?;
+        }
+
+        Ok(Box::new(file))
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        vec![self.file.path.clone()]
     }
 }
 
@@ -155,6 +178,8 @@ inventory::submit! {
 
 impl_generate_config_from_default!(FileConfig);
 
+type Rows<'a> = Box<dyn Iterator<Item = &'a Vec<Value>> + 'a>;
+
 #[derive(Clone)]
 pub struct File {
     data: Vec<Vec<Value>>,
@@ -163,6 +188,7 @@ pub struct File {
         Vec<usize>,
         HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>,
     )>,
+    cidr_indexes: Vec<(usize, CidrIndex)>,
 }
 
 impl File {
@@ -171,9 +197,23 @@ impl File {
             data,
             headers,
             indexes: Vec::new(),
+            cidr_indexes: Vec::new(),
         }
     }
 
+    /// Creates an index of the networks in the given column, used to search
+    /// for the rows containing an IP address without scanning the table.
+    pub fn add_cidr_index(&mut self, column: &str) -> Result<(), String> {
+        let idx = self
+            .column_index(column)
+            .ok_or_else(|| format!("field {} not in table", column))?;
+
+        let index = CidrIndex::new(self.data.iter().map(|row| &row[idx]));
+        self.cidr_indexes.push((idx, index));
+
+        Ok(())
+    }
+
     fn column_index(&self, col: &str) -> Option<usize> {
         self.headers.iter().position(|header| header == col)
     }
@@ -203,13 +243,52 @@ impl File {
                     _ => false,
                 },
             },
+            Condition::BetweenFields {
+                from_field,
+                to_field,
+                value,
+            } => match (self.column_index(from_field), self.column_index(to_field)) {
+                (Some(from), Some(to)) => matches!(
+                    (compare(&row[from], value), compare(value, &row[to])),
+                    (Some(lower), Some(upper))
+                        if lower != Ordering::Greater && upper != Ordering::Greater
+                ),
+                _ => false,
+            },
+            Condition::IpInCidr { field, value } => match self.column_index(field) {
+                None => false,
+                Some(idx) => parse_cidr(&row[idx])
+                    .map(|cidr| cidr.contains(*value))
+                    .unwrap_or(false),
+            },
+            Condition::PrefixOf { field, value } => match self.column_index(field) {
+                None => false,
+                Some(idx) => match (&row[idx], value) {
+                    (Value::Bytes(prefix), Value::Bytes(bytes)) => {
+                        match (std::str::from_utf8(prefix), std::str::from_utf8(bytes)) {
+                            (Ok(prefix), Ok(s)) => {
+                                s.to_lowercase().starts_with(&prefix.to_lowercase())
+                            }
+                            (Err(_), Err(_)) => bytes.starts_with(prefix),
+                            _ => false,
+                        }
+                    }
+                    _ => false,
+                },
+            },
         })
     }
 
-    fn add_columns(&self, row: &[Value]) -> BTreeMap<String, Value> {
+    fn add_columns(&self, select: Option<&[String]>, row: &[Value]) -> BTreeMap<String, Value> {
         self.headers
             .iter()
             .zip(row)
+            .filter(|(header, _)| {
+                select
+                    .map(|select| select.contains(*header))
+                    // If no select is passed, we assume all columns are included
+                    .unwrap_or(true)
+            })
             .map(|(header, col)| (header.clone(), col.clone()))
             .collect()
     }
@@ -266,18 +345,93 @@ impl File {
         &'a self,
         data: I,
         condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
     ) -> impl Iterator<Item = BTreeMap<String, Value>> + 'a
     where
         I: Iterator<Item = &'a Vec<Value>> + 'a,
     {
-        data.filter_map(move |row| {
-            if self.row_equals(condition, &*row) {
-                Some(self.add_columns(&*row))
-            } else {
-                None
-            }
+        self.matching(data, condition)
+            .map(move |row| self.add_columns(select, &*row))
+    }
+
+    /// Sequentially searches through the iterator for the rows matching the condition.
+    fn matching<'a, I>(
+        &'a self,
+        data: I,
+        condition: &'a [Condition<'a>],
+    ) -> impl Iterator<Item = &'a Vec<Value>> + 'a
+    where
+        I: Iterator<Item = &'a Vec<Value>> + 'a,
+    {
+        data.filter(move |row| self.row_equals(condition, row))
+    }
+
+    /// Returns the column searched for an IP address by the condition, if any.
+    fn cidr_column(&self, condition: &[Condition]) -> Option<usize> {
+        condition.iter().find_map(|condition| match condition {
+            Condition::IpInCidr { field, .. } => self.column_index(field),
+            _ => None,
         })
     }
+
+    /// Returns the rows that may match the condition, using the index if one is passed, or the
+    /// CIDR index of a field searched for an IP address. Returns `None` if there are no rows in the
+    /// index for the values searched for.
+    fn candidates<'a>(
+        &'a self,
+        condition: &[Condition],
+        index: Option<IndexHandle>,
+    ) -> Result<Option<Rows<'a>>, String> {
+        if let Some(handle) = index {
+            return Ok(self
+                .indexed(condition, handle)?
+                .map(|rows| Box::new(rows) as Rows<'a>));
+        }
+
+        for condition in condition {
+            if let Condition::IpInCidr { field, value } = condition {
+                let column = self.column_index(field);
+                if let Some((_, index)) = self
+                    .cidr_indexes
+                    .iter()
+                    .find(|(idx, _)| Some(*idx) == column)
+                {
+                    let rows = index.find(*value).into_iter();
+                    return Ok(Some(Box::new(rows.map(move |idx| &self.data[idx]))));
+                }
+            }
+        }
+
+        // No index can be used so we need to do a Sequential Scan.
+        Ok(Some(Box::new(self.data.iter())))
+    }
+
+    /// Uses the given index to find the rows that may match the condition, returns `None` if
+    /// there are no rows in the index for the values searched for.
+    /// We are assuming that the caller has passed an index that represents the fields being
+    /// passed in the condition.
+    fn indexed<'a>(
+        &'a self,
+        condition: &[Condition],
+        handle: IndexHandle,
+    ) -> Result<Option<impl Iterator<Item = &'a Vec<Value>> + 'a>, String> {
+        let mut hash = seahash::SeaHasher::default();
+
+        for header in self.headers.iter() {
+            if let Some(Condition::Equals { value, .. }) = condition.iter().find(
+                |condition| matches!(condition, Condition::Equals { field, .. } if field == header),
+            ) {
+                hash_value(&mut hash, value)?;
+            }
+        }
+
+        let key = hash.finish();
+
+        Ok(self.indexes[handle.0]
+            .1
+            .get(&key)
+            .map(move |rows| rows.iter().map(move |idx| &self.data[*idx])))
+    }
 }
 
 /// Adds the bytes from the given value to the hash.
@@ -303,6 +457,45 @@ fn hash_value(hasher: &mut seahash::SeaHasher, value: &Value) -> Result<(), Stri
     Ok(())
 }
 
+/// Parses the network in CIDR notation held by a column.
+fn parse_cidr(value: &Value) -> Option<IpCidr> {
+    match value {
+        Value::Bytes(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|cidr| IpCidr::from_str(cidr).ok()),
+        _ => None,
+    }
+}
+
+/// Keeps the rows with the longest network prefix in the given column, so that
+/// an IP address contained in overlapping networks resolves to the most
+/// specific one.
+fn longest_prefix<'a>(
+    rows: impl Iterator<Item = &'a Vec<Value>>,
+    column: usize,
+) -> Vec<&'a Vec<Value>> {
+    let mut longest = Vec::new();
+    let mut longest_length = None;
+
+    for row in rows {
+        let length = parse_cidr(&row[column]).map(|cidr| match cidr {
+            IpCidr::V4(cidr) => cidr.get_bits(),
+            IpCidr::V6(cidr) => cidr.get_bits(),
+        });
+        match length.cmp(&longest_length) {
+            Ordering::Greater => {
+                longest.clear();
+                longest.push(row);
+                longest_length = length;
+            }
+            Ordering::Equal => longest.push(row),
+            Ordering::Less => (),
+        }
+    }
+
+    longest
+}
+
 /// Returns an error if the iterator doesn't yield exactly one result.
 fn single_or_err<I, T>(mut iter: T) -> Result<I, String>
 where
@@ -318,45 +511,116 @@ where
     }
 }
 
+/// Compares numbers and timestamps, returning `None` for other values.
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => a.partial_cmp(b),
+        (Value::Integer(a), Value::Float(b)) => (*a as f64).partial_cmp(&b.into_inner()),
+        (Value::Float(a), Value::Integer(b)) => a.into_inner().partial_cmp(&(*b as f64)),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+        (Value::Timestamp(a), Value::Timestamp(b)) => a.partial_cmp(b),
+        _ => None,
+    }
+}
+
+/// An index of the networks in a column. The networks are grouped by their
+/// prefix length, so finding the networks containing an IP address takes a
+/// lookup for each of the prefix lengths in the column.
+#[derive(Clone, Default)]
+struct CidrIndex {
+    // The prefix lengths of the IPv4 and IPv6 networks in the column.
+    v4_lengths: Vec<u8>,
+    v6_lengths: Vec<u8>,
+    networks: HashMap<(bool, u8, u128), Vec<usize>>,
+}
+
+impl CidrIndex {
+    fn new<'a>(column: impl Iterator<Item = &'a Value>) -> Self {
+        let mut index = Self::default();
+
+        for (idx, value) in column.enumerate() {
+            let key = match parse_cidr(value) {
+                Some(IpCidr::V4(cidr)) => (false, cidr.get_bits(), cidr.get_prefix() as u128),
+                Some(IpCidr::V6(cidr)) => (true, cidr.get_bits(), cidr.get_prefix()),
+                // Rows without a network can't contain an IP address.
+                None => continue,
+            };
+
+            let lengths = if key.0 {
+                &mut index.v6_lengths
+            } else {
+                &mut index.v4_lengths
+            };
+            if !lengths.contains(&key.1) {
+                lengths.push(key.1);
+            }
+
+            index.networks.entry(key).or_insert_with(Vec::new).push(idx);
+        }
+
+        index
+    }
+
+    /// Returns the rows with a network containing the IP address, in the order
+    /// they are in the table.
+    fn find(&self, ip: IpAddr) -> Vec<usize> {
+        let (v6, ip, bits, lengths) = match ip {
+            IpAddr::V4(ip) => (false, u32::from(ip) as u128, 32, &self.v4_lengths),
+            IpAddr::V6(ip) => (true, u128::from(ip), 128, &self.v6_lengths),
+        };
+
+        let mut rows = lengths
+            .iter()
+            .filter_map(|length| {
+                // Shifting by the number of bits in the value overflows.
+                let network = match bits - *length as u32 {
+                    128 => 0,
+                    host_bits => ip >> host_bits << host_bits,
+                };
+                self.networks.get(&(v6, *length, network))
+            })
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+
+        rows
+    }
+}
+
 impl Table for File {
     fn find_table_row<'a>(
         &self,
         condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
         index: Option<IndexHandle>,
     ) -> Result<BTreeMap<String, Value>, String> {
-        match index {
-            None => {
-                // No index has been passed so we need to do a Sequential Scan.
-                single_or_err(self.sequential(self.data.iter(), condition))
-            }
-            Some(IndexHandle(handle)) => {
-                // The index to use has been passed, we can use this to search the data.
-                // We are assuming that the caller has passed an index that represents the fields
-                // being passed in the condition.
-                let mut hash = seahash::SeaHasher::default();
-
-                for header in self.headers.iter() {
-                    if let Some(Condition::Equals { value, .. }) = condition.iter().find(|condition|
-                    {
-                        matches!(condition, Condition::Equals { field, .. } if field == header)
-                    })
-                    {
-                        hash_value(&mut hash, value)?;
-                    }
-                }
-
-                let key = hash.finish();
+        let result = self
+            .candidates(condition, index)?
+            .ok_or_else(|| "no rows found in index".to_string())?;
+
+        // Perform a sequential scan over the indexed result.
+        let rows = self.matching(result, condition);
+        let row = match self.cidr_column(condition) {
+            // Overlapping networks all contain the IP addresses of the most
+            // specific one.
+            Some(column) => single_or_err(longest_prefix(rows, column).into_iter())?,
+            None => single_or_err(rows)?,
+        };
 
-                let result = self.indexes[handle]
-                    .1
-                    .get(&key)
-                    .ok_or_else(|| "no rows found in index".to_string())?
-                    .iter()
-                    .map(|idx| &self.data[*idx]);
+        Ok(self.add_columns(select, row))
+    }
 
-                // Perform a sequential scan over the indexed result.
-                single_or_err(self.sequential(result, condition))
-            }
+    fn find_table_rows<'a>(
+        &self,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        match self.candidates(condition, index)? {
+            // Perform a sequential scan over the indexed result.
+            Some(result) => Ok(self.sequential(result, condition, select).collect()),
+            None => Ok(Vec::new()),
         }
     }
 
@@ -376,6 +640,22 @@ impl Table for File {
             }
         }
     }
+
+    fn index_fields(&self) -> Vec<Vec<String>> {
+        self.indexes
+            .iter()
+            .map(|(fields, _)| {
+                fields
+                    .iter()
+                    .map(|idx| self.headers[*idx].clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn row_count(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
 }
 
 impl std::fmt::Debug for File {
@@ -431,7 +711,102 @@ mod tests {
                 "field1" => "zirp",
                 "field2" => "zurp",
             }),
-            file.find_table_row(&[condition], None)
+            file.find_table_row(&[condition], None, None)
+        );
+    }
+
+    #[test]
+    fn finds_row_with_select() {
+        let file = File::new(
+            vec![
+                vec!["zip".into(), "zup".into()],
+                vec!["zirp".into(), "zurp".into()],
+            ],
+            vec!["field1".to_string(), "field2".to_string()],
+        );
+
+        let condition = Condition::Equals {
+            field: "field1",
+            value: Value::from("zirp"),
+        };
+
+        assert_eq!(
+            Ok(btreemap! {
+                "field2" => "zurp",
+            }),
+            file.find_table_row(&[condition], Some(&["field2".to_string()]), None)
+        );
+    }
+
+    #[test]
+    fn finds_rows() {
+        let file = File::new(
+            vec![
+                vec!["zip".into(), "zup".into()],
+                vec!["zirp".into(), "zurp".into()],
+                vec!["zip".into(), "zoop".into()],
+            ],
+            vec!["field1".to_string(), "field2".to_string()],
+        );
+
+        let condition = Condition::Equals {
+            field: "field1",
+            value: Value::from("zip"),
+        };
+
+        assert_eq!(
+            Ok(vec![
+                btreemap! {
+                    "field1" => "zip",
+                    "field2" => "zup",
+                },
+                btreemap! {
+                    "field1" => "zip",
+                    "field2" => "zoop",
+                },
+            ]),
+            file.find_table_rows(&[condition], None, None)
+        );
+    }
+
+    #[test]
+    fn finds_rows_with_index() {
+        let mut file = File::new(
+            vec![
+                vec!["zip".into(), "zup".into()],
+                vec!["zirp".into(), "zurp".into()],
+                vec!["zip".into(), "zoop".into()],
+            ],
+            vec!["field1".to_string(), "field2".to_string()],
+        );
+
+        let handle = file.add_index(&["field1"]).unwrap();
+
+        let condition = Condition::Equals {
+            field: "field1",
+            value: Value::from("zip"),
+        };
+
+        assert_eq!(
+            Ok(vec![
+                btreemap! {
+                    "field2" => "zup",
+                },
+                btreemap! {
+                    "field2" => "zoop",
+                },
+            ]),
+            file.find_table_rows(&[condition], Some(&["field2".to_string()]), Some(handle))
+        );
+
+        let condition = Condition::Equals {
+            field: "field1",
+            value: Value::from("zorp"),
+        };
+
+        assert_eq!(
+            Ok(vec![]),
+            file.find_table_rows(&[condition], None, Some(handle))
         );
     }
 
@@ -453,6 +828,29 @@ mod tests {
         assert_eq!(1, file.indexes.len());
     }
 
+    #[test]
+    fn index_fields() {
+        let mut file = File::new(
+            Vec::new(),
+            vec![
+                "field1".to_string(),
+                "field2".to_string(),
+                "field3".to_string(),
+            ],
+        );
+
+        file.add_index(&["field3", "field1"]).unwrap();
+        file.add_index(&["field2"]).unwrap();
+
+        assert_eq!(
+            vec![
+                vec!["field1".to_string(), "field3".to_string()],
+                vec!["field2".to_string()],
+            ],
+            file.index_fields()
+        );
+    }
+
     #[test]
     fn finds_row_with_index() {
         let mut file = File::new(
@@ -475,7 +873,7 @@ mod tests {
                 "field1" => "zirp",
                 "field2" => "zurp",
             }),
-            file.find_table_row(&[condition], Some(handle))
+            file.find_table_row(&[condition], None, Some(handle))
         );
     }
 
@@ -514,7 +912,7 @@ mod tests {
                 "field1" => "zip",
                 "field2" => Value::Timestamp(chrono::Utc.ymd(2016, 12, 7).and_hms(0, 0, 0)),
             }),
-            file.find_table_row(&conditions, Some(handle))
+            file.find_table_row(&conditions, None, Some(handle))
         );
     }
 
@@ -535,7 +933,7 @@ mod tests {
 
         assert_eq!(
             Err("no rows found".to_string()),
-            file.find_table_row(&[condition], None)
+            file.find_table_row(&[condition], None, None)
         );
     }
 
@@ -558,7 +956,161 @@ mod tests {
 
         assert_eq!(
             Err("no rows found in index".to_string()),
-            file.find_table_row(&[condition], Some(handle))
+            file.find_table_row(&[condition], None, Some(handle))
+        );
+    }
+
+    fn networks() -> File {
+        File::new(
+            vec![
+                vec!["10.0.0.0/8".into(), "internal".into()],
+                vec!["10.1.0.0/16".into(), "office".into()],
+                vec!["192.168.0.0/24".into(), "lab".into()],
+                vec!["2001:db8::/32".into(), "docs".into()],
+            ],
+            vec!["network".to_string(), "name".to_string()],
+        )
+    }
+
+    #[test]
+    fn finds_rows_in_cidr() {
+        let mut indexed = networks();
+        indexed.add_cidr_index("network").unwrap();
+
+        for file in &[networks(), indexed] {
+            let find = |ip: &str| {
+                file.find_table_rows(
+                    &[Condition::IpInCidr {
+                        field: "network",
+                        value: ip.parse().unwrap(),
+                    }],
+                    Some(&["name".to_string()]),
+                    None,
+                )
+            };
+
+            assert_eq!(
+                Ok(vec![
+                    btreemap! { "name" => "internal" },
+                    btreemap! { "name" => "office" },
+                ]),
+                find("10.1.2.3")
+            );
+            assert_eq!(
+                Ok(vec![btreemap! { "name" => "lab" }]),
+                find("192.168.0.255")
+            );
+            assert_eq!(
+                Ok(vec![btreemap! { "name" => "docs" }]),
+                find("2001:db8::1")
+            );
+            assert_eq!(Ok(vec![]), find("192.168.1.1"));
+        }
+    }
+
+    #[test]
+    fn finds_row_in_most_specific_cidr() {
+        let mut indexed = networks();
+        indexed.add_cidr_index("network").unwrap();
+
+        for file in &[networks(), indexed] {
+            let find = |ip: &str| {
+                file.find_table_row(
+                    &[Condition::IpInCidr {
+                        field: "network",
+                        value: ip.parse().unwrap(),
+                    }],
+                    Some(&["name".to_string()]),
+                    None,
+                )
+            };
+
+            assert_eq!(Ok(btreemap! { "name" => "office" }), find("10.1.2.3"));
+            assert_eq!(Ok(btreemap! { "name" => "internal" }), find("10.2.0.1"));
+            assert_eq!(Err("no rows found".to_string()), find("192.168.1.1"));
+        }
+
+        let duplicated = File::new(
+            vec![
+                vec!["10.0.0.0/8".into(), "internal".into()],
+                vec!["10.0.0.0/8".into(), "private".into()],
+            ],
+            vec!["network".to_string(), "name".to_string()],
+        );
+        assert_eq!(
+            Err("more than one row found".to_string()),
+            duplicated.find_table_row(
+                &[Condition::IpInCidr {
+                    field: "network",
+                    value: "10.0.0.1".parse().unwrap(),
+                }],
+                None,
+                None,
+            )
+        );
+    }
+
+    #[test]
+    fn finds_row_between_fields() {
+        let file = File::new(
+            vec![
+                vec!["a".into(), 0.into(), 9.into()],
+                vec!["b".into(), 10.into(), 19.into()],
+                vec!["c".into(), 20.into(), 29.into()],
+            ],
+            vec!["name".to_string(), "from".to_string(), "to".to_string()],
+        );
+
+        let find = |value: Value| {
+            file.find_table_row(
+                &[Condition::BetweenFields {
+                    from_field: "from",
+                    to_field: "to",
+                    value,
+                }],
+                Some(&["name".to_string()]),
+                None,
+            )
+        };
+
+        assert_eq!(Ok(btreemap! { "name" => "b" }), find(10.into()));
+        assert_eq!(Ok(btreemap! { "name" => "b" }), find(14.5.into()));
+        assert_eq!(Ok(btreemap! { "name" => "c" }), find(29.into()));
+        assert_eq!(Err("no rows found".to_string()), find(30.into()));
+        assert_eq!(Err("no rows found".to_string()), find("b".into()));
+    }
+
+    #[test]
+    fn finds_row_with_prefix() {
+        let mut file = File::new(
+            vec![
+                vec!["44".into(), "uk".into()],
+                vec!["441".into(), "uk".into()],
+                vec!["1".into(), "us".into()],
+                vec!["33".into(), "fr".into()],
+            ],
+            vec!["prefix".to_string(), "country".to_string()],
+        );
+
+        let handle = file.add_index(&["country"]).unwrap();
+
+        let condition = [
+            Condition::Equals {
+                field: "country",
+                value: "UK".into(),
+            },
+            Condition::PrefixOf {
+                field: "prefix",
+                value: "4420".into(),
+            },
+        ];
+
+        assert_eq!(
+            Ok(btreemap! {
+                "prefix" => "44",
+                "country" => "uk",
+            }),
+            file.find_table_row(&condition, None, Some(handle))
         );
     }
 }
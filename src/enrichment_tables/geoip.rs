@@ -0,0 +1,491 @@
+use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig};
+use enrichment::{Condition, IndexHandle, Table};
+use maxminddb::{
+    geoip2::{City, ConnectionType, Isp},
+    Reader,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use vrl::Value;
+
+// MaxMind GeoIP database files have a type field we can use to recognize specific
+// products. If it is an unknown type, an error will be returned.
+#[derive(Copy, Clone, Debug)]
+enum DatabaseKind {
+    Asn,
+    Isp,
+    ConnectionType,
+    City,
+}
+
+impl std::convert::TryFrom<&str> for DatabaseKind {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "GeoLite2-ASN" => Ok(Self::Asn),
+            "GeoIP2-ISP" => Ok(Self::Isp),
+            "GeoIP2-Connection-Type" => Ok(Self::ConnectionType),
+            "GeoIP2-City" | "GeoLite2-City" => Ok(Self::City),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GeoipConfig {
+    pub path: String,
+    #[serde(default = "default_locale")]
+    pub locale: String,
+}
+
+// The locale is used to pick the names of the cities, countries, continents and
+// subdivisions returned for City databases.
+fn default_locale() -> String {
+    "en".to_string()
+}
+
+impl GenerateConfig for GeoipConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            path: "/path/to/GeoLite2-City.mmdb".to_string(),
+            locale: default_locale(),
+        })
+        .unwrap()
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<GeoipConfig>("geoip")
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "geoip")]
+impl EnrichmentTableConfig for GeoipConfig {
+    async fn build(
+        &self,
+        _: &str,
+        _: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(Geoip::new(self.clone())?))
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        vec![PathBuf::from(&self.path)]
+    }
+}
+
+#[derive(Clone)]
+pub struct Geoip {
+    config: GeoipConfig,
+    // The reader is shared between the clones of the table, the database is
+    // read only.
+    dbreader: Arc<Reader<Vec<u8>>>,
+    dbkind: DatabaseKind,
+}
+
+impl Geoip {
+    pub fn new(config: GeoipConfig) -> crate::Result<Self> {
+        let dbreader = Arc::new(Reader::open_readfile(config.path.clone())?);
+        let dbkind = std::convert::TryFrom::try_from(dbreader.metadata.database_type.as_str())
+            .map_err(|_| {
+                format!(
+                    "Unsupported MMDB database type ({}). Use GeoIP2-City, GeoLite2-City, GeoIP2-ISP, GeoLite2-ASN or GeoIP2-Connection-Type.",
+                    dbreader.metadata.database_type
+                )
+            })?;
+
+        Ok(Geoip {
+            config,
+            dbreader,
+            dbkind,
+        })
+    }
+
+    fn lookup(&self, ip: IpAddr, select: Option<&[String]>) -> Option<BTreeMap<String, Value>> {
+        let mut map = BTreeMap::new();
+        let mut add_field = |key: &str, value: Option<Value>| {
+            if select
+                .map(|fields| fields.iter().any(|field| field == key))
+                .unwrap_or(true)
+            {
+                map.insert(key.to_string(), value.unwrap_or(Value::Null));
+            }
+        };
+
+        match self.dbkind {
+            DatabaseKind::Asn | DatabaseKind::Isp => {
+                let data = self.dbreader.lookup::<Isp>(ip).ok()?;
+
+                add_field(
+                    "autonomous_system_number",
+                    data.autonomous_system_number.map(|n| (n as i64).into()),
+                );
+                add_field(
+                    "autonomous_system_organization",
+                    data.autonomous_system_organization.map(Into::into),
+                );
+                add_field("isp", data.isp.map(Into::into));
+                add_field("organization", data.organization.map(Into::into));
+            }
+            DatabaseKind::ConnectionType => {
+                let data = self.dbreader.lookup::<ConnectionType>(ip).ok()?;
+
+                add_field("connection_type", data.connection_type.map(Into::into));
+            }
+            DatabaseKind::City => {
+                let data = self.dbreader.lookup::<City>(ip).ok()?;
+                let locale = self.config.locale.as_str();
+
+                add_field(
+                    "city_name",
+                    data.city
+                        .and_then(|c| c.names)
+                        .and_then(|names| names.get(locale).map(|&name| name.into())),
+                );
+
+                let continent = data.continent;
+                add_field(
+                    "continent_code",
+                    continent.as_ref().and_then(|c| c.code).map(Into::into),
+                );
+                add_field(
+                    "continent_name",
+                    continent
+                        .and_then(|c| c.names)
+                        .and_then(|names| names.get(locale).map(|&name| name.into())),
+                );
+
+                let country = data.country;
+                add_field(
+                    "country_code",
+                    country.as_ref().and_then(|c| c.iso_code).map(Into::into),
+                );
+                add_field(
+                    "country_name",
+                    country
+                        .and_then(|c| c.names)
+                        .and_then(|names| names.get(locale).map(|&name| name.into())),
+                );
+
+                let location = data.location;
+                add_field(
+                    "timezone",
+                    location.as_ref().and_then(|l| l.time_zone).map(Into::into),
+                );
+                add_field(
+                    "latitude",
+                    location.as_ref().and_then(|l| l.latitude).map(Into::into),
+                );
+                add_field(
+                    "longitude",
+                    location.as_ref().and_then(|l| l.longitude).map(Into::into),
+                );
+                add_field(
+                    "metro_code",
+                    location
+                        .as_ref()
+                        .and_then(|l| l.metro_code)
+                        .map(|code| (code as i64).into()),
+                );
+
+                // The most specific subdivision is the last one.
+                let subdivision = data.subdivisions.as_ref().and_then(|s| s.last());
+                add_field(
+                    "region_code",
+                    subdivision.and_then(|s| s.iso_code).map(Into::into),
+                );
+                add_field(
+                    "region_name",
+                    subdivision
+                        .and_then(|s| s.names.as_ref())
+                        .and_then(|names| names.get(locale).map(|&name| name.into())),
+                );
+
+                add_field(
+                    "postal_code",
+                    data.postal.and_then(|p| p.code).map(Into::into),
+                );
+            }
+        }
+
+        Some(map)
+    }
+}
+
+/// Returns the IP address searched for by the condition.
+fn condition_ip(condition: &[Condition]) -> Result<IpAddr, String> {
+    match condition {
+        [Condition::Equals { field: "ip", value }] => value
+            .try_bytes_utf8_lossy()
+            .map_err(|_| "ip condition must be a string".to_string())?
+            .parse::<IpAddr>()
+            .map_err(|_| format!("{} is not a valid IP address", value)),
+        _ => Err("only an equality condition on the `ip` field is supported".to_string()),
+    }
+}
+
+impl Table for Geoip {
+    /// Searches the enrichment table data with the given condition.
+    /// The only condition supported is an equality check on the `ip` field.
+    ///
+    /// # Errors
+    /// Errors if the condition is not supported, or the IP is not found in the
+    /// database.
+    fn find_table_row<'a>(
+        &self,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let ip = condition_ip(condition)?;
+
+        self.lookup(ip, select)
+            .ok_or_else(|| format!("IP {} not found", ip))
+    }
+
+    /// Searches the enrichment table data with the given condition.
+    /// An IP address is only ever found once in the database, so at most one
+    /// row is returned.
+    ///
+    /// # Errors
+    /// Errors if the condition is not supported.
+    fn find_table_rows<'a>(
+        &self,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        let ip = condition_ip(condition)?;
+
+        Ok(self.lookup(ip, select).into_iter().collect())
+    }
+
+    /// Hints to the enrichment table what data is going to be searched.
+    /// The database is already indexed by IP address, so no work is done here.
+    ///
+    /// # Errors
+    /// Errors if the fields are not in the table.
+    fn add_index(&mut self, fields: &[&str]) -> Result<IndexHandle, String> {
+        match fields {
+            ["ip"] => Ok(IndexHandle(0)),
+            _ => Err("only the `ip` field can be searched".to_string()),
+        }
+    }
+}
+
+impl std::fmt::Debug for Geoip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Geoip {} database {}",
+            self.config.locale, self.config.path
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    fn find(ip: &str, database: &str) -> Result<BTreeMap<String, Value>, String> {
+        find_select(ip, database, None)
+    }
+
+    fn find_select(
+        ip: &str,
+        database: &str,
+        select: Option<&[String]>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        Geoip::new(GeoipConfig {
+            path: database.to_string(),
+            locale: default_locale(),
+        })
+        .unwrap()
+        .find_table_row(
+            &[Condition::Equals {
+                field: "ip",
+                value: ip.into(),
+            }],
+            select,
+            None,
+        )
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GeoipConfig>();
+    }
+
+    #[test]
+    fn city() {
+        let values = find("2.125.160.216", "tests/data/GeoIP2-City-Test.mmdb").unwrap();
+
+        let expected: BTreeMap<String, Value> = btreemap! {
+            "city_name" => "Boxford",
+            "country_code" => "GB",
+            "country_name" => "United Kingdom",
+            "continent_code" => "EU",
+            "continent_name" => "Europe",
+            "region_code" => "WBK",
+            "region_name" => "West Berkshire",
+            "timezone" => "Europe/London",
+            "latitude" => Value::from(51.75),
+            "longitude" => Value::from(-1.25),
+            "postal_code" => "OX1",
+            "metro_code" => Value::Null,
+        };
+
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn city_partial_results() {
+        let values = find("67.43.156.9", "tests/data/GeoIP2-City-Test.mmdb").unwrap();
+
+        let expected: BTreeMap<String, Value> = btreemap! {
+            "city_name" => Value::Null,
+            "country_code" => "BT",
+            "country_name" => "Bhutan",
+            "continent_code" => "AS",
+            "continent_name" => "Asia",
+            "region_code" => Value::Null,
+            "region_name" => Value::Null,
+            "timezone" => "Asia/Thimphu",
+            "latitude" => Value::from(27.5),
+            "longitude" => Value::from(90.5),
+            "postal_code" => Value::Null,
+            "metro_code" => Value::Null,
+        };
+
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn city_select() {
+        let values = find_select(
+            "2.125.160.216",
+            "tests/data/GeoIP2-City-Test.mmdb",
+            Some(&["latitude".to_string(), "longitude".to_string()]),
+        )
+        .unwrap();
+
+        let expected: BTreeMap<String, Value> = btreemap! {
+            "latitude" => Value::from(51.75),
+            "longitude" => Value::from(-1.25),
+        };
+
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn city_no_results() {
+        let values = find("10.1.12.1", "tests/data/GeoIP2-City-Test.mmdb");
+
+        assert_eq!(values, Err("IP 10.1.12.1 not found".to_string()));
+    }
+
+    #[test]
+    fn isp() {
+        let values = find("208.192.1.2", "tests/data/GeoIP2-ISP-Test.mmdb").unwrap();
+
+        let expected: BTreeMap<String, Value> = btreemap! {
+            "autonomous_system_number" => 701,
+            "autonomous_system_organization" => "MCI Communications Services, Inc. d/b/a Verizon Business",
+            "isp" => "Verizon Business",
+            "organization" => "Verizon Business",
+        };
+
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn asn() {
+        let values = find("2600:7000::1", "tests/data/GeoLite2-ASN-Test.mmdb").unwrap();
+
+        let expected: BTreeMap<String, Value> = btreemap! {
+            "autonomous_system_number" => 6939,
+            "autonomous_system_organization" => "Hurricane Electric, Inc.",
+            "isp" => Value::Null,
+            "organization" => Value::Null,
+        };
+
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn find_rows() {
+        let table = Geoip::new(GeoipConfig {
+            path: "tests/data/GeoLite2-ASN-Test.mmdb".to_string(),
+            locale: default_locale(),
+        })
+        .unwrap();
+        let find_rows = |ip: &str| {
+            table.find_table_rows(
+                &[Condition::Equals {
+                    field: "ip",
+                    value: ip.into(),
+                }],
+                Some(&["autonomous_system_number".to_string()]),
+                None,
+            )
+        };
+
+        assert_eq!(
+            find_rows("2600:7000::1"),
+            Ok(vec![btreemap! {
+                "autonomous_system_number" => 6939,
+            }])
+        );
+        assert_eq!(find_rows("10.1.12.1"), Ok(vec![]));
+    }
+
+    #[test]
+    fn invalid_ip() {
+        let values = find("not an ip", "tests/data/GeoIP2-City-Test.mmdb");
+
+        assert_eq!(
+            values,
+            Err(r#""not an ip" is not a valid IP address"#.to_string())
+        );
+    }
+
+    #[test]
+    fn unsupported_condition() {
+        let values = Geoip::new(GeoipConfig {
+            path: "tests/data/GeoIP2-City-Test.mmdb".to_string(),
+            locale: default_locale(),
+        })
+        .unwrap()
+        .find_table_row(
+            &[Condition::Equals {
+                field: "address",
+                value: "2.125.160.216".into(),
+            }],
+            None,
+            None,
+        );
+
+        assert_eq!(
+            values,
+            Err("only an equality condition on the `ip` field is supported".to_string())
+        );
+    }
+
+    #[test]
+    fn add_index() {
+        let mut table = Geoip::new(GeoipConfig {
+            path: "tests/data/GeoIP2-City-Test.mmdb".to_string(),
+            locale: default_locale(),
+        })
+        .unwrap();
+
+        assert_eq!(Ok(IndexHandle(0)), table.add_index(&["ip"]));
+        assert!(table.add_index(&["ip", "address"]).is_err());
+    }
+}
@@ -0,0 +1,497 @@
+use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig};
+use enrichment::{Condition, IndexHandle, Table};
+use lazy_static::lazy_static;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use vrl::Value;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryConfig {
+    pub key_field: String,
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+}
+
+// Rows that haven't been written for this long are no longer returned. Setting
+// it to 0 keeps rows until they are evicted.
+const fn default_ttl_secs() -> u64 {
+    3600
+}
+
+const fn default_max_entries() -> usize {
+    100_000
+}
+
+impl GenerateConfig for MemoryConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            key_field: "id".to_string(),
+            ttl_secs: default_ttl_secs(),
+            max_entries: default_max_entries(),
+        })
+        .unwrap()
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<MemoryConfig>("memory")
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "memory")]
+impl EnrichmentTableConfig for MemoryConfig {
+    async fn build(
+        &self,
+        name: &str,
+        _: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(Memory {
+            store: MemoryStore::get_or_create(name, self),
+        }))
+    }
+}
+
+lazy_static! {
+    // The stores are kept for the lifetime of the process, so the rows written
+    // to a table survive the topology being reloaded.
+    static ref STORES: Mutex<HashMap<String, Arc<MemoryStore>>> = Mutex::new(HashMap::new());
+}
+
+/// Returns the store of the memory table with the given name, if the table
+/// has been built.
+pub fn store(name: &str) -> Option<Arc<MemoryStore>> {
+    STORES.lock().unwrap().get(name).cloned()
+}
+
+struct Entry {
+    written: Instant,
+    row: BTreeMap<String, Value>,
+}
+
+struct Rows {
+    key_field: String,
+    ttl: Option<Duration>,
+    entries: LruCache<String, Entry>,
+}
+
+impl Rows {
+    fn is_live(&self, entry: &Entry) -> bool {
+        self.ttl
+            .map(|ttl| entry.written.elapsed() < ttl)
+            .unwrap_or(true)
+    }
+}
+
+/// The rows of a memory table, which are written by the `enrichment_table`
+/// sink and searched by the table.
+///
+/// Rows are keyed by the value of the key field, so writing a row with the
+/// same key replaces the existing row. Once the table is full the row that
+/// was written least recently is evicted.
+pub struct MemoryStore {
+    rows: RwLock<Rows>,
+}
+
+impl MemoryStore {
+    fn get_or_create(name: &str, config: &MemoryConfig) -> Arc<Self> {
+        let ttl = match config.ttl_secs {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        };
+        // The cache can't have a capacity of 0.
+        let max_entries = config.max_entries.max(1);
+
+        let mut stores = STORES.lock().unwrap();
+        match stores.get(name) {
+            Some(store) => {
+                let mut rows = store.rows.write().unwrap();
+                // The existing rows are keyed by the old field.
+                if rows.key_field != config.key_field {
+                    rows.key_field = config.key_field.clone();
+                    rows.entries.clear();
+                }
+                rows.ttl = ttl;
+                rows.entries.resize(max_entries);
+                drop(rows);
+
+                Arc::clone(store)
+            }
+            None => {
+                let store = Arc::new(Self {
+                    rows: RwLock::new(Rows {
+                        key_field: config.key_field.clone(),
+                        ttl,
+                        entries: LruCache::new(max_entries),
+                    }),
+                });
+                stores.insert(name.to_string(), Arc::clone(&store));
+
+                store
+            }
+        }
+    }
+
+    pub fn key_field(&self) -> String {
+        self.rows.read().unwrap().key_field.clone()
+    }
+
+    /// Writes the row to the table, replacing any row with the same key.
+    ///
+    /// Returns `false` if the row doesn't have the key field.
+    pub fn insert(&self, row: BTreeMap<String, Value>) -> bool {
+        let mut rows = self.rows.write().unwrap();
+        let key = match row.get(&rows.key_field) {
+            Some(value) => key(value),
+            None => return false,
+        };
+
+        // Reads don't change the order of the rows, so the expired rows are
+        // the least recently used.
+        while matches!(rows.entries.peek_lru(), Some((_, entry)) if !rows.is_live(entry)) {
+            rows.entries.pop_lru();
+        }
+
+        rows.entries.put(
+            key,
+            Entry {
+                written: Instant::now(),
+                row,
+            },
+        );
+
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.read().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn key(value: &Value) -> String {
+    match value {
+        Value::Bytes(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        value => value.to_string(),
+    }
+}
+
+/// An enrichment table whose rows are written by events sent to the
+/// `enrichment_table` sink, rather than loaded from a file.
+#[derive(Clone)]
+pub struct Memory {
+    store: Arc<MemoryStore>,
+}
+
+impl Memory {
+    fn search(
+        &self,
+        condition: &[Condition],
+        select: Option<&[String]>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        if let Some(condition) = condition.iter().find(|condition| {
+            !matches!(
+                condition,
+                Condition::Equals { .. } | Condition::BetweenDates { .. }
+            )
+        }) {
+            return Err(format!(
+                "condition {:?} is not supported by memory tables",
+                condition
+            ));
+        }
+
+        let rows = self.store.rows.read().unwrap();
+
+        // A search for the key only needs to look at a single row.
+        let keyed = condition.iter().find_map(|condition| match condition {
+            Condition::Equals { field, value } if *field == rows.key_field => Some(key(value)),
+            _ => None,
+        });
+        let entries: Box<dyn Iterator<Item = &Entry>> = match keyed {
+            Some(key) => Box::new(rows.entries.peek(&key).into_iter()),
+            None => Box::new(rows.entries.iter().map(|(_, entry)| entry)),
+        };
+
+        Ok(entries
+            .filter(|entry| rows.is_live(entry) && row_matches(condition, &entry.row))
+            .map(|entry| {
+                entry
+                    .row
+                    .iter()
+                    .filter(|(field, _)| {
+                        select
+                            .map(|select| select.contains(*field))
+                            // If no select is passed, we assume all fields are included
+                            .unwrap_or(true)
+                    })
+                    .map(|(field, value)| (field.clone(), value.clone()))
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+fn row_matches(condition: &[Condition], row: &BTreeMap<String, Value>) -> bool {
+    condition.iter().all(|condition| match condition {
+        Condition::Equals { field, value } => row.get(*field) == Some(value),
+        Condition::BetweenDates { field, from, to } => match row.get(*field) {
+            Some(Value::Timestamp(date)) => from <= date && date <= to,
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+impl Table for Memory {
+    /// Searches the table for the single row that matches the condition.
+    /// Unlike the `file` table, strings are matched case sensitively.
+    ///
+    /// # Errors
+    /// Errors if no rows, or more than 1 row is found.
+    fn find_table_row<'a>(
+        &self,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let mut rows = self.search(condition, select)?;
+        match rows.len() {
+            0 => Err("no rows found".to_string()),
+            1 => Ok(rows.remove(0)),
+            _ => Err("more than one row found".to_string()),
+        }
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        self.search(condition, select)
+    }
+
+    /// The rows are only indexed by the key field, which is looked up directly
+    /// when it is searched for. Since the fields of the rows aren't known in
+    /// advance, any fields can be searched.
+    fn add_index(&mut self, _: &[&str]) -> Result<IndexHandle, String> {
+        Ok(IndexHandle(0))
+    }
+}
+
+impl std::fmt::Debug for Memory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Memory {} rows", self.store.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use shared::btreemap;
+
+    fn config(key_field: &str) -> MemoryConfig {
+        MemoryConfig {
+            key_field: key_field.to_string(),
+            ttl_secs: default_ttl_secs(),
+            max_entries: default_max_entries(),
+        }
+    }
+
+    fn memory(name: &str, config: &MemoryConfig) -> Memory {
+        Memory {
+            store: MemoryStore::get_or_create(name, config),
+        }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MemoryConfig>();
+    }
+
+    #[test]
+    fn finds_row_by_key() {
+        let memory = memory("finds_row_by_key", &config("id"));
+        assert!(memory.store.insert(btreemap! {
+            "id" => "1",
+            "host" => "alpha",
+        }));
+        assert!(memory.store.insert(btreemap! {
+            "id" => "2",
+            "host" => "beta",
+        }));
+        assert!(!memory.store.insert(btreemap! { "host" => "gamma" }));
+
+        let condition = Condition::Equals {
+            field: "id",
+            value: Value::from("2"),
+        };
+
+        assert_eq!(
+            Ok(btreemap! {
+                "id" => "2",
+                "host" => "beta",
+            }),
+            memory.find_table_row(&[condition.clone()], None, None)
+        );
+        assert_eq!(
+            Ok(btreemap! { "host" => "beta" }),
+            memory.find_table_row(&[condition], Some(&["host".to_string()]), None)
+        );
+    }
+
+    #[test]
+    fn replaces_row_with_same_key() {
+        let memory = memory("replaces_row_with_same_key", &config("id"));
+        memory
+            .store
+            .insert(btreemap! { "id" => 1, "host" => "alpha" });
+        memory
+            .store
+            .insert(btreemap! { "id" => 1, "host" => "beta" });
+
+        assert_eq!(
+            Ok(vec![btreemap! { "id" => 1, "host" => "beta" }]),
+            memory.find_table_rows(
+                &[Condition::Equals {
+                    field: "id",
+                    value: Value::from(1),
+                }],
+                None,
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn finds_rows_by_other_fields() {
+        let memory = memory("finds_rows_by_other_fields", &config("id"));
+        memory.store.insert(btreemap! {
+            "id" => "1",
+            "host" => "alpha",
+            "seen" => chrono::Utc.ymd(2021, 6, 1).and_hms(0, 0, 0),
+        });
+        memory.store.insert(btreemap! {
+            "id" => "2",
+            "host" => "alpha",
+            "seen" => chrono::Utc.ymd(2021, 8, 1).and_hms(0, 0, 0),
+        });
+        memory.store.insert(btreemap! {
+            "id" => "3",
+            "host" => "beta",
+            "seen" => chrono::Utc.ymd(2021, 6, 1).and_hms(0, 0, 0),
+        });
+
+        let condition = vec![
+            Condition::Equals {
+                field: "host",
+                value: Value::from("alpha"),
+            },
+            Condition::BetweenDates {
+                field: "seen",
+                from: chrono::Utc.ymd(2021, 5, 1).and_hms(0, 0, 0),
+                to: chrono::Utc.ymd(2021, 7, 1).and_hms(0, 0, 0),
+            },
+        ];
+
+        assert_eq!(
+            Ok(vec![btreemap! { "id" => "1" }]),
+            memory.find_table_rows(&condition, Some(&["id".to_string()]), None)
+        );
+        assert_eq!(
+            Err("more than one row found".to_string()),
+            memory.find_table_row(&condition[..1], None, None)
+        );
+    }
+
+    #[test]
+    fn evicts_least_recently_written_rows() {
+        let memory = memory(
+            "evicts_least_recently_written_rows",
+            &MemoryConfig {
+                max_entries: 2,
+                ..config("id")
+            },
+        );
+        memory.store.insert(btreemap! { "id" => "1" });
+        memory.store.insert(btreemap! { "id" => "2" });
+        // Reading a row doesn't keep it in the table.
+        memory
+            .find_table_row(
+                &[Condition::Equals {
+                    field: "id",
+                    value: Value::from("1"),
+                }],
+                None,
+                None,
+            )
+            .unwrap();
+        memory.store.insert(btreemap! { "id" => "3" });
+
+        let mut ids = memory
+            .find_table_rows(&[], None, None)
+            .unwrap()
+            .into_iter()
+            .map(|row| row["id"].clone())
+            .collect::<Vec<_>>();
+        ids.sort_by_key(|id| id.to_string());
+
+        assert_eq!(vec![Value::from("2"), Value::from("3")], ids);
+    }
+
+    #[test]
+    fn expires_rows() {
+        let memory = memory("expires_rows", &config("id"));
+        memory.store.insert(btreemap! { "id" => "1" });
+        memory.store.rows.write().unwrap().ttl = Some(Duration::from_millis(0));
+
+        assert_eq!(Ok(vec![]), memory.find_table_rows(&[], None, None));
+
+        // The expired row is removed once another row is written.
+        memory.store.insert(btreemap! { "id" => "2" });
+        assert_eq!(1, memory.store.len());
+    }
+
+    #[test]
+    fn keeps_rows_when_rebuilt() {
+        let first = memory("keeps_rows_when_rebuilt", &config("id"));
+        first.store.insert(btreemap! { "id" => "1" });
+
+        let second = memory("keeps_rows_when_rebuilt", &config("id"));
+        assert_eq!(1, second.store.len());
+        assert_eq!(
+            Some(1),
+            store("keeps_rows_when_rebuilt").map(|store| store.len())
+        );
+
+        // The rows can't be found by a different key.
+        let third = memory("keeps_rows_when_rebuilt", &config("host"));
+        assert!(third.store.is_empty());
+    }
+
+    #[test]
+    fn unsupported_condition() {
+        let memory = memory("unsupported_condition", &config("id"));
+
+        assert!(memory
+            .find_table_rows(
+                &[Condition::PrefixOf {
+                    field: "id",
+                    value: Value::from("1"),
+                }],
+                None,
+                None
+            )
+            .is_err());
+    }
+}
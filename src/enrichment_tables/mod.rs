@@ -2,3 +2,10 @@ pub use enrichment::{Condition, IndexHandle, Table};
 
 #[cfg(feature = "enrichment-tables-file")]
 pub mod file;
+#[cfg(feature = "enrichment-tables-geoip")]
+pub mod geoip;
+#[cfg(feature = "enrichment-tables-memory")]
+pub mod memory;
+pub mod reload;
+#[cfg(feature = "enrichment-tables-sqlite")]
+pub mod sqlite;
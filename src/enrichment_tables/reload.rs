@@ -0,0 +1,234 @@
+//! Reloads enrichment tables in the background when the files they are loaded
+//! from change.
+//!
+//! Each table with a `reload_interval_secs` gets a task that checks the
+//! modification time and size of the files returned by
+//! `EnrichmentTableConfig::watch_paths` at every interval. Once they have
+//! changed the table is rebuilt from its config on a blocking thread, and
+//! swapped into the `TableRegistry` for all the transforms searching it.
+
+use crate::{
+    config::{EnrichmentTableConfig, GlobalOptions},
+    internal_events::{EnrichmentTableReloadFailed, EnrichmentTableReloaded},
+};
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+use tokio::task::JoinHandle;
+
+lazy_static! {
+    static ref RELOADERS: Mutex<HashMap<String, JoinHandle<()>>> = Mutex::new(HashMap::new());
+}
+
+/// The state of the files a table is loaded from, used to detect changes.
+/// Files that can't be read are recorded as `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fingerprint(Vec<Option<(SystemTime, u64)>>);
+
+impl Fingerprint {
+    pub fn new(paths: &[PathBuf]) -> Self {
+        Self(
+            paths
+                .iter()
+                .map(|path| {
+                    let metadata = std::fs::metadata(path).ok()?;
+                    Some((metadata.modified().ok()?, metadata.len()))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Starts reloading the given table every `interval`, replacing any task
+/// already reloading a table with the same name.
+///
+/// The fingerprint is the state of the files when the table was loaded.
+pub fn watch(
+    table: String,
+    config: Box<dyn EnrichmentTableConfig>,
+    globals: GlobalOptions,
+    interval: Duration,
+    fingerprint: Fingerprint,
+    registry: enrichment::TableRegistry,
+) {
+    let handle = tokio::spawn(reload_loop(
+        table.clone(),
+        config,
+        globals,
+        interval,
+        fingerprint,
+        registry,
+    ));
+
+    if let Some(previous) = RELOADERS.lock().unwrap().insert(table, handle) {
+        previous.abort();
+    }
+}
+
+/// Stops reloading the given table.
+pub fn unwatch(table: &str) {
+    if let Some(previous) = RELOADERS.lock().unwrap().remove(table) {
+        previous.abort();
+    }
+}
+
+async fn reload_loop(
+    table: String,
+    config: Box<dyn EnrichmentTableConfig>,
+    globals: GlobalOptions,
+    interval: Duration,
+    mut fingerprint: Fingerprint,
+    registry: enrichment::TableRegistry,
+) {
+    let paths = config.watch_paths();
+    let mut interval = tokio::time::interval(interval);
+    // The first tick completes immediately, and the table has just been loaded.
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let current = Fingerprint::new(&paths);
+        if !paths.is_empty() && current == fingerprint {
+            continue;
+        }
+
+        match reload(&table, &*config, &globals, &registry).await {
+            Ok(rows) => {
+                emit!(EnrichmentTableReloaded {
+                    table: &table,
+                    rows
+                });
+                fingerprint = current;
+            }
+            // The fingerprint is kept, so the reload is attempted again at the
+            // next interval. This handles files that are still being written.
+            Err(error) => emit!(EnrichmentTableReloadFailed {
+                table: &table,
+                error
+            }),
+        }
+    }
+}
+
+async fn reload(
+    table: &str,
+    config: &dyn EnrichmentTableConfig,
+    globals: &GlobalOptions,
+    registry: &enrichment::TableRegistry,
+) -> Result<Option<usize>, String> {
+    let table = table.to_owned();
+    let config = dyn_clone::clone_box(config);
+    let globals = globals.clone();
+    let registry = registry.clone();
+    let runtime = tokio::runtime::Handle::current();
+
+    // Reading the files and indexing the rows is blocking work, which can
+    // take a while for large tables.
+    tokio::task::spawn_blocking(move || -> Result<Option<usize>, String> {
+        let data = runtime
+            .block_on(config.build(&table, &globals))
+            .map_err(|error| error.to_string())?;
+        let rows = data.row_count();
+
+        registry.reload_table(&table, data)?;
+
+        Ok(rows)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+#[cfg(all(test, feature = "enrichment-tables-file"))]
+mod tests {
+    use super::*;
+    use crate::{enrichment_tables::Condition, test_util::temp_file};
+    use shared::btreemap;
+
+    fn file_config(path: &std::path::Path) -> Box<dyn EnrichmentTableConfig> {
+        toml::from_str::<crate::config::EnrichmentTableOuter>(&format!(
+            r#"
+                type = "file"
+
+                [file]
+                path = "{}"
+                encoding = {{ type = "csv" }}
+            "#,
+            path.display()
+        ))
+        .unwrap()
+        .inner
+    }
+
+    #[test]
+    fn fingerprint_changes() {
+        let path = temp_file();
+        let missing = Fingerprint::new(&[path.clone()]);
+        assert_eq!(Fingerprint(vec![None]), missing);
+
+        std::fs::write(&path, "id,name\n1,one\n").unwrap();
+        let written = Fingerprint::new(&[path.clone()]);
+        assert_ne!(missing, written);
+
+        std::fs::write(&path, "id,name\n1,one\n2,two\n").unwrap();
+        assert_ne!(written, Fingerprint::new(&[path]));
+    }
+
+    #[tokio::test]
+    async fn reloads_changed_file() {
+        let path = temp_file();
+        std::fs::write(&path, "id,name\n1,one\n").unwrap();
+
+        let config = file_config(&path);
+        let globals = GlobalOptions::default();
+        let fingerprint = Fingerprint::new(&[path.clone()]);
+        let mut table = config.build("ids", &globals).await.unwrap();
+        let index = table.add_index(&["id"]).unwrap();
+
+        let registry = enrichment::TableRegistry::default();
+        let mut tables = HashMap::new();
+        tables.insert("ids".to_string(), table);
+        registry.load(tables);
+        registry.finish_load();
+
+        watch(
+            "ids".to_string(),
+            config,
+            globals,
+            Duration::from_millis(10),
+            fingerprint,
+            registry.clone(),
+        );
+
+        std::fs::write(&path, "id,name\n1,eins\n").unwrap();
+
+        let search = registry.as_readonly();
+        let condition = [Condition::Equals {
+            field: "id",
+            value: "1".into(),
+        }];
+        let mut found = None;
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            found = search
+                .find_table_row("ids", &condition, None, Some(index))
+                .ok();
+            if found.as_ref().and_then(|row| row.get("name")) == Some(&"eins".into()) {
+                break;
+            }
+        }
+        unwatch("ids");
+
+        assert_eq!(
+            Some(btreemap! {
+                "id" => "1",
+                "name" => "eins",
+            }),
+            found
+        );
+    }
+}
@@ -0,0 +1,702 @@
+use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig};
+use bytes::Bytes;
+use enrichment::{Condition, IndexHandle, Table};
+use lru::LruCache;
+use rusqlite::{
+    params_from_iter,
+    types::{Value as SqlValue, ValueRef},
+    Connection, OpenFlags,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+use vrl::Value;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteConfig {
+    pub path: PathBuf,
+    pub table: String,
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+}
+
+// The number of distinct lookups whose results are kept in memory. Setting it
+// to 0 disables the cache.
+const fn default_cache_size() -> usize {
+    10_000
+}
+
+impl GenerateConfig for SqliteConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            path: PathBuf::from("/path/to/database.sqlite"),
+            table: "assets".to_string(),
+            cache_size: default_cache_size(),
+        })
+        .unwrap()
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<SqliteConfig>("sqlite")
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sqlite")]
+impl EnrichmentTableConfig for SqliteConfig {
+    async fn build(
+        &self,
+        _: &str,
+        _: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(Sqlite::new(self.clone())?))
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+}
+
+type Rows = Vec<BTreeMap<String, Value>>;
+
+/// An enrichment table that queries a table in a SQLite database as rows are
+/// searched for, rather than loading the data into memory.
+///
+/// Conditions are converted into a parameterised query, so any indexes in the
+/// database for the searched columns are used.
+#[derive(Clone)]
+pub struct Sqlite {
+    config: SqliteConfig,
+    columns: Vec<String>,
+    // The columns of each of the indexes in the database table.
+    database_indexes: Vec<Vec<String>>,
+    indexes: Vec<Vec<String>>,
+    // Connections can't be shared between threads, so the transforms searching
+    // the table take one from the pool for the duration of each query.
+    connections: Arc<Mutex<Vec<Connection>>>,
+    cache: Option<Arc<Mutex<LruCache<String, Rows>>>>,
+}
+
+impl Sqlite {
+    pub fn new(config: SqliteConfig) -> crate::Result<Self> {
+        let connection = open(&config)?;
+        let table = quote_identifier(&config.table);
+
+        let columns: Vec<String> = connection
+            .prepare(&format!("SELECT * FROM {} LIMIT 0", table))?
+            .column_names()
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        let index_names = connection
+            .prepare(&format!("PRAGMA index_list({})", table))?
+            .query_map([], |row| row.get::<_, String>("name"))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let database_indexes = index_names
+            .iter()
+            .map(|name| {
+                connection
+                    .prepare(&format!("PRAGMA index_info({})", quote_identifier(name)))?
+                    .query_map([], |row| row.get::<_, Option<String>>("name"))?
+                    .filter_map(Result::transpose)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let cache = match config.cache_size {
+            0 => None,
+            size => Some(Arc::new(Mutex::new(LruCache::new(size)))),
+        };
+
+        Ok(Self {
+            config,
+            columns,
+            database_indexes,
+            indexes: Vec::new(),
+            connections: Arc::new(Mutex::new(vec![connection])),
+            cache,
+        })
+    }
+
+    fn column(&self, field: &str) -> Result<(), String> {
+        if self.columns.iter().any(|column| column == field) {
+            Ok(())
+        } else {
+            Err(format!(
+                "field {} not in table {}",
+                field, self.config.table
+            ))
+        }
+    }
+
+    /// Builds the query for the condition, returning the SQL, its parameters
+    /// and the columns that are selected.
+    fn query(
+        &self,
+        condition: &[Condition],
+        select: Option<&[String]>,
+        limit: Option<usize>,
+    ) -> Result<(String, Vec<SqlValue>, Vec<String>), String> {
+        let columns = self
+            .columns
+            .iter()
+            .filter(|column| {
+                select
+                    .map(|select| select.contains(*column))
+                    // If no select is passed, we assume all columns are included
+                    .unwrap_or(true)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut sql = if columns.is_empty() {
+            "SELECT NULL".to_string()
+        } else {
+            format!(
+                "SELECT {}",
+                columns
+                    .iter()
+                    .map(|column| quote_identifier(column))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        sql.push_str(" FROM ");
+        sql.push_str(&quote_identifier(&self.config.table));
+
+        let mut params = Vec::new();
+        let mut clauses = Vec::new();
+        for condition in condition {
+            match condition {
+                Condition::Equals { field, value } => {
+                    self.column(field)?;
+                    match to_sql(value)? {
+                        SqlValue::Null => {
+                            clauses.push(format!("{} IS NULL", quote_identifier(field)))
+                        }
+                        value => {
+                            clauses.push(format!("{} = ?", quote_identifier(field)));
+                            params.push(value);
+                        }
+                    }
+                }
+                // Dates are expected to be stored in one of the formats understood by SQLite's
+                // date and time functions.
+                Condition::BetweenDates { field, from, to } => {
+                    self.column(field)?;
+                    clauses.push(format!(
+                        "julianday({}) BETWEEN julianday(?) AND julianday(?)",
+                        quote_identifier(field)
+                    ));
+                    params.push(SqlValue::Text(format_timestamp(from)));
+                    params.push(SqlValue::Text(format_timestamp(to)));
+                }
+                Condition::BetweenFields {
+                    from_field,
+                    to_field,
+                    value,
+                } => {
+                    self.column(from_field)?;
+                    self.column(to_field)?;
+                    let (from_field, to_field) =
+                        (quote_identifier(from_field), quote_identifier(to_field));
+                    match value {
+                        Value::Timestamp(timestamp) => {
+                            clauses.push(format!(
+                                "julianday(?) BETWEEN julianday({}) AND julianday({})",
+                                from_field, to_field
+                            ));
+                            params.push(SqlValue::Text(format_timestamp(timestamp)));
+                        }
+                        value => {
+                            clauses.push(format!("? BETWEEN {} AND {}", from_field, to_field));
+                            params.push(to_sql(value)?);
+                        }
+                    }
+                }
+                Condition::PrefixOf { field, value } => {
+                    self.column(field)?;
+                    clauses.push(format!(
+                        "substr(?, 1, length({0})) = {0}",
+                        quote_identifier(field)
+                    ));
+                    params.push(to_sql(value)?);
+                }
+                Condition::IpInCidr { .. } => return Err(
+                    "searching for an IP address in a network is not supported by sqlite tables"
+                        .to_string(),
+                ),
+            }
+        }
+
+        if !clauses.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        Ok((sql, params, columns))
+    }
+
+    /// Searches the table, using the cached rows if the same search has been
+    /// made before.
+    fn search(
+        &self,
+        condition: &[Condition],
+        select: Option<&[String]>,
+        limit: Option<usize>,
+    ) -> Result<Rows, String> {
+        let (sql, params, columns) = self.query(condition, select, limit)?;
+
+        let key = match self.cache {
+            Some(ref cache) => {
+                let key = format!("{}\0{:?}", sql, params);
+                if let Some(rows) = cache.lock().unwrap().get(&key) {
+                    return Ok(rows.clone());
+                }
+                Some(key)
+            }
+            None => None,
+        };
+
+        let rows = self
+            .with_connection(|connection| {
+                let mut statement = connection.prepare_cached(&sql)?;
+                let mut rows = statement.query(params_from_iter(params.iter()))?;
+
+                let mut result = Rows::new();
+                while let Some(row) = rows.next()? {
+                    result.push(
+                        columns
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, column)| Ok((column.clone(), from_sql(row.get_ref(idx)?))))
+                            .collect::<rusqlite::Result<_>>()?,
+                    );
+                }
+
+                Ok(result)
+            })
+            .map_err(|error| format!("unable to search table {}: {}", self.config.table, error))?;
+
+        if let (Some(cache), Some(key)) = (&self.cache, key) {
+            cache.lock().unwrap().put(key, rows.clone());
+        }
+
+        Ok(rows)
+    }
+
+    /// Runs the function with a connection from the pool, opening a new
+    /// connection if they are all in use.
+    fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+    ) -> rusqlite::Result<T> {
+        let pooled = self.connections.lock().unwrap().pop();
+        let connection = match pooled {
+            Some(connection) => connection,
+            None => open(&self.config)?,
+        };
+
+        let result = f(&connection);
+        self.connections.lock().unwrap().push(connection);
+
+        result
+    }
+}
+
+fn open(config: &SqliteConfig) -> rusqlite::Result<Connection> {
+    Connection::open_with_flags(
+        &config.path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn format_timestamp(timestamp: &chrono::DateTime<chrono::Utc>) -> String {
+    timestamp.format("%Y-%m-%d %H:%M:%S%.f").to_string()
+}
+
+fn to_sql(value: &Value) -> Result<SqlValue, String> {
+    Ok(match value {
+        Value::Bytes(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => SqlValue::Text(text.to_string()),
+            Err(_) => SqlValue::Blob(bytes.to_vec()),
+        },
+        Value::Integer(integer) => SqlValue::Integer(*integer),
+        Value::Float(float) => SqlValue::Real(float.into_inner()),
+        Value::Boolean(boolean) => SqlValue::Integer(*boolean as i64),
+        Value::Timestamp(timestamp) => SqlValue::Text(format_timestamp(timestamp)),
+        Value::Null => SqlValue::Null,
+        value => {
+            return Err(format!(
+                "unable to search for a value of type {}",
+                value.kind()
+            ))
+        }
+    })
+}
+
+fn from_sql(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(integer) => Value::Integer(integer),
+        ValueRef::Real(real) => real.into(),
+        ValueRef::Text(bytes) | ValueRef::Blob(bytes) => {
+            Value::Bytes(Bytes::copy_from_slice(bytes))
+        }
+    }
+}
+
+/// Returns an error if there isn't exactly one row.
+fn single_or_err(mut rows: Rows) -> Result<BTreeMap<String, Value>, String> {
+    match rows.len() {
+        0 => Err("no rows found".to_string()),
+        1 => Ok(rows.remove(0)),
+        _ => Err("more than one row found".to_string()),
+    }
+}
+
+impl Table for Sqlite {
+    /// Searches the table for the single row that matches the condition.
+    /// Unlike the `file` table, strings are matched case sensitively, so that
+    /// the indexes in the database can be used.
+    ///
+    /// # Errors
+    /// Errors if no rows, or more than 1 row is found.
+    fn find_table_row<'a>(
+        &self,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        // Only two rows are needed to know there is more than one.
+        single_or_err(self.search(condition, select, Some(2))?)
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        self.search(condition, select, None)
+    }
+
+    /// The data is indexed by the database, so this only checks the fields are
+    /// in the table and warns if there is no index in the database to use for
+    /// them.
+    ///
+    /// # Errors
+    /// Errors if the fields are not in the table.
+    fn add_index(&mut self, fields: &[&str]) -> Result<IndexHandle, String> {
+        for field in fields {
+            self.column(field)?;
+        }
+
+        // Order the fields in the index according to the position they are found in the table.
+        let normalized = self
+            .columns
+            .iter()
+            .filter(|column| fields.contains(&column.as_str()))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // SQLite can only use an index if the first column in it is searched.
+        if !normalized.is_empty()
+            && !self.database_indexes.iter().any(|index| {
+                index
+                    .first()
+                    .map(|column| normalized.contains(column))
+                    .unwrap_or(false)
+            })
+        {
+            warn!(
+                message = "No index in the database for the searched fields, searches will scan the table.",
+                table = %self.config.table,
+                fields = ?normalized,
+            );
+        }
+
+        match self.indexes.iter().position(|index| *index == normalized) {
+            Some(pos) => Ok(IndexHandle(pos)),
+            None => {
+                self.indexes.push(normalized);
+                Ok(IndexHandle(self.indexes.len() - 1))
+            }
+        }
+    }
+
+    fn index_fields(&self) -> Vec<Vec<String>> {
+        self.indexes.clone()
+    }
+}
+
+impl std::fmt::Debug for Sqlite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Sqlite table {} in {}",
+            self.config.table,
+            self.config.path.display()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_file;
+    use chrono::{TimeZone, Utc};
+    use shared::btreemap;
+
+    fn database() -> PathBuf {
+        let path = temp_file();
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                r#"
+                CREATE TABLE hosts (
+                    hostname TEXT NOT NULL,
+                    owner TEXT,
+                    cores INTEGER,
+                    load REAL,
+                    added TEXT
+                );
+                CREATE INDEX hosts_hostname ON hosts (hostname);
+                INSERT INTO hosts VALUES
+                    ('alpha', 'ops', 4, 0.5, '2021-01-01 10:00:00'),
+                    ('beta', 'dev', 8, 1.5, '2021-06-01 10:00:00'),
+                    ('gamma', 'dev', NULL, NULL, '2021-09-01 10:00:00');
+                "#,
+            )
+            .unwrap();
+        path
+    }
+
+    fn table(path: &std::path::Path, cache_size: usize) -> Sqlite {
+        Sqlite::new(SqliteConfig {
+            path: path.to_path_buf(),
+            table: "hosts".to_string(),
+            cache_size,
+        })
+        .unwrap()
+    }
+
+    fn equals<'a>(field: &'a str, value: &str) -> Condition<'a> {
+        Condition::Equals {
+            field,
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqliteConfig>();
+    }
+
+    #[test]
+    fn finds_row() {
+        let table = table(&database(), default_cache_size());
+
+        assert_eq!(
+            Ok(btreemap! {
+                "hostname" => "beta",
+                "owner" => "dev",
+                "cores" => 8,
+                "load" => 1.5,
+                "added" => "2021-06-01 10:00:00",
+            }),
+            table.find_table_row(&[equals("hostname", "beta")], None, None)
+        );
+    }
+
+    #[test]
+    fn finds_row_with_select() {
+        let table = table(&database(), default_cache_size());
+
+        assert_eq!(
+            Ok(btreemap! {
+                "cores" => Value::Null,
+                "owner" => "dev",
+            }),
+            table.find_table_row(
+                &[equals("hostname", "gamma")],
+                Some(&["owner".to_string(), "cores".to_string()]),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn finds_rows() {
+        let table = table(&database(), default_cache_size());
+
+        assert_eq!(
+            Ok(vec![
+                btreemap! { "hostname" => "beta" },
+                btreemap! { "hostname" => "gamma" },
+            ]),
+            table.find_table_rows(
+                &[equals("owner", "dev")],
+                Some(&["hostname".to_string()]),
+                None
+            )
+        );
+        assert_eq!(
+            Ok(vec![]),
+            table.find_table_rows(&[equals("owner", "qa")], None, None)
+        );
+    }
+
+    #[test]
+    fn single_row_errors() {
+        let table = table(&database(), default_cache_size());
+
+        assert_eq!(
+            Err("more than one row found".to_string()),
+            table.find_table_row(&[equals("owner", "dev")], None, None)
+        );
+        assert_eq!(
+            Err("no rows found".to_string()),
+            table.find_table_row(&[equals("hostname", "delta")], None, None)
+        );
+    }
+
+    #[test]
+    fn finds_rows_with_dates() {
+        let table = table(&database(), default_cache_size());
+
+        assert_eq!(
+            Ok(vec![btreemap! { "hostname" => "beta" }]),
+            table.find_table_rows(
+                &[
+                    equals("owner", "dev"),
+                    Condition::BetweenDates {
+                        field: "added",
+                        from: Utc.ymd(2021, 5, 1).and_hms(0, 0, 0),
+                        to: Utc.ymd(2021, 7, 1).and_hms(0, 0, 0),
+                    },
+                ],
+                Some(&["hostname".to_string()]),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn finds_row_between_fields_and_with_prefix() {
+        let path = temp_file();
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                r#"
+                CREATE TABLE numbers (prefix TEXT, low INTEGER, high INTEGER, country TEXT);
+                INSERT INTO numbers VALUES
+                    ('44', 1000, 1999, 'uk'),
+                    ('44', 2000, 2999, 'uk-mobile'),
+                    ('33', 1000, 2999, 'fr');
+                "#,
+            )
+            .unwrap();
+        let table = Sqlite::new(SqliteConfig {
+            path,
+            table: "numbers".to_string(),
+            cache_size: default_cache_size(),
+        })
+        .unwrap();
+
+        assert_eq!(
+            Ok(btreemap! { "country" => "uk-mobile" }),
+            table.find_table_row(
+                &[
+                    Condition::PrefixOf {
+                        field: "prefix",
+                        value: "4420".into(),
+                    },
+                    Condition::BetweenFields {
+                        from_field: "low",
+                        to_field: "high",
+                        value: 2000.into(),
+                    },
+                ],
+                Some(&["country".to_string()]),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_field() {
+        let mut table = table(&database(), default_cache_size());
+
+        assert_eq!(
+            Err("field name not in table hosts".to_string()),
+            table.find_table_row(&[equals("name", "beta")], None, None)
+        );
+        assert_eq!(
+            Err("field name not in table hosts".to_string()),
+            table.add_index(&["name"])
+        );
+    }
+
+    #[test]
+    fn index_fields() {
+        let mut table = table(&database(), default_cache_size());
+
+        assert_eq!(Ok(IndexHandle(0)), table.add_index(&["owner", "hostname"]));
+        assert_eq!(Ok(IndexHandle(1)), table.add_index(&["cores"]));
+        assert_eq!(Ok(IndexHandle(0)), table.add_index(&["hostname", "owner"]));
+
+        assert_eq!(
+            vec![
+                vec!["hostname".to_string(), "owner".to_string()],
+                vec!["cores".to_string()],
+            ],
+            table.index_fields()
+        );
+    }
+
+    #[test]
+    fn caches_rows() {
+        let path = database();
+        let cached = table(&path, default_cache_size());
+        let uncached = table(&path, 0);
+        let condition = [equals("hostname", "alpha")];
+
+        assert!(cached.find_table_row(&condition, None, None).is_ok());
+        assert!(uncached.find_table_row(&condition, None, None).is_ok());
+
+        Connection::open(&path)
+            .unwrap()
+            .execute("DELETE FROM hosts WHERE hostname = 'alpha'", [])
+            .unwrap();
+
+        assert!(cached.find_table_row(&condition, None, None).is_ok());
+        assert_eq!(
+            Err("no rows found".to_string()),
+            uncached.find_table_row(&condition, None, None)
+        );
+    }
+
+    #[test]
+    fn missing_table() {
+        let result = Sqlite::new(SqliteConfig {
+            path: database(),
+            table: "users".to_string(),
+            cache_size: default_cache_size(),
+        });
+
+        assert!(result.is_err());
+    }
+}
@@ -15,3 +15,48 @@ impl InternalEvent for DedupeEventDiscarded {
         counter!("events_discarded_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct DedupeCacheLookup {
+    pub hit: bool,
+}
+
+impl InternalEvent for DedupeCacheLookup {
+    fn emit_metrics(&self) {
+        if self.hit {
+            counter!("cache_hits_total", 1);
+        } else {
+            counter!("cache_misses_total", 1);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DedupeCacheEvicted {
+    pub reason: &'static str,
+}
+
+impl InternalEvent for DedupeCacheEvicted {
+    fn emit_metrics(&self) {
+        counter!("cache_evictions_total", 1, "reason" => self.reason);
+    }
+}
+
+#[derive(Debug)]
+pub struct DedupeKeyFailed {
+    pub error: String,
+}
+
+impl InternalEvent for DedupeKeyFailed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to build key; passing event through.",
+            error = %self.error,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "failed_key");
+    }
+}
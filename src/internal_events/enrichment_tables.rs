@@ -0,0 +1,118 @@
+use super::InternalEvent;
+use metrics::{counter, gauge};
+
+#[derive(Debug)]
+pub struct EnrichmentTableLoaded<'a> {
+    pub table: &'a str,
+    pub rows: Option<usize>,
+}
+
+impl InternalEvent for EnrichmentTableLoaded<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Loaded enrichment table.",
+            table = %self.table,
+            rows = ?self.rows,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        if let Some(rows) = self.rows {
+            gauge!(
+                "enrichment_table_rows", rows as f64,
+                "table" => self.table.to_owned(),
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloaded<'a> {
+    pub table: &'a str,
+    pub rows: Option<usize>,
+}
+
+impl InternalEvent for EnrichmentTableReloaded<'_> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Reloaded enrichment table.",
+            table = %self.table,
+            rows = ?self.rows,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "enrichment_table_reloads_total", 1,
+            "table" => self.table.to_owned(),
+        );
+        if let Some(rows) = self.rows {
+            gauge!(
+                "enrichment_table_rows", rows as f64,
+                "table" => self.table.to_owned(),
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloadFailed<'a> {
+    pub table: &'a str,
+    pub error: String,
+}
+
+impl InternalEvent for EnrichmentTableReloadFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to reload enrichment table, keeping the current data.",
+            table = %self.table,
+            error = %self.error,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "enrichment_table_reload_errors_total", 1,
+            "table" => self.table.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableRowWritten<'a> {
+    pub table: &'a str,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for EnrichmentTableRowWritten<'_> {
+    fn emit_metrics(&self) {
+        counter!("processed_bytes_total", self.byte_size as u64);
+        counter!(
+            "enrichment_table_rows_written_total", 1,
+            "table" => self.table.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableKeyFieldMissing<'a> {
+    pub table: &'a str,
+    pub field: &'a str,
+}
+
+impl InternalEvent for EnrichmentTableKeyFieldMissing<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Key field does not exist, dropping event.",
+            table = %self.table,
+            field = %self.field,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "field_missing");
+        counter!("events_discarded_total", 1);
+    }
+}
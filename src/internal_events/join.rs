@@ -0,0 +1,57 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct JoinEventsMatched;
+
+impl InternalEvent for JoinEventsMatched {
+    fn emit_metrics(&self) {
+        counter!("joined_events_total", 1);
+    }
+}
+
+/// Why unmatched events were flushed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JoinFlushReason {
+    /// The window of their key ended.
+    WindowEnded,
+    /// Their key was the oldest one once `max_pending` was reached.
+    TooManyKeys,
+    /// Their side of the key already held `max_events_per_key` events.
+    TooManyEvents,
+}
+
+#[derive(Debug)]
+pub struct JoinUnmatchedEventsFlushed {
+    pub count: usize,
+    pub reason: JoinFlushReason,
+    pub discarded: bool,
+}
+
+impl InternalEvent for JoinUnmatchedEventsFlushed {
+    fn emit_logs(&self) {
+        match self.reason {
+            JoinFlushReason::WindowEnded => (),
+            JoinFlushReason::TooManyKeys => debug!(
+                message = "Too many pending keys, flushing the oldest unmatched events.",
+                count = %self.count,
+                internal_log_rate_secs = 10
+            ),
+            JoinFlushReason::TooManyEvents => debug!(
+                message = "Too many events for a key, flushing the event unmatched.",
+                count = %self.count,
+                internal_log_rate_secs = 10
+            ),
+        }
+    }
+
+    fn emit_metrics(&self) {
+        counter!("stale_events_flushed_total", self.count as u64);
+        if self.reason != JoinFlushReason::WindowEnded {
+            counter!("join_evicted_events_total", self.count as u64);
+        }
+        if self.discarded {
+            counter!("events_discarded_total", self.count as u64);
+        }
+    }
+}
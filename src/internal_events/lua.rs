@@ -41,3 +41,24 @@ impl InternalEvent for LuaBuildError {
         counter!("processing_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct LuaSandboxViolation {
+    pub hook: &'static str,
+    pub violation: crate::transforms::lua::v2::Violation,
+}
+
+impl InternalEvent for LuaSandboxViolation {
+    fn emit_logs(&self) {
+        error!(
+            message = "Lua script exceeded a sandbox limit.",
+            hook = %self.hook,
+            violation = %self.violation,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => self.violation.as_str());
+    }
+}
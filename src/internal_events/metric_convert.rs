@@ -0,0 +1,17 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct MetricConvertSeriesExpired {
+    pub count: usize,
+}
+
+impl InternalEvent for MetricConvertSeriesExpired {
+    fn emit_logs(&self) {
+        trace!(message = "Expired idle series.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("series_expired_total", self.count as u64);
+    }
+}
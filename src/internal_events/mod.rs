@@ -48,6 +48,7 @@ mod dnstap;
 mod docker_logs;
 mod elasticsearch;
 mod encoding_transcode;
+mod enrichment_tables;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 mod eventstoredb_metrics;
 #[cfg(feature = "sources-exec")]
@@ -67,6 +68,8 @@ mod heartbeat;
 mod host_metrics;
 mod http;
 pub mod http_client;
+#[cfg(feature = "transforms-join")]
+mod join;
 #[cfg(all(unix, feature = "sources-journald"))]
 mod journald;
 #[cfg(feature = "transforms-json_parser")]
@@ -86,6 +89,8 @@ mod logplex;
 mod loki;
 #[cfg(feature = "transforms-lua")]
 mod lua;
+#[cfg(feature = "transforms-metric_convert")]
+mod metric_convert;
 #[cfg(feature = "transforms-metric_to_log")]
 mod metric_to_log;
 #[cfg(feature = "sources-mongodb_metrics")]
@@ -107,11 +112,15 @@ mod redis;
 mod reduce;
 #[cfg(feature = "transforms-regex_parser")]
 mod regex_parser;
+#[cfg(feature = "transforms-relabel")]
+mod relabel;
 mod remap;
 #[cfg(feature = "transforms-remove_fields")]
 mod remove_fields;
 #[cfg(feature = "transforms-rename_fields")]
 mod rename_fields;
+#[cfg(feature = "transforms-reorder")]
+mod reorder;
 #[cfg(feature = "transforms-route")]
 mod route;
 mod sample;
@@ -121,6 +130,8 @@ mod socket;
 mod split;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
+#[cfg(feature = "transforms-sql")]
+mod sql;
 #[cfg(feature = "sinks-statsd")]
 mod statsd_sink;
 #[cfg(feature = "sources-statsd")]
@@ -131,12 +142,18 @@ mod syslog;
 mod tag_cardinality_limit;
 mod tcp;
 mod template;
+#[cfg(feature = "transforms-throttle")]
+mod throttle;
 #[cfg(feature = "transforms-tokenizer")]
 mod tokenizer;
 mod topology;
+#[cfg(any(feature = "transforms-dedupe", feature = "transforms-reduce"))]
+mod transform_state;
 mod udp;
 mod unix;
 mod vector;
+#[cfg(feature = "transforms-wasm")]
+mod wasm;
 
 pub mod kubernetes;
 
@@ -184,6 +201,7 @@ pub(crate) use self::dnstap::*;
 pub use self::docker_logs::*;
 pub use self::elasticsearch::*;
 pub use self::encoding_transcode::*;
+pub use self::enrichment_tables::*;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 pub use self::eventstoredb_metrics::*;
 #[cfg(feature = "sources-exec")]
@@ -209,6 +227,8 @@ pub use self::heartbeat::*;
 pub(crate) use self::host_metrics::*;
 #[cfg(any(feature = "sources-utils-http", feature = "sinks-http"))]
 pub(crate) use self::http::*;
+#[cfg(feature = "transforms-join")]
+pub(crate) use self::join::*;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub(crate) use self::journald::*;
 #[cfg(feature = "transforms-json_parser")]
@@ -228,6 +248,8 @@ pub use self::logplex::*;
 pub(crate) use self::loki::*;
 #[cfg(feature = "transforms-lua")]
 pub use self::lua::*;
+#[cfg(feature = "transforms-metric_convert")]
+pub(crate) use self::metric_convert::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
 #[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
@@ -247,11 +269,15 @@ pub use self::redis::*;
 pub(crate) use self::reduce::*;
 #[cfg(feature = "transforms-regex_parser")]
 pub(crate) use self::regex_parser::*;
+#[cfg(feature = "transforms-relabel")]
+pub(crate) use self::relabel::*;
 pub use self::remap::*;
 #[cfg(feature = "transforms-remove_fields")]
 pub use self::remove_fields::*;
 #[cfg(feature = "transforms-rename_fields")]
 pub use self::rename_fields::*;
+#[cfg(feature = "transforms-reorder")]
+pub(crate) use self::reorder::*;
 #[cfg(feature = "transforms-route")]
 pub use self::route::*;
 pub use self::sample::*;
@@ -261,6 +287,8 @@ pub(crate) use self::socket::*;
 pub use self::split::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
+#[cfg(feature = "transforms-sql")]
+pub(crate) use self::sql::*;
 #[cfg(feature = "sinks-statsd")]
 pub use self::statsd_sink::*;
 #[cfg(feature = "sources-statsd")]
@@ -271,12 +299,18 @@ pub use self::syslog::*;
 pub(crate) use self::tag_cardinality_limit::*;
 pub use self::tcp::*;
 pub use self::template::*;
+#[cfg(feature = "transforms-throttle")]
+pub use self::throttle::*;
 #[cfg(feature = "transforms-tokenizer")]
 pub(crate) use self::tokenizer::*;
 pub use self::topology::*;
+#[cfg(any(feature = "transforms-dedupe", feature = "transforms-reduce"))]
+pub(crate) use self::transform_state::*;
 pub use self::udp::*;
 pub use self::unix::*;
 pub use self::vector::*;
+#[cfg(feature = "transforms-wasm")]
+pub(crate) use self::wasm::*;
 #[cfg(windows)]
 pub use self::windows::*;
 #[cfg(feature = "sources-mongodb_metrics")]
@@ -0,0 +1,11 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct RelabelEventDropped;
+
+impl InternalEvent for RelabelEventDropped {
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
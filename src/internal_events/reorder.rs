@@ -0,0 +1,46 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct ReorderLateEventRouted;
+
+impl InternalEvent for ReorderLateEventRouted {
+    fn emit_logs(&self) {
+        trace!(message = "Routed event arriving later than the watermark.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("late_events_total", 1);
+    }
+}
+
+/// Why buffered events were flushed before the watermark passed them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReorderFlushReason {
+    /// Their group received no events for the maximum lateness.
+    Idle,
+    /// `max_buffered_events` was reached.
+    TooManyEvents,
+}
+
+#[derive(Debug)]
+pub struct ReorderEventsFlushed {
+    pub count: usize,
+    pub reason: ReorderFlushReason,
+}
+
+impl InternalEvent for ReorderEventsFlushed {
+    fn emit_logs(&self) {
+        if self.reason == ReorderFlushReason::TooManyEvents {
+            debug!(
+                message = "Too many buffered events, flushing all of them.",
+                count = %self.count,
+                internal_log_rate_secs = 10
+            );
+        }
+    }
+
+    fn emit_metrics(&self) {
+        counter!("stale_events_flushed_total", self.count as u64);
+    }
+}
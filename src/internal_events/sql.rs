@@ -0,0 +1,22 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct SqlWindowFlushed {
+    pub groups: usize,
+    pub rows: usize,
+}
+
+impl InternalEvent for SqlWindowFlushed {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Flushed closed window.",
+            groups = %self.groups,
+            rows = %self.rows
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("windows_flushed_total", 1);
+    }
+}
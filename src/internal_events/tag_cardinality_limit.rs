@@ -2,6 +2,7 @@ use super::InternalEvent;
 use metrics::counter;
 
 pub struct TagCardinalityLimitRejectingEvent<'a> {
+    pub metric_name: &'a str,
     pub tag_key: &'a str,
     pub tag_value: &'a str,
 }
@@ -10,6 +11,7 @@ impl<'a> InternalEvent for TagCardinalityLimitRejectingEvent<'a> {
     fn emit_logs(&self) {
         debug!(
             message = "Event containing tag with new value after hitting configured 'value_limit'; discarding event.",
+            metric_name = self.metric_name,
             tag_key = self.tag_key,
             tag_value = self.tag_value,
             internal_log_rate_secs = 10,
@@ -17,11 +19,13 @@ impl<'a> InternalEvent for TagCardinalityLimitRejectingEvent<'a> {
     }
 
     fn emit_metrics(&self) {
-        counter!("tag_value_limit_exceeded_total", 1);
+        counter!("tag_value_limit_exceeded_total", 1,
+                 "tag_key" => self.tag_key.to_owned());
     }
 }
 
 pub struct TagCardinalityLimitRejectingTag<'a> {
+    pub metric_name: &'a str,
     pub tag_key: &'a str,
     pub tag_value: &'a str,
 }
@@ -30,6 +34,7 @@ impl<'a> InternalEvent for TagCardinalityLimitRejectingTag<'a> {
     fn emit_logs(&self) {
         debug!(
             message = "Rejecting tag after hitting configured 'value_limit'.",
+            metric_name = self.metric_name,
             tag_key = self.tag_key,
             tag_value = self.tag_value,
             internal_log_rate_secs = 10,
@@ -37,11 +42,13 @@ impl<'a> InternalEvent for TagCardinalityLimitRejectingTag<'a> {
     }
 
     fn emit_metrics(&self) {
-        counter!("tag_value_limit_exceeded_total", 1);
+        counter!("tag_value_limit_exceeded_total", 1,
+                 "tag_key" => self.tag_key.to_owned());
     }
 }
 
 pub struct TagCardinalityValueLimitReached<'a> {
+    pub metric_name: &'a str,
     pub key: &'a str,
 }
 
@@ -50,6 +57,7 @@ impl<'a> InternalEvent for TagCardinalityValueLimitReached<'a> {
         debug!(
             "Value_limit reached for key {}. New values for this key will be rejected.",
             key = self.key,
+            metric_name = self.metric_name,
         );
     }
 
@@ -0,0 +1,29 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct ThrottleEventThrottled<'a> {
+    pub key: Option<&'a str>,
+    /// Whether the event was discarded, rather than rerouted.
+    pub discarded: bool,
+}
+
+impl InternalEvent for ThrottleEventThrottled<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Rate limit exceeded.",
+            key = ?self.key,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "events_throttled_total", 1,
+            "key" => self.key.unwrap_or_default().to_owned(),
+        );
+        if self.discarded {
+            counter!("events_discarded_total", 1);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct TransformStateCheckpointed {
+    pub bytes: usize,
+}
+
+impl InternalEvent for TransformStateCheckpointed {
+    fn emit_logs(&self) {
+        debug!(message = "State checkpointed.", bytes = %self.bytes);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoints_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct TransformStateCheckpointFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for TransformStateCheckpointFailed {
+    fn emit_logs(&self) {
+        warn!(message = "Failed writing state checkpoint.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_write_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct TransformStateRestored {
+    pub bytes: usize,
+}
+
+impl InternalEvent for TransformStateRestored {
+    fn emit_logs(&self) {
+        info!(message = "State restored from checkpoint.", bytes = %self.bytes);
+    }
+}
+
+#[derive(Debug)]
+pub struct TransformStateCheckpointIgnored;
+
+impl InternalEvent for TransformStateCheckpointIgnored {
+    fn emit_logs(&self) {
+        info!(message = "Ignoring state checkpoint written with a different configuration.");
+    }
+}
+
+#[derive(Debug)]
+pub struct TransformStateRestoreFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for TransformStateRestoreFailed {
+    fn emit_logs(&self) {
+        warn!(message = "Failed reading state checkpoint, starting with empty state.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_read_errors_total", 1);
+    }
+}
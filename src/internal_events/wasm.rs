@@ -0,0 +1,22 @@
+use super::InternalEvent;
+use crate::transforms::wasm::ProcessError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct WasmEventProcessingFailed<'a> {
+    pub error: &'a ProcessError,
+}
+
+impl<'a> InternalEvent for WasmEventProcessingFailed<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Error in wasm module; discarding event.",
+            error = %self.error,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => self.error.error_type());
+    }
+}
@@ -198,29 +198,18 @@ where
                     // Start flushing all existing data, stop polling `inner`.
                     *this.draining = Some(
                         this.logic
-                            .buffers
                             .drain()
-                            .map(|(src, (_, aggregate))| {
-                                let (line, context) = aggregate.merge();
-                                (src, line, context)
-                            })
+                            .map(|(src, (line, context))| (src, line, context))
                             .collect(),
                     );
                 }
                 Poll::Pending => {
                     // We didn't get any lines from `inner`, so we just give
                     // a line from keys that have hit their timeout.
-                    while let Poll::Ready(Some(Ok(expired_key))) =
-                        this.logic.timeouts.poll_expired(cx)
-                    {
-                        let key = expired_key.into_inner();
-                        if let Some((_, aggregate)) = this.logic.buffers.remove(&key) {
-                            let (line, context) = aggregate.merge();
-                            return Poll::Ready(Some((key, line, context)));
-                        }
-                    }
-
-                    return Poll::Pending;
+                    return this
+                        .logic
+                        .poll_expired(cx)
+                        .map(|(src, (line, context))| Some((src, line, context)));
                 }
             };
         }
@@ -350,6 +339,24 @@ where
             }
         }
     }
+
+    /// Poll for a buffered line whose key has hit its timeout.
+    pub fn poll_expired(&mut self, cx: &mut Context<'_>) -> Poll<(K, (Bytes, C))> {
+        while let Poll::Ready(Some(Ok(expired_key))) = self.timeouts.poll_expired(cx) {
+            let key = expired_key.into_inner();
+            if let Some((_, aggregate)) = self.buffers.remove(&key) {
+                return Poll::Ready((key, aggregate.merge()));
+            }
+        }
+        Poll::Pending
+    }
+
+    /// Flush all the buffered lines, regardless of their timeouts.
+    pub fn drain(&mut self) -> impl Iterator<Item = (K, (Bytes, C))> + '_ {
+        self.buffers
+            .drain()
+            .map(|(src, (_, aggregate))| (src, aggregate.merge()))
+    }
 }
 
 struct Aggregate<C> {
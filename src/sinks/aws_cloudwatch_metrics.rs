@@ -186,6 +186,22 @@ impl CloudWatchMetricsSvc {
                         dimensions,
                         ..Default::default()
                     }),
+                    // Each bin of a sketch is sent as its representative
+                    // value, along with its count.
+                    MetricValue::Sketch { sketch } if !sketch.is_empty() => {
+                        let (values, counts) = sketch
+                            .bin_values()
+                            .map(|(value, count)| (value, f64::from(count)))
+                            .unzip();
+                        Some(MetricDatum {
+                            metric_name,
+                            values: Some(values),
+                            counts: Some(counts),
+                            timestamp,
+                            dimensions,
+                            ..Default::default()
+                        })
+                    }
                     MetricValue::Set { values } => Some(MetricDatum {
                         metric_name,
                         value: Some(values.len() as f64),
@@ -278,7 +294,7 @@ fn tags_to_dimensions(tags: &BTreeMap<String, String>) -> Vec<Dimension> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+    use crate::event::metric::{DDSketch, Metric, MetricKind, MetricValue, StatisticKind};
     use chrono::offset::TimeZone;
     use pretty_assertions::assert_eq;
 
@@ -400,6 +416,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encode_events_sketch() {
+        let mut sketch = DDSketch::new();
+        sketch.insert_n(11.0, 100);
+        let events = vec![Metric::new(
+            "latency",
+            MetricKind::Incremental,
+            MetricValue::Sketch { sketch },
+        )];
+
+        assert_eq!(
+            svc().encode_events(events),
+            vec![MetricDatum {
+                metric_name: "latency".into(),
+                values: Some(vec![11.0]),
+                counts: Some(vec![100.0]),
+                ..Default::default()
+            }]
+        );
+    }
+
     #[test]
     fn encode_events_set() {
         let events = vec![Metric::new(
@@ -5,7 +5,7 @@ use crate::{
     internal_events::{ConsoleEventProcessed, ConsoleFieldNotFound},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
-        StreamSink,
+        StreamSink, SKETCH_QUANTILES,
     },
 };
 use async_trait::async_trait;
@@ -113,14 +113,17 @@ fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option
                 }
             }
         },
-        Event::Metric(metric) => match encoding.codec() {
-            Encoding::Json => serde_json::to_string(&metric)
-                .map_err(|error| {
-                    error!(message = "Error encoding json.", %error);
-                })
-                .ok(),
-            Encoding::Text => Some(format!("{}", metric)),
-        },
+        Event::Metric(metric) => {
+            let metric = metric.with_sketch_as_summary(SKETCH_QUANTILES);
+            match encoding.codec() {
+                Encoding::Json => serde_json::to_string(&metric)
+                    .map_err(|error| {
+                        error!(message = "Error encoding json.", %error);
+                    })
+                    .ok(),
+                Encoding::Text => Some(format!("{}", metric)),
+            }
+        }
     }
 }
 
@@ -156,7 +159,7 @@ impl StreamSink for WriterSink {
 #[cfg(test)]
 mod test {
     use super::{encode_event, ConsoleSinkConfig, Encoding, EncodingConfig};
-    use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+    use crate::event::metric::{DDSketch, Metric, MetricKind, MetricValue, StatisticKind};
     use crate::event::{Event, Value};
     use chrono::{offset::TimeZone, Utc};
     use pretty_assertions::assert_eq;
@@ -245,6 +248,21 @@ mod test {
         );
     }
 
+    #[test]
+    fn encodes_sketch_as_summary() {
+        let mut sketch = DDSketch::new();
+        sketch.insert_n(2.0, 3);
+        let event = Event::Metric(Metric::new(
+            "latency",
+            MetricKind::Incremental,
+            MetricValue::Sketch { sketch },
+        ));
+        assert_eq!(
+            r#"{"name":"latency","kind":"incremental","aggregated_summary":{"quantiles":[{"upper_limit":0.5,"value":2.0},{"upper_limit":0.75,"value":2.0},{"upper_limit":0.9,"value":2.0},{"upper_limit":0.95,"value":2.0},{"upper_limit":0.99,"value":2.0}],"count":3,"sum":6.0}}"#,
+            encode_event(event, &EncodingConfig::from(Encoding::Json)).unwrap()
+        );
+    }
+
     #[test]
     fn encodes_metric_text() {
         let event = Event::Metric(Metric::new(
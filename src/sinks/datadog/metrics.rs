@@ -109,10 +109,60 @@ impl DatadogConfig {
 struct DatadogDistributionMetric {
     metric: String,
     interval: Option<i64>,
-    points: Vec<DatadogPoint<Vec<f64>>>,
+    points: Vec<DatadogPoint<DistributionValues>>,
     tags: Option<Vec<String>>,
 }
 
+/// The most values sent for a distribution point. Points with more
+/// observations are downsampled to this many values, keeping the proportion
+/// of each value, so that the size of requests doesn't grow with the number
+/// of observations.
+const MAX_DISTRIBUTION_VALUES: u64 = 10_000;
+
+/// The values of a distribution point, along with how many times each was
+/// observed. Distribution points can't be weighted, so each value is
+/// repeated by its count as it is serialized, rather than beforehand.
+#[derive(Debug, Clone, PartialEq)]
+struct DistributionValues(Vec<(f64, u32)>);
+
+impl DistributionValues {
+    fn new(mut values: Vec<(f64, u32)>) -> Self {
+        let total = values.iter().map(|(_, count)| *count as u64).sum::<u64>();
+        if total > MAX_DISTRIBUTION_VALUES {
+            // Scale the counts down, and hand the values lost to rounding to
+            // the counts that lost the most to it.
+            let mut remainders = Vec::with_capacity(values.len());
+            let mut kept = 0;
+            for (idx, (_, count)) in values.iter_mut().enumerate() {
+                let scaled = *count as u64 * MAX_DISTRIBUTION_VALUES;
+                remainders.push((scaled % total, idx));
+                *count = (scaled / total) as u32;
+                kept += *count as u64;
+            }
+            remainders.sort_unstable_by(|a, b| b.cmp(a));
+            for (_, idx) in remainders
+                .into_iter()
+                .take((MAX_DISTRIBUTION_VALUES - kept) as usize)
+            {
+                values[idx].1 += 1;
+            }
+            values.retain(|(_, count)| *count > 0);
+        }
+
+        Self(values)
+    }
+}
+
+impl Serialize for DistributionValues {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            self.0
+                .iter()
+                .flat_map(|&(value, count)| std::iter::repeat(value).take(count as usize)),
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 struct DatadogMetric {
     metric: String,
@@ -166,7 +216,8 @@ impl DatadogEndpoint {
             MetricValue::Distribution {
                 statistic: StatisticKind::Summary,
                 ..
-            } => Self::Distribution,
+            }
+            | MetricValue::Sketch { .. } => Self::Distribution,
             _ => Self::Series,
         }
     }
@@ -491,8 +542,8 @@ fn encode_distribution_events(
                     } => {
                         let samples = samples
                             .iter()
-                            .map(|sample| (0..sample.rate).map(move |_| sample.value))
-                            .flatten()
+                            .filter(|sample| sample.rate > 0)
+                            .map(|sample| (sample.value, sample.rate))
                             .collect::<Vec<_>>();
 
                         if samples.is_empty() {
@@ -501,11 +552,25 @@ fn encode_distribution_events(
                             Some(DatadogDistributionMetric {
                                 metric: fullname,
                                 interval: Some(interval),
-                                points: vec![DatadogPoint(ts, samples)],
+                                points: vec![DatadogPoint(ts, DistributionValues::new(samples))],
                                 tags,
                             })
                         }
                     }
+                    // Each bin of a sketch is sent as its representative
+                    // value along with its count, which keeps the
+                    // accuracy of the sketch up to the downsampling of
+                    // large counts.
+                    MetricValue::Sketch { sketch } if !sketch.is_empty() => {
+                        let bins = sketch.bin_values().collect::<Vec<_>>();
+
+                        Some(DatadogDistributionMetric {
+                            metric: fullname,
+                            interval: Some(interval),
+                            points: vec![DatadogPoint(ts, DistributionValues::new(bins))],
+                            tags,
+                        })
+                    }
                     _ => None,
                 },
                 _ => None,
@@ -519,7 +584,10 @@ fn encode_distribution_events(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{event::metric::Sample, sinks::util::test::load_sink};
+    use crate::{
+        event::metric::{DDSketch, Sample},
+        sinks::util::test::load_sink,
+    };
     use chrono::offset::TimeZone;
     use http::Method;
     use pretty_assertions::assert_eq;
@@ -802,4 +870,71 @@ mod tests {
             r#"{"series":[{"metric":"requests","interval":60,"points":[[1542182950,[1.0,1.0,1.0,2.0,2.0,2.0,3.0,3.0]]],"tags":null}]}"#
         );
     }
+
+    #[test]
+    fn encode_sketch() {
+        let mut sketch = DDSketch::new();
+        sketch.insert_n(1.0, 2);
+        sketch.insert(3.0);
+        // 3.0 is sent as the value representing its bin
+        let (representative, _) = sketch.bin_values().last().unwrap();
+        assert!((representative - 3.0).abs() < 0.03);
+        let events = vec![Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Sketch { sketch },
+        )
+        .with_timestamp(Some(ts()))];
+        assert_eq!(
+            DatadogEndpoint::from_metric(&events[0]),
+            DatadogEndpoint::Distribution
+        );
+        let input = encode_distribution_events(events, None, 60);
+        let json = serde_json::to_string(&input).unwrap();
+
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"series":[{{"metric":"requests","interval":60,"points":[[1542182950,[1.0,1.0,{:?}]]],"tags":null}}]}}"#,
+                representative
+            )
+        );
+    }
+
+    #[test]
+    fn encode_large_sketch_bounded() {
+        let mut sketch = DDSketch::new();
+        sketch.insert_n(1.0, 3_000_000);
+        sketch.insert_n(100.0, 1_000_000);
+        sketch.insert(10_000.0);
+        let events = vec![Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Sketch { sketch },
+        )
+        .with_timestamp(Some(ts()))];
+        let input = encode_distribution_events(events, None, 60);
+
+        let values = &input.series[0].points[0].1;
+        let counts = values.0.iter().map(|(_, count)| *count).collect::<Vec<_>>();
+        // The proportions are kept, and values too rare to be sent are
+        // dropped.
+        assert_eq!(counts, vec![7_500, 2_500]);
+        let json = serde_json::to_string(&input).unwrap();
+        assert!(json.len() < 30 * MAX_DISTRIBUTION_VALUES as usize);
+    }
+
+    #[test]
+    fn downsample_distribution_values() {
+        let values = DistributionValues::new(vec![(1.0, 10), (2.0, 20)]);
+        assert_eq!(values.0, vec![(1.0, 10), (2.0, 20)]);
+
+        let values = DistributionValues::new(vec![(1.0, 10_000), (2.0, 10_000), (3.0, 10_000)]);
+        let total = values.0.iter().map(|(_, count)| *count as u64).sum::<u64>();
+        assert_eq!(total, MAX_DISTRIBUTION_VALUES);
+        assert!(values
+            .0
+            .iter()
+            .all(|(_, count)| (3_333..=3_334).contains(count)));
+    }
 }
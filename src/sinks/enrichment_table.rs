@@ -0,0 +1,182 @@
+use crate::{
+    buffers::Acker,
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    emit,
+    enrichment_tables::memory::{self, MemoryStore},
+    internal_events::{EnrichmentTableKeyFieldMissing, EnrichmentTableRowWritten},
+    sinks::util::StreamSink,
+};
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::sync::Arc;
+use vector_core::event::Event;
+use vector_core::ByteSizeOf;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("No memory enrichment table named {:?} is configured", table))]
+    MissingTable { table: String },
+}
+
+/// Writes log events as rows of a `memory` enrichment table, so that they can
+/// be looked up when enriching other events.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnrichmentTableSinkConfig {
+    pub table: String,
+}
+
+inventory::submit! {
+    SinkDescription::new::<EnrichmentTableSinkConfig>("enrichment_table")
+}
+
+impl GenerateConfig for EnrichmentTableSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            table: "my_table".to_string(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "enrichment_table")]
+impl SinkConfig for EnrichmentTableSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        // Enrichment tables are built before the sinks, so the table's store
+        // exists by now if it is configured.
+        let store = memory::store(&self.table).ok_or_else(|| BuildError::MissingTable {
+            table: self.table.clone(),
+        })?;
+        let sink = EnrichmentTableSink {
+            table: self.table.clone(),
+            store,
+            acker: cx.acker(),
+        };
+        let healthcheck = future::ok(()).boxed();
+
+        Ok((super::VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "enrichment_table"
+    }
+}
+
+pub struct EnrichmentTableSink {
+    table: String,
+    store: Arc<MemoryStore>,
+    acker: Acker,
+}
+
+#[async_trait]
+impl StreamSink for EnrichmentTableSink {
+    async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        while let Some(event) = input.next().await {
+            let byte_size = event.size_of();
+            let (fields, _) = event.into_log().into_parts();
+            let row = fields
+                .into_iter()
+                .map(|(field, value)| (field, value.into()))
+                .collect();
+
+            if self.store.insert(row) {
+                emit!(EnrichmentTableRowWritten {
+                    table: &self.table,
+                    byte_size,
+                });
+            } else {
+                emit!(EnrichmentTableKeyFieldMissing {
+                    table: &self.table,
+                    field: &self.store.key_field(),
+                });
+            }
+
+            self.acker.ack(1);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{EnrichmentTableConfig, GlobalOptions},
+        enrichment_tables::{memory::MemoryConfig, Condition, Table},
+        event::LogEvent,
+    };
+    use shared::btreemap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EnrichmentTableSinkConfig>();
+    }
+
+    #[tokio::test]
+    async fn writes_rows() {
+        let table = MemoryConfig {
+            key_field: "id".to_string(),
+            ttl_secs: 60,
+            max_entries: 10,
+        }
+        .build(
+            "enrichment_table_sink_writes_rows",
+            &GlobalOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let config = EnrichmentTableSinkConfig {
+            table: "enrichment_table_sink_writes_rows".to_string(),
+        };
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let events = vec![
+            Event::from(LogEvent::from(btreemap! {
+                "id" => "1",
+                "host" => "alpha",
+            })),
+            Event::from(LogEvent::from(btreemap! { "host" => "beta" })),
+        ];
+        sink.run(futures::stream::iter(events)).await.unwrap();
+
+        assert_eq!(
+            Ok(vec![btreemap! {
+                "id" => "1",
+                "host" => "alpha",
+            }]),
+            table.find_table_rows(&[], None, None)
+        );
+        assert_eq!(
+            Ok(btreemap! { "host" => "alpha" }),
+            table.find_table_row(
+                &[Condition::Equals {
+                    field: "id",
+                    value: "1".into(),
+                }],
+                Some(&["host".to_string()]),
+                None
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_without_table() {
+        let config = EnrichmentTableSinkConfig {
+            table: "enrichment_table_sink_missing".to_string(),
+        };
+
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+}
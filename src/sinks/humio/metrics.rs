@@ -4,7 +4,10 @@ use crate::{
         DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription, TransformConfig,
         TransformContext,
     },
-    sinks::util::{encoding::EncodingConfig, BatchConfig, Compression, TowerRequestConfig},
+    event::Event,
+    sinks::util::{
+        encoding::EncodingConfig, BatchConfig, Compression, TowerRequestConfig, SKETCH_QUANTILES,
+    },
     sinks::{Healthcheck, VectorSink},
     template::Template,
     tls::TlsOptions,
@@ -90,6 +93,13 @@ impl SinkConfig for HumioMetricsConfig {
         let (sink, healthcheck) = sink.clone().build(cx).await?;
 
         let sink = Box::new(sink.into_sink().with_flat_map(move |e| {
+            // Humio can't aggregate sketches, so they are sent as summaries.
+            let e = match e {
+                Event::Metric(metric) => {
+                    Event::Metric(metric.with_sketch_as_summary(SKETCH_QUANTILES))
+                }
+                e => e,
+            };
             let mut buf = Vec::with_capacity(1);
             transform.as_function().transform(&mut buf, e);
             stream::iter(buf.into_iter()).map(Ok)
@@ -112,8 +122,8 @@ mod tests {
     use super::*;
     use crate::{
         event::{
-            metric::{MetricKind, MetricValue, StatisticKind},
-            Event, Metric,
+            metric::{DDSketch, MetricKind, MetricValue, StatisticKind},
+            Metric,
         },
         sinks::util::test::{build_test_server, load_sink},
         test_util,
@@ -200,6 +210,20 @@ mod tests {
                 ))
                 .with_timestamp(Some(Utc.ymd(2020, 8, 18).and_hms(21, 0, 2))),
             ),
+            Event::from(
+                Metric::new(
+                    "metric3",
+                    MetricKind::Incremental,
+                    MetricValue::Sketch {
+                        sketch: {
+                            let mut sketch = DDSketch::new();
+                            sketch.insert_n(2.0, 3);
+                            sketch
+                        },
+                    },
+                )
+                .with_timestamp(Some(Utc.ymd(2020, 8, 18).and_hms(21, 0, 3))),
+            ),
         ];
 
         let len = metrics.len();
@@ -214,5 +238,9 @@ mod tests {
             r#"{"event":{"distribution":{"samples":[{"rate":100,"value":1.0},{"rate":200,"value":2.0},{"rate":300,"value":3.0}],"statistic":"histogram"},"kind":"absolute","name":"metric2","tags":{"os.host":"somehost"}},"fields":{},"time":1597784402.0}"#,
             output[1].1
         );
+        assert_eq!(
+            r#"{"event":{"aggregated_summary":{"count":3,"quantiles":[{"upper_limit":0.5,"value":2.0},{"upper_limit":0.75,"value":2.0},{"upper_limit":0.9,"value":2.0},{"upper_limit":0.95,"value":2.0},{"upper_limit":0.99,"value":2.0}],"sum":6.0},"kind":"incremental","name":"metric3"},"fields":{},"time":1597784403.0}"#,
+            output[2].1
+        );
     }
 }
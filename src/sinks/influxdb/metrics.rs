@@ -1,7 +1,7 @@
 use crate::{
     config::{DataType, SinkConfig, SinkContext, SinkDescription},
     event::{
-        metric::{Metric, MetricValue, Sample, StatisticKind},
+        metric::{Metric, MetricValue, Quantile, Sample, StatisticKind},
         Event,
     },
     http::HttpClient,
@@ -297,21 +297,16 @@ fn get_type_and_fields(
             quantiles,
             count,
             sum,
-        } => {
-            let mut fields: HashMap<String, Field> = quantiles
-                .iter()
-                .map(|quantile| {
-                    (
-                        format!("quantile_{}", quantile.upper_limit),
-                        Field::Float(quantile.value),
-                    )
-                })
-                .collect();
-            fields.insert("count".to_owned(), Field::UnsignedInt(*count));
-            fields.insert("sum".to_owned(), Field::Float(*sum));
-
-            ("summary", Some(fields))
-        }
+        } => ("summary", Some(encode_summary(quantiles, *count, *sum))),
+        // Sketches are converted to summaries with the configured quantiles
+        MetricValue::Sketch { sketch } => (
+            "summary",
+            Some(encode_summary(
+                &sketch.to_quantiles(quantiles),
+                sketch.count(),
+                sketch.sum(),
+            )),
+        ),
         MetricValue::Distribution { samples, statistic } => {
             let quantiles = match statistic {
                 StatisticKind::Histogram => &[0.95] as &[_],
@@ -323,6 +318,21 @@ fn get_type_and_fields(
     }
 }
 
+fn encode_summary(quantiles: &[Quantile], count: u32, sum: f64) -> HashMap<String, Field> {
+    let mut fields: HashMap<String, Field> = quantiles
+        .iter()
+        .map(|quantile| {
+            (
+                format!("quantile_{}", quantile.upper_limit),
+                Field::Float(quantile.value),
+            )
+        })
+        .collect();
+    fields.insert("count".to_owned(), Field::UnsignedInt(count));
+    fields.insert("sum".to_owned(), Field::Float(sum));
+    fields
+}
+
 fn encode_distribution(samples: &[Sample], quantiles: &[f64]) -> Option<HashMap<String, Field>> {
     let statistic = DistributionStatistic::from_samples(samples, quantiles)?;
 
@@ -356,7 +366,7 @@ fn to_fields(value: f64) -> HashMap<String, Field> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+    use crate::event::metric::{DDSketch, Metric, MetricKind, MetricValue, StatisticKind};
     use crate::sinks::influxdb::test_util::{assert_fields, split_line_protocol, tags, ts};
     use pretty_assertions::assert_eq;
 
@@ -587,6 +597,38 @@ mod tests {
         assert_eq!("1542182950000000011", line_protocol1.3);
     }
 
+    #[test]
+    fn test_encode_sketch() {
+        let mut sketch = DDSketch::new();
+        for value in &[1.0, 2.0, 3.0] {
+            sketch.insert(*value);
+        }
+        let events = vec![Metric::new(
+            "requests",
+            MetricKind::Absolute,
+            MetricValue::Sketch { sketch },
+        )
+        .with_namespace(Some("ns"))
+        .with_tags(Some(tags()))
+        .with_timestamp(Some(ts()))];
+
+        let line_protocols = encode_events(ProtocolVersion::V2, events, None, None, &[0.0, 1.0]);
+        let line_protocols: Vec<&str> = line_protocols.split('\n').collect();
+        assert_eq!(line_protocols.len(), 1);
+
+        let line_protocol1 = split_line_protocol(line_protocols[0]);
+        assert_eq!("ns.requests", line_protocol1.0);
+        assert_eq!(
+            "metric_type=summary,normal_tag=value,true_tag=true",
+            line_protocol1.1
+        );
+        assert_fields(
+            line_protocol1.2.to_string(),
+            ["count=3u", "quantile_0=1", "quantile_1=3", "sum=6"].to_vec(),
+        );
+        assert_eq!("1542182950000000011", line_protocol1.3);
+    }
+
     #[test]
     fn test_encode_distribution() {
         let events = vec![
@@ -29,6 +29,8 @@ pub mod console;
 pub mod datadog;
 #[cfg(feature = "sinks-elasticsearch")]
 pub mod elasticsearch;
+#[cfg(feature = "sinks-enrichment_table")]
+pub mod enrichment_table;
 #[cfg(feature = "sinks-file")]
 pub mod file;
 #[cfg(feature = "sinks-gcp")]
@@ -178,6 +178,21 @@ pub(super) trait MetricCollector {
                     self.emit_value(timestamp, name, "_sum", *sum, tags, None);
                     self.emit_value(timestamp, name, "_count", *count as f64, tags, None);
                 }
+                MetricValue::Sketch { sketch } => {
+                    // convert sketches into summaries
+                    for quantile in sketch.to_quantiles(quantiles) {
+                        self.emit_value(
+                            timestamp,
+                            name,
+                            "",
+                            quantile.value,
+                            tags,
+                            Some(("quantile", quantile.upper_limit.to_string())),
+                        );
+                    }
+                    self.emit_value(timestamp, name, "_sum", sketch.sum(), tags, None);
+                    self.emit_value(timestamp, name, "_count", sketch.count() as f64, tags, None);
+                }
             }
         }
     }
@@ -380,7 +395,7 @@ const fn prometheus_metric_type(metric_value: &MetricValue) -> proto::MetricType
             ..
         } => MetricType::Summary,
         MetricValue::AggregatedHistogram { .. } => MetricType::Histogram,
-        MetricValue::AggregatedSummary { .. } => MetricType::Summary,
+        MetricValue::AggregatedSummary { .. } | MetricValue::Sketch { .. } => MetricType::Summary,
     }
 }
 
@@ -388,7 +403,7 @@ const fn prometheus_metric_type(metric_value: &MetricValue) -> proto::MetricType
 mod tests {
     use super::super::default_summary_quantiles;
     use super::*;
-    use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+    use crate::event::metric::{DDSketch, Metric, MetricKind, MetricValue, StatisticKind};
     use chrono::{DateTime, TimeZone};
     use indoc::indoc;
     use pretty_assertions::assert_eq;
@@ -718,6 +733,51 @@ mod tests {
         encode_one::<T>(Some("ns"), &[], &[], false, &metric)
     }
 
+    #[test]
+    fn encodes_sketch_text() {
+        assert_eq!(
+            encode_sketch::<StringCollector>(),
+            indoc! {r#"
+                # HELP ns_requests requests
+                # TYPE ns_requests summary
+                ns_requests{code="200",quantile="0"} 1 1612325106789
+                ns_requests{code="200",quantile="1"} 3 1612325106789
+                ns_requests_sum{code="200"} 15 1612325106789
+                ns_requests_count{code="200"} 8 1612325106789
+            "#}
+        );
+    }
+
+    #[test]
+    fn encodes_sketch_request() {
+        assert_eq!(
+            encode_sketch::<TimeSeries>(),
+            write_request!(
+                "ns_requests", "requests", Summary [
+                    "" @ 1612325106789 = 1.0 ["code" => "200", "quantile" => "0"],
+                    "" @ 1612325106789 = 3.0 ["code" => "200", "quantile" => "1"],
+                    "_sum" @ 1612325106789 = 15.0 ["code" => "200"],
+                    "_count" @ 1612325106789 = 8.0 ["code" => "200"]
+                ]
+            )
+        );
+    }
+
+    fn encode_sketch<T: MetricCollector>() -> T::Output {
+        let mut sketch = DDSketch::new();
+        sketch.insert_n(1.0, 3);
+        sketch.insert_n(2.0, 3);
+        sketch.insert_n(3.0, 2);
+        let metric = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Sketch { sketch },
+        )
+        .with_tags(Some(tags()))
+        .with_timestamp(Some(timestamp()));
+        encode_one::<T>(Some("ns"), &[], &[0.0, 1.0], false, &metric)
+    }
+
     #[test]
     fn encodes_distribution_summary_text() {
         assert_eq!(
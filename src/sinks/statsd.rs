@@ -204,6 +204,13 @@ fn encode_event(event: Event, default_namespace: Option<&str>) -> Option<Vec<u8>
                 push_event(&mut buf, metric, val, "s", None);
             }
         }
+        // Each bin of a sketch is sent as a distribution sample of its
+        // representative value, sampled at the rate of its count.
+        MetricValue::Sketch { sketch } => {
+            for (value, count) in sketch.bin_values() {
+                push_event(&mut buf, metric, value, "d", Some(count));
+            }
+        }
         _ => {
             emit!(StatsdInvalidMetricReceived {
                 value: metric.value(),
@@ -246,7 +253,10 @@ mod test {
     use tokio_util::{codec::BytesCodec, udp::UdpFramed};
 
     #[cfg(feature = "sources-statsd")]
-    use {crate::sources::statsd::parser::parse, std::str::from_utf8};
+    use {
+        crate::{event::metric::DDSketch, sources::statsd::parser::parse},
+        std::str::from_utf8,
+    };
 
     #[test]
     fn generate_config() {
@@ -368,6 +378,21 @@ mod test {
         shared::assert_event_data_eq!(metric1, metric2);
     }
 
+    #[cfg(feature = "sources-statsd")]
+    #[test]
+    fn test_encode_sketch() {
+        let mut sketch = DDSketch::new();
+        sketch.insert_n(1.0, 4);
+        let metric = Metric::new(
+            "sketch",
+            MetricKind::Incremental,
+            MetricValue::Sketch { sketch },
+        );
+        let event = Event::Metric(metric);
+        let frame = &encode_event(event, None).unwrap();
+        assert_eq!("sketch:1|d|@0.25\n", from_utf8(frame).unwrap());
+    }
+
     #[cfg(feature = "sources-statsd")]
     #[test]
     fn test_encode_set() {
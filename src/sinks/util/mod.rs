@@ -111,6 +111,10 @@ pub fn encode_log(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Opti
     .ok()
 }
 
+/// The quantiles sketches are summarized with by the sinks that can't encode
+/// them as they are.
+pub const SKETCH_QUANTILES: &[f64] = &[0.5, 0.75, 0.9, 0.95, 0.99];
+
 /// Joins namespace with name via delimiter if namespace is present.
 pub fn encode_namespace<'a>(
     namespace: Option<&str>,
@@ -6,8 +6,9 @@ use super::{
 use crate::{
     buffers,
     config::{ComponentKey, DataType, ProxyConfig, SinkContext, SourceContext, TransformContext},
+    enrichment_tables::reload,
     event::Event,
-    internal_events::{EventIn, EventOut},
+    internal_events::{EnrichmentTableLoaded, EventIn, EventOut},
     shutdown::SourceShutdownCoordinator,
     transforms::Transform,
     Pipeline,
@@ -55,6 +56,7 @@ pub async fn build_pieces(
     let mut errors = vec![];
 
     let mut enrichment_tables = HashMap::new();
+    let mut enrichment_table_reloads = Vec::new();
 
     // Build enrichment tables
     for (name, table) in config
@@ -62,14 +64,31 @@ pub async fn build_pieces(
         .iter()
         .filter(|(name, _)| diff.enrichment_tables.contains_new(name))
     {
-        let table = match table.inner.build(&config.global).await {
+        // Taken before building, so changes made whilst the table is loading are picked up by the
+        // next reload.
+        let fingerprint = reload::Fingerprint::new(&table.inner.watch_paths());
+
+        let built = match table.inner.build(&name.to_string(), &config.global).await {
             Ok(table) => table,
             Err(error) => {
                 errors.push(format!("Enrichment Table \"{}\": {}", name, error));
                 continue;
             }
         };
-        enrichment_tables.insert(name.to_string(), table);
+        emit!(EnrichmentTableLoaded {
+            table: &name.to_string(),
+            rows: built.row_count(),
+        });
+
+        if let Some(secs) = table.reload_interval_secs {
+            enrichment_table_reloads.push((
+                name.to_string(),
+                dyn_clone::clone_box(&*table.inner),
+                Duration::from_secs(secs),
+                fingerprint,
+            ));
+        }
+        enrichment_tables.insert(name.to_string(), built);
     }
 
     // Build sources
@@ -128,11 +147,6 @@ pub async fn build_pieces(
 
     ENRICHMENT_TABLES.load(enrichment_tables);
 
-    let context = TransformContext {
-        globals: config.global.clone(),
-        enrichment_tables: ENRICHMENT_TABLES.clone(),
-    };
-
     // Build transforms
     for (key, transform) in config
         .transforms
@@ -144,6 +158,11 @@ pub async fn build_pieces(
         let typetag = transform.inner.transform_type();
 
         let input_type = transform.inner.input_type();
+        let context = TransformContext {
+            key: Some(key.to_string()),
+            globals: config.global.clone(),
+            enrichment_tables: ENRICHMENT_TABLES.clone(),
+        };
         let transform = match transform.inner.build(&context).await {
             Err(error) => {
                 errors.push(format!("Transform \"{}\": {}", key, error));
@@ -327,6 +346,20 @@ pub async fn build_pieces(
     ENRICHMENT_TABLES.finish_load();
 
     if errors.is_empty() {
+        for name in diff.enrichment_tables.removed_and_changed() {
+            reload::unwatch(&name.to_string());
+        }
+        for (name, table, interval, fingerprint) in enrichment_table_reloads {
+            reload::watch(
+                name,
+                table,
+                config.global.clone(),
+                interval,
+                fingerprint,
+                ENRICHMENT_TABLES.clone(),
+            );
+        }
+
         let pieces = Pieces {
             inputs,
             outputs,
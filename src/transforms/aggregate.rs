@@ -5,7 +5,9 @@ use crate::{
     transforms::{TaskTransform, Transform},
 };
 use async_stream::stream;
+use chrono::{DateTime, Utc};
 use futures::{Stream, StreamExt};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -19,6 +21,69 @@ pub struct AggregateConfig {
     /// The interval between flushes in milliseconds.
     #[serde(default = "default_interval_ms")]
     pub interval_ms: u64,
+
+    /// How metrics are aggregated, unless overridden in `modes`.
+    pub mode: AggregationMode,
+
+    /// How metrics are aggregated, by metric name.
+    pub modes: IndexMap<String, AggregationMode>,
+
+    /// The quantiles calculated by the `quantiles` mode.
+    pub quantiles: Option<Vec<f64>>,
+
+    /// The tags to keep. The series that only differ by the other tags are
+    /// grouped into one: in the `auto` mode, their incremental values are
+    /// summed, as are the latest values of each of their absolute series. In
+    /// the other modes, every value of every series is a sample of the group,
+    /// except for absolute counters in the `sum` mode, which are summed as in
+    /// the `auto` mode.
+    pub group_by: Option<Vec<String>>,
+}
+
+/// How the values of each metric series received during an interval are
+/// aggregated.
+///
+/// All modes other than `auto` only apply to counters and gauges, and treat
+/// each value received as a sample. Other metrics are aggregated as with
+/// `auto`, except that distributions are first converted to sketches in the
+/// `sketch` mode.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationMode {
+    /// Sums incremental metrics and keeps the latest value of absolute
+    /// metrics.
+    Auto,
+    /// The number of samples, as an incremental counter.
+    Count,
+    /// The sum of the samples, as a metric of the same type and kind as the
+    /// samples. Absolute counters are running totals, so the latest value of
+    /// each of their series is summed instead.
+    Sum,
+    /// The smallest sample, as a gauge.
+    Min,
+    /// The largest sample, as a gauge.
+    Max,
+    /// The mean of the samples, as a gauge.
+    Mean,
+    /// The population standard deviation of the samples, as a gauge.
+    Stdev,
+    /// The last sample, as a gauge.
+    Latest,
+    /// The `quantiles` of the samples, as an aggregated summary.
+    Quantiles,
+    /// The samples, as an incremental sketch which can be merged with the
+    /// sketches of other intervals or hosts.
+    Sketch,
+}
+
+impl Default for AggregationMode {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+fn default_quantiles() -> Vec<f64> {
+    vec![0.5, 0.75, 0.9, 0.95, 0.99]
 }
 
 const fn default_interval_ms() -> u64 {
@@ -53,24 +118,243 @@ impl TransformConfig for AggregateConfig {
 
 type MetricEntry = (metric::MetricData, EventMetadata);
 
+/// The samples of a counter or gauge series received during an interval.
+#[derive(Debug)]
+struct Samples {
+    mode: AggregationMode,
+    // The type and kind of the first sample, for the `sum` mode.
+    counter: bool,
+    kind: metric::MetricKind,
+    timestamp: Option<DateTime<Utc>>,
+    count: u32,
+    sum: f64,
+    min: f64,
+    max: f64,
+    latest: f64,
+    // The running mean and sum of squared differences from it, for the
+    // `stdev` mode.
+    mean: f64,
+    m2: f64,
+    // Only kept for the `quantiles` mode.
+    values: Vec<f64>,
+    // Only kept for the `sketch` mode.
+    sketch: metric::DDSketch,
+    metadata: EventMetadata,
+}
+
+impl Samples {
+    fn new(mode: AggregationMode, data: &metric::MetricData, metadata: EventMetadata) -> Self {
+        Self {
+            mode,
+            counter: matches!(data.value, metric::MetricValue::Counter { .. }),
+            kind: data.kind,
+            timestamp: data.timestamp,
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            latest: 0.0,
+            mean: 0.0,
+            m2: 0.0,
+            values: Vec::new(),
+            sketch: metric::DDSketch::new(),
+            metadata,
+        }
+    }
+
+    fn add(&mut self, value: f64, timestamp: Option<DateTime<Utc>>) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.latest = value;
+        self.timestamp = timestamp.or(self.timestamp);
+
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+
+        match self.mode {
+            AggregationMode::Quantiles => self.values.push(value),
+            AggregationMode::Sketch => self.sketch.insert(value),
+            _ => {}
+        }
+    }
+
+    fn into_metric(mut self, series: metric::MetricSeries, quantiles: &[f64]) -> metric::Metric {
+        let gauge = |value| {
+            (
+                metric::MetricKind::Absolute,
+                metric::MetricValue::Gauge { value },
+            )
+        };
+        let (kind, value) = match self.mode {
+            AggregationMode::Count => (
+                metric::MetricKind::Incremental,
+                metric::MetricValue::Counter {
+                    value: self.count as f64,
+                },
+            ),
+            AggregationMode::Sum if self.counter => {
+                (self.kind, metric::MetricValue::Counter { value: self.sum })
+            }
+            AggregationMode::Sum => (self.kind, metric::MetricValue::Gauge { value: self.sum }),
+            AggregationMode::Min => gauge(self.min),
+            AggregationMode::Max => gauge(self.max),
+            AggregationMode::Mean => gauge(self.mean),
+            AggregationMode::Stdev => gauge((self.m2 / self.count as f64).sqrt()),
+            AggregationMode::Auto | AggregationMode::Latest => gauge(self.latest),
+            AggregationMode::Quantiles => {
+                self.values
+                    .sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+                (
+                    metric::MetricKind::Absolute,
+                    metric::MetricValue::AggregatedSummary {
+                        quantiles: quantiles
+                            .iter()
+                            .map(|&upper_limit| metric::Quantile {
+                                upper_limit,
+                                value: quantile(&self.values, upper_limit),
+                            })
+                            .collect(),
+                        count: self.count,
+                        sum: self.sum,
+                    },
+                )
+            }
+            AggregationMode::Sketch => (
+                metric::MetricKind::Incremental,
+                metric::MetricValue::Sketch {
+                    sketch: self.sketch,
+                },
+            ),
+        };
+
+        metric::Metric::from_parts(
+            series,
+            metric::MetricData {
+                timestamp: self.timestamp,
+                kind,
+                value,
+            },
+            self.metadata,
+        )
+    }
+}
+
+/// Returns the quantile of the sorted values, interpolating between the
+/// closest values.
+fn quantile(values: &[f64], q: f64) -> f64 {
+    match values.len() {
+        0 => 0.0,
+        1 => values[0],
+        len => {
+            let rank = q * (len - 1) as f64;
+            let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+            values[lower] + (values[upper] - values[lower]) * (rank - lower as f64)
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 
 #[derive(Debug)]
 pub struct Aggregate {
     interval: Duration,
+    mode: AggregationMode,
+    modes: IndexMap<String, AggregationMode>,
+    quantiles: Vec<f64>,
+    group_by: Option<Vec<String>>,
     map: HashMap<metric::MetricSeries, MetricEntry>,
+    // The latest value of each of the absolute series grouped by `group_by`.
+    grouped: HashMap<metric::MetricSeries, IndexMap<metric::MetricSeries, MetricEntry>>,
+    samples: HashMap<metric::MetricSeries, Samples>,
 }
 
 impl Aggregate {
     pub fn new(config: &AggregateConfig) -> crate::Result<Self> {
+        let quantiles = config.quantiles.clone().unwrap_or_else(default_quantiles);
+        if quantiles.iter().any(|q| !(0.0..=1.0).contains(q)) {
+            return Err("quantiles must be between 0 and 1".into());
+        }
+
         Ok(Self {
             interval: Duration::from_millis(config.interval_ms),
+            mode: config.mode,
+            modes: config.modes.clone(),
+            quantiles,
+            group_by: config.group_by.clone(),
             map: HashMap::new(),
+            grouped: HashMap::new(),
+            samples: HashMap::new(),
         })
     }
 
     fn record(&mut self, event: Event) {
-        let (series, data, metadata) = event.into_metric().into_parts();
+        let (series, mut data, metadata) = event.into_metric().into_parts();
+
+        let (series, original) = match &self.group_by {
+            Some(group_by) => {
+                let mut grouped = series.clone();
+                if let Some(tags) = &mut grouped.tags {
+                    tags.retain(|tag, _| group_by.contains(tag));
+                    if tags.is_empty() {
+                        grouped.tags = None;
+                    }
+                }
+                (grouped, Some(series))
+            }
+            None => (series, None),
+        };
+
+        let mode = self
+            .modes
+            .get(&series.name.name)
+            .copied()
+            .unwrap_or(self.mode);
+        if mode == AggregationMode::Sketch {
+            if let metric::MetricValue::Distribution { samples, .. } = &data.value {
+                let mut sketch = metric::DDSketch::new();
+                for sample in samples {
+                    sketch.insert_n(sample.value, sample.rate);
+                }
+                data.value = metric::MetricValue::Sketch { sketch };
+            }
+        }
+
+        let sample = match data.value {
+            metric::MetricValue::Counter { value } | metric::MetricValue::Gauge { value } => {
+                Some(value)
+            }
+            _ => None,
+        };
+        // Summing the samples of an absolute counter would count the earlier
+        // samples again with every later one.
+        let auto = match mode {
+            AggregationMode::Auto => true,
+            AggregationMode::Sum => {
+                data.kind == metric::MetricKind::Absolute
+                    && matches!(data.value, metric::MetricValue::Counter { .. })
+            }
+            _ => false,
+        };
+        if let (false, Some(value)) = (auto, sample) {
+            match self.samples.entry(series) {
+                Entry::Occupied(mut entry) => {
+                    let samples = entry.get_mut();
+                    samples.metadata.merge(metadata);
+                    samples.add(value, data.timestamp);
+                }
+                Entry::Vacant(entry) => {
+                    entry
+                        .insert(Samples::new(mode, &data, metadata))
+                        .add(value, data.timestamp);
+                }
+            }
+
+            emit!(AggregateEventRecorded);
+            return;
+        }
 
         match data.kind {
             metric::MetricKind::Incremental => match self.map.entry(series) {
@@ -88,10 +372,20 @@ impl Aggregate {
                     entry.insert((data, metadata));
                 }
             },
-            metric::MetricKind::Absolute => {
+            metric::MetricKind::Absolute => match original {
+                // Keep the latest value of each series of the group, to be
+                // summed when flushed.
+                Some(original) => {
+                    self.grouped
+                        .entry(series)
+                        .or_default()
+                        .insert(original, (data, metadata));
+                }
                 // Always replace/store
-                self.map.insert(series, (data, metadata));
-            }
+                None => {
+                    self.map.insert(series, (data, metadata));
+                }
+            },
         };
 
         emit!(AggregateEventRecorded);
@@ -102,6 +396,22 @@ impl Aggregate {
             let metric = metric::Metric::from_parts(series, entry.0, entry.1);
             output.push(Event::Metric(metric));
         }
+        for (series, entries) in self.grouped.drain() {
+            let mut entries = entries.into_iter().map(|(_, entry)| entry);
+            let (mut data, mut metadata) = entries.next().expect("groups are never empty");
+            for (other, other_metadata) in entries {
+                if data.update(&other) {
+                    metadata.merge(other_metadata);
+                } else {
+                    emit!(AggregateUpdateFailed);
+                }
+            }
+            let metric = metric::Metric::from_parts(series, data, metadata);
+            output.push(Event::Metric(metric));
+        }
+        for (series, samples) in self.samples.drain() {
+            output.push(Event::Metric(samples.into_metric(series, &self.quantiles)));
+        }
 
         emit!(AggregateFlushed);
     }
@@ -167,6 +477,7 @@ mod tests {
     fn incremental() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -237,6 +548,7 @@ mod tests {
     fn absolute() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -302,6 +614,7 @@ mod tests {
     fn conflicting_value_type() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -359,6 +672,7 @@ mod tests {
     fn conflicting_kinds() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -409,6 +723,350 @@ mod tests {
         assert_eq!(&summed, &out[0]);
     }
 
+    fn make_tagged_gauge(name: &'static str, host: &str, value: f64) -> Event {
+        Event::Metric(
+            Metric::new(
+                name,
+                metric::MetricKind::Absolute,
+                metric::MetricValue::Gauge { value },
+            )
+            .with_tags(Some(
+                vec![
+                    ("host".to_string(), host.to_string()),
+                    ("region".to_string(), "us".to_string()),
+                ]
+                .into_iter()
+                .collect(),
+            )),
+        )
+    }
+
+    fn flushed_value(agg: &mut Aggregate) -> metric::MetricValue {
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        assert_eq!(1, out.len());
+        out.pop().unwrap().into_metric().value().clone()
+    }
+
+    #[test]
+    fn modes() {
+        let expected = vec![
+            (
+                AggregationMode::Count,
+                metric::MetricValue::Counter { value: 4.0 },
+            ),
+            (
+                AggregationMode::Sum,
+                metric::MetricValue::Gauge { value: 16.0 },
+            ),
+            (
+                AggregationMode::Min,
+                metric::MetricValue::Gauge { value: 2.0 },
+            ),
+            (
+                AggregationMode::Max,
+                metric::MetricValue::Gauge { value: 6.0 },
+            ),
+            (
+                AggregationMode::Mean,
+                metric::MetricValue::Gauge { value: 4.0 },
+            ),
+            (
+                AggregationMode::Stdev,
+                metric::MetricValue::Gauge {
+                    value: 2.0_f64.sqrt(),
+                },
+            ),
+            (
+                AggregationMode::Latest,
+                metric::MetricValue::Gauge { value: 4.0 },
+            ),
+        ];
+
+        for (mode, value) in expected {
+            let mut agg = Aggregate::new(&AggregateConfig {
+                interval_ms: 1000_u64,
+                mode,
+                ..Default::default()
+            })
+            .unwrap();
+
+            for sample in &[2.0, 6.0, 4.0, 4.0] {
+                agg.record(make_metric(
+                    "gauge_a",
+                    metric::MetricKind::Absolute,
+                    metric::MetricValue::Gauge { value: *sample },
+                ));
+            }
+
+            assert_eq!(value, flushed_value(&mut agg), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn modes_by_metric_name() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            modes: vec![("gauge_a".to_string(), AggregationMode::Max)]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        for (name, value) in &[
+            ("gauge_a", 3.0),
+            ("gauge_a", 1.0),
+            ("gauge_b", 3.0),
+            ("gauge_b", 1.0),
+        ] {
+            agg.record(make_metric(
+                *name,
+                metric::MetricKind::Absolute,
+                metric::MetricValue::Gauge { value: *value },
+            ));
+        }
+
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        assert_eq!(2, out.len());
+        for event in out {
+            let metric = event.into_metric();
+            match metric.series().name.name.as_str() {
+                "gauge_a" => assert_eq!(&metric::MetricValue::Gauge { value: 3.0 }, metric.value()),
+                // Absolute metrics keep their latest value by default.
+                "gauge_b" => assert_eq!(&metric::MetricValue::Gauge { value: 1.0 }, metric.value()),
+                _ => panic!("Unexpected metric name in aggregate output"),
+            }
+        }
+    }
+
+    #[test]
+    fn quantiles() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            mode: AggregationMode::Quantiles,
+            quantiles: Some(vec![0.0, 0.5, 0.9]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        for value in 1..=11 {
+            agg.record(make_metric(
+                "gauge_a",
+                metric::MetricKind::Absolute,
+                metric::MetricValue::Gauge {
+                    value: value as f64,
+                },
+            ));
+        }
+
+        assert_eq!(
+            metric::MetricValue::AggregatedSummary {
+                quantiles: vec![
+                    metric::Quantile {
+                        upper_limit: 0.0,
+                        value: 1.0
+                    },
+                    metric::Quantile {
+                        upper_limit: 0.5,
+                        value: 6.0
+                    },
+                    metric::Quantile {
+                        upper_limit: 0.9,
+                        value: 10.0
+                    },
+                ],
+                count: 11,
+                sum: 66.0,
+            },
+            flushed_value(&mut agg)
+        );
+    }
+
+    #[test]
+    fn sketches() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            mode: AggregationMode::Sketch,
+            ..Default::default()
+        })
+        .unwrap();
+
+        for value in &[2.0, 6.0, 4.0] {
+            agg.record(make_metric(
+                "gauge_a",
+                metric::MetricKind::Absolute,
+                metric::MetricValue::Gauge { value: *value },
+            ));
+        }
+        for samples in vec![
+            vector_core::samples![1.0 => 2, 3.0 => 1],
+            vector_core::samples![3.0 => 1],
+        ] {
+            agg.record(make_metric(
+                "distribution_a",
+                metric::MetricKind::Incremental,
+                metric::MetricValue::Distribution {
+                    samples,
+                    statistic: metric::StatisticKind::Histogram,
+                },
+            ));
+        }
+
+        let sketch = |values: &[(f64, u32)]| {
+            let mut sketch = metric::DDSketch::new();
+            for (value, count) in values {
+                sketch.insert_n(*value, *count);
+            }
+            metric::MetricValue::Sketch { sketch }
+        };
+
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        assert_eq!(2, out.len());
+        for event in out {
+            let metric = event.into_metric();
+            assert_eq!(metric::MetricKind::Incremental, metric.kind());
+            match metric.series().name.name.as_str() {
+                "gauge_a" => assert_eq!(&sketch(&[(2.0, 1), (6.0, 1), (4.0, 1)]), metric.value()),
+                "distribution_a" => {
+                    assert_eq!(&sketch(&[(1.0, 2), (3.0, 2)]), metric.value())
+                }
+                _ => panic!("Unexpected metric name in aggregate output"),
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_quantiles() {
+        assert!(Aggregate::new(&AggregateConfig {
+            quantiles: Some(vec![1.5]),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn group_by_tags() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            mode: AggregationMode::Sum,
+            group_by: Some(vec!["region".to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        agg.record(make_tagged_gauge("gauge_a", "a", 1.0));
+        agg.record(make_tagged_gauge("gauge_a", "b", 2.0));
+
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        assert_eq!(1, out.len());
+        let metric = out.pop().unwrap().into_metric();
+        assert_eq!(
+            Some(
+                &vec![("region".to_string(), "us".to_string())]
+                    .into_iter()
+                    .collect()
+            ),
+            metric.tags()
+        );
+        assert_eq!(&metric::MetricValue::Gauge { value: 3.0 }, metric.value());
+    }
+
+    #[test]
+    fn group_by_tags_sums_latest_absolute_values() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            group_by: Some(vec!["region".to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        agg.record(make_tagged_gauge("gauge_a", "a", 1.0));
+        agg.record(make_tagged_gauge("gauge_a", "b", 2.0));
+        // Replaces the earlier value of host "a".
+        agg.record(make_tagged_gauge("gauge_a", "a", 5.0));
+
+        assert_eq!(
+            metric::MetricValue::Gauge { value: 7.0 },
+            flushed_value(&mut agg)
+        );
+
+        // Each interval starts over.
+        agg.record(make_tagged_gauge("gauge_a", "b", 3.0));
+        assert_eq!(
+            metric::MetricValue::Gauge { value: 3.0 },
+            flushed_value(&mut agg)
+        );
+    }
+
+    #[test]
+    fn sum_of_absolute_counters() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            mode: AggregationMode::Sum,
+            group_by: Some(vec!["region".to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let counter = |host: &str, value| {
+            Event::Metric(
+                Metric::new(
+                    "counter_a",
+                    metric::MetricKind::Absolute,
+                    metric::MetricValue::Counter { value },
+                )
+                .with_tags(Some(
+                    vec![
+                        ("host".to_string(), host.to_string()),
+                        ("region".to_string(), "us".to_string()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                )),
+            )
+        };
+        // Scrapes of two cumulative counters.
+        for value in &[10.0, 12.0, 15.0, 20.0] {
+            agg.record(counter("a", *value));
+        }
+        agg.record(counter("b", 3.0));
+        agg.record(counter("b", 5.0));
+
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        assert_eq!(1, out.len());
+        let metric = out.pop().unwrap().into_metric();
+        assert_eq!(metric::MetricKind::Absolute, metric.kind());
+        assert_eq!(
+            &metric::MetricValue::Counter { value: 25.0 },
+            metric.value()
+        );
+    }
+
+    #[test]
+    fn group_by_tags_samples_every_value() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            mode: AggregationMode::Count,
+            group_by: Some(vec!["region".to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        agg.record(make_tagged_gauge("gauge_a", "a", 1.0));
+        agg.record(make_tagged_gauge("gauge_a", "b", 2.0));
+        agg.record(make_tagged_gauge("gauge_a", "a", 5.0));
+
+        assert_eq!(
+            metric::MetricValue::Counter { value: 3.0 },
+            flushed_value(&mut agg)
+        );
+    }
+
     #[tokio::test]
     async fn transform_shutdown() {
         let agg = toml::from_str::<AggregateConfig>(
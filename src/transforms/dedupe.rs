@@ -3,15 +3,26 @@ use crate::{
         log_schema, DataType, GenerateConfig, TransformConfig, TransformContext,
         TransformDescription,
     },
-    event::{Event, Value},
-    internal_events::DedupeEventDiscarded,
-    transforms::{TaskTransform, Transform},
+    event::{Event, Value, VrlTarget},
+    internal_events::{
+        DedupeCacheEvicted, DedupeCacheLookup, DedupeEventDiscarded, DedupeKeyFailed,
+    },
+    transforms::{state::TransformState, TaskTransform, Transform},
 };
+use async_stream::stream;
 use bytes::Bytes;
+use chrono::Utc;
 use futures::{Stream, StreamExt};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
-use std::{future::ready, pin::Pin};
+use shared::TimeZone;
+use std::{
+    hash::Hasher,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+use twox_hash::XxHash64;
+use vrl::{diagnostic::Formatter, Program, Runtime};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -22,10 +33,24 @@ pub enum FieldMatchConfig {
     IgnoreFields(Vec<String>),
 }
 
+/// Builds the key events are compared by, instead of a set of fields.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum KeyConfig {
+    /// The value of a VRL expression.
+    Source(String),
+    /// A hash of all of the fields of the event.
+    Hash,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct CacheConfig {
     pub num_events: usize,
+    /// How long an event is remembered after it is first seen. Without it,
+    /// events are remembered until the cache is full.
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -33,12 +58,22 @@ pub struct CacheConfig {
 pub struct DedupeConfig {
     #[serde(default)]
     pub fields: Option<FieldMatchConfig>,
+    #[serde(default)]
+    pub key: Option<KeyConfig>,
     #[serde(default = "default_cache_config")]
     pub cache: CacheConfig,
+    /// Checkpoints the cache to the data directory when the input ends, so
+    /// that it survives restarts. Only a graceful shutdown ends the input, so
+    /// the cache is lost if Vector crashes or is killed.
+    #[serde(default)]
+    pub persist_state: bool,
 }
 
 const fn default_cache_config() -> CacheConfig {
-    CacheConfig { num_events: 5000 }
+    CacheConfig {
+        num_events: 5000,
+        ttl_secs: None,
+    }
 }
 
 impl DedupeConfig {
@@ -58,9 +93,16 @@ impl DedupeConfig {
     }
 }
 
+enum DedupeKey {
+    Fields(FieldMatchConfig),
+    Source(Program),
+    Hash,
+}
+
 pub struct Dedupe {
-    fields: FieldMatchConfig,
-    cache: LruCache<CacheEntry, bool>,
+    key: DedupeKey,
+    ttl: Option<Duration>,
+    cache: TransformState<LruCache<CacheEntry, Instant>>,
 }
 
 inventory::submit! {
@@ -71,7 +113,9 @@ impl GenerateConfig for DedupeConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             fields: None,
+            key: None,
             cache: default_cache_config(),
+            persist_state: false,
         })
         .unwrap()
     }
@@ -80,8 +124,17 @@ impl GenerateConfig for DedupeConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "dedupe")]
 impl TransformConfig for DedupeConfig {
-    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::task(Dedupe::new(self.clone())))
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        let num_events = self.cache.num_events;
+        let ttl = self.cache.ttl_secs.map(Duration::from_secs);
+        let cache = TransformState::new(
+            context,
+            self,
+            self.persist_state,
+            |bytes| decode_cache(bytes, num_events, ttl),
+            || LruCache::new(num_events),
+        );
+        Dedupe::with_cache(self.clone(), &context.enrichment_tables, cache).map(Transform::task)
     }
 
     fn input_type(&self) -> DataType {
@@ -99,7 +152,8 @@ impl TransformConfig for DedupeConfig {
 
 type TypeId = u8;
 
-/// A CacheEntry comes in two forms, depending on the FieldMatchConfig in use.
+/// A CacheEntry comes in four forms, depending on the FieldMatchConfig or
+/// KeyConfig in use.
 ///
 /// When matching fields, a CacheEntry contains a vector of optional 2-tuples.
 /// Each element in the vector represents one field in the corresponding
@@ -122,10 +176,19 @@ type TypeId = u8;
 /// iterating over the fields of the incoming Events, we know that the
 /// CacheEntries for 2 equivalent events will always contain the fields in the
 /// same order.
-#[derive(PartialEq, Eq, Hash)]
+///
+/// When keying by a VRL expression, a CacheEntry contains the TypeId and data
+/// as Bytes of the value the expression resolved to.
+///
+/// When keying by a hash, a CacheEntry contains a hash of the names, TypeIds
+/// and data of all of the fields of the LogEvent, which keeps the cache small
+/// at the cost of a negligible chance of collisions.
+#[derive(PartialEq, Eq, Hash, Deserialize, Serialize)]
 enum CacheEntry {
     Match(Vec<Option<(TypeId, Bytes)>>),
     Ignore(Vec<(String, TypeId, Bytes)>),
+    Key(TypeId, Bytes),
+    Hash(u64),
 }
 
 /// Assigns a unique number to each of the types supported by Event::Value.
@@ -143,24 +206,144 @@ const fn type_id_for_value(val: &Value) -> TypeId {
 }
 
 impl Dedupe {
-    pub fn new(config: DedupeConfig) -> Self {
-        let num_entries = config.cache.num_events;
-        let fields = config.fill_default_fields_match();
-        Self {
-            fields,
-            cache: LruCache::new(num_entries),
-        }
+    pub fn new(
+        config: DedupeConfig,
+        enrichment_tables: &enrichment::TableRegistry,
+    ) -> crate::Result<Self> {
+        let cache = TransformState::detached(LruCache::new(config.cache.num_events));
+        Self::with_cache(config, enrichment_tables, cache)
+    }
+
+    fn with_cache(
+        config: DedupeConfig,
+        enrichment_tables: &enrichment::TableRegistry,
+        cache: TransformState<LruCache<CacheEntry, Instant>>,
+    ) -> crate::Result<Self> {
+        let key = match (&config.fields, &config.key) {
+            (Some(_), Some(_)) => {
+                return Err("only one of `fields` and `key` can be provided".into());
+            }
+            (_, None) => DedupeKey::Fields(config.fill_default_fields_match()),
+            (None, Some(KeyConfig::Source(source))) => {
+                let mut functions = vrl_stdlib::all();
+                functions.append(&mut enrichment::vrl_functions());
+
+                let program = vrl::compile(
+                    source,
+                    &functions,
+                    Some(Box::new(enrichment_tables.clone())),
+                )
+                .map_err(|diagnostics| Formatter::new(source, diagnostics).colored().to_string())?;
+                DedupeKey::Source(program)
+            }
+            (None, Some(KeyConfig::Hash)) => DedupeKey::Hash,
+        };
+
+        Ok(Self {
+            key,
+            ttl: config.cache.ttl_secs.map(Duration::from_secs),
+            cache,
+        })
     }
 
     fn transform_one(&mut self, event: Event) -> Option<Event> {
-        let cache_entry = build_cache_entry(&event, &self.fields);
-        if self.cache.put(cache_entry, true).is_some() {
+        let cache_entry = match self.build_cache_entry(&event) {
+            Ok(cache_entry) => cache_entry,
+            Err(error) => {
+                emit!(DedupeKeyFailed { error });
+                return Some(event);
+            }
+        };
+
+        let mut cache = self.cache.lock();
+        let duplicate = match self.ttl {
+            Some(ttl) => {
+                // Entries aren't refreshed when their events are seen again,
+                // so the least recently used entries are the oldest ones.
+                while let Some((_, seen)) = cache.peek_lru() {
+                    if seen.elapsed() < ttl {
+                        break;
+                    }
+                    cache.pop_lru();
+                    emit!(DedupeCacheEvicted { reason: "ttl" });
+                }
+                cache.contains(&cache_entry)
+            }
+            None => cache.get(&cache_entry).is_some(),
+        };
+        emit!(DedupeCacheLookup { hit: duplicate });
+
+        if duplicate {
             emit!(DedupeEventDiscarded { event });
             None
         } else {
+            if cache.len() == cache.cap() {
+                emit!(DedupeCacheEvicted { reason: "size" });
+            }
+            cache.put(cache_entry, Instant::now());
             Some(event)
         }
     }
+
+    fn build_cache_entry(&self, event: &Event) -> Result<CacheEntry, String> {
+        match &self.key {
+            DedupeKey::Fields(fields) => Ok(build_cache_entry(event, fields)),
+            DedupeKey::Source(program) => {
+                // The key is resolved against a copy of the event, so any
+                // changes the program makes to it are discarded.
+                let mut target = VrlTarget::new(event.clone());
+                let value = Runtime::default()
+                    .resolve(&mut target, program, &TimeZone::default())
+                    .map_err(|error| error.to_string())?;
+                let value = Value::from(value);
+                Ok(CacheEntry::Key(type_id_for_value(&value), value.as_bytes()))
+            }
+            DedupeKey::Hash => {
+                let mut hasher = XxHash64::with_seed(0);
+                for (field_name, value) in event.as_log().all_fields() {
+                    let bytes = value.as_bytes();
+                    hasher.write_usize(field_name.len());
+                    hasher.write(field_name.as_bytes());
+                    hasher.write_u8(type_id_for_value(value));
+                    hasher.write_usize(bytes.len());
+                    hasher.write(&bytes);
+                }
+                Ok(CacheEntry::Hash(hasher.finish()))
+            }
+        }
+    }
+}
+
+/// Encodes the cache entries from the least to the most recently used, so
+/// that decoding them in order restores their recency. Each entry is stored
+/// with the time it was first seen, in milliseconds since the epoch.
+fn encode_cache(cache: &LruCache<CacheEntry, Instant>) -> crate::Result<Vec<u8>> {
+    let now = Utc::now().timestamp_millis();
+    let entries = cache
+        .iter()
+        .rev()
+        .map(|(entry, seen)| (entry, now - seen.elapsed().as_millis() as i64))
+        .collect::<Vec<_>>();
+    Ok(serde_json::to_vec(&entries)?)
+}
+
+fn decode_cache(
+    bytes: &[u8],
+    num_events: usize,
+    ttl: Option<Duration>,
+) -> crate::Result<LruCache<CacheEntry, Instant>> {
+    let now = Utc::now().timestamp_millis();
+    let mut cache = LruCache::new(num_events);
+    for (entry, seen) in serde_json::from_slice::<Vec<(CacheEntry, i64)>>(bytes)? {
+        // Time spent stopped counts towards expiring the entries.
+        let age = Duration::from_millis((now - seen).max(0) as u64);
+        if ttl.map_or(false, |ttl| age >= ttl) {
+            continue;
+        }
+        let seen = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+        cache.put(entry, seen);
+    }
+    Ok(cache)
 }
 
 /// Takes in an Event and returns a CacheEntry to place into the LRU cache
@@ -196,13 +379,22 @@ fn build_cache_entry(event: &Event, fields: &FieldMatchConfig) -> CacheEntry {
 impl TaskTransform for Dedupe {
     fn transform(
         self: Box<Self>,
-        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+        mut task: Pin<Box<dyn Stream<Item = Event> + Send>>,
     ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
     where
         Self: 'static,
     {
         let mut inner = self;
-        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+        Box::pin(stream! {
+            while let Some(event) = task.next().await {
+                if let Some(event) = inner.transform_one(event) {
+                    yield event;
+                }
+            }
+            // There is nothing to flush, so the cache is only worth keeping
+            // for the next instance of the transform.
+            inner.cache.release(encode_cache);
+        })
     }
 }
 
@@ -219,10 +411,19 @@ mod tests {
     }
 
     fn make_match_transform(num_events: usize, fields: Vec<String>) -> Dedupe {
-        Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
-            fields: Some(FieldMatchConfig::MatchFields(fields)),
-        })
+        Dedupe::new(
+            DedupeConfig {
+                cache: CacheConfig {
+                    num_events,
+                    ttl_secs: None,
+                },
+                fields: Some(FieldMatchConfig::MatchFields(fields)),
+                key: None,
+                persist_state: false,
+            },
+            &Default::default(),
+        )
+        .unwrap()
     }
 
     fn make_ignore_transform(num_events: usize, given_fields: Vec<String>) -> Dedupe {
@@ -230,10 +431,19 @@ mod tests {
         let mut fields = vec!["message".into(), "timestamp".into()];
         fields.extend(given_fields);
 
-        Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
-            fields: Some(FieldMatchConfig::IgnoreFields(fields)),
-        })
+        Dedupe::new(
+            DedupeConfig {
+                cache: CacheConfig {
+                    num_events,
+                    ttl_secs: None,
+                },
+                fields: Some(FieldMatchConfig::IgnoreFields(fields)),
+                key: None,
+                persist_state: false,
+            },
+            &Default::default(),
+        )
+        .unwrap()
     }
 
     #[test]
@@ -472,4 +682,121 @@ mod tests {
         let new_event = transform.transform_one(event2.clone()).unwrap();
         assert_eq!(new_event, event2);
     }
+
+    fn make_transform(config: &str) -> Dedupe {
+        let config = toml::from_str::<DedupeConfig>(config).unwrap();
+        Dedupe::new(config, &Default::default()).unwrap()
+    }
+
+    #[test]
+    fn dedupe_ttl() {
+        let mut event = Event::from("message");
+        event.as_mut_log().insert("matched", "some value");
+
+        let mut transform = make_transform(
+            r#"
+fields.match = ["matched"]
+cache.num_events = 5
+cache.ttl_secs = 60
+"#,
+        );
+        assert_eq!(transform.transform_one(event.clone()), Some(event.clone()));
+        assert_eq!(transform.transform_one(event.clone()), None);
+
+        // Entries expire straight away, so nothing is a duplicate.
+        let mut transform = make_transform(
+            r#"
+fields.match = ["matched"]
+cache.num_events = 5
+cache.ttl_secs = 0
+"#,
+        );
+        assert_eq!(transform.transform_one(event.clone()), Some(event.clone()));
+        assert_eq!(transform.transform_one(event.clone()), Some(event));
+    }
+
+    #[test]
+    fn dedupe_key_source() {
+        let mut transform = make_transform(r#"key.source = ".id""#);
+
+        let mut event1 = Event::from("message 1");
+        event1.as_mut_log().insert("id", 1);
+
+        // Only the key is considered.
+        let mut event2 = Event::from("message 2");
+        event2.as_mut_log().insert("id", 1);
+
+        // The type of the key matters.
+        let mut event3 = Event::from("message 3");
+        event3.as_mut_log().insert("id", "1");
+
+        assert_eq!(transform.transform_one(event1.clone()), Some(event1));
+        assert_eq!(transform.transform_one(event2), None);
+        assert_eq!(transform.transform_one(event3.clone()), Some(event3));
+    }
+
+    #[test]
+    fn dedupe_key_hash() {
+        let mut transform = make_transform(r#"key = "hash""#);
+
+        let mut event1 = Event::from("message");
+        event1.as_mut_log().insert("matched", "some value");
+
+        let mut event2 = event1.clone();
+        event2.as_mut_log().insert("matched", "some value2");
+
+        assert_eq!(
+            transform.transform_one(event1.clone()),
+            Some(event1.clone())
+        );
+        assert_eq!(transform.transform_one(event2.clone()), Some(event2));
+        assert_eq!(transform.transform_one(event1), None);
+    }
+
+    #[test]
+    fn dedupe_fields_and_key() {
+        let config = toml::from_str::<DedupeConfig>(
+            r#"
+fields.match = ["matched"]
+key = "hash"
+"#,
+        )
+        .unwrap();
+        assert!(Dedupe::new(config, &Default::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn dedupe_restores_checkpoint() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let context = TransformContext {
+            key: Some("dedupe_restores_checkpoint".into()),
+            ..TransformContext::new_with_globals(crate::config::GlobalOptions {
+                data_dir: Some(data_dir.path().to_path_buf()),
+                ..Default::default()
+            })
+        };
+        let config = DedupeConfig {
+            cache: CacheConfig {
+                num_events: 5,
+                ttl_secs: None,
+            },
+            fields: Some(FieldMatchConfig::MatchFields(vec!["matched".into()])),
+            key: None,
+            persist_state: true,
+        };
+
+        let mut event = Event::from("message");
+        event.as_mut_log().insert("matched", "some value");
+
+        let transform = config.build(&context).await.unwrap().into_task();
+        let in_stream = Box::pin(futures::stream::iter(vec![event.clone()]));
+        let output = transform.transform(in_stream).collect::<Vec<_>>().await;
+        assert_eq!(output, vec![event.clone()]);
+
+        // The cache is restored, so the same event is a duplicate.
+        let transform = config.build(&context).await.unwrap().into_task();
+        let in_stream = Box::pin(futures::stream::iter(vec![event]));
+        let output = transform.transform(in_stream).collect::<Vec<_>>().await;
+        assert!(output.is_empty());
+    }
 }
@@ -0,0 +1,602 @@
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{discriminant::Discriminant, Event, EventMetadata, LogEvent},
+    internal_events::{JoinEventsMatched, JoinFlushReason, JoinUnmatchedEventsFlushed},
+    transforms::{
+        reduce::merge_strategy::{get_value_merger, MergeStrategy, ReduceValueMerger},
+        TaskTransform, Transform,
+    },
+};
+use async_stream::stream;
+use futures::{stream, Stream, StreamExt};
+use indexmap::{map::Entry, IndexMap};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+//------------------------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JoinConfig {
+    /// The sides of the join, in the order their fields are merged. Events
+    /// don't record the input they were received from, so each side is
+    /// identified by a condition. Events that don't match any side are passed
+    /// through unchanged.
+    pub sides: IndexMap<String, AnyCondition>,
+
+    /// The fields that must be equal for events to be joined.
+    pub join_by: Vec<String>,
+
+    /// How long after the first event for a key the other sides must arrive.
+    #[serde(default = "default_window_ms")]
+    pub window_ms: u64,
+
+    #[serde(default = "default_flush_period_ms")]
+    pub flush_period_ms: u64,
+
+    /// How fields found on more than one side are merged. The value from the
+    /// earliest side is kept for fields without a strategy.
+    #[serde(default)]
+    pub merge_strategies: IndexMap<String, MergeStrategy>,
+
+    /// The most keys waiting for the other sides. Once reached, the oldest
+    /// key is flushed unmatched.
+    #[serde(default = "default_max_pending")]
+    pub max_pending: usize,
+
+    /// The most events kept for each side of a key. The first events are
+    /// kept, and the events beyond them are flushed unmatched right away.
+    #[serde(default = "default_max_events_per_key")]
+    pub max_events_per_key: usize,
+
+    /// Whether the events that aren't joined are sent on once their window
+    /// has passed, rather than discarded.
+    #[serde(default = "crate::serde::default_true")]
+    pub emit_unmatched: bool,
+}
+
+const fn default_window_ms() -> u64 {
+    30000
+}
+
+const fn default_flush_period_ms() -> u64 {
+    1000
+}
+
+const fn default_max_pending() -> usize {
+    10_000
+}
+
+const fn default_max_events_per_key() -> usize {
+    100
+}
+
+inventory::submit! {
+    TransformDescription::new::<JoinConfig>("join")
+}
+
+impl GenerateConfig for JoinConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            sides: IndexMap::new(),
+            join_by: vec!["request_id".to_string()],
+            window_ms: default_window_ms(),
+            flush_period_ms: default_flush_period_ms(),
+            merge_strategies: IndexMap::new(),
+            max_pending: default_max_pending(),
+            max_events_per_key: default_max_events_per_key(),
+            emit_unmatched: true,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "join")]
+impl TransformConfig for JoinConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Join::new(self, &context.enrichment_tables).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "join"
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct Pending {
+    // Tells this key apart from earlier keys with the same discriminant in
+    // the deadline queue.
+    id: u64,
+    // The events received for each side.
+    sides: Vec<Vec<LogEvent>>,
+}
+
+impl Pending {
+    fn new(sides: usize, id: u64) -> Self {
+        Self {
+            id,
+            sides: vec![Vec::new(); sides],
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.sides.iter().all(|events| !events.is_empty())
+    }
+
+    fn into_events(self) -> impl Iterator<Item = LogEvent> {
+        self.sides.into_iter().flatten()
+    }
+}
+
+/// Merges the events into a single event, in the order they are given.
+fn merge(
+    events: impl Iterator<Item = LogEvent>,
+    strategies: &IndexMap<String, MergeStrategy>,
+) -> LogEvent {
+    let mut metadata = EventMetadata::default();
+    let mut fields: IndexMap<String, Box<dyn ReduceValueMerger>> = IndexMap::new();
+
+    for event in events {
+        let (event_fields, event_metadata) = event.into_parts();
+        metadata.merge(event_metadata);
+
+        for (k, v) in event_fields {
+            let strategy = strategies.get(&k).unwrap_or(&MergeStrategy::Discard);
+            match fields.entry(k) {
+                Entry::Vacant(entry) => match get_value_merger(v, strategy) {
+                    Ok(m) => {
+                        entry.insert(m);
+                    }
+                    Err(error) => {
+                        warn!(message = "Failed to create merger.", field = ?entry.key(), %error);
+                    }
+                },
+                Entry::Occupied(mut entry) => {
+                    if let Err(error) = entry.get_mut().add(v) {
+                        warn!(message = "Failed to merge value.", %error);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut event = LogEvent::new_with_metadata(metadata);
+    for (k, v) in fields {
+        if let Err(error) = v.insert_into(k, &mut event) {
+            warn!(message = "Failed to merge values for field.", %error);
+        }
+    }
+    event
+}
+
+pub struct Join {
+    sides: Vec<Box<dyn Condition>>,
+    join_by: Vec<String>,
+    window: Duration,
+    flush_period: Duration,
+    merge_strategies: IndexMap<String, MergeStrategy>,
+    max_pending: usize,
+    max_events_per_key: usize,
+    emit_unmatched: bool,
+    pending: IndexMap<Discriminant, Pending>,
+    // The keys in the order they were first seen, which is also the order
+    // their windows end in. Keys that have since been joined are skipped
+    // when they reach the front.
+    deadlines: VecDeque<(Instant, u64, Discriminant)>,
+    next_id: u64,
+}
+
+impl Join {
+    pub fn new(
+        config: &JoinConfig,
+        enrichment_tables: &enrichment::TableRegistry,
+    ) -> crate::Result<Self> {
+        if config.sides.len() < 2 {
+            return Err("at least two `sides` must be provided".into());
+        }
+        if config.join_by.is_empty() {
+            return Err("`join_by` must contain at least one field".into());
+        }
+        if config.max_pending == 0 {
+            return Err("`max_pending` must be greater than 0".into());
+        }
+        if config.flush_period_ms == 0 {
+            return Err("`flush_period_ms` must be greater than 0".into());
+        }
+        if config.max_events_per_key == 0 {
+            return Err("`max_events_per_key` must be greater than 0".into());
+        }
+
+        let sides = config
+            .sides
+            .values()
+            .map(|condition| condition.build(enrichment_tables))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Join {
+            sides,
+            join_by: config.join_by.clone(),
+            window: Duration::from_millis(config.window_ms),
+            flush_period: Duration::from_millis(config.flush_period_ms),
+            merge_strategies: config.merge_strategies.clone(),
+            max_pending: config.max_pending,
+            max_events_per_key: config.max_events_per_key,
+            emit_unmatched: config.emit_unmatched,
+            pending: IndexMap::new(),
+            deadlines: VecDeque::new(),
+            next_id: 0,
+        })
+    }
+
+    fn flush_unmatched(
+        &self,
+        events: impl Iterator<Item = LogEvent>,
+        reason: JoinFlushReason,
+        output: &mut Vec<Event>,
+    ) {
+        let events = events.collect::<Vec<_>>();
+        emit!(JoinUnmatchedEventsFlushed {
+            count: events.len(),
+            reason,
+            discarded: !self.emit_unmatched,
+        });
+
+        if self.emit_unmatched {
+            output.extend(events.into_iter().map(Event::from));
+        }
+    }
+
+    /// Removes the pending key of a deadline, unless it has already been
+    /// joined.
+    fn take_pending(&mut self, id: u64, discriminant: &Discriminant) -> Option<Pending> {
+        match self.pending.get(discriminant) {
+            Some(pending) if pending.id == id => self.pending.swap_remove(discriminant),
+            _ => None,
+        }
+    }
+
+    fn flush_into(&mut self, now: Instant, output: &mut Vec<Event>) {
+        while let Some((since, _, _)) = self.deadlines.front() {
+            if now.saturating_duration_since(*since) < self.window {
+                break;
+            }
+
+            let (_, id, discriminant) = self.deadlines.pop_front().unwrap();
+            if let Some(pending) = self.take_pending(id, &discriminant) {
+                self.flush_unmatched(pending.into_events(), JoinFlushReason::WindowEnded, output);
+            }
+        }
+    }
+
+    /// Flushes the oldest key that is still pending.
+    fn evict_into(&mut self, output: &mut Vec<Event>) {
+        while let Some((_, id, discriminant)) = self.deadlines.pop_front() {
+            if let Some(pending) = self.take_pending(id, &discriminant) {
+                self.flush_unmatched(pending.into_events(), JoinFlushReason::TooManyKeys, output);
+                break;
+            }
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        while let Some((_, id, discriminant)) = self.deadlines.pop_front() {
+            if let Some(pending) = self.take_pending(id, &discriminant) {
+                self.flush_unmatched(pending.into_events(), JoinFlushReason::WindowEnded, output);
+            }
+        }
+    }
+
+    fn transform_one(&mut self, now: Instant, output: &mut Vec<Event>, event: Event) {
+        self.flush_into(now, output);
+
+        let side = self.sides.iter().position(|side| side.check(&event));
+        let event = event.into_log();
+        let side = match side {
+            Some(side) if self.join_by.iter().all(|field| event.contains(field)) => side,
+            // The event can't be joined with any others.
+            _ => {
+                output.push(event.into());
+                return;
+            }
+        };
+
+        let discriminant = Discriminant::from_log_event(&event, &self.join_by);
+        let sides = self.sides.len();
+        let pending = match self.pending.entry(discriminant.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                self.next_id += 1;
+                self.deadlines
+                    .push_back((now, self.next_id, entry.key().clone()));
+                entry.insert(Pending::new(sides, self.next_id))
+            }
+        };
+
+        let events = &mut pending.sides[side];
+        if events.len() >= self.max_events_per_key {
+            self.flush_unmatched(
+                std::iter::once(event),
+                JoinFlushReason::TooManyEvents,
+                output,
+            );
+            return;
+        }
+        events.push(event);
+
+        if pending.is_complete() {
+            let pending = self.pending.swap_remove(&discriminant).unwrap();
+            emit!(JoinEventsMatched);
+            output.push(merge(pending.into_events(), &self.merge_strategies).into());
+
+            // Drop the deadlines of joined keys before they pile up.
+            if self.deadlines.len() > 2 * self.max_pending {
+                let pending = &self.pending;
+                self.deadlines.retain(|(_, id, discriminant)| {
+                    pending
+                        .get(discriminant)
+                        .map_or(false, |pending| pending.id == *id)
+                });
+            }
+        } else if self.pending.len() > self.max_pending {
+            self.evict_into(output);
+        }
+    }
+}
+
+impl TaskTransform for Join {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(me.flush_period);
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.tick() => {
+                      me.flush_into(Instant::now(), &mut output);
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_all_into(&mut output);
+                          true
+                        }
+                        Some(event) => {
+                          me.transform_one(Instant::now(), &mut output, event);
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::Value;
+
+    fn config(extra: &str) -> JoinConfig {
+        toml::from_str(&format!(
+            r#"
+join_by = [ "request_id" ]
+window_ms = 10000
+sides.request = '.type == "request"'
+sides.response = '.type == "response"'
+{}
+"#,
+            extra
+        ))
+        .unwrap()
+    }
+
+    fn join(config: &JoinConfig) -> Join {
+        Join::new(config, &Default::default()).unwrap()
+    }
+
+    fn event(kind: &str, request_id: &str, message: &str) -> Event {
+        let mut log = LogEvent::from(message);
+        log.insert("type", kind);
+        log.insert("request_id", request_id);
+        log.into()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<JoinConfig>();
+    }
+
+    #[test]
+    fn joins_matching_events() {
+        let mut join = join(&config(r#"merge_strategies.message = "array""#));
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        join.transform_one(now, &mut output, event("response", "1", "200 OK"));
+        join.transform_one(now, &mut output, event("request", "2", "GET /b"));
+        join.transform_one(now, &mut output, event("request", "1", "GET /a"));
+        assert_eq!(1, output.len());
+        assert_eq!(1, join.pending.len());
+
+        let joined = output.pop().unwrap().into_log();
+        // The fields are merged in the order of the sides.
+        assert_eq!(joined["type"], "request".into());
+        assert_eq!(joined["request_id"], "1".into());
+        assert_eq!(
+            joined["message"],
+            Value::from(vec![Value::from("GET /a"), Value::from("200 OK")])
+        );
+    }
+
+    #[test]
+    fn passes_through_events_that_cant_be_joined() {
+        let mut join = join(&config(""));
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        join.transform_one(now, &mut output, event("other", "1", "unrelated"));
+        join.transform_one(now, &mut output, LogEvent::from("no request id").into());
+        assert_eq!(2, output.len());
+        assert!(join.pending.is_empty());
+    }
+
+    #[test]
+    fn flushes_unmatched_events_after_window() {
+        let mut join = join(&config(""));
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        join.transform_one(now, &mut output, event("request", "1", "GET /a"));
+        join.transform_one(
+            now + Duration::from_secs(5),
+            &mut output,
+            event("request", "2", "GET /b"),
+        );
+        join.flush_into(now + Duration::from_secs(10), &mut output);
+        assert_eq!(1, output.len());
+        assert_eq!(output[0].as_log()["message"], "GET /a".into());
+
+        // The response arrived too late to be joined.
+        join.transform_one(
+            now + Duration::from_secs(16),
+            &mut output,
+            event("response", "2", "200 OK"),
+        );
+        assert_eq!(2, output.len());
+        assert_eq!(output[1].as_log()["message"], "GET /b".into());
+        assert_eq!(1, join.pending.len());
+    }
+
+    #[test]
+    fn discards_unmatched_events() {
+        let mut join = join(&config("emit_unmatched = false"));
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        join.transform_one(now, &mut output, event("request", "1", "GET /a"));
+        join.flush_all_into(&mut output);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn limits_pending_keys() {
+        let mut join = join(&config("max_pending = 2"));
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        for id in &["1", "2", "3"] {
+            join.transform_one(now, &mut output, event("request", id, "GET"));
+        }
+        assert_eq!(2, join.pending.len());
+        assert_eq!(1, output.len());
+        assert_eq!(output[0].as_log()["request_id"], "1".into());
+    }
+
+    #[test]
+    fn limits_events_per_key() {
+        let mut join = join(&config(
+            r#"
+max_events_per_key = 2
+merge_strategies.message = "array"
+"#,
+        ));
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        for message in &["GET /a", "GET /b", "GET /c"] {
+            join.transform_one(now, &mut output, event("request", "1", message));
+        }
+        // The first events of the side are kept.
+        assert_eq!(1, output.len());
+        assert_eq!(output[0].as_log()["message"], "GET /c".into());
+
+        join.transform_one(now, &mut output, event("response", "1", "200 OK"));
+        assert_eq!(2, output.len());
+        assert_eq!(
+            output[1].as_log()["message"],
+            Value::from(vec![
+                Value::from("GET /a"),
+                Value::from("GET /b"),
+                Value::from("200 OK")
+            ])
+        );
+    }
+
+    #[test]
+    fn restarts_window_of_joined_keys() {
+        let mut join = join(&config(""));
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        join.transform_one(now, &mut output, event("request", "1", "GET /a"));
+        join.transform_one(now, &mut output, event("response", "1", "200 OK"));
+        assert_eq!(1, output.len());
+
+        // The key is pending again, with a window of its own.
+        join.transform_one(
+            now + Duration::from_secs(5),
+            &mut output,
+            event("request", "1", "GET /b"),
+        );
+        join.flush_into(now + Duration::from_secs(10), &mut output);
+        assert_eq!(1, output.len());
+        assert_eq!(1, join.pending.len());
+
+        join.flush_into(now + Duration::from_secs(15), &mut output);
+        assert_eq!(2, output.len());
+        assert_eq!(output[1].as_log()["message"], "GET /b".into());
+        assert!(join.pending.is_empty());
+        assert!(join.deadlines.is_empty());
+    }
+
+    #[test]
+    fn requires_two_sides() {
+        let config = toml::from_str::<JoinConfig>(
+            r#"
+join_by = [ "request_id" ]
+sides.request = '.type == "request"'
+"#,
+        )
+        .unwrap();
+
+        assert!(Join::new(&config, &Default::default()).is_err());
+    }
+
+    #[test]
+    fn requires_flush_period() {
+        let config = config("flush_period_ms = 0");
+
+        assert!(Join::new(&config, &Default::default()).is_err());
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
         log_schema, DataType, GenerateConfig, TransformConfig, TransformContext,
         TransformDescription,
     },
-    event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
+    event::metric::{DDSketch, Metric, MetricKind, MetricValue, StatisticKind},
     event::{Event, Value},
     internal_events::{
         LogToMetricFieldNotFound, LogToMetricFieldNull, LogToMetricParseFloatError,
@@ -66,6 +66,14 @@ pub struct SummaryConfig {
     tags: Option<IndexMap<String, String>>,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SketchConfig {
+    field: String,
+    name: Option<String>,
+    namespace: Option<String>,
+    tags: Option<IndexMap<String, String>>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MetricConfig {
@@ -74,6 +82,7 @@ pub enum MetricConfig {
     Gauge(GaugeConfig),
     Set(SetConfig),
     Summary(SummaryConfig),
+    Sketch(SketchConfig),
 }
 
 impl MetricConfig {
@@ -84,6 +93,7 @@ impl MetricConfig {
             MetricConfig::Gauge(GaugeConfig { field, .. }) => field,
             MetricConfig::Set(SetConfig { field, .. }) => field,
             MetricConfig::Summary(SummaryConfig { field, .. }) => field,
+            MetricConfig::Sketch(SketchConfig { field, .. }) => field,
         }
     }
 }
@@ -312,6 +322,37 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             .with_tags(tags)
             .with_timestamp(timestamp))
         }
+        MetricConfig::Sketch(sketch) => {
+            let value = value.to_string_lossy().parse().map_err(|error| {
+                TransformError::ParseFloatError {
+                    field: field.to_string(),
+                    error,
+                }
+            })?;
+
+            let name = sketch.name.as_ref().unwrap_or(&sketch.field);
+            let name = render_template(name, event)?;
+
+            let namespace = sketch.namespace.as_ref();
+            let namespace = namespace
+                .map(|namespace| render_template(namespace, event))
+                .transpose()?;
+
+            let tags = render_tags(&sketch.tags, event)?;
+
+            let mut sketch = DDSketch::new();
+            sketch.insert(value);
+
+            Ok(Metric::new_with_metadata(
+                name,
+                MetricKind::Incremental,
+                MetricValue::Sketch { sketch },
+                metadata,
+            )
+            .with_namespace(namespace)
+            .with_tags(tags)
+            .with_timestamp(timestamp))
+        }
         MetricConfig::Gauge(gauge) => {
             let value = value.to_string_lossy().parse().map_err(|error| {
                 TransformError::ParseFloatError {
@@ -854,4 +895,33 @@ mod tests {
             .with_timestamp(Some(ts()))
         );
     }
+
+    #[test]
+    fn response_time_sketch() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "sketch"
+            field = "response_time"
+            "#,
+        );
+
+        let event = create_event("response_time", "2.5");
+        let metadata = event.metadata().clone();
+        let mut transform = LogToMetric::new(config);
+        let metric = transform_one(&mut transform, event).unwrap();
+
+        let mut sketch = DDSketch::new();
+        sketch.insert(2.5);
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "response_time",
+                MetricKind::Incremental,
+                MetricValue::Sketch { sketch },
+                metadata
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
 }
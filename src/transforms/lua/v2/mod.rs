@@ -1,12 +1,17 @@
 use crate::{
     config::{self, DataType, CONFIG_PATHS},
     event::Event,
-    internal_events::{LuaBuildError, LuaGcTriggered},
+    internal_events::{LuaBuildError, LuaGcTriggered, LuaSandboxViolation},
     transforms::Transform,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::path::PathBuf;
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 pub use vector_core::event::lua;
 use vector_core::transform::runtime_transform::{RuntimeTransform, Timer};
 
@@ -48,6 +53,8 @@ pub struct LuaConfig {
     #[serde(default)]
     timers: Vec<TimerConfig>,
     source: Option<String>,
+    #[serde(default)]
+    sandbox: SandboxConfig,
 }
 
 fn default_config_paths() -> Vec<PathBuf> {
@@ -80,6 +87,63 @@ struct TimerConfig {
     handler: String,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+struct SandboxConfig {
+    #[serde(default)]
+    stdlib: StdlibProfile,
+    /// The memory the Lua runtime may allocate in total.
+    memory_limit_bytes: Option<usize>,
+    /// The instructions a single hook invocation may execute.
+    max_instructions: Option<u64>,
+    /// The time a single hook invocation may run for.
+    max_execution_time_ms: Option<u64>,
+    /// When set, an event whose processing exceeds a limit is emitted as it
+    /// was received with this field (or tag, for metrics) set to the exceeded
+    /// limit, instead of being dropped.
+    violation_field: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum StdlibProfile {
+    /// Leaves out everything that reaches outside of the Lua runtime: the
+    /// `os`, `io` and `package` libraries, `require`, `dofile` and `loadfile`.
+    /// Precompiled chunks can't be loaded either.
+    Restricted,
+    Full,
+}
+
+impl Default for StdlibProfile {
+    fn default() -> Self {
+        Self::Restricted
+    }
+}
+
+/// A sandbox limit exceeded by a script.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Violation {
+    Memory,
+    Instructions,
+    ExecutionTime,
+}
+
+impl Violation {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Memory => "memory_limit_exceeded",
+            Self::Instructions => "instruction_limit_exceeded",
+            Self::ExecutionTime => "time_limit_exceeded",
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 // Implementation of methods from `TransformConfig`
 // Note that they are implemented as struct methods instead of trait implementation methods
 // because `TransformConfig` trait requires specification of a unique `typetag::serde` name.
@@ -112,8 +176,82 @@ impl LuaConfig {
 // after each transform would have significant footprint on the performance.
 const GC_INTERVAL: usize = 16;
 
+// The number of instructions after which the Lua runtime checks whether a hook has exceeded
+// its instruction or time limit. Checking after each instruction would slow scripts down
+// considerably, so the limits are enforced with this granularity.
+const LIMITS_CHECK_INTERVAL: u32 = 1000;
+
+/// The instructions and time used by the current hook invocation.
+struct Budget {
+    max_instructions: Option<u64>,
+    max_execution_time: Option<Duration>,
+    usage: Mutex<Usage>,
+}
+
+#[derive(Default)]
+struct Usage {
+    instructions: u64,
+    started: Option<Instant>,
+    exceeded: Option<Violation>,
+}
+
+impl Budget {
+    fn reset(&self) {
+        *self.usage.lock().unwrap() = Usage {
+            started: Some(Instant::now()),
+            ..Default::default()
+        };
+    }
+
+    // Called by the Lua runtime every `LIMITS_CHECK_INTERVAL` instructions. The error aborts
+    // the script, and is raised again at every check should the script catch it.
+    fn check(&self) -> mlua::Result<()> {
+        let mut usage = self.usage.lock().unwrap();
+        usage.instructions += LIMITS_CHECK_INTERVAL as u64;
+        let exceeded = if self
+            .max_instructions
+            .map_or(false, |max| usage.instructions > max)
+        {
+            Some(Violation::Instructions)
+        } else if self
+            .max_execution_time
+            .zip(usage.started)
+            .map_or(false, |(max, started)| started.elapsed() > max)
+        {
+            Some(Violation::ExecutionTime)
+        } else {
+            None
+        };
+
+        match exceeded {
+            Some(violation) => {
+                usage.exceeded = Some(violation);
+                Err(mlua::Error::RuntimeError(format!(
+                    "sandbox limit exceeded: {}",
+                    violation
+                )))
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn exceeded(&self) -> Option<Violation> {
+        self.usage.lock().unwrap().exceeded
+    }
+}
+
+fn is_memory_error(error: &mlua::Error) -> bool {
+    match error {
+        mlua::Error::MemoryError(_) => true,
+        mlua::Error::CallbackError { cause, .. } => is_memory_error(cause),
+        _ => false,
+    }
+}
+
 pub struct Lua {
     lua: mlua::Lua,
+    budget: Option<Arc<Budget>>,
+    violation_field: Option<String>,
     invocations_after_gc: usize,
     hook_init: Option<mlua::RegistryKey>,
     hook_process: mlua::RegistryKey,
@@ -130,11 +268,60 @@ fn make_registry_value(lua: &mlua::Lua, source: &str) -> mlua::Result<mlua::Regi
 
 impl Lua {
     pub fn new(config: &LuaConfig) -> crate::Result<Self> {
-        // In order to support loading C modules in Lua, we need to create unsafe instance
-        // without debug library.
-        let lua = unsafe {
-            mlua::Lua::unsafe_new_with(mlua::StdLib::ALL_SAFE, mlua::LuaOptions::default())
+        let sandbox = &config.sandbox;
+        let libs = match sandbox.stdlib {
+            StdlibProfile::Restricted => {
+                mlua::StdLib::COROUTINE
+                    | mlua::StdLib::TABLE
+                    | mlua::StdLib::STRING
+                    | mlua::StdLib::UTF8
+                    | mlua::StdLib::MATH
+            }
+            StdlibProfile::Full => mlua::StdLib::ALL_SAFE,
         };
+        let lua = match sandbox.stdlib {
+            // A safe instance also refuses to load precompiled chunks, which could otherwise
+            // be produced with `string.dump` to escape the sandbox.
+            StdlibProfile::Restricted => mlua::Lua::new_with(libs, mlua::LuaOptions::default())?,
+            // In order to support loading C modules in Lua, we need to create unsafe instance
+            // without debug library.
+            StdlibProfile::Full => unsafe {
+                mlua::Lua::unsafe_new_with(libs, mlua::LuaOptions::default())
+            },
+        };
+
+        if sandbox.stdlib == StdlibProfile::Restricted {
+            // These are part of the base library, which is always loaded.
+            let globals = lua.globals();
+            globals.set("dofile", mlua::Value::Nil)?;
+            globals.set("loadfile", mlua::Value::Nil)?;
+        }
+
+        if let Some(limit) = sandbox.memory_limit_bytes {
+            lua.set_memory_limit(limit)?;
+        }
+
+        let budget =
+            if sandbox.max_instructions.is_some() || sandbox.max_execution_time_ms.is_some() {
+                let budget = Arc::new(Budget {
+                    max_instructions: sandbox.max_instructions,
+                    max_execution_time: sandbox.max_execution_time_ms.map(Duration::from_millis),
+                    usage: Mutex::new(Usage::default()),
+                });
+                let hook_budget = Arc::clone(&budget);
+                lua.set_hook(
+                    mlua::HookTriggers {
+                        every_nth_instruction: Some(LIMITS_CHECK_INTERVAL),
+                        ..Default::default()
+                    },
+                    move |_, _| hook_budget.check(),
+                )?;
+                // Evaluating the source and hooks below is subject to the limits as well.
+                budget.reset();
+                Some(budget)
+            } else {
+                None
+            };
 
         let additional_paths = config
             .search_dirs
@@ -145,7 +332,8 @@ impl Lua {
 
         let mut timers = Vec::new();
 
-        if !additional_paths.is_empty() {
+        // Modules can only be required with the full standard library.
+        if !additional_paths.is_empty() && sandbox.stdlib == StdlibProfile::Full {
             let package = lua.globals().get::<_, mlua::Table<'_>>("package")?;
             let current_paths = package
                 .get::<_, String>("path")
@@ -189,6 +377,8 @@ impl Lua {
 
         Ok(Self {
             lua,
+            budget,
+            violation_field: sandbox.violation_field.clone(),
             invocations_after_gc: 0,
             timers,
             hook_init,
@@ -222,6 +412,26 @@ impl Lua {
         Ok(out.into_iter().next())
     }
 
+    /// Runs a hook within the limits of the sandbox, returning the limit it
+    /// exceeded if it did and its result otherwise.
+    fn limited(
+        &self,
+        hook: impl FnOnce() -> mlua::Result<()>,
+    ) -> Result<mlua::Result<()>, Violation> {
+        if let Some(budget) = &self.budget {
+            budget.reset();
+        }
+        let result = hook();
+        match result {
+            Err(error) => match self.budget.as_ref().and_then(|budget| budget.exceeded()) {
+                Some(violation) => Err(violation),
+                None if is_memory_error(&error) => Err(Violation::Memory),
+                None => Ok(Err(error)),
+            },
+            Ok(()) => Ok(Ok(())),
+        }
+    }
+
     fn attempt_gc(&mut self) {
         self.invocations_after_gc += 1;
         if self.invocations_after_gc % GC_INTERVAL == 0 {
@@ -253,18 +463,48 @@ where
 }
 
 impl RuntimeTransform for Lua {
-    fn hook_process<F>(&mut self, event: Event, emit_fn: F)
+    fn hook_process<F>(&mut self, event: Event, mut emit_fn: F)
     where
         F: FnMut(Event),
     {
+        // The event is handed over to the script, so keep it around in case it is to be
+        // emitted on a violation.
+        let original = self.violation_field.as_ref().map(|_| event.clone());
+        // Events emitted by the script are only passed on once it has completed, so that
+        // an aborted invocation doesn't emit anything besides the original event.
+        let mut emitted = Vec::new();
         let lua = &self.lua;
-        let _ = lua
-            .scope(|scope| -> mlua::Result<()> {
+        let result = self.limited(|| {
+            lua.scope(|scope| -> mlua::Result<()> {
                 lua.registry_value::<mlua::Function>(&self.hook_process)?
-                    .call((event, wrap_emit_fn(scope, emit_fn)?))
+                    .call((event, wrap_emit_fn(scope, |event| emitted.push(event))?))
             })
-            .context(RuntimeErrorHooksProcess)
-            .map_err(|e| emit!(LuaBuildError { error: e }));
+        });
+        match result {
+            Ok(result) => {
+                emitted.into_iter().for_each(&mut emit_fn);
+                let _ = result
+                    .context(RuntimeErrorHooksProcess)
+                    .map_err(|e| emit!(LuaBuildError { error: e }));
+            }
+            Err(violation) => {
+                emit!(LuaSandboxViolation {
+                    hook: "process",
+                    violation
+                });
+                if let (Some(field), Some(mut event)) = (&self.violation_field, original) {
+                    match &mut event {
+                        Event::Log(log) => {
+                            log.insert(field.as_str(), violation.as_str());
+                        }
+                        Event::Metric(metric) => {
+                            metric.insert_tag(field.clone(), violation.as_str().to_owned());
+                        }
+                    }
+                    emit_fn(event);
+                }
+            }
+        }
 
         self.attempt_gc();
     }
@@ -274,8 +514,8 @@ impl RuntimeTransform for Lua {
         F: FnMut(Event),
     {
         let lua = &self.lua;
-        let _ = lua
-            .scope(|scope| -> mlua::Result<()> {
+        let result = self.limited(|| {
+            lua.scope(|scope| -> mlua::Result<()> {
                 match &self.hook_init {
                     Some(key) => lua
                         .registry_value::<mlua::Function>(key)?
@@ -283,8 +523,18 @@ impl RuntimeTransform for Lua {
                     None => Ok(()),
                 }
             })
-            .context(RuntimeErrorHooksInit)
-            .map_err(|error| error!(%error, rate_limit = 30));
+        });
+        match result {
+            Ok(result) => {
+                let _ = result
+                    .context(RuntimeErrorHooksInit)
+                    .map_err(|error| error!(%error, rate_limit = 30));
+            }
+            Err(violation) => emit!(LuaSandboxViolation {
+                hook: "init",
+                violation
+            }),
+        }
 
         self.attempt_gc();
     }
@@ -294,8 +544,8 @@ impl RuntimeTransform for Lua {
         F: FnMut(Event),
     {
         let lua = &self.lua;
-        let _ = lua
-            .scope(|scope| -> mlua::Result<()> {
+        let result = self.limited(|| {
+            lua.scope(|scope| -> mlua::Result<()> {
                 match &self.hook_shutdown {
                     Some(key) => lua
                         .registry_value::<mlua::Function>(key)?
@@ -303,8 +553,18 @@ impl RuntimeTransform for Lua {
                     None => Ok(()),
                 }
             })
-            .context(RuntimeErrorHooksShutdown)
-            .map_err(|error| error!(%error, rate_limit = 30));
+        });
+        match result {
+            Ok(result) => {
+                let _ = result
+                    .context(RuntimeErrorHooksShutdown)
+                    .map_err(|error| error!(%error, rate_limit = 30));
+            }
+            Err(violation) => emit!(LuaSandboxViolation {
+                hook: "shutdown",
+                violation
+            }),
+        }
 
         self.attempt_gc();
     }
@@ -314,14 +574,24 @@ impl RuntimeTransform for Lua {
         F: FnMut(Event),
     {
         let lua = &self.lua;
-        let _ = lua
-            .scope(|scope| -> mlua::Result<()> {
+        let result = self.limited(|| {
+            lua.scope(|scope| -> mlua::Result<()> {
                 let handler_key = &self.timers[timer.id as usize].1;
                 lua.registry_value::<mlua::Function>(handler_key)?
                     .call(wrap_emit_fn(scope, emit_fn)?)
             })
-            .context(RuntimeErrorTimerHandler)
-            .map_err(|error| error!(%error, rate_limit = 30));
+        });
+        match result {
+            Ok(result) => {
+                let _ = result
+                    .context(RuntimeErrorTimerHandler)
+                    .map_err(|error| error!(%error, rate_limit = 30));
+            }
+            Err(violation) => emit!(LuaSandboxViolation {
+                hook: "timer",
+                violation
+            }),
+        }
 
         self.attempt_gc();
     }
@@ -728,6 +998,7 @@ mod tests {
             end
             """
             search_dirs = [{:?}]
+            sandbox.stdlib = "full"
             "#,
             dir.path().as_os_str() // This seems a bit weird, but recall we also support windows.
         );
@@ -831,4 +1102,198 @@ mod tests {
         assert_eq!(output.len(), n);
         Ok(())
     }
+
+    async fn process_all(transform: Box<Lua>, events: Vec<Event>) -> Vec<Event> {
+        transform
+            .transform(Box::pin(stream::iter(events)))
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn lua_restricted_stdlib() -> crate::Result<()> {
+        trace_init();
+
+        let hooks = r#"
+            hooks.process = """function (event, emit)
+                event.log.os = type(os)
+                event.log.io = type(io)
+                event.log.require = type(require)
+                event.log.dofile = type(dofile)
+                event.log.string = type(string)
+                emit(event)
+            end
+            """
+            "#;
+
+        let output = process_all(from_config(hooks)?, vec![Event::new_empty_log()]).await;
+        let log = output[0].as_log();
+        assert_eq!(log["os"], "nil".into());
+        assert_eq!(log["io"], "nil".into());
+        assert_eq!(log["require"], "nil".into());
+        assert_eq!(log["dofile"], "nil".into());
+        assert_eq!(log["string"], "table".into());
+
+        let config = format!("{}\nsandbox.stdlib = \"full\"", hooks);
+        let output = process_all(from_config(&config)?, vec![Event::new_empty_log()]).await;
+        let log = output[0].as_log();
+        assert_eq!(log["os"], "table".into());
+        assert_eq!(log["io"], "table".into());
+        assert_eq!(log["require"], "function".into());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lua_restricted_bytecode() -> crate::Result<()> {
+        trace_init();
+
+        let hooks = r#"
+            hooks.process = """function (event, emit)
+                event.log.loaded = type(load(string.dump(function () end)))
+                emit(event)
+            end
+            """
+            "#;
+
+        let output = process_all(from_config(hooks)?, vec![Event::new_empty_log()]).await;
+        assert_eq!(output[0].as_log()["loaded"], "nil".into());
+
+        let config = format!("{}\nsandbox.stdlib = \"full\"", hooks);
+        let output = process_all(from_config(&config)?, vec![Event::new_empty_log()]).await;
+        assert_eq!(output[0].as_log()["loaded"], "function".into());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lua_instruction_limit() -> crate::Result<()> {
+        trace_init();
+
+        let transform = from_config(
+            r#"
+            hooks.process = """function (event, emit)
+                if event.log.loop then
+                    while true do end
+                end
+                emit(event)
+            end
+            """
+            sandbox.max_instructions = 100000
+            "#,
+        )?;
+
+        let mut looping = Event::new_empty_log();
+        looping.as_mut_log().insert("loop", true);
+        let output = process_all(transform, vec![looping, Event::from("after")]).await;
+
+        // The looping event is dropped, and later events are still processed.
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["message"], "after".into());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lua_execution_time_limit() -> crate::Result<()> {
+        trace_init();
+
+        let transform = from_config(
+            r#"
+            hooks.process = """function (event, emit)
+                while true do end
+            end
+            """
+            sandbox.max_execution_time_ms = 50
+            sandbox.violation_field = "violation"
+            "#,
+        )?;
+
+        let output = process_all(transform, vec![Event::from("loop")]).await;
+
+        // The event is emitted as received, marked with the violation.
+        assert_eq!(output.len(), 1);
+        let log = output[0].as_log();
+        assert_eq!(log["message"], "loop".into());
+        assert_eq!(log["violation"], "time_limit_exceeded".into());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lua_violation_after_emit() -> crate::Result<()> {
+        trace_init();
+
+        let transform = from_config(
+            r#"
+            hooks.process = """function (event, emit)
+                event.log.emitted = true
+                emit(event)
+                while true do end
+            end
+            """
+            sandbox.max_instructions = 100000
+            sandbox.violation_field = "violation"
+            "#,
+        )?;
+
+        let output = process_all(transform, vec![Event::from("loop")]).await;
+
+        // Only the original event is emitted, not the one emitted before the violation.
+        assert_eq!(output.len(), 1);
+        let log = output[0].as_log();
+        assert_eq!(log["message"], "loop".into());
+        assert_eq!(log.get("emitted"), None);
+        assert_eq!(log["violation"], "instruction_limit_exceeded".into());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lua_memory_limit() -> crate::Result<()> {
+        trace_init();
+
+        let transform = from_config(
+            r#"
+            hooks.process = """function (event, emit)
+                if event.log.grow then
+                    local t = {}
+                    for i = 1, 100000000 do
+                        t[i] = i
+                    end
+                end
+                emit(event)
+            end
+            """
+            sandbox.memory_limit_bytes = 10000000
+            sandbox.violation_field = "violation"
+            "#,
+        )?;
+
+        let mut growing = Event::new_empty_log();
+        growing.as_mut_log().insert("grow", true);
+        let output = process_all(transform, vec![growing, Event::from("after")]).await;
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].as_log()["violation"],
+            "memory_limit_exceeded".into()
+        );
+        assert_eq!(output[1].as_log()["message"], "after".into());
+        assert_eq!(output[1].as_log().get("violation"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn lua_source_limit() {
+        trace_init();
+
+        let err = from_config(
+            r#"
+            source = "while true do end"
+            hooks.process = "function (event, emit) emit(event) end"
+            sandbox.max_instructions = 100000
+            "#,
+        )
+        .map(|_| ())
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("instruction_limit_exceeded"), "{}", err);
+    }
 }
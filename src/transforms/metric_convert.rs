@@ -0,0 +1,436 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{
+        metric::{Metric, MetricData, MetricKind, MetricSeries, MetricValue},
+        Event,
+    },
+    internal_events::MetricConvertSeriesExpired,
+    transforms::{FunctionTransform, Transform},
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map, HashMap},
+    time::{Duration, Instant},
+};
+
+// How often the state of idle series is looked for.
+const EXPIRE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MetricConvertConfig {
+    pub mode: ConvertMode,
+    /// The time after which the state of a series that hasn't been seen is
+    /// forgotten, so that it starts over the next time it is seen.
+    #[serde(default = "default_expire_after_secs")]
+    pub expire_after_secs: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConvertMode {
+    /// Converts absolute metrics to the increment since the previous value of
+    /// their series. Counters and histograms whose count decreased are taken
+    /// to have been reset, and count in full. Summaries and sketches can't be
+    /// subtracted, and are passed through as they are.
+    Incremental,
+    /// Converts incremental metrics to the sum of the increments of their
+    /// series.
+    Absolute,
+    /// Converts counters to absolute gauges of their per-second rate of
+    /// increase, using the timestamps of the metrics.
+    Rate,
+}
+
+const fn default_expire_after_secs() -> u64 {
+    300
+}
+
+inventory::submit! {
+    TransformDescription::new::<MetricConvertConfig>("metric_convert")
+}
+
+impl GenerateConfig for MetricConvertConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            mode: ConvertMode::Incremental,
+            expire_after_secs: default_expire_after_secs(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "metric_convert")]
+impl TransformConfig for MetricConvertConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(MetricConvert::new(self)))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "metric_convert"
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SeriesState {
+    data: MetricData,
+    last_seen: Instant,
+}
+
+#[derive(Clone, Debug)]
+pub struct MetricConvert {
+    mode: ConvertMode,
+    expire_after: Duration,
+    series: HashMap<MetricSeries, SeriesState>,
+    last_expired: Instant,
+}
+
+impl MetricConvert {
+    pub fn new(config: &MetricConvertConfig) -> Self {
+        Self {
+            mode: config.mode,
+            expire_after: Duration::from_secs(config.expire_after_secs),
+            series: HashMap::new(),
+            last_expired: Instant::now(),
+        }
+    }
+
+    fn transform_one(&mut self, metric: Metric, now: Instant) -> Option<Metric> {
+        self.expire(now);
+        match (self.mode, metric.kind()) {
+            (ConvertMode::Incremental, MetricKind::Incremental) => Some(metric),
+            (ConvertMode::Incremental, MetricKind::Absolute) => {
+                self.absolute_to_incremental(metric, now)
+            }
+            (ConvertMode::Absolute, MetricKind::Incremental) => {
+                Some(self.incremental_to_absolute(metric, now))
+            }
+            (ConvertMode::Absolute, MetricKind::Absolute) => {
+                // Later increments add up from this value.
+                self.record(&metric, metric.data().clone(), now);
+                Some(metric)
+            }
+            (ConvertMode::Rate, _) => self.rate(metric, now),
+        }
+    }
+
+    /// Records the state of the metric's series, returning the previous one.
+    fn record(&mut self, metric: &Metric, data: MetricData, now: Instant) -> Option<MetricData> {
+        self.series
+            .insert(
+                metric.series().clone(),
+                SeriesState {
+                    data,
+                    last_seen: now,
+                },
+            )
+            .map(|previous| previous.data)
+    }
+
+    fn absolute_to_incremental(&mut self, mut metric: Metric, now: Instant) -> Option<Metric> {
+        if matches!(
+            metric.value(),
+            MetricValue::AggregatedSummary { .. } | MetricValue::Sketch { .. }
+        ) {
+            return Some(metric);
+        }
+
+        // The first value of a series only serves as the reference for the
+        // next one.
+        let previous = self.record(&metric, metric.data().clone(), now)?;
+        if is_reset(&previous.value, metric.value()) || metric.subtract(&previous) {
+            Some(metric.into_incremental())
+        } else {
+            // The metric changed type, so it can't be subtracted from the
+            // previous value. It is passed through as it is, and is the
+            // reference from now on.
+            Some(metric)
+        }
+    }
+
+    fn incremental_to_absolute(&mut self, metric: Metric, now: Instant) -> Metric {
+        let value = match self.series.entry(metric.series().clone()) {
+            hash_map::Entry::Occupied(mut entry) => {
+                let state = entry.get_mut();
+                state.last_seen = now;
+                if !state.data.add(metric.data()) {
+                    // The metric changed type, start over from it.
+                    state.data = metric.data().clone();
+                }
+                state.data.value.clone()
+            }
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(SeriesState {
+                    data: metric.data().clone(),
+                    last_seen: now,
+                });
+                metric.value().clone()
+            }
+        };
+        metric.with_value(value).into_absolute()
+    }
+
+    fn rate(&mut self, metric: Metric, now: Instant) -> Option<Metric> {
+        let value = match metric.value() {
+            MetricValue::Counter { value } => *value,
+            _ => return Some(metric),
+        };
+        let timestamp = metric.timestamp().unwrap_or_else(Utc::now);
+        let data = MetricData {
+            timestamp: Some(timestamp),
+            ..metric.data().clone()
+        };
+
+        let previous = self.record(&metric, data, now)?;
+        let previous_value = match previous.value {
+            MetricValue::Counter { value } if previous.kind == metric.kind() => value,
+            // The metric changed type or kind, and is the reference from now on.
+            _ => return None,
+        };
+        let increase = match metric.kind() {
+            MetricKind::Incremental => value,
+            // The counter was reset.
+            MetricKind::Absolute if value < previous_value => value,
+            MetricKind::Absolute => value - previous_value,
+        };
+        let elapsed = previous
+            .timestamp
+            .and_then(|previous| (timestamp - previous).to_std().ok())
+            .map(|elapsed| elapsed.as_secs_f64())
+            .filter(|elapsed| *elapsed > 0.0)?;
+
+        Some(
+            metric
+                .with_timestamp(Some(timestamp))
+                .with_value(MetricValue::Gauge {
+                    value: increase / elapsed,
+                })
+                .into_absolute(),
+        )
+    }
+
+    fn expire(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.last_expired) < EXPIRE_INTERVAL {
+            return;
+        }
+        self.last_expired = now;
+
+        let expire_after = self.expire_after;
+        let count = self.series.len();
+        self.series
+            .retain(|_, state| now.saturating_duration_since(state.last_seen) < expire_after);
+        let expired = count - self.series.len();
+        if expired > 0 {
+            emit!(MetricConvertSeriesExpired { count: expired });
+        }
+    }
+}
+
+fn is_reset(previous: &MetricValue, current: &MetricValue) -> bool {
+    match (previous, current) {
+        (MetricValue::Counter { value: previous }, MetricValue::Counter { value: current }) => {
+            current < previous
+        }
+        (
+            MetricValue::AggregatedHistogram {
+                count: previous, ..
+            },
+            MetricValue::AggregatedHistogram { count: current, .. },
+        ) => current < previous,
+        _ => false,
+    }
+}
+
+impl FunctionTransform for MetricConvert {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        output.extend(
+            self.transform_one(event.into_metric(), Instant::now())
+                .map(Event::Metric),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::Quantile;
+    use chrono::{DateTime, TimeZone};
+
+    fn metric_convert(mode: ConvertMode) -> MetricConvert {
+        MetricConvert::new(&MetricConvertConfig {
+            mode,
+            expire_after_secs: default_expire_after_secs(),
+        })
+    }
+
+    fn counter(kind: MetricKind, value: f64) -> Metric {
+        Metric::new("requests", kind, MetricValue::Counter { value })
+    }
+
+    fn at(metric: Metric, secs: i64) -> Metric {
+        metric.with_timestamp(Some(timestamp(secs)))
+    }
+
+    fn timestamp(secs: i64) -> DateTime<Utc> {
+        Utc.ymd(2021, 10, 1).and_hms(0, 0, 0) + chrono::Duration::seconds(secs)
+    }
+
+    fn value(metric: Option<Metric>) -> Option<(MetricKind, MetricValue)> {
+        metric.map(|metric| (metric.kind(), metric.value().clone()))
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MetricConvertConfig>();
+    }
+
+    #[test]
+    fn absolute_to_incremental() {
+        let mut transform = metric_convert(ConvertMode::Incremental);
+        let now = Instant::now();
+
+        let values = [10.0, 15.0, 15.0, 3.0, 5.0]
+            .iter()
+            .map(|absolute| {
+                value(transform.transform_one(counter(MetricKind::Absolute, *absolute), now))
+            })
+            .collect::<Vec<_>>();
+
+        let incremental = |value| Some((MetricKind::Incremental, MetricValue::Counter { value }));
+        assert_eq!(
+            values,
+            vec![
+                None,
+                incremental(5.0),
+                incremental(0.0),
+                // The counter was reset.
+                incremental(3.0),
+                incremental(2.0),
+            ]
+        );
+
+        // Incremental metrics are passed through.
+        let metric = counter(MetricKind::Incremental, 1.0);
+        assert_eq!(transform.transform_one(metric.clone(), now), Some(metric));
+    }
+
+    #[test]
+    fn absolute_to_incremental_passes_summaries_through() {
+        let mut transform = metric_convert(ConvertMode::Incremental);
+        let now = Instant::now();
+
+        let summary = |count| {
+            Metric::new(
+                "latency",
+                MetricKind::Absolute,
+                MetricValue::AggregatedSummary {
+                    quantiles: vec![Quantile {
+                        upper_limit: 0.5,
+                        value: 1.0,
+                    }],
+                    count,
+                    sum: count as f64,
+                },
+            )
+        };
+        for count in [10, 15, 3].iter() {
+            let metric = summary(*count);
+            assert_eq!(transform.transform_one(metric.clone(), now), Some(metric));
+        }
+    }
+
+    #[test]
+    fn absolute_to_incremental_passes_changed_types_through() {
+        let mut transform = metric_convert(ConvertMode::Incremental);
+        let now = Instant::now();
+
+        assert_eq!(
+            transform.transform_one(counter(MetricKind::Absolute, 10.0), now),
+            None
+        );
+        let gauge = Metric::new(
+            "requests",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 15.0 },
+        );
+        assert_eq!(transform.transform_one(gauge.clone(), now), Some(gauge));
+    }
+
+    #[test]
+    fn incremental_to_absolute() {
+        let mut transform = metric_convert(ConvertMode::Absolute);
+        let now = Instant::now();
+
+        let absolute = |value| Some((MetricKind::Absolute, MetricValue::Counter { value }));
+        let metric = counter(MetricKind::Incremental, 2.0);
+        assert_eq!(
+            value(transform.transform_one(metric.clone(), now)),
+            absolute(2.0)
+        );
+        assert_eq!(value(transform.transform_one(metric, now)), absolute(4.0));
+
+        // Absolute metrics set the value increments add up from.
+        let metric = counter(MetricKind::Absolute, 10.0);
+        assert_eq!(value(transform.transform_one(metric, now)), absolute(10.0));
+        let metric = counter(MetricKind::Incremental, 1.0);
+        assert_eq!(value(transform.transform_one(metric, now)), absolute(11.0));
+    }
+
+    #[test]
+    fn counters_to_rates() {
+        let mut transform = metric_convert(ConvertMode::Rate);
+        let now = Instant::now();
+
+        let gauge = |value| Some((MetricKind::Absolute, MetricValue::Gauge { value }));
+        let metric = at(counter(MetricKind::Absolute, 100.0), 0);
+        assert_eq!(value(transform.transform_one(metric, now)), None);
+        let metric = at(counter(MetricKind::Absolute, 120.0), 10);
+        assert_eq!(value(transform.transform_one(metric, now)), gauge(2.0));
+        // The counter was reset.
+        let metric = at(counter(MetricKind::Absolute, 30.0), 20);
+        assert_eq!(value(transform.transform_one(metric, now)), gauge(3.0));
+
+        let mut transform = metric_convert(ConvertMode::Rate);
+        let metric = at(counter(MetricKind::Incremental, 5.0), 0);
+        assert_eq!(value(transform.transform_one(metric, now)), None);
+        let metric = at(counter(MetricKind::Incremental, 5.0), 5);
+        assert_eq!(value(transform.transform_one(metric, now)), gauge(1.0));
+
+        // Other metrics are passed through.
+        let metric = Metric::new(
+            "temperature",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 20.0 },
+        );
+        assert_eq!(transform.transform_one(metric.clone(), now), Some(metric));
+    }
+
+    #[test]
+    fn expires_idle_series() {
+        let mut transform = metric_convert(ConvertMode::Incremental);
+        let now = Instant::now();
+
+        let metric = counter(MetricKind::Absolute, 10.0);
+        assert_eq!(transform.transform_one(metric, now), None);
+
+        // The series starts over once it has expired.
+        let later = now + Duration::from_secs(default_expire_after_secs() + 1);
+        let metric = counter(MetricKind::Absolute, 15.0);
+        assert_eq!(transform.transform_one(metric, later), None);
+        let metric = counter(MetricKind::Absolute, 20.0);
+        assert_eq!(
+            value(transform.transform_one(metric, later)),
+            Some((MetricKind::Incremental, MetricValue::Counter { value: 5.0 }))
+        );
+    }
+}
@@ -28,6 +28,8 @@ pub mod filter;
 pub mod geoip;
 #[cfg(feature = "transforms-grok_parser")]
 pub mod grok_parser;
+#[cfg(feature = "transforms-join")]
+pub mod join;
 #[cfg(feature = "transforms-json_parser")]
 pub mod json_parser;
 #[cfg(feature = "transforms-key_value_parser")]
@@ -40,12 +42,18 @@ pub mod logfmt_parser;
 pub mod lua;
 #[cfg(feature = "transforms-merge")]
 pub mod merge;
+#[cfg(feature = "transforms-metric_convert")]
+pub mod metric_convert;
 #[cfg(feature = "transforms-metric_to_log")]
 pub mod metric_to_log;
+#[cfg(feature = "transforms-multiline")]
+pub mod multiline;
 #[cfg(feature = "transforms-reduce")]
 pub mod reduce;
 #[cfg(feature = "transforms-regex_parser")]
 pub mod regex_parser;
+#[cfg(feature = "transforms-relabel")]
+pub mod relabel;
 #[cfg(feature = "transforms-remap")]
 pub mod remap;
 #[cfg(feature = "transforms-remove_fields")]
@@ -54,16 +62,26 @@ pub mod remove_fields;
 pub mod remove_tags;
 #[cfg(feature = "transforms-rename_fields")]
 pub mod rename_fields;
+#[cfg(feature = "transforms-reorder")]
+pub mod reorder;
 #[cfg(feature = "transforms-route")]
 pub mod route;
 #[cfg(feature = "transforms-sample")]
 pub mod sample;
 #[cfg(feature = "transforms-split")]
 pub mod split;
+#[cfg(feature = "transforms-sql")]
+pub mod sql;
+#[cfg(any(feature = "transforms-dedupe", feature = "transforms-reduce"))]
+pub(crate) mod state;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub mod tag_cardinality_limit;
+#[cfg(feature = "transforms-throttle")]
+pub mod throttle;
 #[cfg(feature = "transforms-tokenizer")]
 pub mod tokenizer;
+#[cfg(feature = "transforms-wasm")]
+pub mod wasm;
 
 pub use vector_core::transform::{FunctionTransform, TaskTransform, Transform};
 
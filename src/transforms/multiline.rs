@@ -0,0 +1,409 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, EventMetadata, Value},
+    line_agg::{self, Emit, Logic},
+    sources::util::MultilineConfig,
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use bytes::Bytes;
+use futures::{future::poll_fn, stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    convert::TryFrom,
+    pin::Pin,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MultilineTransformConfig {
+    pub start_pattern: String,
+    pub condition_pattern: String,
+    pub mode: line_agg::Mode,
+    pub timeout_ms: u64,
+    /// The field holding the lines to aggregate. Defaults to the message.
+    pub field: Option<String>,
+    /// Fields identifying the streams the lines come from, such as a host
+    /// and a file name. Lines are only aggregated with lines of the same
+    /// stream.
+    #[serde(default)]
+    pub stream_keys: Vec<String>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<MultilineTransformConfig>("multiline")
+}
+
+impl GenerateConfig for MultilineTransformConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"start_pattern = "^[^\\s]"
+            condition_pattern = "^[\\s]+"
+            mode = "continue_through"
+            timeout_ms = 1000
+            stream_keys = ["host"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "multiline")]
+impl TransformConfig for MultilineTransformConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Multiline::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "multiline"
+    }
+}
+
+type StreamKey = Vec<Option<String>>;
+
+pub struct Multiline {
+    field: String,
+    stream_keys: Vec<String>,
+    logic: Logic<StreamKey, Event>,
+    /// The metadata of the events being aggregated for each stream. The
+    /// aggregator only keeps the first event of an aggregate, so the
+    /// metadata of all of them is kept aside and merged into its own.
+    metadata: HashMap<StreamKey, EventMetadata>,
+}
+
+impl Multiline {
+    pub fn new(config: &MultilineTransformConfig) -> crate::Result<Self> {
+        let logic_config = line_agg::Config::try_from(&MultilineConfig {
+            start_pattern: config.start_pattern.clone(),
+            condition_pattern: config.condition_pattern.clone(),
+            mode: config.mode,
+            timeout_ms: config.timeout_ms,
+        })?;
+
+        Ok(Self {
+            field: config
+                .field
+                .clone()
+                .unwrap_or_else(|| log_schema().message_key().into()),
+            stream_keys: config.stream_keys.clone(),
+            logic: Logic::new(logic_config),
+            metadata: HashMap::new(),
+        })
+    }
+
+    fn transform_one(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_log();
+        let line = match log.get(&self.field) {
+            Some(Value::Bytes(line)) => line.clone(),
+            // Events without a line are passed through as they are.
+            _ => {
+                output.push(event);
+                return;
+            }
+        };
+        let key = self
+            .stream_keys
+            .iter()
+            .map(|field| log.get(field).map(Value::to_string_lossy))
+            .collect::<StreamKey>();
+        let metadata = std::mem::take(event.metadata_mut());
+
+        match self.logic.handle_line(key.clone(), line, event) {
+            None => match self.metadata.entry(key) {
+                Entry::Vacant(entry) => {
+                    entry.insert(metadata);
+                }
+                Entry::Occupied(mut entry) => entry.get_mut().merge(metadata),
+            },
+            Some((key, Emit::One((line, event)))) => {
+                // The line either ends an aggregate or isn't part of one.
+                let metadata = match self.metadata.remove(&key) {
+                    Some(mut aggregated) => {
+                        aggregated.merge(metadata);
+                        aggregated
+                    }
+                    None => metadata,
+                };
+                self.emit(output, line, event, metadata);
+            }
+            Some((key, Emit::Two((line, event), (next_line, next_event)))) => {
+                // The line ends an aggregate without being part of it.
+                let aggregated = self.metadata.remove(&key).unwrap_or_default();
+                self.emit(output, line, event, aggregated);
+                self.emit(output, next_line, next_event, metadata);
+            }
+        }
+    }
+
+    fn flush_into(&mut self, output: &mut Vec<Event>, key: StreamKey, line: Bytes, event: Event) {
+        let metadata = self.metadata.remove(&key).unwrap_or_default();
+        self.emit(output, line, event, metadata);
+    }
+
+    fn emit(
+        &self,
+        output: &mut Vec<Event>,
+        line: Bytes,
+        mut event: Event,
+        metadata: EventMetadata,
+    ) {
+        *event.metadata_mut() = metadata;
+        event.as_mut_log().insert(&self.field, line);
+        output.push(event);
+    }
+}
+
+impl TaskTransform for Multiline {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    (key, (line, event)) = poll_fn(|cx| me.logic.poll_expired(cx)) => {
+                      me.flush_into(&mut output, key, line, event);
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          let drained = me.logic.drain().collect::<Vec<_>>();
+                          for (key, (line, event)) in drained {
+                              me.flush_into(&mut output, key, line, event);
+                          }
+                          true
+                        }
+                        Some(event) => {
+                          me.transform_one(&mut output, event);
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::{BatchNotifier, BatchStatus, EventStatus, LogEvent};
+    use std::{sync::Arc, time::Duration};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MultilineTransformConfig>();
+    }
+
+    async fn multiline(config: &str) -> Box<dyn TaskTransform> {
+        toml::from_str::<MultilineTransformConfig>(config)
+            .unwrap()
+            .build(&TransformContext::default())
+            .await
+            .unwrap()
+            .into_task()
+    }
+
+    fn line(host: &str, message: &str) -> Event {
+        let mut log = LogEvent::from(message);
+        log.insert("host", host);
+        log.into()
+    }
+
+    fn messages(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| event.as_log()["message"].to_string_lossy())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn multiline_per_stream() {
+        let multiline = multiline(
+            r#"
+            start_pattern = "^[^\\s]"
+            condition_pattern = "^[\\s]+"
+            mode = "continue_through"
+            timeout_ms = 60000
+            stream_keys = ["host"]
+            "#,
+        )
+        .await;
+
+        let mut no_message = LogEvent::default();
+        no_message.insert("host", "a");
+        let inputs = vec![
+            line("a", "Exception in thread main"),
+            line("b", "Exception in thread worker"),
+            line("a", "  at Foo.bar"),
+            no_message.into(),
+            line("b", "  at Baz.qux"),
+            line("a", "  at Foo.main"),
+            line("a", "done"),
+            line("b", "done"),
+        ];
+        let output = multiline
+            .transform(Box::pin(stream::iter(inputs)))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output.len(), 5);
+        assert!(!output[0].as_log().contains("message"));
+        assert_eq!(
+            messages(&output[1..3]),
+            vec![
+                "Exception in thread main\n  at Foo.bar\n  at Foo.main",
+                "done"
+            ]
+        );
+        assert_eq!(
+            messages(&output[3..]),
+            vec!["Exception in thread worker\n  at Baz.qux", "done"]
+        );
+        assert_eq!(output[1].as_log()["host"], "a".into());
+    }
+
+    #[tokio::test]
+    async fn multiline_modes() {
+        let cases = vec![
+            (
+                "continue_past",
+                r#"\\$"#,
+                vec!["one \\", "two \\", "three", "four"],
+                vec!["one \\\ntwo \\\nthree", "four"],
+            ),
+            (
+                "halt_before",
+                r#"^\\d"#,
+                vec!["1 first", "more", "2 second", "3 third"],
+                vec!["1 first\nmore", "2 second", "3 third"],
+            ),
+            (
+                "halt_with",
+                r#";$"#,
+                vec!["SELECT *", "FROM t;", "SELECT 1;"],
+                vec!["SELECT *\nFROM t;", "SELECT 1;"],
+            ),
+        ];
+
+        for (mode, pattern, lines, expected) in cases {
+            let multiline = multiline(&format!(
+                r#"
+                start_pattern = "{}"
+                condition_pattern = "{}"
+                mode = "{}"
+                timeout_ms = 60000
+                "#,
+                if mode == "continue_past" {
+                    pattern
+                } else {
+                    ".*"
+                },
+                pattern,
+                mode,
+            ))
+            .await;
+            let inputs = lines.into_iter().map(Event::from).collect::<Vec<_>>();
+
+            let output = multiline
+                .transform(Box::pin(stream::iter(inputs)))
+                .collect::<Vec<_>>()
+                .await;
+
+            assert_eq!(messages(&output), expected, "mode {}", mode);
+        }
+    }
+
+    #[tokio::test]
+    async fn multiline_timeout() {
+        let multiline = multiline(
+            r#"
+            start_pattern = "^[^\\s]"
+            condition_pattern = "^[\\s]+"
+            mode = "continue_through"
+            timeout_ms = 10
+            "#,
+        )
+        .await;
+
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let mut output = multiline.transform(Box::pin(rx));
+        tx.unbounded_send(Event::from("first")).unwrap();
+        tx.unbounded_send(Event::from("  second")).unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(5), output.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(messages(&[event]), vec!["first\n  second"]);
+
+        drop(tx);
+        assert!(output.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn multiline_keeps_metadata() {
+        let multiline = multiline(
+            r#"
+            start_pattern = "^[^\\s]"
+            condition_pattern = "^[\\s]+"
+            mode = "continue_through"
+            timeout_ms = 60000
+            "#,
+        )
+        .await;
+
+        let (first_batch, first_receiver) = BatchNotifier::new_with_receiver();
+        let mut first = Event::from(LogEvent::from("first").with_batch_notifier(&first_batch));
+        first
+            .metadata_mut()
+            .set_datadog_api_key(Some(Arc::from("first")));
+        let (second_batch, second_receiver) = BatchNotifier::new_with_receiver();
+        let mut second = Event::from(LogEvent::from("  second").with_batch_notifier(&second_batch));
+        second
+            .metadata_mut()
+            .set_datadog_api_key(Some(Arc::from("second")));
+        drop((first_batch, second_batch));
+
+        let output = multiline
+            .transform(Box::pin(stream::iter(vec![first, second])))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(
+            output[0].metadata().datadog_api_key().as_deref(),
+            Some("first")
+        );
+        // Both events are finalized along with the aggregate.
+        output[0].metadata().update_status(EventStatus::Errored);
+        drop(output);
+        assert_eq!(first_receiver.await, BatchStatus::Errored);
+        assert_eq!(second_receiver.await, BatchStatus::Errored);
+    }
+}
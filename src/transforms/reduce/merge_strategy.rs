@@ -42,6 +42,10 @@ impl ReduceValueMerger for DiscardMerger {
         v.insert(k, self.v);
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        ("discard", self.v.clone())
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -70,6 +74,10 @@ impl ReduceValueMerger for RetainMerger {
         v.insert(k, self.v);
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        ("retain", self.v.clone())
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -107,6 +115,15 @@ impl ReduceValueMerger for ConcatMerger {
         v.insert(k, Value::Bytes(self.v.into()));
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        let kind = if self.join_by == '\n' {
+            "concat_newline"
+        } else {
+            "concat"
+        };
+        (kind, Value::Bytes(Bytes::copy_from_slice(&self.v)))
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -136,6 +153,10 @@ impl ReduceValueMerger for ConcatArrayMerger {
         v.insert(k, Value::Array(self.v));
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        ("concat_array", Value::Array(self.v.clone()))
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -161,6 +182,10 @@ impl ReduceValueMerger for ArrayMerger {
         v.insert(k, Value::Array(self.v));
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        ("array", Value::Array(self.v.clone()))
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -195,6 +220,10 @@ impl ReduceValueMerger for LongestArrayMerger {
         v.insert(k, Value::Array(self.v));
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        ("longest_array", Value::Array(self.v.clone()))
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -229,6 +258,10 @@ impl ReduceValueMerger for ShortestArrayMerger {
         v.insert(k, Value::Array(self.v));
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        ("shortest_array", Value::Array(self.v.clone()))
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -275,6 +308,13 @@ impl ReduceValueMerger for FlatUniqueMerger {
         v.insert(k, Value::Array(self.v.into_iter().collect()));
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        (
+            "flat_unique",
+            Value::Array(self.v.iter().cloned().collect()),
+        )
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -312,6 +352,16 @@ impl ReduceValueMerger for TimestampWindowMerger {
         v.insert(k, Value::Timestamp(self.started));
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        (
+            "timestamp_window",
+            Value::Array(vec![
+                Value::Timestamp(self.started),
+                Value::Timestamp(self.latest),
+            ]),
+        )
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -334,6 +384,15 @@ impl From<f64> for NumberMergerValue {
     }
 }
 
+impl From<NumberMergerValue> for Value {
+    fn from(v: NumberMergerValue) -> Self {
+        match v {
+            NumberMergerValue::Int(i) => Value::Integer(i),
+            NumberMergerValue::Float(f) => Value::Float(f),
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 
 #[derive(Debug, Clone)]
@@ -377,6 +436,10 @@ impl ReduceValueMerger for AddNumbersMerger {
         };
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        ("sum", self.v.clone().into())
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -438,6 +501,10 @@ impl ReduceValueMerger for MaxNumberMerger {
         };
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        ("max", self.v.clone().into())
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -499,6 +566,10 @@ impl ReduceValueMerger for MinNumberMerger {
         };
         Ok(())
     }
+
+    fn checkpoint(&self) -> (&'static str, Value) {
+        ("min", self.v.clone().into())
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -506,6 +577,9 @@ impl ReduceValueMerger for MinNumberMerger {
 pub trait ReduceValueMerger: std::fmt::Debug + Send + Sync {
     fn add(&mut self, v: Value) -> Result<(), String>;
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String>;
+    /// Returns the kind of the merger along with its state, from which
+    /// `restore_value_merger` rebuilds it.
+    fn checkpoint(&self) -> (&'static str, Value);
 }
 
 impl From<Value> for Box<dyn ReduceValueMerger> {
@@ -585,6 +659,45 @@ pub fn get_value_merger(v: Value, m: &MergeStrategy) -> Result<Box<dyn ReduceVal
     }
 }
 
+/// Rebuilds a merger from the kind and state returned by
+/// `ReduceValueMerger::checkpoint`.
+pub fn restore_value_merger(
+    kind: &str,
+    state: Value,
+) -> Result<Box<dyn ReduceValueMerger>, String> {
+    match (kind, state) {
+        ("discard", v) => Ok(Box::new(DiscardMerger::new(v))),
+        ("retain", v) => Ok(Box::new(RetainMerger::new(v))),
+        ("concat", Value::Bytes(b)) => Ok(Box::new(ConcatMerger::new(b, ' '))),
+        ("concat_newline", Value::Bytes(b)) => Ok(Box::new(ConcatMerger::new(b, '\n'))),
+        ("concat_array", Value::Array(a)) => Ok(Box::new(ConcatArrayMerger::new(a))),
+        ("array", Value::Array(a)) => Ok(Box::new(ArrayMerger { v: a })),
+        ("longest_array", Value::Array(a)) => Ok(Box::new(LongestArrayMerger::new(a))),
+        ("shortest_array", Value::Array(a)) => Ok(Box::new(ShortestArrayMerger::new(a))),
+        ("flat_unique", v @ Value::Array(_)) => Ok(Box::new(FlatUniqueMerger::new(v))),
+        ("timestamp_window", Value::Array(a)) => match a.as_slice() {
+            [Value::Timestamp(started), Value::Timestamp(latest)] => {
+                Ok(Box::new(TimestampWindowMerger {
+                    started: *started,
+                    latest: *latest,
+                }))
+            }
+            _ => Err("expected start and end timestamps".into()),
+        },
+        ("sum", Value::Integer(i)) => Ok(Box::new(AddNumbersMerger::new(i.into()))),
+        ("sum", Value::Float(f)) => Ok(Box::new(AddNumbersMerger::new(f.into()))),
+        ("max", Value::Integer(i)) => Ok(Box::new(MaxNumberMerger::new(i.into()))),
+        ("max", Value::Float(f)) => Ok(Box::new(MaxNumberMerger::new(f.into()))),
+        ("min", Value::Integer(i)) => Ok(Box::new(MinNumberMerger::new(i.into()))),
+        ("min", Value::Float(f)) => Ok(Box::new(MinNumberMerger::new(f.into()))),
+        (kind, v) => Err(format!(
+            "unexpected state for '{}' merger: '{}'",
+            kind,
+            v.to_string_lossy()
+        )),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -825,6 +938,63 @@ mod test {
         }
     }
 
+    #[test]
+    fn restoring_mergers() {
+        let cases: Vec<(Value, Value, MergeStrategy)> = vec![
+            ("foo".into(), "bar".into(), MergeStrategy::Discard),
+            ("foo".into(), "bar".into(), MergeStrategy::Retain),
+            ("foo".into(), "bar".into(), MergeStrategy::Concat),
+            ("foo".into(), "bar".into(), MergeStrategy::ConcatNewline),
+            (json!([1]).into(), json!([2]).into(), MergeStrategy::Concat),
+            ("foo".into(), 42.into(), MergeStrategy::Array),
+            (
+                json!([1]).into(),
+                json!([2, 3]).into(),
+                MergeStrategy::LongestArray,
+            ),
+            (
+                json!([1]).into(),
+                json!([2, 3]).into(),
+                MergeStrategy::ShortestArray,
+            ),
+            ("foo".into(), "foo".into(), MergeStrategy::FlatUnique),
+            (1.into(), 2.into(), MergeStrategy::Sum),
+            (1.into(), 2.5.into(), MergeStrategy::Max),
+            (1.5.into(), 2.into(), MergeStrategy::Min),
+        ];
+        for (initial, additional, strategy) in cases {
+            let mut merger = get_value_merger(initial.clone(), &strategy).unwrap();
+            merger.add(additional.clone()).unwrap();
+            let (kind, state) = merger.checkpoint();
+            let restored = restore_value_merger(kind, state).unwrap();
+            assert_eq!(
+                output(restored),
+                merge(initial, additional, &strategy).unwrap(),
+                "{:?}",
+                strategy
+            );
+        }
+
+        let start = Utc::now();
+        let mut merger = Box::<dyn ReduceValueMerger>::from(Value::Timestamp(start));
+        merger
+            .add(Value::Timestamp(start + chrono::Duration::seconds(1)))
+            .unwrap();
+        let (kind, state) = merger.checkpoint();
+        let restored = restore_value_merger(kind, state).unwrap();
+        assert_eq!(output(restored), Value::Timestamp(start));
+
+        assert!(restore_value_merger("sum", "foo".into()).is_err());
+        assert!(restore_value_merger("unknown", "foo".into()).is_err());
+    }
+
+    fn output(merger: Box<dyn ReduceValueMerger>) -> Value {
+        let mut output = Event::new_empty_log();
+        let output = output.as_mut_log();
+        merger.insert_into("out".into(), output).unwrap();
+        output.remove("out").unwrap()
+    }
+
     fn merge(initial: Value, additional: Value, strategy: &MergeStrategy) -> Result<Value, String> {
         let mut merger = get_value_merger(initial, strategy)?;
         merger.add(additional)?;
@@ -1,21 +1,23 @@
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, TransformConfig, TransformContext, TransformDescription},
-    event::{discriminant::Discriminant, Event, EventMetadata, LogEvent},
+    event::{discriminant::Discriminant, proto, Event, EventMetadata, LogEvent, Value},
     internal_events::ReduceStaleEventFlushed,
-    transforms::{TaskTransform, Transform},
+    transforms::{state::TransformState, TaskTransform, Transform},
 };
 use async_stream::stream;
+use chrono::Utc;
 use futures::{stream, Stream, StreamExt};
 use indexmap::IndexMap;
+use prost::Message;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{hash_map, HashMap},
+    collections::{hash_map, BTreeMap, HashMap},
     pin::Pin,
     time::{Duration, Instant},
 };
 
-mod merge_strategy;
+pub(crate) mod merge_strategy;
 
 use merge_strategy::*;
 
@@ -40,6 +42,11 @@ pub struct ReduceConfig {
     /// reduce.
     pub ends_when: Option<AnyCondition>,
     pub starts_when: Option<AnyCondition>,
+
+    /// Checkpoints the unflushed reductions to the data directory when the
+    /// input ends, instead of flushing them. Only a graceful shutdown ends
+    /// the input, so the reductions are lost if Vector crashes or is killed.
+    pub persist_state: bool,
 }
 
 inventory::submit! {
@@ -48,11 +55,24 @@ inventory::submit! {
 
 impl_generate_config_from_default!(ReduceConfig);
 
+impl ReduceConfig {
+    fn expire_after(&self) -> Duration {
+        Duration::from_millis(self.expire_after_ms.unwrap_or(30000))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "reduce")]
 impl TransformConfig for ReduceConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Reduce::new(self, &context.enrichment_tables).map(Transform::task)
+        let states = TransformState::new(
+            context,
+            self,
+            self.persist_state,
+            |bytes| decode_states(bytes, &self.group_by, self.expire_after()),
+            HashMap::new,
+        );
+        Reduce::with_states(self, &context.enrichment_tables, states).map(Transform::task)
     }
 
     fn input_type(&self) -> DataType {
@@ -140,6 +160,117 @@ impl ReduceState {
         }
         event
     }
+
+    /// Encodes the state as a log event. The metadata is left out, as its
+    /// finalizers can't outlive the process: once the state is checkpointed,
+    /// they are dropped and the merged events are acknowledged to their
+    /// sources, and the restored state starts with empty metadata.
+    fn checkpoint(&self, discriminant: &Discriminant, group_by: &[String]) -> LogEvent {
+        let mut group = LogEvent::default();
+        for (field, value) in group_by.iter().zip(discriminant.values()) {
+            if let Some(value) = value {
+                group.insert(field.as_str(), value.clone());
+            }
+        }
+        let fields = self
+            .fields
+            .iter()
+            .map(|(k, merger)| {
+                let (kind, state) = merger.checkpoint();
+                let mut merger = BTreeMap::new();
+                merger.insert("kind".to_owned(), Value::from(kind));
+                merger.insert("state".to_owned(), state);
+                (k.clone(), Value::Map(merger))
+            })
+            .collect();
+        let updated_at = Utc::now()
+            - chrono::Duration::from_std(self.stale_since.elapsed())
+                .unwrap_or_else(|_| chrono::Duration::zero());
+
+        let mut event = LogEvent::default();
+        event.insert("group", Value::Map(group.into_parts().0));
+        event.insert("fields", Value::Map(fields));
+        event.insert("updated_at", updated_at);
+        event
+    }
+
+    fn restore(
+        event: LogEvent,
+        group_by: &[String],
+        expire_after: Duration,
+    ) -> Result<(Discriminant, Self), String> {
+        let (mut parts, _) = event.into_parts();
+        let group = match parts.remove("group") {
+            Some(Value::Map(group)) => LogEvent::from(group),
+            _ => return Err("missing group".into()),
+        };
+        let fields = match parts.remove("fields") {
+            Some(Value::Map(fields)) => fields,
+            _ => return Err("missing fields".into()),
+        };
+        let updated_at = match parts.remove("updated_at") {
+            Some(Value::Timestamp(ts)) => ts,
+            _ => return Err("missing update timestamp".into()),
+        };
+
+        let fields = fields
+            .into_iter()
+            .map(|(k, merger)| match merger {
+                Value::Map(mut merger) => {
+                    let kind = merger
+                        .remove("kind")
+                        .map(|kind| kind.to_string_lossy())
+                        .unwrap_or_default();
+                    let state = merger.remove("state").unwrap_or(Value::Null);
+                    restore_value_merger(&kind, state).map(|merger| (k, merger))
+                }
+                _ => Err(format!("invalid merger for field {:?}", k)),
+            })
+            .collect::<Result<HashMap<_, _>, String>>()?;
+        // Time spent stopped counts towards expiring the state, but no more
+        // than is needed to flush it on the next tick.
+        let elapsed = (Utc::now() - updated_at)
+            .to_std()
+            .unwrap_or_default()
+            .min(expire_after);
+
+        Ok((
+            Discriminant::from_log_event(&group, group_by),
+            Self {
+                fields,
+                stale_since: Instant::now()
+                    .checked_sub(elapsed)
+                    .unwrap_or_else(Instant::now),
+                metadata: EventMetadata::default(),
+            },
+        ))
+    }
+}
+
+fn encode_states(
+    states: &HashMap<Discriminant, ReduceState>,
+    group_by: &[String],
+) -> crate::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for (discriminant, state) in states {
+        let event = Event::from(state.checkpoint(discriminant, group_by));
+        proto::EventWrapper::from(event).encode_length_delimited(&mut bytes)?;
+    }
+    Ok(bytes)
+}
+
+fn decode_states(
+    mut bytes: &[u8],
+    group_by: &[String],
+    expire_after: Duration,
+) -> crate::Result<HashMap<Discriminant, ReduceState>> {
+    let mut states = HashMap::new();
+    while !bytes.is_empty() {
+        let event = Event::from(proto::EventWrapper::decode_length_delimited(&mut bytes)?);
+        let (discriminant, state) = ReduceState::restore(event.into_log(), group_by, expire_after)?;
+        states.insert(discriminant, state);
+    }
+    Ok(states)
 }
 
 //------------------------------------------------------------------------------
@@ -149,7 +280,7 @@ pub struct Reduce {
     flush_period: Duration,
     group_by: Vec<String>,
     merge_strategies: IndexMap<String, MergeStrategy>,
-    reduce_merge_states: HashMap<Discriminant, ReduceState>,
+    reduce_merge_states: TransformState<HashMap<Discriminant, ReduceState>>,
     ends_when: Option<Box<dyn Condition>>,
     starts_when: Option<Box<dyn Condition>>,
 }
@@ -158,6 +289,15 @@ impl Reduce {
     pub fn new(
         config: &ReduceConfig,
         enrichment_tables: &enrichment::TableRegistry,
+    ) -> crate::Result<Self> {
+        let states = TransformState::detached(HashMap::new());
+        Self::with_states(config, enrichment_tables, states)
+    }
+
+    fn with_states(
+        config: &ReduceConfig,
+        enrichment_tables: &enrichment::TableRegistry,
+        reduce_merge_states: TransformState<HashMap<Discriminant, ReduceState>>,
     ) -> crate::Result<Self> {
         if config.ends_when.is_some() && config.starts_when.is_some() {
             return Err("only one of `ends_when` and `starts_when` can be provided".into());
@@ -176,25 +316,26 @@ impl Reduce {
         let group_by = config.group_by.clone().into_iter().collect();
 
         Ok(Reduce {
-            expire_after: Duration::from_millis(config.expire_after_ms.unwrap_or(30000)),
+            expire_after: config.expire_after(),
             flush_period: Duration::from_millis(config.flush_period_ms.unwrap_or(1000)),
             group_by,
             merge_strategies: config.merge_strategies.clone(),
-            reduce_merge_states: HashMap::new(),
+            reduce_merge_states,
             ends_when,
             starts_when,
         })
     }
 
     fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let mut states = self.reduce_merge_states.lock();
         let mut flush_discriminants = Vec::new();
-        for (k, t) in &self.reduce_merge_states {
+        for (k, t) in states.iter() {
             if t.stale_since.elapsed() >= self.expire_after {
                 flush_discriminants.push(k.clone());
             }
         }
         for k in &flush_discriminants {
-            if let Some(t) = self.reduce_merge_states.remove(k) {
+            if let Some(t) = states.remove(k) {
                 emit!(ReduceStaleEventFlushed);
                 output.push(Event::from(t.flush()));
             }
@@ -203,12 +344,13 @@ impl Reduce {
 
     fn flush_all_into(&mut self, output: &mut Vec<Event>) {
         self.reduce_merge_states
+            .lock()
             .drain()
             .for_each(|(_, s)| output.push(Event::from(s.flush())));
     }
 
     fn push_or_new_reduce_state(&mut self, event: LogEvent, discriminant: Discriminant) {
-        match self.reduce_merge_states.entry(discriminant) {
+        match self.reduce_merge_states.lock().entry(discriminant) {
             hash_map::Entry::Vacant(entry) => {
                 entry.insert(ReduceState::new(event, &self.merge_strategies));
             }
@@ -234,13 +376,15 @@ impl Reduce {
         let discriminant = Discriminant::from_log_event(&event, &self.group_by);
 
         if starts_here {
-            if let Some(state) = self.reduce_merge_states.remove(&discriminant) {
+            let state = self.reduce_merge_states.lock().remove(&discriminant);
+            if let Some(state) = state {
                 output.push(state.flush().into());
             }
 
             self.push_or_new_reduce_state(event, discriminant)
         } else if ends_here {
-            output.push(match self.reduce_merge_states.remove(&discriminant) {
+            let state = self.reduce_merge_states.lock().remove(&discriminant);
+            output.push(match state {
                 Some(mut state) => {
                     state.add_event(event, &self.merge_strategies);
                     state.flush().into()
@@ -283,7 +427,14 @@ impl TaskTransform for Reduce {
                     maybe_event = input_rx.next() => {
                       match maybe_event {
                         None => {
-                          me.flush_all_into(&mut output);
+                          // Keep in-flight reductions for the next instance
+                          // of the transform if possible.
+                          let kept = me.reduce_merge_states.release(|states| {
+                              encode_states(states, &me.group_by)
+                          });
+                          if !kept {
+                              me.flush_all_into(&mut output);
+                          }
                           true
                         }
                         Some(event) => {
@@ -561,4 +712,62 @@ merge_strategies.bar = "concat"
         assert_eq!(output_2["bar"], json!([2, 4, 6, 8, "done"]).into());
         assert_eq!(output_2.metadata(), &metadata_2);
     }
+
+    #[tokio::test]
+    async fn reduce_restores_checkpoint() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let context = TransformContext {
+            key: Some("reduce_restores_checkpoint".into()),
+            ..TransformContext::new_with_globals(crate::config::GlobalOptions {
+                data_dir: Some(data_dir.path().to_path_buf()),
+                ..Default::default()
+            })
+        };
+        let config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+persist_state = true
+
+merge_strategies.foo = "concat"
+merge_strategies.bar = "array"
+
+[ends_when]
+  type = "check_fields"
+  "test_end.exists" = true
+"#,
+        )
+        .unwrap();
+
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("counter", 1);
+        e_1.insert("foo", "first foo");
+        e_1.insert("bar", "first bar");
+        e_1.insert("request_id", "1");
+
+        // The reduction is checkpointed rather than flushed when the input
+        // ends.
+        let reduce = config.build(&context).await.unwrap().into_task();
+        let in_stream = Box::pin(stream::iter(vec![e_1.into()]));
+        let output = reduce.transform(in_stream).collect::<Vec<_>>().await;
+        assert!(output.is_empty());
+
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("counter", 2);
+        e_2.insert("foo", "second foo");
+        e_2.insert("bar", 2);
+        e_2.insert("request_id", "1");
+        e_2.insert("test_end", "yep");
+
+        let reduce = config.build(&context).await.unwrap().into_task();
+        let in_stream = Box::pin(stream::iter(vec![e_2.into()]));
+        let mut out_stream = reduce.transform(in_stream);
+
+        let output = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output["message"], "test message 1".into());
+        assert_eq!(output["counter"], Value::from(3));
+        assert_eq!(output["foo"], "first foo second foo".into());
+        assert_eq!(output["bar"], json!(["first bar", 2]).into());
+        assert_eq!(output["request_id"], "1".into());
+        assert!(output.contains("timestamp_end"));
+    }
 }
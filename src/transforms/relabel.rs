@@ -0,0 +1,452 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{
+        metric::{Metric, MetricName, MetricTags},
+        Event,
+    },
+    internal_events::RelabelEventDropped,
+    transforms::{FunctionTransform, Transform},
+};
+use md5::{Digest, Md5};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::convert::TryInto;
+
+/// The label the name of a metric is exposed as, as in Prometheus.
+const NAME_LABEL: &str = "__name__";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid regular expression in rule #{}: {}", index, source))]
+    InvalidRegex { index: usize, source: regex::Error },
+    #[snafu(display("Rule #{} requires \"{}\" for its action", index, field))]
+    MissingField { index: usize, field: &'static str },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelTransformConfig {
+    /// The rules, applied in order, in the syntax of Prometheus'
+    /// `relabel_configs` and `metric_relabel_configs`.
+    #[serde(alias = "metric_relabel_configs")]
+    pub relabel_configs: Vec<RelabelConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelConfig {
+    #[serde(default)]
+    pub source_labels: Vec<String>,
+    #[serde(default = "default_separator")]
+    pub separator: String,
+    pub target_label: Option<String>,
+    #[serde(default = "default_regex")]
+    pub regex: String,
+    pub modulus: Option<u64>,
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+    #[serde(default)]
+    pub action: RelabelAction,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    Replace,
+    Keep,
+    Drop,
+    HashMod,
+    LabelMap,
+    LabelDrop,
+    LabelKeep,
+}
+
+impl Default for RelabelAction {
+    fn default() -> Self {
+        Self::Replace
+    }
+}
+
+fn default_separator() -> String {
+    ";".into()
+}
+
+fn default_regex() -> String {
+    "(.*)".into()
+}
+
+fn default_replacement() -> String {
+    "$1".into()
+}
+
+inventory::submit! {
+    TransformDescription::new::<RelabelTransformConfig>("relabel")
+}
+
+impl GenerateConfig for RelabelTransformConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"[[relabel_configs]]
+            source_labels = ["__name__"]
+            regex = "go_.*"
+            action = "drop""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "relabel")]
+impl TransformConfig for RelabelTransformConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Relabel::new(&self.relabel_configs).map(Transform::function)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "relabel"
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    source_labels: Vec<String>,
+    separator: String,
+    target_label: String,
+    regex: Regex,
+    modulus: u64,
+    replacement: String,
+    action: RelabelAction,
+}
+
+impl Rule {
+    fn new(index: usize, config: &RelabelConfig) -> crate::Result<Self> {
+        let action = config.action;
+        let target_label = match (action, &config.target_label) {
+            (RelabelAction::Replace, None) | (RelabelAction::HashMod, None) => {
+                return Err(BuildError::MissingField {
+                    index,
+                    field: "target_label",
+                }
+                .into())
+            }
+            (_, target_label) => target_label.clone().unwrap_or_default(),
+        };
+        let modulus = match (action, config.modulus) {
+            (RelabelAction::HashMod, None) | (RelabelAction::HashMod, Some(0)) => {
+                return Err(BuildError::MissingField {
+                    index,
+                    field: "modulus",
+                }
+                .into())
+            }
+            (_, modulus) => modulus.unwrap_or_default(),
+        };
+        // Prometheus anchors regular expressions at both ends.
+        let regex =
+            Regex::new(&format!("^(?:{})$", config.regex)).context(InvalidRegex { index })?;
+
+        Ok(Self {
+            source_labels: config.source_labels.clone(),
+            separator: config.separator.clone(),
+            target_label,
+            regex,
+            modulus,
+            replacement: config.replacement.clone(),
+            action,
+        })
+    }
+
+    /// Applies the rule to the labels, returning `false` if the metric is to
+    /// be dropped.
+    fn apply(&self, labels: &mut MetricTags) -> bool {
+        let value = self
+            .source_labels
+            .iter()
+            .map(|label| labels.get(label).map(String::as_str).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(&self.separator);
+
+        match self.action {
+            RelabelAction::Keep => return self.regex.is_match(&value),
+            RelabelAction::Drop => return !self.regex.is_match(&value),
+            RelabelAction::Replace => {
+                if let Some(captures) = self.regex.captures(&value) {
+                    let mut target = String::new();
+                    captures.expand(&self.target_label, &mut target);
+                    let mut replacement = String::new();
+                    captures.expand(&self.replacement, &mut replacement);
+                    if !is_valid_label_name(&target) || replacement.is_empty() {
+                        labels.remove(&self.target_label);
+                    } else {
+                        labels.insert(target, replacement);
+                    }
+                }
+            }
+            RelabelAction::HashMod => {
+                let hash = Md5::digest(value.as_bytes());
+                // Only the lower 64 bits of the hash are used, as in Prometheus.
+                let hash = u64::from_be_bytes(hash[8..].try_into().expect("hash is 16 bytes"));
+                labels.insert(self.target_label.clone(), (hash % self.modulus).to_string());
+            }
+            RelabelAction::LabelMap => {
+                let mapped = labels
+                    .iter()
+                    .filter(|(name, _)| self.regex.is_match(name))
+                    .map(|(name, value)| {
+                        let name = self.regex.replace_all(name, self.replacement.as_str());
+                        (name.into_owned(), value.clone())
+                    })
+                    .collect::<Vec<_>>();
+                labels.extend(mapped);
+            }
+            RelabelAction::LabelDrop => labels.retain(|name, _| !self.regex.is_match(name)),
+            RelabelAction::LabelKeep => labels.retain(|name, _| self.regex.is_match(name)),
+        }
+        true
+    }
+}
+
+fn is_valid_label_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[derive(Clone, Debug)]
+pub struct Relabel {
+    rules: Vec<Rule>,
+}
+
+impl Relabel {
+    pub fn new(configs: &[RelabelConfig]) -> crate::Result<Self> {
+        let rules = configs
+            .iter()
+            .enumerate()
+            .map(|(index, config)| Rule::new(index, config))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    fn transform_one(&self, metric: Metric) -> Option<Metric> {
+        let (mut series, data, metadata) = metric.into_parts();
+        let name = match &series.name.namespace {
+            Some(namespace) => format!("{}_{}", namespace, series.name.name),
+            None => series.name.name.clone(),
+        };
+        let mut labels = series.tags.take().unwrap_or_default();
+        labels.insert(NAME_LABEL.into(), name.clone());
+
+        if !self.rules.iter().all(|rule| rule.apply(&mut labels)) {
+            emit!(RelabelEventDropped);
+            return None;
+        }
+
+        match labels.remove(NAME_LABEL) {
+            Some(new_name) if new_name == name => (),
+            Some(new_name) if !new_name.is_empty() => {
+                series.name = MetricName {
+                    name: new_name,
+                    namespace: None,
+                }
+            }
+            // A metric can't do without a name.
+            _ => {
+                emit!(RelabelEventDropped);
+                return None;
+            }
+        }
+        series.tags = if labels.is_empty() {
+            None
+        } else {
+            Some(labels)
+        };
+
+        Some(Metric::from_parts(series, data, metadata))
+    }
+}
+
+impl FunctionTransform for Relabel {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        output.extend(self.transform_one(event.into_metric()).map(Event::Metric));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::{MetricKind, MetricValue};
+
+    fn relabel(rules: &str) -> Relabel {
+        let config = serde_yaml::from_str::<RelabelTransformConfig>(rules).unwrap();
+        Relabel::new(&config.relabel_configs).unwrap()
+    }
+
+    fn metric(name: &str, tags: &[(&str, &str)]) -> Metric {
+        Metric::new(
+            name,
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        )
+        .with_tags(Some(
+            tags.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        ))
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RelabelTransformConfig>();
+    }
+
+    #[test]
+    fn relabel_replace() {
+        let relabel = relabel(
+            r#"
+            metric_relabel_configs:
+              - source_labels: [a, b]
+                regex: "f(.*);(.*)"
+                target_label: c
+                replacement: "ch${1}-ch${2}"
+              - source_labels: [a]
+                regex: "nomatch"
+                target_label: d
+                replacement: "never"
+              - source_labels: [missing]
+                target_label: b
+                replacement: ""
+            "#,
+        );
+
+        let output = relabel
+            .transform_one(metric("up", &[("a", "foo"), ("b", "bar")]))
+            .unwrap();
+        assert_eq!(output, metric("up", &[("a", "foo"), ("c", "choo-chbar")]));
+    }
+
+    #[test]
+    fn relabel_keep_drop() {
+        let relabel = relabel(
+            r#"
+            relabel_configs:
+              - source_labels: [__name__]
+                regex: "go_.*"
+                action: drop
+              - source_labels: [job]
+                regex: "api|web"
+                action: keep
+            "#,
+        );
+
+        assert!(relabel
+            .transform_one(metric("go_goroutines", &[("job", "api")]))
+            .is_some());
+        assert!(relabel
+            .transform_one(metric("http_requests", &[("job", "api")]))
+            .is_some());
+        assert!(relabel
+            .transform_one(metric("http_requests", &[("job", "db")]))
+            .is_none());
+        assert!(relabel
+            .transform_one(metric("http_requests", &[]))
+            .is_none());
+    }
+
+    #[test]
+    fn relabel_labelmap_labeldrop_labelkeep() {
+        let relabel = relabel(
+            r#"
+            relabel_configs:
+              - regex: "meta_(.*)"
+                action: labelmap
+              - regex: "meta_.*"
+                action: labeldrop
+            "#,
+        );
+        let output = relabel
+            .transform_one(metric("up", &[("meta_zone", "a"), ("job", "api")]))
+            .unwrap();
+        assert_eq!(output, metric("up", &[("zone", "a"), ("job", "api")]));
+
+        let keep = self::relabel(
+            r#"
+            relabel_configs:
+              - regex: "__name__|job"
+                action: labelkeep
+            "#,
+        );
+        let output = keep
+            .transform_one(metric("up", &[("zone", "a"), ("job", "api")]))
+            .unwrap();
+        assert_eq!(output, metric("up", &[("job", "api")]));
+    }
+
+    #[test]
+    fn relabel_hashmod() {
+        let relabel = relabel(
+            r#"
+            relabel_configs:
+              - source_labels: [c]
+                target_label: d
+                modulus: 1000
+                action: hashmod
+            "#,
+        );
+
+        let output = relabel
+            .transform_one(metric("up", &[("c", "baz")]))
+            .unwrap();
+        assert_eq!(output, metric("up", &[("c", "baz"), ("d", "976")]));
+    }
+
+    #[test]
+    fn relabel_name() {
+        let relabel = relabel(
+            r#"
+            relabel_configs:
+              - source_labels: [__name__]
+                regex: "vector_(.*)"
+                target_label: __name__
+                replacement: "agent_${1}"
+            "#,
+        );
+
+        let output = relabel
+            .transform_one(metric("events_total", &[]).with_namespace(Some("vector")))
+            .unwrap();
+        assert_eq!(output.name(), "agent_events_total");
+        assert_eq!(output.namespace(), None);
+
+        // Untouched names keep their namespace.
+        let output = relabel
+            .transform_one(metric("events_total", &[]).with_namespace(Some("other")))
+            .unwrap();
+        assert_eq!(output.name(), "events_total");
+        assert_eq!(output.namespace(), Some("other"));
+    }
+
+    #[test]
+    fn relabel_invalid_rules() {
+        let rules = |rules| {
+            serde_yaml::from_str::<RelabelTransformConfig>(rules)
+                .map(|config| Relabel::new(&config.relabel_configs))
+                .unwrap()
+                .map(|_| ())
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(rules("relabel_configs: [{regex: '('}]").contains("rule #0"));
+        assert!(rules("relabel_configs: [{action: hashmod, target_label: d}]").contains("modulus"));
+        assert!(rules("relabel_configs: [{action: replace}]").contains("target_label"));
+    }
+}
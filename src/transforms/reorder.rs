@@ -0,0 +1,655 @@
+use crate::{
+    config::{
+        log_schema, DataType, ExpandType, GenerateConfig, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, Value},
+    internal_events::{ReorderEventsFlushed, ReorderFlushReason, ReorderLateEventRouted},
+    transforms::{FunctionTransform, TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::{DateTime, Duration, Utc};
+use futures::{stream, Stream, StreamExt};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    time::Instant,
+};
+
+//------------------------------------------------------------------------------
+
+/// Which of the events a lane of the `reorder` transform outputs.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Lane {
+    /// The events that arrive in time, sorted by their timestamps.
+    Sorted,
+    /// The events that arrive later than the watermark, as they arrive.
+    Late,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReorderLaneConfig {
+    lane: Lane,
+    max_lateness_ms: u64,
+    group_by: Vec<String>,
+    expire_after_ms: u64,
+    flush_period_ms: u64,
+    max_buffered_events: usize,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "reorder_lane")]
+impl TransformConfig for ReorderLaneConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        let watermarks = Watermarks::new(self);
+        Ok(match self.lane {
+            Lane::Sorted => Transform::task(Reorder::new(self, watermarks)),
+            Lane::Late => Transform::function(LateEvents { watermarks }),
+        })
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "reorder_lane"
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReorderConfig {
+    /// How far behind the latest timestamp of its group an event may be
+    /// before it is considered late.
+    #[serde(default = "default_max_lateness_ms")]
+    pub max_lateness_ms: u64,
+    /// Fields of logs, or tags of metrics, to group events by. Events are
+    /// only sorted against events of the same group, each of which has its
+    /// own watermark.
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    /// How far behind the latest timestamp of all groups the latest timestamp
+    /// of a group may fall before the group is forgotten.
+    #[serde(default = "default_expire_after_ms")]
+    pub expire_after_ms: u64,
+    /// How often the groups are checked for having received no events for
+    /// `max_lateness_ms`, in which case their events are flushed. Events of
+    /// the group that arrive afterwards are no longer sorted against them.
+    #[serde(default = "default_flush_period_ms")]
+    pub flush_period_ms: u64,
+    /// The most events buffered across all groups. Once reached, all of them
+    /// are flushed, and the events that arrive afterwards are no longer
+    /// sorted against them.
+    #[serde(default = "default_max_buffered_events")]
+    pub max_buffered_events: usize,
+}
+
+const fn default_max_lateness_ms() -> u64 {
+    5000
+}
+
+const fn default_expire_after_ms() -> u64 {
+    300_000
+}
+
+const fn default_flush_period_ms() -> u64 {
+    1000
+}
+
+const fn default_max_buffered_events() -> usize {
+    10_000
+}
+
+inventory::submit! {
+    TransformDescription::new::<ReorderConfig>("reorder")
+}
+
+impl GenerateConfig for ReorderConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            max_lateness_ms: default_max_lateness_ms(),
+            group_by: vec!["partition".into()],
+            expire_after_ms: default_expire_after_ms(),
+            flush_period_ms: default_flush_period_ms(),
+            max_buffered_events: default_max_buffered_events(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "reorder")]
+impl TransformConfig for ReorderConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Err("this transform must be expanded".into())
+    }
+
+    fn expand(
+        &mut self,
+    ) -> crate::Result<Option<(IndexMap<String, Box<dyn TransformConfig>>, ExpandType)>> {
+        if self.max_buffered_events == 0 {
+            return Err("`max_buffered_events` must be greater than 0".into());
+        }
+        if self.flush_period_ms == 0 {
+            return Err("`flush_period_ms` must be greater than 0".into());
+        }
+
+        // Both lanes see the same events in the same order and track the
+        // watermarks from event timestamps alone, so they agree on which
+        // events are late.
+        let mut map: IndexMap<String, Box<dyn TransformConfig>> = IndexMap::new();
+        for (name, lane) in &[("sorted", Lane::Sorted), ("late", Lane::Late)] {
+            map.insert(
+                (*name).to_owned(),
+                Box::new(ReorderLaneConfig {
+                    lane: *lane,
+                    max_lateness_ms: self.max_lateness_ms,
+                    group_by: self.group_by.clone(),
+                    expire_after_ms: self.expire_after_ms,
+                    flush_period_ms: self.flush_period_ms,
+                    max_buffered_events: self.max_buffered_events,
+                }),
+            );
+        }
+        Ok(Some((map, ExpandType::Parallel)))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "reorder"
+    }
+}
+
+//------------------------------------------------------------------------------
+
+type GroupKey = Vec<Option<String>>;
+
+/// The watermarks of the groups of events, which trail the latest timestamp
+/// of each group by the maximum lateness.
+#[derive(Clone, Debug)]
+struct Watermarks {
+    max_lateness: Duration,
+    expire_after: Duration,
+    group_by: Vec<String>,
+    latest: HashMap<GroupKey, DateTime<Utc>>,
+    /// The timestamp at which the groups that fell behind are next expired.
+    next_expiry: Option<DateTime<Utc>>,
+}
+
+impl Watermarks {
+    fn new(config: &ReorderLaneConfig) -> Self {
+        Self {
+            max_lateness: Duration::milliseconds(config.max_lateness_ms as i64),
+            expire_after: Duration::milliseconds(config.expire_after_ms as i64),
+            group_by: config.group_by.clone(),
+            latest: HashMap::new(),
+            next_expiry: None,
+        }
+    }
+
+    fn group_key(&self, event: &Event) -> GroupKey {
+        match event {
+            Event::Log(log) => self
+                .group_by
+                .iter()
+                .map(|field| log.get(field).map(Value::to_string_lossy))
+                .collect(),
+            Event::Metric(metric) => self
+                .group_by
+                .iter()
+                .map(|tag| metric.tag_value(tag))
+                .collect(),
+        }
+    }
+
+    /// Advances the watermark of the event's group, and returns the group
+    /// along with whether the event is late, or `None` if the event has no
+    /// timestamp to order it by.
+    fn observe(&mut self, event: &Event) -> Option<(GroupKey, DateTime<Utc>, bool)> {
+        let timestamp = timestamp(event)?;
+        let key = self.group_key(event);
+        let late = match self.latest.get_mut(&key) {
+            Some(latest) => {
+                let late = timestamp < *latest - self.max_lateness;
+                if timestamp > *latest {
+                    *latest = timestamp;
+                }
+                late
+            }
+            None => {
+                self.latest.insert(key.clone(), timestamp);
+                false
+            }
+        };
+        self.expire(timestamp);
+        Some((key, timestamp, late))
+    }
+
+    /// Forgets the groups whose latest timestamp fell more than
+    /// `expire_after` behind the given one, at most once per `expire_after`.
+    /// This only depends on the timestamps of the events, so both lanes
+    /// forget the same groups at the same events.
+    fn expire(&mut self, timestamp: DateTime<Utc>) {
+        let expire_after = self.expire_after;
+        let next_expiry = *self
+            .next_expiry
+            .get_or_insert_with(|| timestamp + expire_after);
+        if timestamp >= next_expiry {
+            let cutoff = timestamp - expire_after;
+            self.latest.retain(|_, latest| *latest >= cutoff);
+            self.next_expiry = Some(timestamp + expire_after);
+        }
+    }
+
+    fn watermark(&self, key: &[Option<String>]) -> Option<DateTime<Utc>> {
+        self.latest
+            .get(key)
+            .map(|latest| *latest - self.max_lateness)
+    }
+}
+
+fn timestamp(event: &Event) -> Option<DateTime<Utc>> {
+    match event {
+        Event::Log(log) => match log.get(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(timestamp)) => Some(*timestamp),
+            _ => None,
+        },
+        Event::Metric(metric) => metric.timestamp(),
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Outputs the events that arrive in time, once the watermark of their group
+/// passes them. Events without a timestamp are passed through right away.
+pub struct Reorder {
+    watermarks: Watermarks,
+    buffers: HashMap<GroupKey, Buffer>,
+    buffered: usize,
+    idle_after: std::time::Duration,
+    flush_period: std::time::Duration,
+    max_buffered_events: usize,
+    sequence: u64,
+}
+
+/// The buffered events of a group, by timestamp and then arrival.
+struct Buffer {
+    events: BTreeMap<(DateTime<Utc>, u64), Event>,
+    last_seen: Instant,
+}
+
+impl Reorder {
+    fn new(config: &ReorderLaneConfig, watermarks: Watermarks) -> Self {
+        Self {
+            watermarks,
+            buffers: HashMap::new(),
+            buffered: 0,
+            idle_after: std::time::Duration::from_millis(config.max_lateness_ms),
+            flush_period: std::time::Duration::from_millis(config.flush_period_ms),
+            max_buffered_events: config.max_buffered_events,
+            sequence: 0,
+        }
+    }
+
+    fn transform_one(&mut self, now: Instant, output: &mut Vec<Event>, event: Event) {
+        let (key, timestamp) = match self.watermarks.observe(&event) {
+            None => {
+                output.push(event);
+                return;
+            }
+            // Late events are output by the other lane.
+            Some((_, _, true)) => return,
+            Some((key, timestamp, false)) => (key, timestamp),
+        };
+
+        self.sequence += 1;
+        let buffer = self.buffers.entry(key.clone()).or_insert_with(|| Buffer {
+            events: BTreeMap::new(),
+            last_seen: now,
+        });
+        buffer.last_seen = now;
+        buffer.events.insert((timestamp, self.sequence), event);
+        self.buffered += 1;
+
+        if let Some(watermark) = self.watermarks.watermark(&key) {
+            // Everything after the watermark stays buffered.
+            let pending = buffer.events.split_off(&(watermark, u64::MAX));
+            let passed = std::mem::replace(&mut buffer.events, pending);
+            self.buffered -= passed.len();
+            output.extend(passed.into_values());
+            if buffer.events.is_empty() {
+                self.buffers.remove(&key);
+            }
+        }
+
+        if self.buffered >= self.max_buffered_events {
+            emit!(ReorderEventsFlushed {
+                count: self.buffered,
+                reason: ReorderFlushReason::TooManyEvents,
+            });
+            self.flush_all_into(output);
+        }
+    }
+
+    /// Flushes the groups that received no events for `max_lateness_ms`, as
+    /// their watermarks won't pass their events until they receive more.
+    ///
+    /// The watermarks are left as they are, since the late lane tracks its
+    /// own from event timestamps alone and couldn't follow a flush driven by
+    /// the wall clock. Events of a flushed group that arrive afterwards are
+    /// thus only sorted against each other, and may be output after newer
+    /// events that were flushed.
+    fn flush_into(&mut self, now: Instant, output: &mut Vec<Event>) {
+        let idle_after = self.idle_after;
+        let mut count = 0;
+        self.buffers.retain(|_, buffer| {
+            if now.saturating_duration_since(buffer.last_seen) < idle_after {
+                return true;
+            }
+            count += buffer.events.len();
+            output.extend(std::mem::take(&mut buffer.events).into_values());
+            false
+        });
+
+        if count > 0 {
+            self.buffered -= count;
+            emit!(ReorderEventsFlushed {
+                count,
+                reason: ReorderFlushReason::Idle,
+            });
+        }
+    }
+
+    /// Flushes all groups. As with idle groups, the watermarks are left as
+    /// they are.
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        for (_, buffer) in self.buffers.drain() {
+            output.extend(buffer.events.into_values());
+        }
+        self.buffered = 0;
+    }
+}
+
+impl TaskTransform for Reorder {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(me.flush_period);
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.tick() => {
+                      me.flush_into(Instant::now(), &mut output);
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_all_into(&mut output);
+                          true
+                        }
+                        Some(event) => {
+                          me.transform_one(Instant::now(), &mut output, event);
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+/// Outputs the events that arrive later than the watermark of their group.
+#[derive(Clone, Debug)]
+pub struct LateEvents {
+    watermarks: Watermarks,
+}
+
+impl FunctionTransform for LateEvents {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        if let Some((_, _, true)) = self.watermarks.observe(&event) {
+            emit!(ReorderLateEventRouted);
+            output.push(event);
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::{
+        metric::{Metric, MetricKind, MetricValue},
+        LogEvent,
+    };
+    use chrono::TimeZone;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ReorderConfig>();
+    }
+
+    fn log(partition: &str, secs: i64) -> Event {
+        let mut log = LogEvent::from(format!("{}-{}", partition, secs));
+        log.insert("partition", partition);
+        log.insert(log_schema().timestamp_key(), Utc.timestamp(secs, 0));
+        log.into()
+    }
+
+    fn messages(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| event.as_log()["message"].to_string_lossy())
+            .collect()
+    }
+
+    async fn run(config: &str, inputs: Vec<Event>) -> (Vec<Event>, Vec<Event>) {
+        let mut lanes = toml::from_str::<ReorderConfig>(config)
+            .unwrap()
+            .expand()
+            .unwrap()
+            .unwrap()
+            .0;
+        let context = TransformContext::default();
+
+        let sorted = lanes
+            .remove("sorted")
+            .unwrap()
+            .build(&context)
+            .await
+            .unwrap();
+        let sorted = sorted
+            .into_task()
+            .transform(Box::pin(stream::iter(inputs.clone())))
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut late = lanes.remove("late").unwrap().build(&context).await.unwrap();
+        let late_lane = late.as_function();
+        let mut late = Vec::new();
+        for event in inputs {
+            late_lane.transform(&mut late, event);
+        }
+
+        (sorted, late)
+    }
+
+    #[tokio::test]
+    async fn reorder_sorts_and_routes_late_events() {
+        let inputs = vec![
+            log("a", 10),
+            log("a", 8),
+            log("b", 100),
+            log("a", 12),
+            log("a", 9),
+            // More than 5 seconds behind the latest event of "a".
+            log("a", 6),
+            log("b", 101),
+            log("a", 20),
+            log("a", 16),
+        ];
+
+        let (sorted, late) = run(r#"group_by = ["partition"]"#, inputs).await;
+
+        let sorted = messages(&sorted);
+        // The watermark of "a" passed these before the input ended.
+        assert_eq!(sorted[..4], ["a-8", "a-9", "a-10", "a-12"]);
+        let group = |partition: char| {
+            sorted[4..]
+                .iter()
+                .filter(|message| message.starts_with(partition))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(group('a'), vec!["a-16", "a-20"]);
+        assert_eq!(group('b'), vec!["b-100", "b-101"]);
+        assert_eq!(messages(&late), vec!["a-6"]);
+    }
+
+    #[tokio::test]
+    async fn reorder_metrics_and_untimestamped_events() {
+        let metric = |secs| {
+            Event::from(
+                Metric::new(
+                    "requests",
+                    MetricKind::Incremental,
+                    MetricValue::Counter { value: secs as f64 },
+                )
+                .with_timestamp(Some(Utc.timestamp(secs, 0))),
+            )
+        };
+        let untimestamped = Event::from("no timestamp");
+
+        let (sorted, late) = run(
+            "max_lateness_ms = 1000",
+            vec![metric(3), metric(2), untimestamped.clone(), metric(1)],
+        )
+        .await;
+
+        assert_eq!(sorted, vec![metric(2), untimestamped, metric(3)]);
+        assert_eq!(late, vec![metric(1)]);
+    }
+
+    fn lane_config() -> ReorderLaneConfig {
+        toml::from_str(
+            r#"
+            lane = "sorted"
+            max_lateness_ms = 5000
+            group_by = ["partition"]
+            expire_after_ms = 60000
+            flush_period_ms = 1000
+            max_buffered_events = 10
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn reorder_flushes_idle_groups() {
+        let config = lane_config();
+        let mut reorder = Reorder::new(&config, Watermarks::new(&config));
+        let start = Instant::now();
+
+        let secs = |secs| start + std::time::Duration::from_secs(secs);
+
+        let mut output = Vec::new();
+        reorder.transform_one(start, &mut output, log("a", 10));
+        reorder.transform_one(secs(3), &mut output, log("a", 9));
+        assert!(output.is_empty());
+
+        reorder.flush_into(secs(4), &mut output);
+        assert!(output.is_empty());
+
+        reorder.transform_one(secs(5), &mut output, log("b", 10));
+        // Only "a" received no events for the maximum lateness.
+        reorder.flush_into(secs(8), &mut output);
+        assert_eq!(messages(&output), vec!["a-9", "a-10"]);
+        assert_eq!(reorder.buffered, 1);
+    }
+
+    #[test]
+    fn reorder_outputs_events_arriving_after_idle_flush() {
+        let config = lane_config();
+        let mut reorder = Reorder::new(&config, Watermarks::new(&config));
+        let start = Instant::now();
+
+        let secs = |secs| start + std::time::Duration::from_secs(secs);
+
+        let mut output = Vec::new();
+        reorder.transform_one(start, &mut output, log("a", 10));
+        reorder.flush_into(secs(5), &mut output);
+        assert_eq!(messages(&output), vec!["a-10"]);
+
+        // Still within the maximum lateness of "a", so it isn't late, but it
+        // can no longer be output before the flushed event.
+        reorder.transform_one(secs(6), &mut output, log("a", 9));
+        reorder.flush_into(secs(11), &mut output);
+        assert_eq!(messages(&output), vec!["a-10", "a-9"]);
+        assert_eq!(reorder.buffered, 0);
+    }
+
+    #[tokio::test]
+    async fn reorder_flushes_when_full() {
+        let inputs = vec![log("a", 10), log("a", 9), log("a", 8), log("a", 7)];
+
+        let (sorted, late) = run("max_buffered_events = 3", inputs).await;
+
+        // The flushed events are no longer there to sort the last one against.
+        assert_eq!(messages(&sorted), vec!["a-8", "a-9", "a-10", "a-7"]);
+        assert!(late.is_empty());
+    }
+
+    #[test]
+    fn watermarks_expire_idle_groups() {
+        let mut watermarks = Watermarks::new(&lane_config());
+
+        assert!(!watermarks.observe(&log("a", 100)).unwrap().2);
+        assert!(!watermarks.observe(&log("b", 150)).unwrap().2);
+        assert_eq!(watermarks.latest.len(), 2);
+
+        // More than a minute past the first event, "a" is forgotten.
+        assert!(!watermarks.observe(&log("b", 200)).unwrap().2);
+        assert_eq!(watermarks.latest.len(), 1);
+        assert!(!watermarks.observe(&log("a", 90)).unwrap().2);
+    }
+
+    #[test]
+    fn requires_flush_period() {
+        let mut config = toml::from_str::<ReorderConfig>("flush_period_ms = 0").unwrap();
+        let err = config.expand().map(|_| ()).unwrap_err().to_string();
+        assert!(err.contains("flush_period_ms"), "{}", err);
+    }
+}
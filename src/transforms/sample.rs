@@ -6,6 +6,10 @@ use crate::{
     transforms::{FunctionTransform, Transform},
 };
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -13,6 +17,26 @@ pub struct SampleConfig {
     pub rate: u64,
     pub key_field: Option<String>,
     pub exclude: Option<AnyCondition>,
+    pub dynamic: Option<DynamicSampleConfig>,
+}
+
+/// Adapts the rate of each value of `key_field` to its volume, so that the
+/// events kept stay within a budget. Keys that are rare enough to fit in
+/// their share of the budget are always kept, while hot keys are sampled
+/// harder, up to `rate`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DynamicSampleConfig {
+    /// The number of events per second to keep, across all keys.
+    pub target_events_per_sec: f64,
+    /// How long the volume of each key is measured for before its rate is
+    /// adjusted.
+    #[serde(default = "default_window_secs")]
+    pub window_secs: f64,
+}
+
+const fn default_window_secs() -> f64 {
+    30.0
 }
 
 inventory::submit! {
@@ -29,6 +53,7 @@ impl GenerateConfig for SampleConfig {
             rate: 10,
             key_field: None,
             exclude: None::<AnyCondition>,
+            dynamic: None,
         })
         .unwrap()
     }
@@ -38,14 +63,27 @@ impl GenerateConfig for SampleConfig {
 #[typetag::serde(name = "sample")]
 impl TransformConfig for SampleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::function(Sample::new(
+        let sample = Sample::new(
             self.rate,
             self.key_field.clone(),
             self.exclude
                 .as_ref()
                 .map(|condition| condition.build(&context.enrichment_tables))
                 .transpose()?,
-        )))
+        );
+        let sample = match &self.dynamic {
+            Some(dynamic) => {
+                let positive = |value: f64| value.is_finite() && value > 0.0;
+                if !(positive(dynamic.target_events_per_sec) && positive(dynamic.window_secs)) {
+                    return Err(
+                        "dynamic `target_events_per_sec` and `window_secs` must be positive".into(),
+                    );
+                }
+                sample.with_dynamic(dynamic)
+            }
+            None => sample,
+        };
+        Ok(Transform::function(sample))
     }
 
     fn input_type(&self) -> DataType {
@@ -91,6 +129,7 @@ pub struct Sample {
     key_field: Option<String>,
     exclude: Option<Box<dyn Condition>>,
     count: u64,
+    dynamic: Option<DynamicRates>,
 }
 
 impl Sample {
@@ -100,6 +139,80 @@ impl Sample {
             key_field,
             exclude,
             count: 0,
+            dynamic: None,
+        }
+    }
+
+    /// Sample dynamically, with `rate` as the highest rate of any key.
+    pub fn with_dynamic(mut self, config: &DynamicSampleConfig) -> Self {
+        self.dynamic = Some(DynamicRates::new(
+            config.target_events_per_sec * config.window_secs,
+            self.rate,
+            Duration::from_secs_f64(config.window_secs),
+        ));
+        self
+    }
+}
+
+/// The rate of each key, adjusted at the end of every window from the
+/// number of events seen for it during that window.
+#[derive(Clone, Debug)]
+struct DynamicRates {
+    // The number of events to keep per window.
+    budget: f64,
+    max_rate: u64,
+    window: Duration,
+    window_start: Instant,
+    counts: HashMap<Option<String>, u64>,
+    // Keys missing from here were rare in the previous window, or not seen
+    // at all, and are always kept.
+    rates: HashMap<Option<String>, u64>,
+}
+
+impl DynamicRates {
+    fn new(budget: f64, max_rate: u64, window: Duration) -> Self {
+        Self {
+            budget,
+            max_rate: max_rate.max(1),
+            window,
+            window_start: Instant::now(),
+            counts: HashMap::new(),
+            rates: HashMap::new(),
+        }
+    }
+
+    /// Returns the rate of the key, and whether this event is kept.
+    fn sample(&mut self, key: Option<String>, now: Instant) -> (u64, bool) {
+        if now.saturating_duration_since(self.window_start) >= self.window {
+            self.adjust();
+            self.window_start = now;
+        }
+
+        let rate = self.rates.get(&key).copied().unwrap_or(1);
+        let count = self.counts.entry(key).or_insert(0);
+        *count += 1;
+        (rate, (*count - 1) % rate == 0)
+    }
+
+    /// Splits the budget between the keys seen during the window, starting
+    /// from the rarest. Keys which fit within an even share of the remaining
+    /// budget are kept whole, leaving the rest of their share to the hotter
+    /// keys.
+    fn adjust(&mut self) {
+        let mut counts = self.counts.drain().collect::<Vec<_>>();
+        counts.sort_unstable_by_key(|(_, count)| *count);
+
+        self.rates.clear();
+        let mut budget = self.budget;
+        let mut remaining = counts.len();
+        for (key, count) in counts {
+            let share = budget / remaining as f64;
+            let rate = ((count as f64 / share).ceil() as u64).clamp(1, self.max_rate);
+            budget = (budget - count as f64 / rate as f64).max(0.0);
+            remaining -= 1;
+            if rate > 1 {
+                self.rates.insert(key, rate);
+            }
         }
     }
 }
@@ -119,18 +232,22 @@ impl FunctionTransform for Sample {
             .and_then(|key_field| event.as_log().get(key_field))
             .map(|v| v.to_string_lossy());
 
-        let num = if let Some(value) = value {
-            seahash::hash(value.as_bytes())
+        let (rate, keep) = if let Some(dynamic) = self.dynamic.as_mut() {
+            dynamic.sample(value, Instant::now())
         } else {
-            self.count
-        };
+            let num = if let Some(value) = value {
+                seahash::hash(value.as_bytes())
+            } else {
+                self.count
+            };
 
-        self.count = (self.count + 1) % self.rate;
+            self.count = (self.count + 1) % self.rate;
 
-        if num % self.rate == 0 {
-            event
-                .as_mut_log()
-                .insert("sample_rate", self.rate.to_string());
+            (self.rate, num % self.rate == 0)
+        };
+
+        if keep {
+            event.as_mut_log().insert("sample_rate", rate.to_string());
             output.push(event);
         } else {
             emit!(SampleEventDiscarded);
@@ -325,6 +442,90 @@ mod tests {
         }
     }
 
+    #[test]
+    fn dynamic_keeps_rare_keys_and_samples_hot_keys() {
+        let start = Instant::now();
+        let mut rates = DynamicRates::new(10.0, 100, Duration::from_secs(1));
+        rates.window_start = start;
+
+        let run = |rates: &mut DynamicRates, now: Instant, key: &str, events: usize| {
+            (0..events)
+                .map(|_| rates.sample(Some(key.to_string()), now))
+                .filter(|(_, keep)| *keep)
+                .map(|(rate, _)| rate)
+                .collect::<Vec<_>>()
+        };
+
+        // Every key is kept until its volume is known.
+        assert_eq!(run(&mut rates, start, "hot", 100), vec![1; 100]);
+        assert_eq!(run(&mut rates, start, "rare", 2), vec![1; 2]);
+
+        // "rare" fits within half of the budget of 10 events, and "hot" gets
+        // the remaining 8.
+        let next = start + Duration::from_secs(1);
+        assert_eq!(run(&mut rates, next, "rare", 2), vec![1; 2]);
+        assert_eq!(run(&mut rates, next, "hot", 100), vec![13; 8]);
+
+        // Keys which were not seen during the last window are kept.
+        assert_eq!(run(&mut rates, next, "new", 3), vec![1; 3]);
+    }
+
+    #[test]
+    fn dynamic_rates_are_capped() {
+        let start = Instant::now();
+        let mut rates = DynamicRates::new(1.0, 5, Duration::from_secs(1));
+        rates.window_start = start;
+
+        for _ in 0..1000 {
+            rates.sample(None, start);
+        }
+        let next = start + Duration::from_secs(1);
+        assert_eq!(rates.sample(None, next), (5, true));
+        assert_eq!(rates.sample(None, next), (5, false));
+    }
+
+    #[test]
+    fn dynamic_adds_sampling_rate_to_event() {
+        let mut sampler =
+            Sample::new(10, Some("key".into()), None).with_dynamic(&DynamicSampleConfig {
+                target_events_per_sec: 1.0,
+                window_secs: 60.0,
+            });
+
+        let mut event = Event::from("message");
+        event.as_mut_log().insert("key", "value");
+        let passing = transform_one(&mut sampler, event).unwrap();
+        assert_eq!(passing.as_log()["sample_rate"], "1".into());
+    }
+
+    #[tokio::test]
+    async fn dynamic_rejects_invalid_target() {
+        for (target, window) in &[
+            ("0.0", "30.0"),
+            ("nan", "30.0"),
+            ("inf", "30.0"),
+            ("10.0", "-1.0"),
+            ("10.0", "nan"),
+            ("10.0", "inf"),
+        ] {
+            let config = toml::from_str::<SampleConfig>(&format!(
+                r#"
+                rate = 10
+                dynamic.target_events_per_sec = {}
+                dynamic.window_secs = {}
+                "#,
+                target, window
+            ))
+            .unwrap();
+            assert!(
+                config.build(&TransformContext::default()).await.is_err(),
+                "target {}, window {}",
+                target,
+                window
+            );
+        }
+    }
+
     fn random_events(n: usize) -> Vec<Event> {
         random_lines(10).take(n).map(Event::from).collect()
     }
@@ -0,0 +1,472 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricTags, MetricValue},
+        Event, LogEvent, Value,
+    },
+    internal_events::SqlWindowFlushed,
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{stream, Stream, StreamExt};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, pin::Pin, time::Duration};
+
+mod query;
+
+use query::{Accumulator, Query};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SqlConfig {
+    /// A `SELECT` statement aggregating the events, which are the rows of
+    /// whatever table it selects from.
+    pub query: String,
+    pub window: WindowConfig,
+    #[serde(default)]
+    pub output: OutputType,
+}
+
+/// The windows events are aggregated over, which are based on the time events
+/// are processed at.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum WindowConfig {
+    /// Consecutive windows that don't overlap.
+    Tumbling { size_secs: u64 },
+    /// Windows that start every `hop_secs`, and so overlap if that is less
+    /// than their size.
+    Hopping { size_secs: u64, hop_secs: u64 },
+}
+
+impl WindowConfig {
+    /// Returns the size of the windows and the time between their starts, in
+    /// milliseconds.
+    fn size_and_hop(self) -> crate::Result<(i64, i64)> {
+        let (size_secs, hop_secs) = match self {
+            Self::Tumbling { size_secs } => (size_secs, size_secs),
+            Self::Hopping {
+                size_secs,
+                hop_secs,
+            } => (size_secs, hop_secs),
+        };
+        if size_secs == 0 || hop_secs == 0 {
+            return Err("Window size and hop must be greater than zero".into());
+        }
+        if size_secs % hop_secs != 0 {
+            return Err("Window size must be a multiple of the hop".into());
+        }
+        Ok((size_secs as i64 * 1000, hop_secs as i64 * 1000))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputType {
+    /// Each result row is a log event with a field per column.
+    Log,
+    /// Each aggregated column of a result row is a metric, tagged with the
+    /// other columns. Counts and sums are incremental counters for tumbling
+    /// windows, and absolute gauges for hopping ones, as overlapping windows
+    /// count the same events more than once.
+    Metric,
+}
+
+impl Default for OutputType {
+    fn default() -> Self {
+        Self::Log
+    }
+}
+
+inventory::submit! {
+    TransformDescription::new::<SqlConfig>("sql")
+}
+
+impl GenerateConfig for SqlConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"query = "SELECT host, COUNT(*) AS count FROM events GROUP BY host"
+            window = { type = "tumbling", size_secs = 60 }"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sql")]
+impl TransformConfig for SqlConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Sql::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        match self.output {
+            OutputType::Log => DataType::Log,
+            OutputType::Metric => DataType::Metric,
+        }
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "sql"
+    }
+}
+
+struct Group {
+    key: Vec<Value>,
+    accumulators: Vec<Accumulator>,
+}
+
+pub struct Sql {
+    query: Query,
+    output: OutputType,
+    size_ms: i64,
+    hop_ms: i64,
+    /// The groups of the open windows, by the start of the window in
+    /// milliseconds and the serialized key of the group.
+    windows: BTreeMap<i64, IndexMap<String, Group>>,
+}
+
+impl Sql {
+    pub fn new(config: &SqlConfig) -> crate::Result<Self> {
+        let query = Query::parse(&config.query)?;
+        let (size_ms, hop_ms) = config.window.size_and_hop()?;
+        Ok(Self {
+            query,
+            output: config.output,
+            size_ms,
+            hop_ms,
+            windows: BTreeMap::new(),
+        })
+    }
+
+    /// Adds an event to the groups of all windows open at `now_ms`.
+    fn record(&mut self, log: &LogEvent, now_ms: i64) {
+        if !self.query.matches(log) {
+            return;
+        }
+        let key = self.query.group_key(log);
+        let serialized = serde_json::to_string(&key).unwrap_or_default();
+
+        let mut start = now_ms - now_ms.rem_euclid(self.hop_ms);
+        while start > now_ms - self.size_ms {
+            let query = &self.query;
+            let group = self
+                .windows
+                .entry(start)
+                .or_default()
+                .entry(serialized.clone())
+                .or_insert_with(|| Group {
+                    key: key.clone(),
+                    accumulators: query.accumulators(),
+                });
+            query.update(&mut group.accumulators, log);
+            start -= self.hop_ms;
+        }
+    }
+
+    /// Emits the results of the windows that have closed by `now_ms`, or of
+    /// all windows if there is no such time.
+    fn flush_into(&mut self, output: &mut Vec<Event>, now_ms: Option<i64>) {
+        let closed = match now_ms {
+            Some(now_ms) => {
+                // Windows starting after this one are still open.
+                let open = self.windows.split_off(&(now_ms - self.size_ms + 1));
+                std::mem::replace(&mut self.windows, open)
+            }
+            None => std::mem::take(&mut self.windows),
+        };
+
+        for (start_ms, groups) in closed {
+            let start = Utc.timestamp_millis(start_ms);
+            let end = Utc.timestamp_millis(start_ms + self.size_ms);
+            let before = output.len();
+            for group in groups.values() {
+                if let Some(row) = self
+                    .query
+                    .row(&group.key, &group.accumulators, (start, end))
+                {
+                    self.emit_row(output, row, end);
+                }
+            }
+            emit!(SqlWindowFlushed {
+                groups: groups.len(),
+                rows: output.len() - before,
+            });
+        }
+    }
+
+    fn emit_row(&self, output: &mut Vec<Event>, row: Vec<Value>, end: DateTime<Utc>) {
+        let columns = self.query.columns().iter().zip(row);
+        match self.output {
+            OutputType::Log => {
+                let mut log = LogEvent::default();
+                for (column, value) in columns {
+                    log.insert_flat(column.name.clone(), value);
+                }
+                let timestamp_key = crate::config::log_schema().timestamp_key();
+                if !log.contains(timestamp_key) {
+                    log.insert(timestamp_key, end);
+                }
+                output.push(log.into());
+            }
+            OutputType::Metric => {
+                let (aggregated, grouped): (Vec<_>, Vec<_>) =
+                    columns.partition(|(column, _)| column.aggregated);
+                let tags = grouped
+                    .into_iter()
+                    .filter(|(_, value)| !matches!(value, Value::Null))
+                    .map(|(column, value)| (column.name.clone(), value.to_string_lossy()))
+                    .collect::<MetricTags>();
+                for (column, value) in aggregated {
+                    let value = match value {
+                        Value::Integer(integer) => integer as f64,
+                        Value::Float(float) => float,
+                        _ => continue,
+                    };
+                    // Windows that overlap can't be summed.
+                    let (kind, value) = if column.additive && self.hop_ms == self.size_ms {
+                        (MetricKind::Incremental, MetricValue::Counter { value })
+                    } else {
+                        (MetricKind::Absolute, MetricValue::Gauge { value })
+                    };
+                    let metric = Metric::new(column.name.clone(), kind, value)
+                        .with_tags(Some(tags.clone()))
+                        .with_timestamp(Some(end));
+                    output.push(metric.into());
+                }
+            }
+        }
+    }
+}
+
+impl TaskTransform for Sql {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(Duration::from_secs(1));
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.tick() => {
+                      me.flush_into(&mut output, Some(Utc::now().timestamp_millis()));
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_into(&mut output, None);
+                          true
+                        }
+                        Some(event) => {
+                          me.record(event.as_log(), Utc::now().timestamp_millis());
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqlConfig>();
+    }
+
+    fn sql(config: &str) -> Sql {
+        Sql::new(&toml::from_str::<SqlConfig>(config).unwrap()).unwrap()
+    }
+
+    fn log(host: &str, status: i64) -> LogEvent {
+        let mut log = LogEvent::default();
+        log.insert("host", host);
+        log.insert("status", status);
+        log
+    }
+
+    fn flush(sql: &mut Sql, now_ms: Option<i64>) -> Vec<Event> {
+        let mut output = Vec::new();
+        sql.flush_into(&mut output, now_ms);
+        output
+    }
+
+    #[test]
+    fn invalid_config() {
+        let error = |config: &str| {
+            Sql::new(&toml::from_str::<SqlConfig>(config).unwrap())
+                .err()
+                .unwrap()
+                .to_string()
+        };
+
+        assert!(error(
+            r#"query = "SELECT host FROM events"
+            window = { type = "tumbling", size_secs = 10 }"#
+        )
+        .contains("GROUP BY"));
+        assert!(error(
+            r#"query = "SELECT COUNT(*) FROM events"
+            window = { type = "tumbling", size_secs = 0 }"#
+        )
+        .contains("greater than zero"));
+        assert!(error(
+            r#"query = "SELECT COUNT(*) FROM events"
+            window = { type = "hopping", size_secs = 10, hop_secs = 3 }"#
+        )
+        .contains("multiple of the hop"));
+    }
+
+    #[test]
+    fn tumbling_window() {
+        let mut sql = sql(
+            r#"query = "SELECT host, COUNT(*) AS errors FROM events WHERE status >= 500 GROUP BY host HAVING COUNT(*) > 1"
+            window = { type = "tumbling", size_secs = 10 }"#,
+        );
+
+        sql.record(&log("a", 500), 1_000);
+        sql.record(&log("a", 502), 2_000);
+        sql.record(&log("a", 200), 3_000);
+        sql.record(&log("b", 500), 4_000);
+        // The next window.
+        sql.record(&log("a", 503), 10_000);
+
+        assert!(flush(&mut sql, Some(9_999)).is_empty());
+
+        let output = flush(&mut sql, Some(10_000));
+        assert_eq!(output.len(), 1);
+        let log = output[0].as_log();
+        assert_eq!(log["host"], "a".into());
+        assert_eq!(log["errors"], Value::Integer(2));
+        assert_eq!(log["timestamp"], Value::Timestamp(Utc.timestamp(10, 0)));
+
+        // Only a single error was left for the last window.
+        assert!(flush(&mut sql, None).is_empty());
+        assert!(sql.windows.is_empty());
+    }
+
+    #[test]
+    fn hopping_window() {
+        let mut sql = sql(
+            r#"query = "SELECT window_start, COUNT(*) AS count FROM events"
+            window = { type = "hopping", size_secs = 10, hop_secs = 5 }"#,
+        );
+
+        sql.record(&log("a", 200), 7_000);
+        sql.record(&log("a", 200), 12_000);
+
+        let output = flush(&mut sql, Some(15_000));
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].as_log()["window_start"],
+            Value::Timestamp(Utc.timestamp(0, 0))
+        );
+        assert_eq!(output[0].as_log()["count"], Value::Integer(1));
+        assert_eq!(
+            output[1].as_log()["window_start"],
+            Value::Timestamp(Utc.timestamp(5, 0))
+        );
+        assert_eq!(output[1].as_log()["count"], Value::Integer(2));
+
+        let output = flush(&mut sql, None);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["count"], Value::Integer(1));
+    }
+
+    #[test]
+    fn hopping_window_metric_output() {
+        let mut sql = sql(
+            r#"query = "SELECT host, COUNT(*) AS requests FROM events GROUP BY host"
+            window = { type = "hopping", size_secs = 10, hop_secs = 5 }
+            output = "metric""#,
+        );
+
+        sql.record(&log("a", 200), 7_000);
+
+        // The event is counted by two windows, so their counts can't be added
+        // up.
+        let output = flush(&mut sql, Some(10_000));
+        let tags = Some(
+            vec![("host".to_owned(), "a".to_owned())]
+                .into_iter()
+                .collect(),
+        );
+        assert_eq!(
+            output,
+            vec![Event::from(
+                Metric::new(
+                    "requests",
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: 1.0 }
+                )
+                .with_tags(tags)
+                .with_timestamp(Some(Utc.timestamp(10, 0)))
+            )]
+        );
+    }
+
+    #[test]
+    fn metric_output() {
+        let mut sql = sql(
+            r#"query = "SELECT host, COUNT(*) AS requests, AVG(status) AS status FROM events GROUP BY host"
+            window = { type = "tumbling", size_secs = 10 }
+            output = "metric""#,
+        );
+
+        sql.record(&log("a", 200), 1_000);
+        sql.record(&log("a", 400), 2_000);
+
+        let output = flush(&mut sql, None);
+        let tags = Some(
+            vec![("host".to_owned(), "a".to_owned())]
+                .into_iter()
+                .collect(),
+        );
+        assert_eq!(
+            output,
+            vec![
+                Event::from(
+                    Metric::new(
+                        "requests",
+                        MetricKind::Incremental,
+                        MetricValue::Counter { value: 2.0 }
+                    )
+                    .with_tags(tags.clone())
+                    .with_timestamp(Some(Utc.timestamp(10, 0)))
+                ),
+                Event::from(
+                    Metric::new(
+                        "status",
+                        MetricKind::Absolute,
+                        MetricValue::Gauge { value: 300.0 }
+                    )
+                    .with_tags(tags)
+                    .with_timestamp(Some(Utc.timestamp(10, 0)))
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,767 @@
+//! Compiles the query of the `sql` transform and evaluates it over events.
+
+use crate::event::{LogEvent, Value};
+use chrono::{DateTime, Utc};
+use snafu::{ResultExt, Snafu};
+use sqlparser::{
+    ast::{self, FunctionArg, SelectItem, SetExpr, Statement, TableFactor},
+    dialect::GenericDialect,
+    parser::{Parser, ParserError},
+};
+use std::cmp::Ordering;
+
+/// The columns that hold the bounds of the window a result row is for.
+const WINDOW_START: &str = "window_start";
+const WINDOW_END: &str = "window_end";
+
+#[derive(Debug, Snafu)]
+pub enum QueryError {
+    #[snafu(display("Invalid query: {}", source))]
+    Parse { source: ParserError },
+    #[snafu(display("Query must be a single SELECT statement"))]
+    NotSelect,
+    #[snafu(display("Query must select FROM a single table"))]
+    NotSingleTable,
+    #[snafu(display("Query must use GROUP BY or aggregate functions"))]
+    NotAggregate,
+    #[snafu(display("{} is not supported", what))]
+    Unsupported { what: String },
+    #[snafu(display(
+        "Column {} must appear in GROUP BY or be used in an aggregate function",
+        column
+    ))]
+    NotGrouped { column: String },
+    #[snafu(display("Aggregate functions are not allowed in {}", clause))]
+    MisplacedAggregate { clause: &'static str },
+}
+
+fn unsupported(what: impl std::fmt::Display) -> QueryError {
+    QueryError::Unsupported {
+        what: what.to_string(),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum UnaryOp {
+    Not,
+    Neg,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+/// An expression over leaves that are resolved when it is evaluated.
+#[derive(Clone, Debug)]
+enum Expr<L> {
+    Leaf(L),
+    Literal(Value),
+    Unary(UnaryOp, Box<Expr<L>>),
+    Binary(Box<Expr<L>>, BinaryOp, Box<Expr<L>>),
+    IsNull { expr: Box<Expr<L>>, negated: bool },
+}
+
+/// An expression over the fields of an event.
+type RowExpr = Expr<String>;
+
+/// An expression over the results of a group.
+type GroupExpr = Expr<GroupLeaf>;
+
+#[derive(Clone, Debug)]
+enum GroupLeaf {
+    Key(usize),
+    Aggregate(usize),
+    WindowStart,
+    WindowEnd,
+}
+
+impl<L> Expr<L> {
+    /// Compiles the structure of an expression, leaving the expressions
+    /// `leaf` resolves to it.
+    fn compile(
+        expr: &ast::Expr,
+        leaf: &mut dyn FnMut(&ast::Expr) -> Result<Option<L>, QueryError>,
+    ) -> Result<Self, QueryError> {
+        if let Some(resolved) = leaf(expr)? {
+            return Ok(Self::Leaf(resolved));
+        }
+        Ok(match expr {
+            ast::Expr::Value(value) => Self::Literal(literal(value)?),
+            ast::Expr::Nested(expr) => Self::compile(expr, leaf)?,
+            ast::Expr::UnaryOp { op, expr } => {
+                let op = match op {
+                    ast::UnaryOperator::Not => UnaryOp::Not,
+                    ast::UnaryOperator::Minus => UnaryOp::Neg,
+                    ast::UnaryOperator::Plus => return Self::compile(expr, leaf),
+                    op => return Err(unsupported(format!("Operator {}", op))),
+                };
+                Self::Unary(op, Box::new(Self::compile(expr, leaf)?))
+            }
+            ast::Expr::BinaryOp { left, op, right } => {
+                let op = match op {
+                    ast::BinaryOperator::Plus => BinaryOp::Add,
+                    ast::BinaryOperator::Minus => BinaryOp::Sub,
+                    ast::BinaryOperator::Multiply => BinaryOp::Mul,
+                    ast::BinaryOperator::Divide => BinaryOp::Div,
+                    ast::BinaryOperator::Modulus => BinaryOp::Mod,
+                    ast::BinaryOperator::Eq => BinaryOp::Eq,
+                    ast::BinaryOperator::NotEq => BinaryOp::NotEq,
+                    ast::BinaryOperator::Lt => BinaryOp::Lt,
+                    ast::BinaryOperator::LtEq => BinaryOp::LtEq,
+                    ast::BinaryOperator::Gt => BinaryOp::Gt,
+                    ast::BinaryOperator::GtEq => BinaryOp::GtEq,
+                    ast::BinaryOperator::And => BinaryOp::And,
+                    ast::BinaryOperator::Or => BinaryOp::Or,
+                    op => return Err(unsupported(format!("Operator {}", op))),
+                };
+                Self::Binary(
+                    Box::new(Self::compile(left, leaf)?),
+                    op,
+                    Box::new(Self::compile(right, leaf)?),
+                )
+            }
+            ast::Expr::IsNull(expr) => Self::IsNull {
+                expr: Box::new(Self::compile(expr, leaf)?),
+                negated: false,
+            },
+            ast::Expr::IsNotNull(expr) => Self::IsNull {
+                expr: Box::new(Self::compile(expr, leaf)?),
+                negated: true,
+            },
+            expr => return Err(unsupported(format!("Expression {}", expr))),
+        })
+    }
+
+    fn eval(&self, leaf: &impl Fn(&L) -> Value) -> Value {
+        match self {
+            Self::Leaf(resolved) => leaf(resolved),
+            Self::Literal(value) => value.clone(),
+            Self::Unary(op, expr) => unary(*op, expr.eval(leaf)),
+            Self::Binary(left, op, right) => binary(left.eval(leaf), *op, right.eval(leaf)),
+            Self::IsNull { expr, negated } => {
+                Value::Boolean(matches!(expr.eval(leaf), Value::Null) != *negated)
+            }
+        }
+    }
+
+    fn any_leaf(&self, predicate: &impl Fn(&L) -> bool) -> bool {
+        match self {
+            Self::Leaf(resolved) => predicate(resolved),
+            Self::Literal(_) => false,
+            Self::Unary(_, expr) | Self::IsNull { expr, .. } => expr.any_leaf(predicate),
+            Self::Binary(left, _, right) => left.any_leaf(predicate) || right.any_leaf(predicate),
+        }
+    }
+}
+
+fn literal(value: &ast::Value) -> Result<Value, QueryError> {
+    Ok(match value {
+        ast::Value::Number(number, _) => match number.parse::<i64>() {
+            Ok(integer) => Value::Integer(integer),
+            Err(_) => Value::Float(
+                number
+                    .parse::<f64>()
+                    .map_err(|_| unsupported(format!("Number {}", number)))?,
+            ),
+        },
+        ast::Value::SingleQuotedString(string) => Value::from(string.as_str()),
+        ast::Value::Boolean(boolean) => Value::Boolean(*boolean),
+        ast::Value::Null => Value::Null,
+        value => return Err(unsupported(format!("Value {}", value))),
+    })
+}
+
+/// Resolves the column references of row expressions to fields of events.
+fn row_leaf(expr: &ast::Expr, clause: &'static str) -> Result<Option<String>, QueryError> {
+    match expr {
+        ast::Expr::Identifier(ident) => Ok(Some(ident.value.clone())),
+        ast::Expr::CompoundIdentifier(idents) => Ok(Some(
+            idents
+                .iter()
+                .map(|ident| ident.value.as_str())
+                .collect::<Vec<_>>()
+                .join("."),
+        )),
+        ast::Expr::Function(function) if AggregateFunction::from_name(function).is_some() => {
+            Err(QueryError::MisplacedAggregate { clause })
+        }
+        _ => Ok(None),
+    }
+}
+
+fn compile_row(expr: &ast::Expr, clause: &'static str) -> Result<RowExpr, QueryError> {
+    RowExpr::compile(expr, &mut |expr| row_leaf(expr, clause))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    fn from_name(function: &ast::Function) -> Option<Self> {
+        match function.name.to_string().to_lowercase().as_str() {
+            "count" => Some(Self::Count),
+            "sum" => Some(Self::Sum),
+            "avg" => Some(Self::Avg),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Aggregate {
+    function: AggregateFunction,
+    /// The argument of the function, which is absent for `COUNT(*)`.
+    arg: Option<RowExpr>,
+}
+
+impl Aggregate {
+    fn compile(function: &ast::Function) -> Result<Option<Self>, QueryError> {
+        let kind = match AggregateFunction::from_name(function) {
+            Some(kind) => kind,
+            None => return Ok(None),
+        };
+        if function.distinct || function.over.is_some() {
+            return Err(unsupported(format!("Function call {}", function)));
+        }
+        let arg = match function.args.as_slice() {
+            [FunctionArg::Unnamed(ast::Expr::Wildcard)] if kind == AggregateFunction::Count => None,
+            [FunctionArg::Unnamed(arg)] => Some(compile_row(arg, "aggregate function arguments")?),
+            _ => return Err(unsupported(format!("Function call {}", function))),
+        };
+        Ok(Some(Self {
+            function: kind,
+            arg,
+        }))
+    }
+}
+
+/// The state of an aggregate function over the rows of a group.
+#[derive(Clone, Debug)]
+pub struct Accumulator(AccumulatorState);
+
+#[derive(Clone, Debug)]
+enum AccumulatorState {
+    Count(i64),
+    Sum(Value),
+    Avg { sum: f64, count: u64 },
+    Min(Value),
+    Max(Value),
+}
+
+impl Accumulator {
+    fn new(function: AggregateFunction) -> Self {
+        Self(match function {
+            AggregateFunction::Count => AccumulatorState::Count(0),
+            AggregateFunction::Sum => AccumulatorState::Sum(Value::Null),
+            AggregateFunction::Avg => AccumulatorState::Avg { sum: 0.0, count: 0 },
+            AggregateFunction::Min => AccumulatorState::Min(Value::Null),
+            AggregateFunction::Max => AccumulatorState::Max(Value::Null),
+        })
+    }
+
+    /// Adds a row, whose value is absent for `COUNT(*)`. Like in SQL, null
+    /// values are ignored.
+    fn update(&mut self, value: Option<Value>) {
+        if let Some(Value::Null) = value {
+            return;
+        }
+        match (&mut self.0, value) {
+            (AccumulatorState::Count(count), _) => *count += 1,
+            (AccumulatorState::Sum(sum), Some(value)) if as_f64(&value).is_some() => {
+                *sum = match sum {
+                    Value::Null => value,
+                    sum => binary(sum.clone(), BinaryOp::Add, value),
+                }
+            }
+            (AccumulatorState::Avg { sum, count }, Some(value)) => {
+                if let Some(value) = as_f64(&value) {
+                    *sum += value;
+                    *count += 1;
+                }
+            }
+            (AccumulatorState::Min(min), Some(value)) => {
+                if matches!(min, Value::Null) || compare(&value, min) == Some(Ordering::Less) {
+                    *min = value;
+                }
+            }
+            (AccumulatorState::Max(max), Some(value)) => {
+                if matches!(max, Value::Null) || compare(&value, max) == Some(Ordering::Greater) {
+                    *max = value;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn finish(&self) -> Value {
+        match &self.0 {
+            AccumulatorState::Count(count) => Value::Integer(*count),
+            AccumulatorState::Avg { count: 0, .. } => Value::Null,
+            AccumulatorState::Avg { sum, count } => Value::Float(*sum / *count as f64),
+            AccumulatorState::Sum(value)
+            | AccumulatorState::Min(value)
+            | AccumulatorState::Max(value) => value.clone(),
+        }
+    }
+}
+
+/// A column of the rows the query results in.
+#[derive(Clone, Debug)]
+pub struct Column {
+    pub name: String,
+    expr: GroupExpr,
+    /// Whether the column is computed from aggregate functions, as opposed to
+    /// the values the rows are grouped by.
+    pub aggregated: bool,
+    /// Whether the column is a count or a sum, which add up across windows.
+    pub additive: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Query {
+    filter: Option<RowExpr>,
+    group_by: Vec<RowExpr>,
+    aggregates: Vec<Aggregate>,
+    columns: Vec<Column>,
+    having: Option<GroupExpr>,
+}
+
+impl Query {
+    pub fn parse(sql: &str) -> Result<Self, QueryError> {
+        let mut statements = Parser::parse_sql(&GenericDialect {}, sql).context(Parse)?;
+        let query = match (statements.pop(), statements.is_empty()) {
+            (Some(Statement::Query(query)), true) => query,
+            _ => return Err(QueryError::NotSelect),
+        };
+        if query.with.is_some() {
+            return Err(unsupported("WITH"));
+        }
+        if !query.order_by.is_empty() {
+            return Err(unsupported("ORDER BY"));
+        }
+        if query.limit.is_some() || query.offset.is_some() || query.fetch.is_some() {
+            return Err(unsupported("LIMIT"));
+        }
+        let select = match &query.body {
+            SetExpr::Select(select) => select,
+            _ => return Err(QueryError::NotSelect),
+        };
+        if select.distinct {
+            return Err(unsupported("SELECT DISTINCT"));
+        }
+        match select.from.as_slice() {
+            [table] if table.joins.is_empty() => match &table.relation {
+                TableFactor::Table { .. } => (),
+                _ => return Err(QueryError::NotSingleTable),
+            },
+            _ => return Err(QueryError::NotSingleTable),
+        }
+
+        let filter = select
+            .selection
+            .as_ref()
+            .map(|expr| compile_row(expr, "WHERE"))
+            .transpose()?;
+        let group_by = select
+            .group_by
+            .iter()
+            .map(|expr| compile_row(expr, "GROUP BY"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut aggregates = Vec::new();
+        let mut compile_group = |expr: &ast::Expr| {
+            GroupExpr::compile(expr, &mut |expr| {
+                if let Some(index) = select.group_by.iter().position(|key| key == expr) {
+                    return Ok(Some(GroupLeaf::Key(index)));
+                }
+                match expr {
+                    ast::Expr::Function(function) => {
+                        Ok(Aggregate::compile(function)?.map(|aggregate| {
+                            aggregates.push(aggregate);
+                            GroupLeaf::Aggregate(aggregates.len() - 1)
+                        }))
+                    }
+                    ast::Expr::Identifier(ident) if ident.value == WINDOW_START => {
+                        Ok(Some(GroupLeaf::WindowStart))
+                    }
+                    ast::Expr::Identifier(ident) if ident.value == WINDOW_END => {
+                        Ok(Some(GroupLeaf::WindowEnd))
+                    }
+                    ast::Expr::Identifier(_) | ast::Expr::CompoundIdentifier(_) => {
+                        Err(QueryError::NotGrouped {
+                            column: expr.to_string(),
+                        })
+                    }
+                    _ => Ok(None),
+                }
+            })
+        };
+
+        let mut columns = Vec::new();
+        for item in &select.projection {
+            let (expr, name) = match item {
+                SelectItem::UnnamedExpr(expr) => (expr, expr.to_string()),
+                SelectItem::ExprWithAlias { expr, alias } => (expr, alias.value.clone()),
+                item => return Err(unsupported(format!("Selecting {}", item))),
+            };
+            let expr = compile_group(expr)?;
+            let aggregated = expr.any_leaf(&|leaf| matches!(leaf, GroupLeaf::Aggregate(_)));
+            columns.push(Column {
+                name,
+                expr,
+                aggregated,
+                additive: false,
+            });
+        }
+        let having = select.having.as_ref().map(compile_group).transpose()?;
+
+        if group_by.is_empty() && aggregates.is_empty() {
+            return Err(QueryError::NotAggregate);
+        }
+        for column in &mut columns {
+            column.additive = matches!(
+                column.expr,
+                Expr::Leaf(GroupLeaf::Aggregate(index))
+                    if matches!(aggregates[index].function, AggregateFunction::Count | AggregateFunction::Sum)
+            );
+        }
+
+        Ok(Self {
+            filter,
+            group_by,
+            aggregates,
+            columns,
+            having,
+        })
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Returns whether the event passes the `WHERE` clause.
+    pub fn matches(&self, log: &LogEvent) -> bool {
+        self.filter.as_ref().map_or(true, |filter| {
+            matches!(filter.eval(&field(log)), Value::Boolean(true))
+        })
+    }
+
+    /// Returns the values the event is grouped by.
+    pub fn group_key(&self, log: &LogEvent) -> Vec<Value> {
+        self.group_by
+            .iter()
+            .map(|expr| expr.eval(&field(log)))
+            .collect()
+    }
+
+    pub fn accumulators(&self) -> Vec<Accumulator> {
+        self.aggregates
+            .iter()
+            .map(|aggregate| Accumulator::new(aggregate.function))
+            .collect()
+    }
+
+    pub fn update(&self, accumulators: &mut [Accumulator], log: &LogEvent) {
+        for (accumulator, aggregate) in accumulators.iter_mut().zip(&self.aggregates) {
+            accumulator.update(aggregate.arg.as_ref().map(|arg| arg.eval(&field(log))));
+        }
+    }
+
+    /// Returns the values of the columns of the row for a group, unless it
+    /// doesn't pass the `HAVING` clause.
+    pub fn row(
+        &self,
+        key: &[Value],
+        accumulators: &[Accumulator],
+        window: (DateTime<Utc>, DateTime<Utc>),
+    ) -> Option<Vec<Value>> {
+        let results = accumulators
+            .iter()
+            .map(Accumulator::finish)
+            .collect::<Vec<_>>();
+        let leaf = |leaf: &GroupLeaf| match leaf {
+            GroupLeaf::Key(index) => key[*index].clone(),
+            GroupLeaf::Aggregate(index) => results[*index].clone(),
+            GroupLeaf::WindowStart => Value::Timestamp(window.0),
+            GroupLeaf::WindowEnd => Value::Timestamp(window.1),
+        };
+
+        if let Some(having) = &self.having {
+            if !matches!(having.eval(&leaf), Value::Boolean(true)) {
+                return None;
+            }
+        }
+        Some(
+            self.columns
+                .iter()
+                .map(|column| column.expr.eval(&leaf))
+                .collect(),
+        )
+    }
+}
+
+fn field(log: &LogEvent) -> impl Fn(&String) -> Value + '_ {
+    move |path| log.get(path).cloned().unwrap_or(Value::Null)
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(integer) => Some(*integer as f64),
+        Value::Float(float) => Some(*float),
+        _ => None,
+    }
+}
+
+fn as_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Boolean(boolean) => Some(*boolean),
+        _ => None,
+    }
+}
+
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Integer(left), Value::Integer(right)) => Some(left.cmp(right)),
+        (Value::Bytes(left), Value::Bytes(right)) => Some(left.cmp(right)),
+        (Value::Boolean(left), Value::Boolean(right)) => Some(left.cmp(right)),
+        (Value::Timestamp(left), Value::Timestamp(right)) => Some(left.cmp(right)),
+        (left, right) => as_f64(left)?.partial_cmp(&as_f64(right)?),
+    }
+}
+
+fn unary(op: UnaryOp, value: Value) -> Value {
+    match (op, value) {
+        (UnaryOp::Not, Value::Boolean(boolean)) => Value::Boolean(!boolean),
+        (UnaryOp::Neg, Value::Integer(integer)) => {
+            integer.checked_neg().map_or(Value::Null, Value::Integer)
+        }
+        (UnaryOp::Neg, Value::Float(float)) => Value::Float(-float),
+        _ => Value::Null,
+    }
+}
+
+/// Applies an operator with the semantics of SQL, in which operations on null
+/// or mismatched values result in null.
+fn binary(left: Value, op: BinaryOp, right: Value) -> Value {
+    let ordering = |accept: fn(Ordering) -> bool| {
+        compare(&left, &right).map_or(Value::Null, |ordering| Value::Boolean(accept(ordering)))
+    };
+    match op {
+        BinaryOp::And => match (as_bool(&left), as_bool(&right)) {
+            (Some(false), _) | (_, Some(false)) => Value::Boolean(false),
+            (Some(true), Some(true)) => Value::Boolean(true),
+            _ => Value::Null,
+        },
+        BinaryOp::Or => match (as_bool(&left), as_bool(&right)) {
+            (Some(true), _) | (_, Some(true)) => Value::Boolean(true),
+            (Some(false), Some(false)) => Value::Boolean(false),
+            _ => Value::Null,
+        },
+        BinaryOp::Eq => ordering(|ordering| ordering == Ordering::Equal),
+        BinaryOp::NotEq => ordering(|ordering| ordering != Ordering::Equal),
+        BinaryOp::Lt => ordering(|ordering| ordering == Ordering::Less),
+        BinaryOp::LtEq => ordering(|ordering| ordering != Ordering::Greater),
+        BinaryOp::Gt => ordering(|ordering| ordering == Ordering::Greater),
+        BinaryOp::GtEq => ordering(|ordering| ordering != Ordering::Less),
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Mod => match (&left, &right) {
+            (Value::Integer(left), Value::Integer(right)) => match op {
+                BinaryOp::Add => left.checked_add(*right),
+                BinaryOp::Sub => left.checked_sub(*right),
+                BinaryOp::Mul => left.checked_mul(*right),
+                _ => left.checked_rem(*right),
+            }
+            .map_or(Value::Null, Value::Integer),
+            _ => match (as_f64(&left), as_f64(&right)) {
+                (Some(left), Some(right)) => Value::Float(match op {
+                    BinaryOp::Add => left + right,
+                    BinaryOp::Sub => left - right,
+                    BinaryOp::Mul => left * right,
+                    _ => left % right,
+                }),
+                _ => Value::Null,
+            },
+        },
+        // Division always results in a float, so that averages computed
+        // from sums and counts aren't truncated.
+        BinaryOp::Div => match (as_f64(&left), as_f64(&right)) {
+            (Some(_), Some(right)) if right == 0.0 => Value::Null,
+            (Some(left), Some(right)) => Value::Float(left / right),
+            _ => Value::Null,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn log(fields: &[(&str, Value)]) -> LogEvent {
+        let mut log = LogEvent::default();
+        for (name, value) in fields {
+            log.insert(*name, value.clone());
+        }
+        log
+    }
+
+    fn run(sql: &str, logs: &[LogEvent]) -> Vec<Vec<Value>> {
+        let query = Query::parse(sql).unwrap();
+        let mut groups: Vec<(Vec<Value>, Vec<Accumulator>)> = Vec::new();
+        for log in logs.iter().filter(|log| query.matches(log)) {
+            let key = query.group_key(log);
+            let index = match groups.iter().position(|(existing, _)| *existing == key) {
+                Some(index) => index,
+                None => {
+                    groups.push((key, query.accumulators()));
+                    groups.len() - 1
+                }
+            };
+            query.update(&mut groups[index].1, log);
+        }
+        let window = (Utc.timestamp(0, 0), Utc.timestamp(60, 0));
+        groups
+            .iter()
+            .filter_map(|(key, accumulators)| query.row(key, accumulators, window))
+            .collect()
+    }
+
+    #[test]
+    fn aggregates() {
+        let logs = vec![
+            log(&[("host", "a".into()), ("duration", 10.into())]),
+            log(&[("host", "a".into()), ("duration", 20.into())]),
+            log(&[("host", "b".into()), ("duration", 5.5.into())]),
+            log(&[("host", "b".into())]),
+        ];
+
+        let rows = run(
+            "SELECT host, COUNT(*), COUNT(duration), SUM(duration), AVG(duration), \
+             MIN(duration), MAX(duration), SUM(duration) / COUNT(*) AS mean \
+             FROM events GROUP BY host",
+            &logs,
+        );
+
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    "a".into(),
+                    Value::Integer(2),
+                    Value::Integer(2),
+                    Value::Integer(30),
+                    Value::Float(15.0),
+                    Value::Integer(10),
+                    Value::Integer(20),
+                    Value::Float(15.0),
+                ],
+                vec![
+                    "b".into(),
+                    Value::Integer(2),
+                    Value::Integer(1),
+                    Value::Float(5.5),
+                    Value::Float(5.5),
+                    Value::Float(5.5),
+                    Value::Float(5.5),
+                    Value::Float(2.75),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn where_and_having() {
+        let logs = vec![
+            log(&[("host", "a".into()), ("status", 500.into())]),
+            log(&[("host", "a".into()), ("status", 503.into())]),
+            log(&[("host", "b".into()), ("status", 502.into())]),
+            log(&[("host", "b".into()), ("status", 200.into())]),
+            log(&[("host", "c".into())]),
+        ];
+
+        let rows = run(
+            "SELECT host, COUNT(*) AS errors FROM events \
+             WHERE status >= 500 AND NOT status IS NULL \
+             GROUP BY host HAVING COUNT(*) > 1",
+            &logs,
+        );
+
+        assert_eq!(rows, vec![vec!["a".into(), Value::Integer(2)]]);
+    }
+
+    #[test]
+    fn window_columns_and_nested_fields() {
+        let logs = vec![log(&[("request.method", "GET".into())])];
+
+        let rows = run(
+            "SELECT window_start, window_end, request.method, COUNT(*) \
+             FROM events GROUP BY request.method",
+            &logs,
+        );
+
+        assert_eq!(
+            rows,
+            vec![vec![
+                Value::Timestamp(Utc.timestamp(0, 0)),
+                Value::Timestamp(Utc.timestamp(60, 0)),
+                "GET".into(),
+                Value::Integer(1),
+            ]]
+        );
+    }
+
+    #[test]
+    fn columns() {
+        let query =
+            Query::parse("SELECT host, COUNT(*), AVG(duration) AS avg FROM events GROUP BY host")
+                .unwrap();
+
+        let columns = query
+            .columns()
+            .iter()
+            .map(|column| (column.name.as_str(), column.aggregated, column.additive))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                ("host", false, false),
+                ("COUNT(*)", true, true),
+                ("avg", true, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_queries() {
+        let error = |sql| Query::parse(sql).unwrap_err().to_string();
+
+        assert!(error("SELEC host").starts_with("Invalid query"));
+        assert!(error("INSERT INTO events VALUES (1)").contains("single SELECT"));
+        assert!(error("SELECT 1 FROM events").contains("GROUP BY or aggregate"));
+        assert!(error("SELECT host, COUNT(*) FROM events").contains("Column host"));
+        assert!(error("SELECT COUNT(*) FROM events WHERE COUNT(*) > 1").contains("WHERE"));
+        assert!(error("SELECT COUNT(*) FROM events ORDER BY 1").contains("ORDER BY"));
+        assert!(error("SELECT COUNT(*) FROM a JOIN b ON a.x = b.x").contains("single table"));
+        assert!(error("SELECT COUNT(DISTINCT host) FROM events").contains("not supported"));
+    }
+}
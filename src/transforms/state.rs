@@ -0,0 +1,331 @@
+//! State that stateful transforms keep across reloads and restarts.
+//!
+//! When a transform is rebuilt during a reload without its own configuration
+//! changing (for example, because only its inputs changed), the new instance
+//! takes over the state of the running one instead of starting empty. If the
+//! transform opts into it, its state is also checkpointed to the data
+//! directory once its input ends, and restored by the next instance built
+//! with the same key and configuration. The input only ends on a graceful
+//! shutdown or reload, so the state is lost if Vector crashes or is killed.
+
+use crate::{
+    config::TransformContext,
+    internal_events::{
+        TransformStateCheckpointFailed, TransformStateCheckpointIgnored,
+        TransformStateCheckpointed, TransformStateRestoreFailed, TransformStateRestored,
+    },
+};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::{
+    any::Any,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, Weak},
+};
+
+const CHECKPOINT_FILE: &str = "state.checkpoint";
+
+lazy_static! {
+    // Only weak references are kept, so the state of a transform is dropped
+    // along with its last instance.
+    static ref STATES: Mutex<HashMap<String, Registered>> = Mutex::new(HashMap::new());
+}
+
+struct Registered {
+    fingerprint: String,
+    state: Weak<dyn Any + Send + Sync>,
+}
+
+/// The state of a transform, shared with other instances of the transform
+/// built under the same key and configuration.
+pub struct TransformState<T> {
+    key: Option<String>,
+    fingerprint: String,
+    state: Arc<Mutex<T>>,
+    checkpoint_path: Option<PathBuf>,
+}
+
+impl<T: Send + 'static> TransformState<T> {
+    /// Returns the state for the transform being built. It is taken over from
+    /// a running instance of the transform with the same configuration if
+    /// there is one, restored from the last checkpoint if `checkpoint` is set
+    /// and there is one, or created with `init` otherwise.
+    pub fn new<C: Serialize>(
+        context: &TransformContext,
+        config: &C,
+        checkpoint: bool,
+        restore: impl FnOnce(&[u8]) -> crate::Result<T>,
+        init: impl FnOnce() -> T,
+    ) -> Self {
+        let key = match &context.key {
+            Some(key) => key.clone(),
+            None => return Self::detached(init()),
+        };
+        // Configurations are compared the same way the topology diffs them.
+        let fingerprint = serde_json::to_string(config).unwrap_or_default();
+        let checkpoint_path = if checkpoint {
+            match context.globals.resolve_and_make_data_subdir(None, &key) {
+                Ok(dir) => Some(dir.join(CHECKPOINT_FILE)),
+                Err(error) => {
+                    warn!(message = "State will not be checkpointed.", %error);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut states = STATES.lock().unwrap();
+        let running = states
+            .get(&key)
+            .filter(|registered| registered.fingerprint == fingerprint)
+            .and_then(|registered| registered.state.upgrade())
+            .and_then(|state| state.downcast::<Mutex<T>>().ok());
+        let state = match running {
+            Some(state) => state,
+            None => {
+                let restored = checkpoint_path
+                    .as_deref()
+                    .filter(|path| path.exists())
+                    .and_then(|path| match read_checkpoint(path, &fingerprint, restore) {
+                        Ok(state) => state,
+                        Err(error) => {
+                            emit!(TransformStateRestoreFailed { error });
+                            None
+                        }
+                    });
+                Arc::new(Mutex::new(restored.unwrap_or_else(init)))
+            }
+        };
+
+        let shared: Arc<dyn Any + Send + Sync> = Arc::clone(&state) as _;
+        states.insert(
+            key.clone(),
+            Registered {
+                fingerprint: fingerprint.clone(),
+                state: Arc::downgrade(&shared),
+            },
+        );
+
+        Self {
+            key: Some(key),
+            fingerprint,
+            state,
+            checkpoint_path,
+        }
+    }
+
+    /// Returns state that is neither shared nor checkpointed.
+    pub fn detached(state: T) -> Self {
+        Self {
+            key: None,
+            fingerprint: String::new(),
+            state: Arc::new(Mutex::new(state)),
+            checkpoint_path: None,
+        }
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.state.lock().unwrap()
+    }
+
+    /// Keeps the state once the transform's input has ended, by leaving it to
+    /// a newer instance of the transform that shares it, or by checkpointing
+    /// it if the transform opted into it. Returns `false` if the state was
+    /// not kept, in which case the caller should flush it.
+    pub fn release(&self, encode: impl FnOnce(&T) -> crate::Result<Vec<u8>>) -> bool {
+        let key = match &self.key {
+            Some(key) => key,
+            None => return false,
+        };
+        if Arc::strong_count(&self.state) > 1 {
+            return true;
+        }
+        let path = match &self.checkpoint_path {
+            Some(path) => path,
+            None => return false,
+        };
+        if !self.is_current(key) {
+            // The transform has been rebuilt with a different configuration,
+            // which starts from scratch.
+            return false;
+        }
+
+        match encode(&self.lock())
+            .and_then(|bytes| write_checkpoint(path, &self.fingerprint, &bytes))
+        {
+            Ok(bytes) => {
+                emit!(TransformStateCheckpointed { bytes });
+                true
+            }
+            Err(error) => {
+                emit!(TransformStateCheckpointFailed { error });
+                false
+            }
+        }
+    }
+
+    fn is_current(&self, key: &str) -> bool {
+        STATES
+            .lock()
+            .unwrap()
+            .get(key)
+            .map(|registered| {
+                registered.state.strong_count() == 0
+                    || registered.state.as_ptr() as *const ()
+                        == Arc::as_ptr(&self.state) as *const ()
+            })
+            .unwrap_or(true)
+    }
+}
+
+/// Reads and removes the checkpoint, returning the state in it unless it was
+/// written by the transform with a different configuration.
+fn read_checkpoint<T>(
+    path: &Path,
+    fingerprint: &str,
+    restore: impl FnOnce(&[u8]) -> crate::Result<T>,
+) -> crate::Result<Option<T>> {
+    let checkpoint = fs::read(path)?;
+    // A checkpoint is only restored once, so that state that has since been
+    // flushed is never restored again.
+    fs::remove_file(path)?;
+
+    // Checkpoints start with the fingerprint of the configuration, on a line
+    // of its own.
+    let newline = checkpoint
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .ok_or("missing configuration fingerprint")?;
+    if &checkpoint[..newline] != fingerprint.as_bytes() {
+        emit!(TransformStateCheckpointIgnored);
+        return Ok(None);
+    }
+
+    let bytes = &checkpoint[newline + 1..];
+    let state = restore(bytes)?;
+    emit!(TransformStateRestored { bytes: bytes.len() });
+    Ok(Some(state))
+}
+
+fn write_checkpoint(path: &Path, fingerprint: &str, bytes: &[u8]) -> crate::Result<usize> {
+    let mut checkpoint = Vec::with_capacity(fingerprint.len() + 1 + bytes.len());
+    checkpoint.extend_from_slice(fingerprint.as_bytes());
+    checkpoint.push(b'\n');
+    checkpoint.extend_from_slice(bytes);
+
+    // Write to a temporary file first so that a crash never leaves a
+    // truncated checkpoint behind.
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, &checkpoint)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(bytes.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::GlobalOptions;
+
+    fn context(key: &str, data_dir: Option<PathBuf>) -> TransformContext {
+        TransformContext {
+            key: Some(key.into()),
+            ..TransformContext::new_with_globals(GlobalOptions {
+                data_dir,
+                ..Default::default()
+            })
+        }
+    }
+
+    fn build(
+        context: &TransformContext,
+        config: &str,
+        checkpoint: bool,
+    ) -> TransformState<Vec<u8>> {
+        TransformState::new(
+            context,
+            &config,
+            checkpoint,
+            |bytes| Ok(bytes.to_vec()),
+            Vec::new,
+        )
+    }
+
+    #[test]
+    fn shares_state_with_same_config() {
+        let context = context("state_shares", None);
+        let old = build(&context, "a", false);
+        old.lock().push(1);
+
+        let new = build(&context, "a", false);
+        assert_eq!(*new.lock(), vec![1]);
+        // The old instance leaves the state to the new one.
+        assert!(old.release(|state| Ok(state.clone())));
+        // The new instance no longer shares it.
+        drop(old);
+        assert!(!new.release(|state| Ok(state.clone())));
+    }
+
+    #[test]
+    fn starts_over_with_changed_config() {
+        let context = context("state_starts_over", None);
+        let old = build(&context, "a", false);
+        old.lock().push(1);
+
+        let new = build(&context, "b", false);
+        assert!(new.lock().is_empty());
+        assert!(!old.release(|state| Ok(state.clone())));
+    }
+
+    #[test]
+    fn restores_checkpoint() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let context = context("state_restores", Some(data_dir.path().to_path_buf()));
+        let old = build(&context, "a", true);
+        old.lock().push(1);
+        assert!(old.release(|state| Ok(state.clone())));
+        drop(old);
+
+        let new = build(&context, "a", true);
+        assert_eq!(*new.lock(), vec![1]);
+        drop(new);
+
+        // The checkpoint is removed once restored.
+        let new = build(&context, "a", true);
+        assert!(new.lock().is_empty());
+    }
+
+    #[test]
+    fn ignores_checkpoint_of_changed_config() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let context = context("state_ignores", Some(data_dir.path().to_path_buf()));
+        let old = build(&context, "a", true);
+        old.lock().push(1);
+        assert!(old.release(|state| Ok(state.clone())));
+        drop(old);
+
+        let new = build(&context, "b", true);
+        assert!(new.lock().is_empty());
+    }
+
+    #[test]
+    fn checkpoints_only_when_enabled() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let context = context("state_checkpoints", Some(data_dir.path().to_path_buf()));
+        let old = build(&context, "a", false);
+        old.lock().push(1);
+        assert!(!old.release(|state| Ok(state.clone())));
+        drop(old);
+
+        let new = build(&context, "a", true);
+        assert!(new.lock().is_empty());
+    }
+
+    #[test]
+    fn detached_state_is_not_kept() {
+        let state = TransformState::detached(vec![1]);
+        assert!(!state.release(|state| Ok(state.clone())));
+    }
+}
@@ -1,15 +1,21 @@
 use crate::transforms::TaskTransform;
 use crate::{
     config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
-    event::Event,
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event,
+    },
     internal_events::{
         TagCardinalityLimitRejectingEvent, TagCardinalityLimitRejectingTag,
         TagCardinalityValueLimitReached,
     },
     transforms::Transform,
 };
+use async_stream::stream;
 use bloom::{BloomFilter, ASMS};
-use futures::{Stream, StreamExt};
+use chrono::Utc;
+use futures::{stream, Stream, StreamExt};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::{Borrow, Cow},
@@ -17,6 +23,7 @@ use std::{
     fmt,
     future::ready,
     pin::Pin,
+    time::Duration,
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -29,6 +36,24 @@ pub struct TagCardinalityLimitConfig {
     #[serde(default = "default_limit_exceeded_action")]
     pub limit_exceeded_action: LimitExceededAction,
 
+    /// Value limits for metrics whose name matches a glob pattern, used
+    /// instead of `value_limit`. The first matching pattern applies, and the
+    /// tags of matching metrics are always tracked per metric name.
+    #[serde(default)]
+    pub per_metric_limits: IndexMap<String, u32>,
+
+    /// Tracks the values of each tag separately for every metric name, rather
+    /// than across all metrics.
+    #[serde(default)]
+    pub track_per_metric: bool,
+
+    /// How often to emit `tag_value_limit_exceeded` counters, tagged with the
+    /// metric name and tag key, for the tags whose values were rejected since
+    /// the last report. Reports are opt-in, as they add a series per limited
+    /// metric to the output; the `tag_value_limit_exceeded_total` internal
+    /// metric is only tagged with the tag key.
+    pub report_interval_secs: Option<u64>,
+
     #[serde(flatten)]
     pub mode: Mode,
 }
@@ -56,7 +81,11 @@ pub enum LimitExceededAction {
 #[derive(Debug)]
 pub struct TagCardinalityLimit {
     config: TagCardinalityLimitConfig,
+    per_metric_limits: Vec<(glob::Pattern, u32)>,
     accepted_tags: HashMap<String, TagValueSet>,
+    accepted_metric_tags: HashMap<String, HashMap<String, TagValueSet>>,
+    /// Rejected values per metric name and tag key, since the last report.
+    rejected: HashMap<(String, String), u64>,
 }
 
 const fn default_limit_exceeded_action() -> LimitExceededAction {
@@ -81,6 +110,9 @@ impl GenerateConfig for TagCardinalityLimitConfig {
             mode: Mode::Exact,
             value_limit: default_value_limit(),
             limit_exceeded_action: default_limit_exceeded_action(),
+            per_metric_limits: IndexMap::new(),
+            track_per_metric: false,
+            report_interval_secs: None,
         })
         .unwrap()
     }
@@ -90,7 +122,7 @@ impl GenerateConfig for TagCardinalityLimitConfig {
 #[typetag::serde(name = "tag_cardinality_limit")]
 impl TransformConfig for TagCardinalityLimitConfig {
     async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::task(TagCardinalityLimit::new(self.clone())))
+        TagCardinalityLimit::new(self.clone()).map(Transform::task)
     }
 
     fn input_type(&self) -> DataType {
@@ -172,11 +204,29 @@ impl TagValueSet {
 }
 
 impl TagCardinalityLimit {
-    fn new(config: TagCardinalityLimitConfig) -> Self {
-        Self {
+    fn new(config: TagCardinalityLimitConfig) -> crate::Result<Self> {
+        if config.report_interval_secs == Some(0) {
+            return Err("`report_interval_secs` must be greater than 0".into());
+        }
+        let per_metric_limits = config
+            .per_metric_limits
+            .iter()
+            .map(|(pattern, value_limit)| {
+                glob::Pattern::new(pattern)
+                    .map(|pattern| (pattern, *value_limit))
+                    .map_err(|error| {
+                        format!("Invalid metric name pattern {:?}: {}", pattern, error)
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
             config,
+            per_metric_limits,
             accepted_tags: HashMap::new(),
-        }
+            accepted_metric_tags: HashMap::new(),
+            rejected: HashMap::new(),
+        })
     }
 
     /// Takes in key and a value corresponding to a tag on an incoming Metric
@@ -187,14 +237,18 @@ impl TagCardinalityLimit {
     /// for the key and returns true, otherwise returns false.  A false return
     /// value indicates to the caller that the value is not accepted for this
     /// key, and the configured limit_exceeded_action should be taken.
-    fn try_accept_tag(&mut self, key: &str, value: Cow<'_, String>) -> bool {
-        if !self.accepted_tags.contains_key(key) {
-            self.accepted_tags.insert(
-                key.to_string(),
-                TagValueSet::new(self.config.value_limit, &self.config.mode),
-            );
+    fn try_accept_tag(
+        accepted_tags: &mut HashMap<String, TagValueSet>,
+        value_limit: u32,
+        mode: &Mode,
+        metric_name: &str,
+        key: &str,
+        value: Cow<'_, String>,
+    ) -> bool {
+        if !accepted_tags.contains_key(key) {
+            accepted_tags.insert(key.to_string(), TagValueSet::new(value_limit, mode));
         }
-        let tag_value_set = self.accepted_tags.get_mut(key).unwrap();
+        let tag_value_set = accepted_tags.get_mut(key).unwrap();
 
         if tag_value_set.contains(value.clone()) {
             // Tag value has already been accepted, nothing more to do.
@@ -202,12 +256,12 @@ impl TagCardinalityLimit {
         }
 
         // Tag value not yet part of the accepted set.
-        if tag_value_set.len() < self.config.value_limit as usize {
+        if tag_value_set.len() < value_limit as usize {
             // accept the new value
             tag_value_set.insert(value);
 
-            if tag_value_set.len() == self.config.value_limit as usize {
-                emit!(TagCardinalityValueLimitReached { key });
+            if tag_value_set.len() == value_limit as usize {
+                emit!(TagCardinalityValueLimitReached { metric_name, key });
             }
 
             true
@@ -217,52 +271,161 @@ impl TagCardinalityLimit {
         }
     }
 
+    fn record_rejection(&mut self, metric_name: &str, key: &str) {
+        if self.config.report_interval_secs.is_some() {
+            *self
+                .rejected
+                .entry((metric_name.to_owned(), key.to_owned()))
+                .or_insert(0) += 1;
+        }
+    }
+
     fn transform_one(&mut self, mut event: Event) -> Option<Event> {
         let metric = event.as_mut_metric();
+        let metric_name = metric.name().to_owned();
+        let (value_limit, per_metric) = match self
+            .per_metric_limits
+            .iter()
+            .find(|(pattern, _)| pattern.matches(&metric_name))
+        {
+            Some((_, value_limit)) => (*value_limit, true),
+            None => (self.config.value_limit, self.config.track_per_metric),
+        };
+        let accepted_tags = if per_metric {
+            if !self.accepted_metric_tags.contains_key(&metric_name) {
+                self.accepted_metric_tags
+                    .insert(metric_name.clone(), HashMap::new());
+            }
+            self.accepted_metric_tags.get_mut(&metric_name).unwrap()
+        } else {
+            &mut self.accepted_tags
+        };
+
+        let mut rejected = Vec::new();
         if let Some(tags_map) = metric.tags() {
-            match self.config.limit_exceeded_action {
-                LimitExceededAction::DropEvent => {
-                    for (key, value) in tags_map {
-                        if !self.try_accept_tag(key, Cow::Borrowed(value)) {
-                            emit!(TagCardinalityLimitRejectingEvent {
-                                tag_key: key,
-                                tag_value: value,
-                            });
-                            return None;
-                        }
-                    }
+            for (key, value) in tags_map {
+                let accepted = Self::try_accept_tag(
+                    accepted_tags,
+                    value_limit,
+                    &self.config.mode,
+                    &metric_name,
+                    key,
+                    Cow::Borrowed(value),
+                );
+                if accepted {
+                    continue;
                 }
-                LimitExceededAction::DropTag => {
-                    let mut to_delete = Vec::new();
-                    for (key, value) in tags_map {
-                        if !self.try_accept_tag(key, Cow::Borrowed(value)) {
-                            emit!(TagCardinalityLimitRejectingTag {
-                                tag_key: key,
-                                tag_value: value,
-                            });
-                            to_delete.push(key.clone());
-                        }
+                rejected.push(key.clone());
+                match self.config.limit_exceeded_action {
+                    LimitExceededAction::DropEvent => {
+                        emit!(TagCardinalityLimitRejectingEvent {
+                            metric_name: &metric_name,
+                            tag_key: key,
+                            tag_value: value,
+                        });
+                        break;
                     }
-                    for key in to_delete {
-                        metric.remove_tag(&key);
+                    LimitExceededAction::DropTag => {
+                        emit!(TagCardinalityLimitRejectingTag {
+                            metric_name: &metric_name,
+                            tag_key: key,
+                            tag_value: value,
+                        });
                     }
                 }
             }
         }
-        Some(event)
+
+        for key in &rejected {
+            self.record_rejection(&metric_name, key);
+        }
+        match self.config.limit_exceeded_action {
+            LimitExceededAction::DropEvent if !rejected.is_empty() => None,
+            LimitExceededAction::DropEvent => Some(event),
+            LimitExceededAction::DropTag => {
+                let metric = event.as_mut_metric();
+                for key in rejected {
+                    metric.remove_tag(&key);
+                }
+                Some(event)
+            }
+        }
+    }
+
+    /// Reports the metrics and tags that had values rejected since the last
+    /// report.
+    fn report_into(&mut self, output: &mut Vec<Event>) {
+        let timestamp = Utc::now();
+        output.extend(
+            self.rejected
+                .drain()
+                .map(|((metric_name, tag_key), count)| {
+                    Metric::new(
+                        "tag_value_limit_exceeded",
+                        MetricKind::Incremental,
+                        MetricValue::Counter {
+                            value: count as f64,
+                        },
+                    )
+                    .with_namespace(Some("vector"))
+                    .with_tags(Some(
+                        vec![
+                            ("metric_name".to_owned(), metric_name),
+                            ("tag_key".to_owned(), tag_key),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ))
+                    .with_timestamp(Some(timestamp))
+                    .into()
+                }),
+        );
     }
 }
 
 impl TaskTransform for TagCardinalityLimit {
     fn transform(
         self: Box<Self>,
-        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+        mut task: Pin<Box<dyn Stream<Item = Event> + Send>>,
     ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
     where
         Self: 'static,
     {
         let mut inner = self;
-        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+        let report_interval = match inner.config.report_interval_secs {
+            Some(secs) => Duration::from_secs(secs),
+            None => return Box::pin(task.filter_map(move |v| ready(inner.transform_one(v)))),
+        };
+        let mut report_stream = tokio::time::interval(report_interval);
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = report_stream.tick() => {
+                      inner.report_into(&mut output);
+                      false
+                    }
+                    maybe_event = task.next() => {
+                      match maybe_event {
+                        None => {
+                          inner.report_into(&mut output);
+                          true
+                        }
+                        Some(event) => {
+                          output.extend(inner.transform_one(event));
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
     }
 }
 
@@ -279,9 +442,13 @@ mod tests {
     }
 
     fn make_metric(tags: BTreeMap<String, String>) -> Event {
+        make_named_metric("event", tags)
+    }
+
+    fn make_named_metric(name: &str, tags: BTreeMap<String, String>) -> Event {
         Event::Metric(
             Metric::new(
-                "event",
+                name,
                 metric::MetricKind::Incremental,
                 metric::MetricValue::Counter { value: 1.0 },
             )
@@ -296,8 +463,12 @@ mod tests {
         TagCardinalityLimit::new(TagCardinalityLimitConfig {
             value_limit,
             limit_exceeded_action,
+            per_metric_limits: IndexMap::new(),
+            track_per_metric: false,
+            report_interval_secs: None,
             mode: Mode::Exact,
         })
+        .unwrap()
     }
 
     fn make_transform_bloom(
@@ -307,10 +478,14 @@ mod tests {
         TagCardinalityLimit::new(TagCardinalityLimitConfig {
             value_limit,
             limit_exceeded_action,
+            per_metric_limits: IndexMap::new(),
+            track_per_metric: false,
+            report_interval_secs: None,
             mode: Mode::Probabilistic(BloomFilterConfig {
                 cache_size_per_key: default_cache_size(),
             }),
         })
+        .unwrap()
     }
 
     #[test]
@@ -442,4 +617,103 @@ mod tests {
         assert_eq!(new_event2, event2);
         assert_eq!(new_event3, event3);
     }
+
+    fn make_transform(config: &str) -> TagCardinalityLimit {
+        TagCardinalityLimit::new(toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn tags(value: &str) -> BTreeMap<String, String> {
+        vec![("tag1".into(), value.into())].into_iter().collect()
+    }
+
+    #[test]
+    fn tag_cardinality_limit_per_metric_limits() {
+        let mut transform = make_transform(
+            r#"
+value_limit = 1
+limit_exceeded_action = "drop_event"
+mode = "exact"
+per_metric_limits."http_*" = 2
+"#,
+        );
+
+        // The default limit applies to other metrics.
+        assert!(transform
+            .transform_one(make_named_metric("other", tags("val1")))
+            .is_some());
+        assert!(transform
+            .transform_one(make_named_metric("other", tags("val2")))
+            .is_none());
+
+        // Matching metrics get their own limit, and are tracked separately
+        // from each other.
+        for name in &["http_requests", "http_responses"] {
+            for value in &["val1", "val2"] {
+                assert!(transform
+                    .transform_one(make_named_metric(name, tags(value)))
+                    .is_some());
+            }
+            assert!(transform
+                .transform_one(make_named_metric(name, tags("val3")))
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn tag_cardinality_limit_track_per_metric() {
+        let mut transform = make_transform(
+            r#"
+value_limit = 1
+limit_exceeded_action = "drop_tag"
+mode = "exact"
+track_per_metric = true
+"#,
+        );
+
+        let event = transform
+            .transform_one(make_named_metric("first", tags("val1")))
+            .unwrap();
+        assert!(event.as_metric().tags().unwrap().contains_key("tag1"));
+        let event = transform
+            .transform_one(make_named_metric("second", tags("val2")))
+            .unwrap();
+        assert!(event.as_metric().tags().unwrap().contains_key("tag1"));
+        let event = transform
+            .transform_one(make_named_metric("first", tags("val2")))
+            .unwrap();
+        assert!(event
+            .as_metric()
+            .tags()
+            .map_or(true, |tags| !tags.contains_key("tag1")));
+    }
+
+    #[tokio::test]
+    async fn tag_cardinality_limit_report() {
+        let transform = make_transform(
+            r#"
+value_limit = 1
+limit_exceeded_action = "drop_tag"
+mode = "exact"
+report_interval_secs = 60
+"#,
+        );
+
+        let inputs = vec![
+            make_named_metric("event", tags("val1")),
+            make_named_metric("event", tags("val2")),
+            make_named_metric("event", tags("val3")),
+        ];
+        let output = Box::new(transform)
+            .transform(Box::pin(futures::stream::iter(inputs)))
+            .collect::<Vec<_>>()
+            .await;
+
+        // The three events and a report when the input ends.
+        assert_eq!(output.len(), 4);
+        let report = output[3].as_metric();
+        assert_eq!(report.name(), "tag_value_limit_exceeded");
+        assert_eq!(report.value(), &MetricValue::Counter { value: 2.0 });
+        assert_eq!(report.tags().unwrap()["metric_name"], "event");
+        assert_eq!(report.tags().unwrap()["tag_key"], "tag1");
+    }
 }
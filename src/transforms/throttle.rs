@@ -0,0 +1,462 @@
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{
+        DataType, ExpandType, GenerateConfig, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::Event,
+    internal_events::{TemplateRenderingFailed, ThrottleEventThrottled},
+    template::Template,
+    transforms::{FunctionTransform, Transform},
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ThrottleConfig {
+    pub threshold: u32,
+    pub window_secs: f64,
+    pub key_field: Option<Template>,
+    pub exclude: Option<AnyCondition>,
+    /// Sends the throttled events to `<name>.dropped` rather than discarding
+    /// them. The events that are let through are then sent to
+    /// `<name>.allowed`.
+    #[serde(default)]
+    pub reroute_dropped: bool,
+}
+
+inventory::submit! {
+    TransformDescription::new::<ThrottleConfig>("throttle")
+}
+
+impl GenerateConfig for ThrottleConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            threshold: 100,
+            window_secs: 1.0,
+            key_field: None,
+            exclude: None::<AnyCondition>,
+            reroute_dropped: false,
+        })
+        .unwrap()
+    }
+}
+
+impl ThrottleConfig {
+    fn limiter(&self) -> crate::Result<Arc<Mutex<Decisions>>> {
+        if self.threshold == 0 {
+            return Err("threshold must be greater than 0".into());
+        }
+        if !self.window_secs.is_finite() || self.window_secs <= 0.0 {
+            return Err("window_secs must be greater than 0".into());
+        }
+
+        Ok(Arc::new(Mutex::new(Decisions {
+            limiter: Limiter::new(self.threshold, Duration::from_secs_f64(self.window_secs)),
+            pending: Default::default(),
+        })))
+    }
+
+    fn build_throttle(
+        &self,
+        context: &TransformContext,
+        decisions: Arc<Mutex<Decisions>>,
+        lane: Option<Lane>,
+    ) -> crate::Result<Transform> {
+        Ok(Transform::function(Throttle {
+            key_field: self.key_field.clone(),
+            exclude: self
+                .exclude
+                .as_ref()
+                .map(|condition| condition.build(&context.enrichment_tables))
+                .transpose()?,
+            decisions,
+            lane,
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "throttle")]
+impl TransformConfig for ThrottleConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        self.build_throttle(context, self.limiter()?, None)
+    }
+
+    fn expand(
+        &mut self,
+    ) -> crate::Result<Option<(IndexMap<String, Box<dyn TransformConfig>>, ExpandType)>> {
+        if !self.reroute_dropped {
+            return Ok(None);
+        }
+
+        // Both lanes receive every event, so they share the decisions made
+        // for them.
+        let decisions = self.limiter()?;
+        let mut map: IndexMap<String, Box<dyn TransformConfig>> = IndexMap::new();
+        for lane in [Lane::Allowed, Lane::Dropped].iter() {
+            map.insert(
+                lane.name().to_string(),
+                Box::new(ThrottleLaneConfig {
+                    config: self.clone(),
+                    lane: *lane,
+                    decisions: Arc::clone(&decisions),
+                }),
+            );
+        }
+
+        Ok(Some((map, ExpandType::Parallel)))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "throttle"
+    }
+}
+
+#[derive(Deserialize, Serialize, Derivative, Clone)]
+#[derivative(Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ThrottleLaneConfig {
+    config: ThrottleConfig,
+    lane: Lane,
+    #[serde(skip, default = "default_decisions")]
+    #[derivative(Debug = "ignore")]
+    decisions: Arc<Mutex<Decisions>>,
+}
+
+fn default_decisions() -> Arc<Mutex<Decisions>> {
+    Arc::new(Mutex::new(Decisions {
+        limiter: Limiter::new(1, Duration::from_secs(1)),
+        pending: Default::default(),
+    }))
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "throttle_lane")]
+impl TransformConfig for ThrottleLaneConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        self.config
+            .build_throttle(context, Arc::clone(&self.decisions), Some(self.lane))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "throttle_lane"
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Lane {
+    Allowed,
+    Dropped,
+}
+
+impl Lane {
+    const fn name(self) -> &'static str {
+        match self {
+            Lane::Allowed => "allowed",
+            Lane::Dropped => "dropped",
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+
+    const fn other(self) -> Self {
+        match self {
+            Lane::Allowed => Lane::Dropped,
+            Lane::Dropped => Lane::Allowed,
+        }
+    }
+}
+
+/// Counts the events for each key in fixed windows, starting from the first
+/// event seen for the key.
+struct Limiter {
+    threshold: u32,
+    window: Duration,
+    windows: HashMap<Option<String>, (Instant, u32)>,
+    last_pruned: Option<Instant>,
+}
+
+impl Limiter {
+    fn new(threshold: u32, window: Duration) -> Self {
+        Self {
+            threshold,
+            window,
+            windows: HashMap::new(),
+            last_pruned: None,
+        }
+    }
+
+    /// Counts the event, returning whether it is within the threshold.
+    fn check(&mut self, key: Option<String>, now: Instant) -> bool {
+        // Forget the keys that haven't been seen for a whole window.
+        let window = self.window;
+        let prune = self
+            .last_pruned
+            .map(|last_pruned| now.saturating_duration_since(last_pruned) >= window)
+            .unwrap_or(true);
+        if prune {
+            self.windows
+                .retain(|_, (start, _)| now.saturating_duration_since(*start) < window);
+            self.last_pruned = Some(now);
+        }
+
+        let (start, count) = self.windows.entry(key).or_insert((now, 0));
+        if now.saturating_duration_since(*start) >= window {
+            *start = now;
+            *count = 0;
+        }
+
+        if *count < self.threshold {
+            *count += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+struct Decisions {
+    limiter: Limiter,
+    // The decisions one lane has made that the other lane hasn't used yet.
+    pending: [VecDeque<bool>; 2],
+}
+
+#[derive(Clone)]
+pub struct Throttle {
+    key_field: Option<Template>,
+    exclude: Option<Box<dyn Condition>>,
+    decisions: Arc<Mutex<Decisions>>,
+    lane: Option<Lane>,
+}
+
+impl Throttle {
+    /// Returns whether the event is within the threshold for its key.
+    fn allowed(&self, event: &Event) -> bool {
+        let mut decisions = self.decisions.lock().unwrap();
+
+        // The other lane has already seen this event.
+        if let Some(lane) = self.lane {
+            if let Some(allowed) = decisions.pending[lane.index()].pop_front() {
+                return allowed;
+            }
+        }
+
+        let key = self.key_field.as_ref().and_then(|template| {
+            template
+                .render_string(event)
+                .map_err(|error| {
+                    emit!(TemplateRenderingFailed {
+                        error,
+                        field: Some("key_field"),
+                        drop_event: false,
+                    })
+                })
+                .ok()
+        });
+
+        let allowed = decisions.limiter.check(key.clone(), Instant::now());
+        if !allowed {
+            emit!(ThrottleEventThrottled {
+                key: key.as_deref(),
+                discarded: self.lane.is_none(),
+            });
+        }
+
+        if let Some(lane) = self.lane {
+            decisions.pending[lane.other().index()].push_back(allowed);
+        }
+
+        allowed
+    }
+}
+
+impl FunctionTransform for Throttle {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        let allowed = match self.exclude.as_ref() {
+            Some(condition) if condition.check(&event) => true,
+            _ => self.allowed(&event),
+        };
+
+        let forward = match self.lane {
+            None | Some(Lane::Allowed) => allowed,
+            Some(Lane::Dropped) => !allowed,
+        };
+        if forward {
+            output.push(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::LogEvent, transforms::test::transform_one};
+    use std::convert::TryFrom;
+
+    fn config(key_field: Option<&str>, exclude: Option<&str>) -> ThrottleConfig {
+        ThrottleConfig {
+            threshold: 2,
+            window_secs: 60.0,
+            key_field: key_field.map(|key_field| Template::try_from(key_field).unwrap()),
+            exclude: exclude.map(|source| AnyCondition::String(source.to_string())),
+            reroute_dropped: false,
+        }
+    }
+
+    fn event(service: &str, message: &str) -> Event {
+        let mut log = LogEvent::default();
+        log.insert("service", service);
+        log.insert("message", message);
+        log.into()
+    }
+
+    fn throttle(
+        config: &ThrottleConfig,
+        lane: Option<Lane>,
+        decisions: &Arc<Mutex<Decisions>>,
+    ) -> Throttle {
+        Throttle {
+            key_field: config.key_field.clone(),
+            exclude: config
+                .exclude
+                .as_ref()
+                .map(|condition| condition.build(&Default::default()).unwrap()),
+            decisions: Arc::clone(decisions),
+            lane,
+        }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ThrottleConfig>();
+    }
+
+    #[test]
+    fn limits_events_per_window() {
+        let mut limiter = Limiter::new(2, Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert!(limiter.check(None, start));
+        assert!(limiter.check(None, start + Duration::from_secs(1)));
+        assert!(!limiter.check(None, start + Duration::from_secs(9)));
+        assert!(limiter.check(None, start + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn forgets_idle_keys() {
+        let mut limiter = Limiter::new(1, Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert!(limiter.check(Some("a".to_string()), start));
+        assert!(limiter.check(Some("b".to_string()), start + Duration::from_secs(5)));
+        assert!(limiter.check(Some("c".to_string()), start + Duration::from_secs(12)));
+        assert_eq!(2, limiter.windows.len());
+    }
+
+    #[test]
+    fn throttles_per_key() {
+        let config = config(Some("{{ service }}"), None);
+        let decisions = config.limiter().unwrap();
+        let mut throttle = throttle(&config, None, &decisions);
+
+        assert!(transform_one(&mut throttle, event("api", "one")).is_some());
+        assert!(transform_one(&mut throttle, event("api", "two")).is_some());
+        assert!(transform_one(&mut throttle, event("api", "three")).is_none());
+        assert!(transform_one(&mut throttle, event("web", "one")).is_some());
+    }
+
+    #[test]
+    fn passes_excluded_events() {
+        let config = config(None, Some(r#".message == "important""#));
+        let decisions = config.limiter().unwrap();
+        let mut throttle = throttle(&config, None, &decisions);
+
+        for _ in 0..2 {
+            assert!(transform_one(&mut throttle, event("api", "noise")).is_some());
+        }
+        assert!(transform_one(&mut throttle, event("api", "noise")).is_none());
+        assert!(transform_one(&mut throttle, event("api", "important")).is_some());
+    }
+
+    #[test]
+    fn reroutes_dropped_events() {
+        let mut config = config(None, Some(r#".message == "important""#));
+        config.reroute_dropped = true;
+        let decisions = config.limiter().unwrap();
+        let mut allowed = throttle(&config, Some(Lane::Allowed), &decisions);
+        let mut dropped = throttle(&config, Some(Lane::Dropped), &decisions);
+
+        let events = vec![
+            event("api", "one"),
+            event("api", "two"),
+            event("api", "three"),
+            event("api", "important"),
+            event("api", "four"),
+        ];
+
+        // The lanes may see the events at different times, but agree on
+        // which were throttled.
+        let mut passed = Vec::new();
+        for event in events.clone() {
+            allowed.transform(&mut passed, event);
+        }
+        let mut rerouted = Vec::new();
+        for event in events.clone() {
+            dropped.transform(&mut rerouted, event);
+        }
+
+        assert_eq!(
+            vec![events[0].clone(), events[1].clone(), events[3].clone()],
+            passed
+        );
+        assert_eq!(vec![events[2].clone(), events[4].clone()], rerouted);
+    }
+
+    #[test]
+    fn expands_into_lanes() {
+        let mut config = config(None, None);
+        assert!(config.expand().unwrap().is_none());
+
+        config.reroute_dropped = true;
+        let (lanes, expand_type) = config.expand().unwrap().unwrap();
+        assert_eq!(
+            vec!["allowed", "dropped"],
+            lanes.keys().map(String::as_str).collect::<Vec<_>>()
+        );
+        assert!(matches!(expand_type, ExpandType::Parallel));
+    }
+
+    #[test]
+    fn rejects_invalid_window() {
+        let mut config = config(None, None);
+        config.window_secs = 0.0;
+
+        assert!(config.limiter().is_err());
+    }
+}
@@ -0,0 +1,624 @@
+//! Runs events through a WebAssembly module.
+//!
+//! The module is run by an interpreter and talks to Vector through the
+//! following ABI, in which events are encoded as `EventWrapper` protobuf
+//! messages (see `lib/vector-core/proto/event.proto`):
+//!
+//! * It exports its linear memory as `memory`.
+//! * It exports `allocate(len: i32) -> i32`, which returns a pointer to `len`
+//!   bytes of its memory. Vector writes each event it receives there.
+//! * It exports `process(ptr: i32, len: i32)`, which is called with the event
+//!   written to the allocated bytes. The bytes belong to the module from then
+//!   on.
+//! * It may export `init()`, which is called once after the module is
+//!   instantiated.
+//! * It may import `vector.emit(ptr: i32, len: i32)` to emit the event
+//!   encoded in the given bytes of its memory. `process` may emit any number
+//!   of events.
+//!
+//! The metadata of events, such as their finalizers, isn't encoded. The
+//! metadata of the event being processed is merged into every event emitted
+//! by `process`, and is dropped along with the event if it emits none.
+//!
+//! Each call into the module is given `fuel`, roughly one unit per
+//! instruction executed, and its memory can't grow past `memory_limit_bytes`.
+//! When a call runs out of fuel or traps, the event being processed is dropped
+//! and the module is instantiated anew, as its state can't be relied upon.
+
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{proto, Event},
+    internal_events::WasmEventProcessingFailed,
+    transforms::{TaskTransform, Transform},
+};
+use futures::{stream, Stream, StreamExt};
+use parity_wasm::elements::{self, MemoryType};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{fs, path::PathBuf, pin::Pin, thread};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use wasmi::{
+    Externals, FuncInstance, FuncRef, HostError, ImportsBuilder, MemoryRef, ModuleImportResolver,
+    ModuleInstance, ModuleRef, RuntimeArgs, RuntimeValue, Signature, Trap, TrapKind, ValueType,
+};
+
+/// The module the functions provided to the WebAssembly module are imported
+/// from.
+const HOST_MODULE: &str = "vector";
+
+const EMIT_FUNC: usize = 0;
+const GAS_FUNC: usize = 1;
+
+const WASM_PAGE_SIZE: u64 = 64 * 1024;
+
+const CHANNEL_CAPACITY: usize = 100;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Could not read module {:?}: {}", path, source))]
+    ReadModule {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Invalid module: {}", source))]
+    InvalidModule { source: elements::Error },
+    #[snafu(display("Could not meter the instructions of the module"))]
+    Metering,
+    #[snafu(display(
+        "The initial memory of the module, {} bytes, exceeds \"memory_limit_bytes\"",
+        bytes
+    ))]
+    InitialMemoryTooLarge { bytes: u64 },
+    #[snafu(display("Could not load module: {}", source))]
+    LoadModule { source: wasmi::Error },
+}
+
+#[derive(Debug, Snafu)]
+pub enum ProcessError {
+    #[snafu(display("Could not instantiate module: {}", source))]
+    Instantiate { source: wasmi::Error },
+    #[snafu(display("Module does not export {:?}", name))]
+    MissingExport { name: &'static str },
+    #[snafu(display("Module returned an invalid pointer from \"allocate\""))]
+    InvalidPointer,
+    #[snafu(display("Module ran out of fuel"))]
+    FuelExhausted,
+    #[snafu(display("Module failed: {}", source))]
+    Call { source: wasmi::Error },
+}
+
+impl ProcessError {
+    pub const fn error_type(&self) -> &'static str {
+        match self {
+            Self::FuelExhausted => "fuel_exhausted",
+            _ => "module_failed",
+        }
+    }
+}
+
+/// Raised from the functions provided to the module to abort it.
+#[derive(Debug, Snafu)]
+enum HostTrap {
+    #[snafu(display("fuel exhausted"))]
+    FuelExhausted,
+    #[snafu(display("memory access out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("invalid event emitted: {}", source))]
+    InvalidEvent { source: prost::DecodeError },
+}
+
+impl HostError for HostTrap {}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WasmConfig {
+    pub module: PathBuf,
+    #[serde(default = "default_fuel")]
+    pub fuel: u64,
+    #[serde(default = "default_memory_limit_bytes")]
+    pub memory_limit_bytes: u64,
+}
+
+const fn default_fuel() -> u64 {
+    10_000_000
+}
+
+const fn default_memory_limit_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
+inventory::submit! {
+    TransformDescription::new::<WasmConfig>("wasm")
+}
+
+impl GenerateConfig for WasmConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            module: PathBuf::from("/path/to/module.wasm"),
+            fuel: default_fuel(),
+            memory_limit_bytes: default_memory_limit_bytes(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "wasm")]
+impl TransformConfig for WasmConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        let wasm = Wasm::new(self)?;
+        // Surface a module that doesn't follow the ABI at build time.
+        Instance::new(&wasm.module, wasm.fuel)?;
+        Ok(Transform::task(wasm))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "wasm"
+    }
+}
+
+pub struct Wasm {
+    module: wasmi::Module,
+    fuel: u64,
+}
+
+impl Wasm {
+    pub fn new(config: &WasmConfig) -> crate::Result<Self> {
+        let bytes = fs::read(&config.module).context(ReadModule {
+            path: &config.module,
+        })?;
+        Self::from_bytes(&bytes, config)
+    }
+
+    fn from_bytes(bytes: &[u8], config: &WasmConfig) -> crate::Result<Self> {
+        let module =
+            parity_wasm::deserialize_buffer::<elements::Module>(bytes).context(InvalidModule)?;
+        let mut module = pwasm_utils::inject_gas_counter(
+            module,
+            &pwasm_utils::rules::Set::default(),
+            HOST_MODULE,
+        )
+        .map_err(|_| BuildError::Metering)?;
+
+        let max_pages = (config.memory_limit_bytes / WASM_PAGE_SIZE) as u32;
+        if let Some(memories) = module.memory_section_mut() {
+            for memory in memories.entries_mut() {
+                let initial = memory.limits().initial();
+                if initial > max_pages {
+                    return Err(BuildError::InitialMemoryTooLarge {
+                        bytes: initial as u64 * WASM_PAGE_SIZE,
+                    }
+                    .into());
+                }
+                let maximum = memory
+                    .limits()
+                    .maximum()
+                    .map_or(max_pages, |maximum| maximum.min(max_pages));
+                *memory = MemoryType::new(initial, Some(maximum));
+            }
+        }
+
+        let module = wasmi::Module::from_parity_wasm_module(module).context(LoadModule)?;
+        Ok(Self {
+            module,
+            fuel: config.fuel,
+        })
+    }
+}
+
+impl TaskTransform for Wasm {
+    fn transform(
+        self: Box<Self>,
+        mut task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let (events_tx, mut events_rx) = mpsc::channel::<Event>(CHANNEL_CAPACITY);
+        let (output_tx, output_rx) = mpsc::channel::<Vec<Event>>(CHANNEL_CAPACITY);
+
+        tokio::spawn(async move {
+            while let Some(event) = task.next().await {
+                if events_tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        // The interpreter can't be moved between threads, so it is given one
+        // of its own.
+        thread::Builder::new()
+            .name("wasm".into())
+            .spawn(move || {
+                let mut instance = None;
+                while let Some(event) = events_rx.blocking_recv() {
+                    let mut output = Vec::new();
+                    if let Err(error) = self.process(&mut instance, event, &mut output) {
+                        emit!(WasmEventProcessingFailed { error: &error });
+                        // Start over with a fresh instance.
+                        instance = None;
+                    }
+                    if output_tx.blocking_send(output).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("Failed to spawn wasm thread");
+
+        Box::pin(ReceiverStream::new(output_rx).flat_map(stream::iter))
+    }
+}
+
+impl Wasm {
+    fn process(
+        &self,
+        instance: &mut Option<Instance>,
+        event: Event,
+        output: &mut Vec<Event>,
+    ) -> Result<(), ProcessError> {
+        if instance.is_none() {
+            *instance = Some(Instance::new(&self.module, self.fuel)?);
+        }
+        instance
+            .as_mut()
+            .expect("instance was just created")
+            .process(event, self.fuel, output)
+    }
+}
+
+struct Instance {
+    module: ModuleRef,
+    host: Host,
+}
+
+impl Instance {
+    fn new(module: &wasmi::Module, fuel: u64) -> Result<Self, ProcessError> {
+        let mut host = Host {
+            memory: None,
+            fuel,
+            emitted: Vec::new(),
+        };
+        let module = ModuleInstance::new(
+            module,
+            &ImportsBuilder::new().with_resolver(HOST_MODULE, &Imports),
+        )
+        .context(Instantiate)?
+        .run_start(&mut host)
+        .map_err(|trap| call_error(wasmi::Error::Trap(trap)))?;
+
+        host.memory = Some(
+            module
+                .export_by_name("memory")
+                .and_then(|export| export.as_memory().cloned())
+                .ok_or(ProcessError::MissingExport { name: "memory" })?,
+        );
+        for name in &["allocate", "process"] {
+            if module
+                .export_by_name(name)
+                .and_then(|export| export.as_func().cloned())
+                .is_none()
+            {
+                return Err(ProcessError::MissingExport { name: *name });
+            }
+        }
+
+        let mut instance = Self { module, host };
+        if instance.module.export_by_name("init").is_some() {
+            instance.call("init", &[], fuel)?;
+        }
+        Ok(instance)
+    }
+
+    fn process(
+        &mut self,
+        event: Event,
+        fuel: u64,
+        output: &mut Vec<Event>,
+    ) -> Result<(), ProcessError> {
+        let metadata = event.metadata().clone();
+        let mut bytes = Vec::new();
+        proto::EventWrapper::from(event)
+            .encode(&mut bytes)
+            .expect("Vec can always grow");
+        let len = RuntimeValue::I32(bytes.len() as i32);
+
+        let ptr = self
+            .call("allocate", &[len], fuel)?
+            .and_then(|ptr| ptr.try_into::<i32>())
+            .ok_or(ProcessError::InvalidPointer)?;
+        self.host
+            .memory()
+            .set(ptr as u32, &bytes)
+            .map_err(|_| ProcessError::InvalidPointer)?;
+
+        self.call("process", &[RuntimeValue::I32(ptr), len], fuel)?;
+        if self.host.emitted.is_empty() {
+            // The module dropped the event.
+            drop(metadata);
+        } else {
+            output.extend(self.host.emitted.drain(..).map(|mut event| {
+                event.metadata_mut().merge(metadata.clone());
+                event
+            }));
+        }
+        Ok(())
+    }
+
+    fn call(
+        &mut self,
+        name: &str,
+        args: &[RuntimeValue],
+        fuel: u64,
+    ) -> Result<Option<RuntimeValue>, ProcessError> {
+        self.host.fuel = fuel;
+        self.host.emitted.clear();
+        self.module
+            .invoke_export(name, args, &mut self.host)
+            .map_err(call_error)
+    }
+}
+
+fn call_error(error: wasmi::Error) -> ProcessError {
+    if let wasmi::Error::Trap(trap) = &error {
+        if let TrapKind::Host(host_error) = trap.kind() {
+            if let Some(HostTrap::FuelExhausted) = host_error.downcast_ref::<HostTrap>() {
+                return ProcessError::FuelExhausted;
+            }
+        }
+    }
+    ProcessError::Call { source: error }
+}
+
+/// The functions provided to the module.
+struct Host {
+    memory: Option<MemoryRef>,
+    fuel: u64,
+    emitted: Vec<Event>,
+}
+
+impl Host {
+    fn memory(&self) -> &MemoryRef {
+        self.memory
+            .as_ref()
+            .expect("memory is set once the module is instantiated")
+    }
+}
+
+impl Externals for Host {
+    fn invoke_index(
+        &mut self,
+        index: usize,
+        args: RuntimeArgs<'_>,
+    ) -> Result<Option<RuntimeValue>, Trap> {
+        match index {
+            EMIT_FUNC => {
+                let ptr = args.nth_checked::<i32>(0)? as u32;
+                let len = args.nth_checked::<i32>(1)? as u32 as usize;
+                let bytes = self
+                    .memory
+                    .as_ref()
+                    .ok_or(HostTrap::OutOfBounds)?
+                    .get(ptr, len)
+                    .map_err(|_| HostTrap::OutOfBounds)?;
+                let event = proto::EventWrapper::decode(&bytes[..]).context(InvalidEvent)?;
+                self.emitted.push(event.into());
+                Ok(None)
+            }
+            GAS_FUNC => {
+                let amount = args.nth_checked::<i32>(0)? as u32 as u64;
+                match self.fuel.checked_sub(amount) {
+                    Some(fuel) => {
+                        self.fuel = fuel;
+                        Ok(None)
+                    }
+                    None => {
+                        self.fuel = 0;
+                        Err(HostTrap::FuelExhausted.into())
+                    }
+                }
+            }
+            _ => unreachable!("unknown host function {}", index),
+        }
+    }
+}
+
+struct Imports;
+
+impl ModuleImportResolver for Imports {
+    fn resolve_func(&self, name: &str, signature: &Signature) -> Result<FuncRef, wasmi::Error> {
+        let (index, expected) = match name {
+            "emit" => (
+                EMIT_FUNC,
+                Signature::new(&[ValueType::I32, ValueType::I32][..], None),
+            ),
+            "gas" => (GAS_FUNC, Signature::new(&[ValueType::I32][..], None)),
+            _ => {
+                return Err(wasmi::Error::Instantiation(format!(
+                    "Unknown import \"{}.{}\"",
+                    HOST_MODULE, name
+                )))
+            }
+        };
+        if signature != &expected {
+            return Err(wasmi::Error::Instantiation(format!(
+                "Import \"{}.{}\" has signature {:?}, expected {:?}",
+                HOST_MODULE, name, signature, expected
+            )));
+        }
+        Ok(FuncInstance::alloc_host(expected, index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{BatchNotifier, BatchStatus, EventStatus, LogEvent, Value};
+    use std::sync::Arc;
+
+    // Emits each event it receives twice.
+    const ECHO: &str = r#"
+        (module
+            (import "vector" "emit" (func $emit (param i32 i32)))
+            (memory (export "memory") 1)
+            (func (export "allocate") (param $len i32) (result i32)
+                i32.const 1024)
+            (func (export "process") (param $ptr i32) (param $len i32)
+                (call $emit (local.get $ptr) (local.get $len))
+                (call $emit (local.get $ptr) (local.get $len))))
+    "#;
+
+    const LOOP: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (func (export "allocate") (param $len i32) (result i32)
+                i32.const 0)
+            (func (export "process") (param $ptr i32) (param $len i32)
+                (loop $forever (br $forever))))
+    "#;
+
+    // Emits the event only if its memory could grow by ten pages.
+    const GROW: &str = r#"
+        (module
+            (import "vector" "emit" (func $emit (param i32 i32)))
+            (memory (export "memory") 1)
+            (func (export "allocate") (param $len i32) (result i32)
+                i32.const 0)
+            (func (export "process") (param $ptr i32) (param $len i32)
+                (if (i32.ne (memory.grow (i32.const 10)) (i32.const -1))
+                    (then (call $emit (local.get $ptr) (local.get $len))))))
+    "#;
+
+    fn config() -> WasmConfig {
+        WasmConfig {
+            module: PathBuf::new(),
+            fuel: default_fuel(),
+            memory_limit_bytes: default_memory_limit_bytes(),
+        }
+    }
+
+    fn build(wat: &str, config: &WasmConfig) -> crate::Result<Box<Wasm>> {
+        let bytes = wat::parse_str(wat).unwrap();
+        let wasm = Wasm::from_bytes(&bytes, config)?;
+        Instance::new(&wasm.module, wasm.fuel)?;
+        Ok(Box::new(wasm))
+    }
+
+    async fn process_all(wasm: Box<Wasm>, events: Vec<Event>) -> Vec<Event> {
+        wasm.transform(Box::pin(stream::iter(events)))
+            .collect()
+            .await
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WasmConfig>();
+    }
+
+    #[tokio::test]
+    async fn wasm_emits_events() {
+        let wasm = build(ECHO, &config()).unwrap();
+
+        let output = process_all(wasm, vec![Event::from("hello"), Event::from("world")]).await;
+
+        assert_eq!(output.len(), 4);
+        assert_eq!(output[0].as_log()["message"], Value::from("hello"));
+        assert_eq!(output[1].as_log()["message"], Value::from("hello"));
+        assert_eq!(output[2].as_log()["message"], Value::from("world"));
+        assert_eq!(output[3].as_log()["message"], Value::from("world"));
+    }
+
+    #[tokio::test]
+    async fn wasm_keeps_metadata() {
+        let wasm = build(ECHO, &config()).unwrap();
+
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let mut event = Event::from(LogEvent::from("hello").with_batch_notifier(&batch));
+        event
+            .metadata_mut()
+            .set_datadog_api_key(Some(Arc::from("key")));
+        drop(batch);
+
+        let output = process_all(wasm, vec![event]).await;
+
+        assert_eq!(output.len(), 2);
+        for event in &output {
+            assert_eq!(event.metadata().datadog_api_key().as_deref(), Some("key"));
+        }
+        // The event is finalized along with all of the events emitted for it.
+        output[0].metadata().update_status(EventStatus::Errored);
+        drop(output);
+        assert_eq!(receiver.await, BatchStatus::Errored);
+    }
+
+    #[test]
+    fn wasm_fuel_limit() {
+        let wasm = build(
+            LOOP,
+            &WasmConfig {
+                fuel: 10_000,
+                ..config()
+            },
+        )
+        .unwrap();
+
+        let mut instance = None;
+        let error = wasm
+            .process(&mut instance, Event::from("hello"), &mut Vec::new())
+            .unwrap_err();
+        assert!(matches!(error, ProcessError::FuelExhausted), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn wasm_memory_limit() {
+        let output = process_all(build(GROW, &config()).unwrap(), vec![Event::from("hello")]).await;
+        assert_eq!(output.len(), 1);
+
+        let limited = WasmConfig {
+            memory_limit_bytes: 2 * WASM_PAGE_SIZE,
+            ..config()
+        };
+        let output = process_all(build(GROW, &limited).unwrap(), vec![Event::from("hello")]).await;
+        assert!(output.is_empty());
+
+        let too_small = WasmConfig {
+            memory_limit_bytes: 0,
+            ..config()
+        };
+        assert!(build(GROW, &too_small).is_err());
+    }
+
+    #[test]
+    fn wasm_unknown_import() {
+        let error = build(
+            r#"
+            (module
+                (import "vector" "exec" (func (param i32)))
+                (memory (export "memory") 1))
+            "#,
+            &config(),
+        )
+        .map(|_| ())
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("vector.exec"), "{}", error);
+    }
+
+    #[test]
+    fn wasm_missing_export() {
+        let error = build(r#"(module (memory (export "memory") 1))"#, &config())
+            .map(|_| ())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("allocate"), "{}", error);
+    }
+}
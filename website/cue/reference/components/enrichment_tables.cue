@@ -0,0 +1,270 @@
+package metadata
+
+// Enrichment tables aren't components of the topology, events don't flow
+// through them, so they are documented as a global configuration option and
+// referenced from the components and functions that use them.
+configuration: configuration: enrichment_tables: {
+	common: false
+	description: """
+		Configures tables that `remap` transforms can look up rows in to enrich events, using the
+		[`get_enrichment_table_record`](\(urls.vrl_functions)#get_enrichment_table_record) function. Each
+		table is named by its key.
+		"""
+	required: false
+	warnings: []
+	type: object: {
+		examples: []
+		options: {
+			"*": {
+				description: "The name of the table, used to refer to it in VRL programs."
+				required:    true
+				warnings: []
+				type: object: options: {
+					type: {
+						description: "The type of the table."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								file:   "Rows are loaded from a CSV file, and kept in memory."
+								geoip:  "Rows are looked up by IP address in a [MaxMind](\(urls.maxmind)) GeoIP database."
+								memory: "Rows are written by an [`enrichment_table` sink](\(urls.vector_sinks)/enrichment_table/), and kept in memory."
+								sqlite: "Rows are queried from a table of a [SQLite](\(urls.sqlite)) database as they are searched for."
+							}
+							syntax: "literal"
+						}
+					}
+
+					reload_interval_secs: {
+						common:      false
+						description: """
+							The interval at which to check the files the table is loaded from for changes. Once they have
+							changed, the table is loaded again in the background, and swapped in for all the `remap`
+							transforms searching it, without reloading the rest of the topology. Tables are only loaded
+							once by default.
+							"""
+						required: false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [60]
+							unit: "seconds"
+						}
+					}
+
+					file: {
+						description:   "The file to load the rows from."
+						relevant_when: "type = \"file\""
+						required:      true
+						warnings: []
+						type: object: options: {
+							path: {
+								description: "The path of the file."
+								required:    true
+								warnings: []
+								type: string: {
+									examples: ["/etc/vector/assets.csv"]
+									syntax: "file_system_path"
+								}
+							}
+							encoding: {
+								description: "The encoding of the file."
+								required:    true
+								warnings: []
+								type: object: options: {
+									type: {
+										description: "The format of the file."
+										required:    true
+										warnings: []
+										type: string: {
+											enum: csv: "Comma separated values."
+											syntax: "literal"
+										}
+									}
+									include_headers: {
+										common:      false
+										description: "Whether the first row holds the names of the columns. Otherwise the columns are named by their index."
+										required:    false
+										warnings: []
+										type: bool: default: true
+									}
+									delimiter: {
+										common:      false
+										description: "The character separating the columns."
+										required:    false
+										warnings: []
+										type: string: {
+											default: ","
+											syntax:  "literal"
+										}
+									}
+								}
+							}
+						}
+					}
+
+					schema: {
+						common:        false
+						description:   "The types to parse the columns as, by column name. Columns are strings by default."
+						relevant_when: "type = \"file\""
+						required:      false
+						warnings: []
+						type: object: options: "*": {
+							description: "The type of the column."
+							required:    true
+							warnings: []
+							type: string: {
+								enum: {
+									string:    "The value as is."
+									date:      "A date, formatted as `%Y-%m-%d`."
+									date_time: "A timestamp, formatted as RFC 3339."
+									integer:   "A signed integer."
+									float:     "A floating point number."
+									boolean:   "`true` or `false`."
+									cidr:      "A network in CIDR notation, such as `10.0.0.0/8`. The column is indexed for `contains_ip` conditions."
+								}
+								syntax: "literal"
+							}
+						}
+					}
+
+					path: {
+						description:   "The path of the MaxMind database, in the MMDB format, or of the SQLite database."
+						relevant_when: "type = \"geoip\" or \"sqlite\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["/etc/vector/GeoLite2-City.mmdb"]
+							syntax: "file_system_path"
+						}
+					}
+
+					locale: {
+						common:        false
+						description:   "The locale of the names of cities, regions, countries and continents returned from City databases."
+						relevant_when: "type = \"geoip\""
+						required:      false
+						warnings: []
+						type: string: {
+							default: "en"
+							examples: ["en", "de", "pt-BR"]
+							syntax: "literal"
+						}
+					}
+
+					table: {
+						description:   "The name of the database table to query."
+						relevant_when: "type = \"sqlite\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["assets"]
+							syntax: "literal"
+						}
+					}
+
+					cache_size: {
+						common:        false
+						description:   "The number of distinct searches whose rows are kept in memory. Set to `0` to disable the cache."
+						relevant_when: "type = \"sqlite\""
+						required:      false
+						warnings: []
+						type: uint: {
+							default: 10000
+							unit:    null
+						}
+					}
+
+					key_field: {
+						description:   "The field of the rows that identifies them. A row written with the same key as an existing row replaces it."
+						relevant_when: "type = \"memory\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["host"]
+							syntax: "literal"
+						}
+					}
+
+					ttl_secs: {
+						common:        false
+						description:   "How long rows are kept after they are written. Set to `0` to keep rows until they are evicted."
+						relevant_when: "type = \"memory\""
+						required:      false
+						warnings: []
+						type: uint: {
+							default: 3600
+							unit:    "seconds"
+						}
+					}
+
+					max_entries: {
+						common:        false
+						description:   "The maximum number of rows kept. Once it is reached, the least recently written row is evicted."
+						relevant_when: "type = \"memory\""
+						required:      false
+						warnings: []
+						type: uint: {
+							default: 100000
+							unit:    null
+						}
+					}
+				}
+			}
+		}
+	}
+}
+
+configuration: how_it_works: enrichment_tables_geoip: {
+	title: "GeoIP enrichment tables"
+	body: """
+		A `geoip` table looks up a single IP address at a time, with a condition on the `ip` field:
+
+		```coffee
+		location = get_enrichment_table_record!("geoip", { "ip": .client_ip }, select: ["city_name", "country_code"])
+		```
+
+		The fields returned depend on the type of the database:
+
+		Database | Fields
+		:--------|:------
+		GeoIP2-City, GeoLite2-City | `city_name`, `continent_code`, `continent_name`, `country_code`, `country_name`, `region_code`, `region_name`, `postal_code`, `metro_code`, `timezone`, `latitude`, `longitude`
+		GeoLite2-ASN, GeoIP2-ISP | `autonomous_system_number`, `autonomous_system_organization`, `isp`, `organization`
+		GeoIP2-Connection-Type | `connection_type`
+
+		Fields missing from the database for the address are `null`, and addresses missing from the
+		database altogether fail the lookup.
+		"""
+}
+
+configuration: how_it_works: enrichment_tables_reloading: {
+	title: "Reloading enrichment tables"
+	body: """
+		Tables with a `reload_interval_secs` compare the modification time and size of their files at every
+		interval. Once a file has changed, the table is loaded again, and the `enrichment_table_reloads_total`
+		and `enrichment_table_rows` internal metrics are updated. Lookups keep using the previous data while the
+		table is loading, and if loading fails, `enrichment_table_reload_errors_total` is incremented and the
+		previous data is kept until the next interval.
+		"""
+}
+
+configuration: how_it_works: enrichment_tables_sqlite: {
+	title: "SQLite enrichment tables"
+	body: """
+		A `sqlite` table doesn't load the database into memory. Every search is turned into a parameterised
+		query of the database table, so the indexes of the database are used for the columns searched, and the
+		rows found are cached by `cache_size`. The database is opened read-only, and every row matching the
+		condition is returned by `find_enrichment_table_records`.
+		"""
+}
+
+configuration: how_it_works: enrichment_tables_memory: {
+	title: "Memory enrichment tables"
+	body: """
+		A `memory` table starts empty, and is filled by the events sent to an
+		[`enrichment_table` sink](\(urls.vector_sinks)/enrichment_table/) naming it. Each log event is written
+		as a row, keyed by its `key_field`, so lookups see the latest event written for each key. Rows expire
+		after `ttl_secs`, and are kept across reloads of the configuration as long as the table is still
+		configured.
+		"""
+}
@@ -80,4 +80,17 @@ components: sinks: datadog_metrics: {
 			summary:      false
 		}
 	}
+
+	how_it_works: {
+		distributions: {
+			title: "Distributions"
+			body:  """
+				Distributions and sketches are sent as distribution points, which list each value observed
+				as many times as it was observed. To keep requests bounded in size, points with more than
+				10,000 observations are downsampled to 10,000 values, keeping the proportion of each value.
+				The quantiles computed by Datadog keep their accuracy, but the counts it reports for these
+				points are scaled down, and values too rare to be represented are left out.
+				"""
+		}
+	}
 }
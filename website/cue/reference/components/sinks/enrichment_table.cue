@@ -0,0 +1,86 @@
+package metadata
+
+components: sinks: enrichment_table: {
+	title: "Enrichment Table"
+
+	description: """
+		Writes log events as rows of a `memory` [enrichment table](\(urls.vector_configuration)#enrichment_tables),
+		so that `remap` transforms can look them up to enrich other events.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: true
+	}
+
+	features: {
+		buffer: enabled:      false
+		healthcheck: enabled: false
+		send: {
+			compression: enabled: false
+			encoding: enabled:    false
+			request: enabled:     false
+			tls: enabled:         false
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: [
+			"""
+				Rows are only kept in memory, so the table is empty again whenever Vector restarts.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: {
+		table: {
+			description: "The name of the `memory` enrichment table to write the events to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["my_table"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		rows: {
+			title: "Rows"
+			body: """
+				Every field of an event becomes a column of its row, and the row replaces any row already written
+				with the same value of the table's `key_field`. Events missing the `key_field` are discarded. Rows
+				are looked up with `get_enrichment_table_record` and `find_enrichment_table_records` as soon as
+				they are written.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		enrichment_table_rows_written_total: components.sources.internal_metrics.output.metrics.enrichment_table_rows_written_total
+		events_discarded_total:              components.sources.internal_metrics.output.metrics.events_discarded_total
+		processed_bytes_total:               components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processing_errors_total:             components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
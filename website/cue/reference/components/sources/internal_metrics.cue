@@ -254,6 +254,39 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		cache_evictions_total: {
+			description:       "The total number of entries evicted from the cache of the `dedupe` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				reason: {
+					description: "The reason the entry was evicted."
+					required:    true
+					enum: {
+						size: "The cache was full."
+						ttl:  "The entry outlived `cache.ttl_secs`."
+					}
+				}
+			}
+		}
+		cache_hits_total: {
+			description:       "The total number of events found in the cache of the `dedupe` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		cache_misses_total: {
+			description:       "The total number of events not found in the cache of the `dedupe` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		checkpoint_read_errors_total: {
+			description:       "The total number of errors reading checkpoints."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
 		checkpoint_write_errors_total: {
 			description:       "The total number of errors writing checkpoints."
 			type:              "counter"
@@ -437,6 +470,38 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		enrichment_table_reload_errors_total: {
+			description:       "The total number of failed reloads of an enrichment table."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				table: _table
+			}
+		}
+		enrichment_table_reloads_total: {
+			description:       "The total number of reloads of an enrichment table."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				table: _table
+			}
+		}
+		enrichment_table_rows_written_total: {
+			description:       "The total number of rows written to a `memory` enrichment table by `enrichment_table` sinks."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				table: _table
+			}
+		}
+		enrichment_table_rows: {
+			description:       "The number of rows of an enrichment table, as of its last reload."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				table: _table
+			}
+		}
 		events_discarded_total: {
 			description:       "The total number of events discarded by this component."
 			type:              "counter"
@@ -492,6 +557,17 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		events_throttled_total: {
+			description:       "The total number of events throttled by the `throttle` transform, by key."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				key: {
+					description: "The rendered `key_field` of the throttled events. Empty if no key is configured."
+					required:    true
+				}
+			}
+		}
 		processed_events_total: {
 			description:       """
 				The total number of events processed by this component.
@@ -685,6 +761,24 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		join_evicted_events_total: {
+			description:       "The total number of unmatched events flushed by the `join` transform before their window ended, to stay within `max_pending` or `max_events_per_key`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		joined_events_total: {
+			description:       "The total number of events joined by the `join` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		late_events_total: {
+			description:       "The total number of events routed to the late output of the `reorder` transform, as they arrived behind the watermark."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		logging_driver_errors_total: {
 			description: """
 				The total number of logging driver errors encountered caused by not using either
@@ -812,6 +906,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		series_expired_total: {
+			description:       "The total number of idle series whose state has been forgotten by the `metric_convert` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		streams_total: {
 			description:       "The total number of streams."
 			type:              "counter"
@@ -894,7 +994,12 @@ components: sources: internal_metrics: {
 				"""
 			type:              "counter"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {
+				tag_key: {
+					description: "The key of the rejected tag."
+					required:    true
+				}
+			}
 		}
 		timestamp_parse_errors_total: {
 			description:       "The total number of errors encountered parsing [RFC 3339](\(urls.rfc_3339)) timestamps."
@@ -937,6 +1042,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		windows_flushed_total: {
+			description:       "The total number of time windows closed and flushed by the `sql` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 
 		// Windows metrics
 		windows_service_does_not_exist_total: {
@@ -1041,13 +1152,19 @@ components: sources: internal_metrics: {
 			description: "The type of the error"
 			required:    true
 			enum: {
+				"failed_key":                  "The key of the event could not be built."
 				"field_missing":               "The event field was missing."
+				"fuel_exhausted":              "The WebAssembly module ran out of fuel."
+				"instruction_limit_exceeded":  "The Lua script exceeded its instruction limit."
 				"invalid_metric":              "The metric was invalid."
 				"mapping_failed":              "The mapping failed."
 				"match_failed":                "The match operation failed."
+				"module_failed":               "The WebAssembly module failed."
+				"memory_limit_exceeded":       "The Lua script exceeded its memory limit."
 				"out_of_order":				   "The event was out of order."
 				"parse_failed":                "The parsing operation failed."
 				"render_error":                "The rendering operation failed."
+				"time_limit_exceeded":         "The Lua script exceeded its execution time limit."
 				"type_conversion_failed":      "The type conversion operating failed."
 				"type_field_does_not_exist":   "The type field does not exist."
 				"type_ip_address_parse_error": "The IP address did not parse."
@@ -1089,5 +1206,10 @@ components: sources: internal_metrics: {
 				"oversized":    "The event was too large."				
 			}
 		}
+		_table: {
+			description: "The name of the enrichment table."
+			required:    true
+			examples: ["assets"]
+		}
 	}
 }
@@ -37,6 +37,25 @@ components: transforms: aggregate: {
 	}
 
 	configuration: {
+		group_by: {
+			common: false
+			description: """
+				The tags to keep. The series that only differ by the other tags are grouped into one. In the `auto`
+				mode, the incremental values of the series of a group are summed, as are the latest values of each
+				of its absolute series. In the other modes, every value of every series of the group is a sample,
+				except for absolute counters in the `sum` mode, which are summed as in the `auto` mode.
+				All the tags are kept by default.
+				"""
+			required: false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["region", "service"]
+					syntax: "literal"
+				}
+			}
+		}
 		interval_ms: {
 			common: true
 			description: """
@@ -50,6 +69,63 @@ components: transforms: aggregate: {
 				unit:    "milliseconds"
 			}
 		}
+		mode: {
+			common: true
+			description: """
+				How the values of each series received during an interval are aggregated. All the modes other
+				than `auto` only apply to counters and gauges, and treat each value received as a sample. Other
+				metrics are aggregated as in the `auto` mode, except that distributions are converted to sketches
+				in the `sketch` mode.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: "auto"
+				enum: {
+					auto:      "Sums incremental metrics, and keeps the latest value of absolute metrics."
+					count:     "The number of samples, as an incremental counter."
+					sum:       "The sum of the samples, as a metric of the same type and kind as the samples. Absolute counters are running totals, so the latest value of each of their series is summed instead."
+					min:       "The smallest sample, as a gauge."
+					max:       "The largest sample, as a gauge."
+					mean:      "The mean of the samples, as a gauge."
+					stdev:     "The population standard deviation of the samples, as a gauge."
+					latest:    "The last sample, as a gauge."
+					quantiles: "The `quantiles` of the samples, as an aggregated summary."
+					sketch:    "The samples, as an incremental sketch, which can be merged with the sketches of other intervals or hosts."
+				}
+				syntax: "literal"
+			}
+		}
+		modes: {
+			common:      false
+			description: "Overrides the `mode` by metric name."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"http_request_duration_seconds": "quantiles"}]
+				options: {
+					"*": {
+						description: "The mode aggregating the metrics of this name."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["max", "quantiles"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		quantiles: {
+			common:      false
+			description: "The quantiles calculated in the `quantiles` mode, between 0 and 1."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.5, 0.75, 0.9, 0.95, 0.99]
+				items: type: float: examples: [0.5, 0.99]
+			}
+		}
 	}
 
 	input: {
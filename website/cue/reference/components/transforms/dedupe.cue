@@ -52,12 +52,24 @@ components: transforms: dedupe: {
 							unit:    null
 						}
 					}
+					ttl_secs: {
+						common:      false
+						description: "How long an Event is remembered after it is first seen. Seeing a duplicate doesn't extend it. When not set, Events are remembered until they are evicted by newer ones."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [600]
+							unit: "seconds"
+						}
+					}
 				}
 			}
 		}
 		fields: {
-			description: "Options controlling what fields to match against."
-			required:    true
+			common:      true
+			description: "Options controlling what fields to match against. Incompatible with the `key` option."
+			required:    false
 			warnings: []
 			type: object: {
 				options: {
@@ -90,6 +102,35 @@ components: transforms: dedupe: {
 				}
 			}
 		}
+		key: {
+			common:      false
+			description: "Builds the key Events are compared by, instead of the fields in `fields`. Set it to `\"hash\"` to compare Events by a hash of all of their fields. Incompatible with the `fields` option."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{source: ".request_id"}]
+				options: {
+					source: {
+						common:      true
+						description: "A [Vector Remap Language](\(urls.vrl_reference)) (VRL) expression whose value is the key. Changes the expression makes to the Event are discarded. Events for which the expression fails are passed through."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: [".request_id", "join!([.host, .message])"]
+							syntax: "remap_program"
+						}
+					}
+				}
+			}
+		}
+		persist_state: {
+			common:      false
+			description: "Checkpoints the cache to the [`data_dir`](\(urls.vector_configuration_global)/#data_dir) when the transform's input ends, so that events seen before a restart are still recognized as duplicates afterwards."
+			required:    false
+			warnings: ["The checkpoint is only written on a graceful shutdown or reload. The cache is lost if Vector crashes or is killed."]
+			type: bool: default: false
+		}
 	}
 
 	input: {
@@ -102,6 +143,9 @@ components: transforms: dedupe: {
 			title: "Cache Behavior"
 			body: """
 				This transform is backed by an LRU cache of size `cache.num_events`.
+				If `cache.ttl_secs` is set, entries are also evicted once they
+				are older than it, so that whether a duplicate is caught doesn't
+				depend on the volume of Events in between.
 				That means that this transform will cache information in memory for
 				the last `cache.num_events` Events that it has processed. Entries
 				will be removed from the cache in the order they were inserted. If
@@ -112,6 +156,20 @@ components: transforms: dedupe: {
 				"""
 		}
 
+		keys: {
+			title: "Keys"
+			body: """
+				By default, Events are compared by the fields in `fields`. Setting
+				`key` compares them by something else instead:
+
+				* `key.source` compares them by the value of a VRL expression,
+				  such as an ID already present in the Events.
+				* `key = "hash"` compares them by a hash of all of their fields,
+				  which only keeps the hash in the cache rather than copies of the
+				  values.
+				"""
+		}
+
 		memory_usage_details: {
 			title: "Memory Usage Details"
 			body: """
@@ -157,9 +215,34 @@ components: transforms: dedupe: {
 				b:5}" will be considered different to the event "{b:5}".
 				"""
 		}
+
+		state: {
+			title: "State"
+			body: """
+				When Vector reloads its configuration and this transform's own
+				options are unchanged, the new instance of the transform takes
+				over the cache of the running one. With `persist_state` enabled,
+				the cache is also checkpointed to the
+				[`data_dir`](\(urls.vector_configuration_global)/#data_dir) when
+				the transform's input ends, and restored once by the next instance
+				of the transform with the same options, for example after Vector
+				restarts. The transform's input only ends on a graceful shutdown
+				or reload, so nothing is checkpointed if Vector crashes or is
+				killed, and the events seen before aren't recognized as duplicates
+				afterwards. The cache holds no events, so it doesn't delay their
+				acknowledgement.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
-		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+		cache_evictions_total:         components.sources.internal_metrics.output.metrics.cache_evictions_total
+		cache_hits_total:              components.sources.internal_metrics.output.metrics.cache_hits_total
+		cache_misses_total:            components.sources.internal_metrics.output.metrics.cache_misses_total
+		checkpoint_read_errors_total:  components.sources.internal_metrics.output.metrics.checkpoint_read_errors_total
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		checkpoints_total:             components.sources.internal_metrics.output.metrics.checkpoints_total
+		events_discarded_total:        components.sources.internal_metrics.output.metrics.events_discarded_total
+		processing_errors_total:       components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
 }
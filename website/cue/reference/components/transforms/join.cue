@@ -0,0 +1,215 @@
+package metadata
+
+components: transforms: join: {
+	title: "Join"
+
+	description: """
+		Joins log events from several sides that share the values of a set of fields, and arrive within a
+		time window of each other, into a single log event.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		emit_unmatched: {
+			common:      false
+			description: "Whether the events that aren't joined are sent on unchanged once their window ends, rather than discarded."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		flush_period_ms: {
+			common:      false
+			description: "How often to check for keys whose window has ended."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		join_by: {
+			description: "The fields whose values must be equal for events to be joined."
+			required:    true
+			warnings: []
+			type: array: items: type: string: {
+				examples: ["request_id"]
+				syntax: "field_path"
+			}
+		}
+		max_events_per_key: {
+			common: false
+			description: """
+				The maximum number of events kept for each side of a key. The first events of a side are kept, and
+				the events beyond them are flushed unmatched right away.
+				"""
+			required: false
+			warnings: []
+			type: uint: {
+				default: 100
+				unit:    null
+			}
+		}
+		max_pending: {
+			common:      false
+			description: "The maximum number of keys waiting for their other sides. Once reached, the oldest key is flushed unmatched."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		merge_strategies: {
+			common: false
+			description: """
+				A map of field names to the strategies merging the values of the field found on more than one side.
+				The value from the earliest side is kept for the other fields. The strategies are the same as the
+				ones of the [`reduce` transform](\(urls.vector_transforms)/reduce/#merge_strategies).
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: [{message: "array"}]
+				options: {
+					"*": {
+						description: "The merge strategy to use for the field."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["array", "concat", "retain", "sum"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		sides: {
+			description: """
+				The sides of the join, by name, in the order their fields are merged. Each side is identified by a
+				condition, and an event belongs to the first side whose condition it matches. Events that match
+				no side, or miss a `join_by` field, are passed through unchanged.
+				"""
+			required: true
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						request:  #".type == "request""#
+						response: #".type == "response""#
+					},
+				]
+				options: {
+					"*": {
+						description: "The condition identifying the events of the side."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: [#".type == "request""#]
+							syntax: "remap_boolean_expression"
+						}
+					}
+				}
+			}
+		}
+		window_ms: {
+			common:      false
+			description: "How long after the first event of a key its other sides must arrive to be joined."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 30000
+				unit:    "milliseconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Join requests and responses"
+			configuration: {
+				join_by: ["request_id"]
+				sides: {
+					request:  #".type == "request""#
+					response: #".type == "response""#
+				}
+				merge_strategies: message: "array"
+			}
+			input: [
+				{
+					log: {
+						type:       "response"
+						request_id: "1"
+						message:    "200 OK"
+					}
+				},
+				{
+					log: {
+						type:       "request"
+						request_id: "1"
+						message:    "GET /"
+					}
+				},
+			]
+			output: [
+				{
+					log: {
+						type:       "request"
+						request_id: "1"
+						message: ["GET /", "200 OK"]
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		windows: {
+			title: "Windows"
+			body: """
+				A key is pending from its first event until every side has an event, at which point all the
+				events of the key are merged, in the order of the sides. Keys that are still missing a side once
+				`window_ms` has passed are flushed unmatched. The events of a side beyond `max_events_per_key`, and
+				the events of the oldest key beyond `max_pending`, are flushed unmatched early, to bound the memory
+				used by the transform.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total:     components.sources.internal_metrics.output.metrics.events_discarded_total
+		join_evicted_events_total:  components.sources.internal_metrics.output.metrics.join_evicted_events_total
+		joined_events_total:        components.sources.internal_metrics.output.metrics.joined_events_total
+		stale_events_flushed_total: components.sources.internal_metrics.output.metrics.stale_events_flushed_total
+	}
+}
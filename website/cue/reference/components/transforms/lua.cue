@@ -115,9 +115,78 @@ components: transforms: lua: {
 				}
 			}
 		}
+		sandbox: {
+			common:        false
+			description:   "Limits the resources scripts may use, and the parts of the Lua standard library available to them."
+			relevant_when: "version = \"2\""
+			required:      false
+			warnings: []
+			type: object: {
+				options: {
+					max_execution_time_ms: {
+						common:      false
+						description: "The time a single hook invocation may run for."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [100]
+							unit: "milliseconds"
+						}
+					}
+					max_instructions: {
+						common:      false
+						description: "The number of Lua instructions a single hook invocation may execute."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [1000000]
+							unit: null
+						}
+					}
+					memory_limit_bytes: {
+						common:      false
+						description: "The memory the Lua runtime may allocate in total, across all hook invocations."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [67108864]
+							unit: "bytes"
+						}
+					}
+					stdlib: {
+						common:      false
+						description: "The parts of the Lua standard library available to scripts."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "restricted"
+							enum: {
+								restricted: "Leaves out everything that reaches outside of the Lua runtime: the `os`, `io` and `package` libraries, `require`, `dofile` and `loadfile`. Precompiled chunks can't be loaded either."
+								full:       "The full standard library, except for the `debug` library."
+							}
+							syntax: "literal"
+						}
+					}
+					violation_field: {
+						common:      false
+						description: "When set, an event whose processing exceeds a limit is emitted as it was received, with this field (or tag, for metrics) set to the exceeded limit, instead of being dropped. This allows routing the offending events elsewhere."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["lua_violation"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
 		search_dirs: {
 			common:      false
-			description: "A list of directories to search when loading a Lua file via the `require` function. If not specified, the modules are looked up in the directories of Vector's configs."
+			description: "A list of directories to search when loading a Lua file via the `require` function. If not specified, the modules are looked up in the directories of Vector's configs. Requires `sandbox.stdlib` to be `full`."
 			required:    false
 			warnings: []
 			type: array: {
@@ -474,6 +543,25 @@ components: transforms: lua: {
 				[the manual](\(urls.lua_manual)) would suffice.
 				"""
 		}
+		sandbox: {
+			title: "Sandbox"
+			body:  """
+				By default, scripts run with a restricted standard library that
+				leaves out the `os`, `io` and `package` libraries, as well as
+				`require`, `dofile` and `loadfile`, and that refuses to load
+				precompiled chunks. Set `sandbox.stdlib` to `full` for scripts
+				that need them.
+
+				The `sandbox` options can also bound the memory used by the Lua
+				runtime and the instructions and time used by each hook
+				invocation. Instruction and time limits are checked every 1000
+				instructions. When a hook exceeds a limit, it is aborted, a
+				`processing_errors_total` error is counted with the exceeded limit
+				as its `error_type`, and the event being processed is dropped, or
+				emitted unchanged with `sandbox.violation_field` set. Events that
+				the hook emitted before it was aborted are discarded.
+				"""
+		}
 		search_dirs: {
 			title: "Search Directories"
 			body:  """
@@ -481,6 +569,8 @@ components: transforms: lua: {
 				absolute paths that will be searched when using the
 				[Lua `require` function](\(urls.lua_require)). If this option is not
 				set, the directories of the configuration files will be used instead.
+				As `require` is only available with the full standard library,
+				`sandbox.stdlib` must be set to `full` to load modules.
 				"""
 		}
 	}
@@ -0,0 +1,152 @@
+package metadata
+
+components: transforms: metric_convert: {
+	title: "Metric Convert"
+
+	description: """
+		Converts absolute metrics to incremental ones and back, or counters to their per-second rate, by
+		tracking the previous value of each series.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		expire_after_secs: {
+			common:      false
+			description: "The time after which the state of a series that hasn't been seen is forgotten, so that it starts over the next time it is seen."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+		}
+		mode: {
+			description: "The conversion applied to the metrics."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					incremental: "Converts absolute metrics to the increment since the previous value of their series. Incremental metrics are passed through."
+					absolute:    "Converts incremental metrics to the sum of the increments of their series. Absolute metrics are passed through, and later increments add up from their value."
+					rate:        "Converts counters to absolute gauges of their per-second rate of increase, using the timestamps of the metrics. Other metrics are passed through."
+				}
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Convert a counter to its rate"
+			configuration: {
+				mode: "rate"
+			}
+			input: [
+				{
+					metric: {
+						kind:      "absolute"
+						name:      "requests_total"
+						timestamp: "2021-10-01T00:00:00Z"
+						counter: {
+							value: 100.0
+						}
+					}
+				},
+				{
+					metric: {
+						kind:      "absolute"
+						name:      "requests_total"
+						timestamp: "2021-10-01T00:00:10Z"
+						counter: {
+							value: 150.0
+						}
+					}
+				},
+			]
+			output: [
+				{
+					metric: {
+						kind:      "absolute"
+						name:      "requests_total"
+						timestamp: "2021-10-01T00:00:10Z"
+						gauge: {
+							value: 5.0
+						}
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		first_values: {
+			title: "First Values"
+			body: """
+				In the `incremental` and `rate` modes, the first value of a series only
+				serves as the reference for the next one, and isn't emitted. The same
+				applies after the state of a series expired.
+				"""
+		}
+
+		resets: {
+			title: "Counter Resets"
+			body: """
+				In the `incremental` and `rate` modes, an absolute counter, or an
+				aggregated histogram, whose value decreased is taken to have been reset,
+				and its new value counts in full.
+				"""
+		}
+
+		unconvertible_metrics: {
+			title: "Unconvertible Metrics"
+			body: """
+				In the `incremental` mode, aggregated summaries and sketches, which can't
+				be subtracted from each other, are passed through unchanged. So is a
+				metric whose type differs from the previous value of its series, which
+				becomes the reference for the next one.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		series_expired_total: components.sources.internal_metrics.output.metrics.series_expired_total
+	}
+}
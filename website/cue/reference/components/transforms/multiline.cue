@@ -0,0 +1,153 @@
+package metadata
+
+components: transforms: multiline: {
+	title: "Multiline"
+
+	description: """
+		Aggregates consecutive log events into one, such as the lines of a stack trace, with the same
+		options as the `multiline` option of the [`file` source](\(urls.vector_file_source)). Unlike it,
+		this works on events from any source.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		condition_pattern: {
+			description: "Condition regex pattern to look for. Exact behavior is configured via `mode`."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["^[\\s]+", "\\\\$", "^(INFO|ERROR) ", ";$"]
+				syntax: "regex"
+			}
+		}
+		field: {
+			common:      false
+			description: "The field holding the lines to aggregate. Events without it, or whose value of it isn't a string, are passed through unchanged."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "message"
+				examples: ["message", "log"]
+				syntax: "literal"
+			}
+		}
+		mode: {
+			description: "Mode of operation, specifies how the `condition_pattern` is interpreted."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					continue_through: "All consecutive lines matching this pattern are included in the group. The first line (the line that matched the start pattern) does not need to match the `ContinueThrough` pattern. This is useful in cases such as a Java stack trace, where some indicator in the line (such as leading whitespace) indicates that it is an extension of the preceding line."
+					continue_past:    "All consecutive lines matching this pattern, plus one additional line, are included in the group. This is useful in cases where a log message ends with a continuation marker, such as a backslash, indicating that the following line is part of the same message."
+					halt_before:      "All consecutive lines not matching this pattern are included in the group. This is useful where a log line contains a marker indicating that it begins a new message."
+					halt_with:        "All consecutive lines, up to and including the first line matching this pattern, are included in the group. This is useful where a log line ends with a termination marker, such as a semicolon."
+				}
+				syntax: "literal"
+			}
+		}
+		start_pattern: {
+			description: "Start regex pattern to look for as a beginning of the message."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["^[^\\s]", "\\\\$", "^(INFO|ERROR) ", "[^;]$"]
+				syntax: "regex"
+			}
+		}
+		stream_keys: {
+			common:      true
+			description: "The fields identifying the stream each line comes from, such as a host and a file name. Lines are only aggregated with lines of the same stream. By default, all the events belong to a single stream."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["host", "file"]
+					syntax: "literal"
+				}
+			}
+		}
+		timeout_ms: {
+			description: "The maximum time to wait for the continuation. Once this timeout is reached, the buffered message is guaranteed to be flushed, even if incomplete."
+			required:    true
+			warnings: []
+			type: uint: {
+				examples: [1_000, 600_000]
+				unit: "milliseconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Java stack traces"
+			configuration: {
+				start_pattern:     "^[^\\s]"
+				condition_pattern: "^[\\s]+"
+				mode:              "continue_through"
+				timeout_ms:        1000
+				stream_keys: ["host"]
+			}
+			input: [
+				{log: {host: "web-1", message: "Exception in thread \"main\" java.lang.NullPointerException"}},
+				{log: {host: "web-1", message: "    at com.example.Foo.bar(Foo.java:10)"}},
+				{log: {host: "web-1", message: "    at com.example.Foo.main(Foo.java:5)"}},
+			]
+			output: [
+				{log: {host: "web-1", message: "Exception in thread \"main\" java.lang.NullPointerException\n    at com.example.Foo.bar(Foo.java:10)\n    at com.example.Foo.main(Foo.java:5)"}},
+			]
+		},
+	]
+
+	how_it_works: {
+		aggregated_events: {
+			title: "Aggregated Events"
+			body: """
+				An aggregated event is the first event of the group, with the lines of
+				all of them joined with newlines in `field`. The other fields of the
+				later events are discarded, but the event is only acknowledged once all
+				the events it was aggregated from can be.
+				"""
+		}
+
+		flushing: {
+			title: "Flushing"
+			body: """
+				A group is emitted once a line ends it, or once `timeout_ms` has passed
+				without it being continued. All the groups are emitted when Vector
+				shuts down.
+				"""
+		}
+	}
+}
@@ -137,6 +137,16 @@ components: transforms: reduce: {
 				}
 			}
 		}
+		persist_state: {
+			common:      false
+			description: "Checkpoints the unflushed reductions to the [`data_dir`](\(urls.vector_configuration_global)/#data_dir) when the transform's input ends, instead of flushing them, so that they survive restarts."
+			required:    false
+			warnings: [
+				"The checkpoint is only written on a graceful shutdown or reload. The unflushed reductions are lost if Vector crashes or is killed.",
+				"Checkpointed events are acknowledged to their sources before the restored reduction is flushed.",
+			]
+			type: bool: default: false
+		}
 		starts_when: {
 			common: false
 			description: """
@@ -271,7 +281,38 @@ components: transforms: reduce: {
 		},
 	]
 
+	how_it_works: {
+		state: {
+			title: "State"
+			body: """
+				When Vector reloads its configuration and this transform's own
+				options are unchanged, the new instance of the transform takes
+				over the unflushed reductions of the running one. Otherwise, the
+				reductions are flushed when the transform's input ends.
+
+				With `persist_state` enabled, the unflushed reductions are
+				instead checkpointed to the [`data_dir`](\(urls.vector_configuration_global)/#data_dir)
+				and restored once by the next instance of the transform with the
+				same options, for example after Vector restarts. The checkpoint is
+				ignored if the transform's options changed in the meantime.
+				Checkpointed events are acknowledged to their sources as soon as
+				they are written, so with end-to-end acknowledgements enabled they
+				are no longer redelivered if Vector stops before the restored
+				reduction is flushed.
+
+				The transform's input only ends on a graceful shutdown or reload,
+				so nothing is checkpointed if Vector crashes or is killed, and the
+				unflushed reductions are lost. Their events haven't been
+				acknowledged then, so with end-to-end acknowledgements enabled,
+				sources that support it deliver them again.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
-		stale_events_flushed_total: components.sources.internal_metrics.output.metrics.stale_events_flushed_total
+		checkpoint_read_errors_total:  components.sources.internal_metrics.output.metrics.checkpoint_read_errors_total
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		checkpoints_total:             components.sources.internal_metrics.output.metrics.checkpoints_total
+		stale_events_flushed_total:    components.sources.internal_metrics.output.metrics.stale_events_flushed_total
 	}
 }
@@ -0,0 +1,220 @@
+package metadata
+
+components: transforms: relabel: {
+	title: "Relabel"
+
+	description: """
+		Rewrites the names and tags of metrics, or drops metrics, with rules in the syntax of
+		[Prometheus' `relabel_configs`](\(urls.prometheus_relabel_config)), so that existing
+		rules can be reused unchanged.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		relabel_configs: {
+			description: "The rules, applied in order. `metric_relabel_configs` is accepted as an alias."
+			required:    true
+			warnings: []
+			type: array: items: type: object: {
+				examples: []
+				options: {
+					action: {
+						common:      true
+						description: "The action the rule performs."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "replace"
+							enum: {
+								replace:   "Sets `target_label` to `replacement` if `regex` matches the joined `source_labels`. Both can refer to the capture groups of `regex`."
+								keep:      "Drops the metric unless `regex` matches the joined `source_labels`."
+								drop:      "Drops the metric if `regex` matches the joined `source_labels`."
+								hashmod:   "Sets `target_label` to the hash of the joined `source_labels`, modulo `modulus`."
+								labelmap:  "Copies the tags whose name matches `regex` to tags named after `replacement`."
+								labeldrop: "Removes the tags whose name matches `regex`."
+								labelkeep: "Removes the tags whose name doesn't match `regex`."
+							}
+							syntax: "literal"
+						}
+					}
+					modulus: {
+						common:        false
+						description:   "The modulus of the hash of the joined `source_labels`."
+						relevant_when: "action = \"hashmod\""
+						required:      false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [8]
+							unit: null
+						}
+					}
+					regex: {
+						common:      true
+						description: "The regular expression matched against the joined `source_labels`, or against the tag names for the `labelmap`, `labeldrop` and `labelkeep` actions. It is anchored at both ends."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "(.*)"
+							examples: ["go_.*", "kubernetes_(.+)"]
+							syntax: "regex"
+						}
+					}
+					replacement: {
+						common:      false
+						description: "The value `target_label` is set to for the `replace` action, or the name of the copied tags for the `labelmap` action."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "$1"
+							examples: ["$1", "${1}_total"]
+							syntax: "literal"
+						}
+					}
+					separator: {
+						common:      false
+						description: "The separator the values of `source_labels` are joined with."
+						required:    false
+						warnings: []
+						type: string: {
+							default: ";"
+							syntax:  "literal"
+						}
+					}
+					source_labels: {
+						common:      true
+						description: "The tags whose values are joined and matched against `regex`. The name of the metric is available as `__name__`."
+						required:    false
+						warnings: []
+						type: array: {
+							default: []
+							items: type: string: {
+								examples: ["__name__", "job"]
+								syntax: "literal"
+							}
+						}
+					}
+					target_label: {
+						common:        true
+						description:   "The tag set by the `replace` and `hashmod` actions. Setting `__name__` renames the metric."
+						relevant_when: "action = \"replace\" or action = \"hashmod\""
+						required:      false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["instance", "__name__"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Drop Go runtime metrics and rename a tag"
+			configuration: {
+				relabel_configs: [
+					{source_labels: ["__name__"], regex: "go_.*", action: "drop"},
+					{source_labels: ["pod"], target_label: "kubernetes_pod"},
+					{regex: "pod", action: "labeldrop"},
+				]
+			}
+			input: [
+				{
+					metric: {
+						kind: "incremental"
+						name: "go_gc_duration_seconds_count"
+						tags: {
+							pod: "api-0"
+						}
+						counter: {
+							value: 1.0
+						}
+					}
+				},
+				{
+					metric: {
+						kind: "incremental"
+						name: "http_requests_total"
+						tags: {
+							pod: "api-0"
+						}
+						counter: {
+							value: 1.0
+						}
+					}
+				},
+			]
+			output: [
+				{
+					metric: {
+						kind: "incremental"
+						name: "http_requests_total"
+						tags: {
+							kubernetes_pod: "api-0"
+						}
+						counter: {
+							value: 1.0
+						}
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		metric_names: {
+			title: "Metric Names"
+			body: """
+				As in Prometheus, the name of a metric is exposed to the rules as the
+				`__name__` tag, prefixed with its namespace if it has one. Setting
+				`__name__` renames the metric, dropping its namespace. Metrics whose
+				`__name__` is removed are dropped, as a metric can't do without a name.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
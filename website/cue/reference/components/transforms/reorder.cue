@@ -0,0 +1,167 @@
+package metadata
+
+components: transforms: reorder: {
+	title: "Reorder"
+
+	description: """
+		Buffers events for a bounded lateness, and outputs them sorted by their timestamps, for
+		destinations that reject out of order writes. Events that arrive too late to be sorted are
+		routed to a separate output.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		expire_after_ms: {
+			common:      false
+			description: "How far behind the latest timestamp of all groups the latest timestamp of a group may fall before the group is forgotten. The next event of a forgotten group is never late."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 300_000
+				unit:    "milliseconds"
+			}
+		}
+		flush_period_ms: {
+			common:      false
+			description: "How often the groups are checked for having received no events for `max_lateness_ms`, in which case their events are flushed. Must be greater than 0."
+			required:    false
+			warnings: ["Events of a flushed group that arrive afterwards are no longer sorted against the flushed events."]
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		group_by: {
+			common:      true
+			description: "The fields of logs, or tags of metrics, to group events by. Events are only sorted against events of the same group, each of which has its own watermark."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["partition", "host"]
+					syntax: "literal"
+				}
+			}
+		}
+		max_buffered_events: {
+			common:      false
+			description: "The most events buffered across all groups. Once reached, all of them are flushed. Must be greater than 0."
+			required:    false
+			warnings: ["Events that arrive after a flush are no longer sorted against the flushed events."]
+			type: uint: {
+				default: 10_000
+				unit:    "events"
+			}
+		}
+		max_lateness_ms: {
+			common:      true
+			description: "How far behind the latest timestamp of its group an event may be before it is considered late."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 5000
+				unit:    "milliseconds"
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Sort events by partition"
+			configuration: {
+				max_lateness_ms: 5000
+				group_by: ["partition"]
+			}
+			input: [
+				{log: {partition: "0", message: "second", timestamp: "2021-10-01T00:00:02Z"}},
+				{log: {partition: "0", message: "first", timestamp: "2021-10-01T00:00:01Z"}},
+			]
+			output: [
+				{log: {partition: "0", message: "first", timestamp: "2021-10-01T00:00:01Z"}},
+				{log: {partition: "0", message: "second", timestamp: "2021-10-01T00:00:02Z"}},
+			]
+		},
+	]
+
+	how_it_works: {
+		outputs: {
+			title: "Outputs"
+			body: """
+				The events that arrive in time are output sorted by
+				`<transform_name>.sorted`, and those that arrive late are output as they
+				arrive by `<transform_name>.late`. Events are sorted by the
+				`timestamp` field of logs, or the timestamp of metrics. Events without
+				one are output by `<transform_name>.sorted` right away.
+				"""
+		}
+
+		watermarks: {
+			title: "Watermarks"
+			body: """
+				The watermark of a group trails the latest timestamp of the group by
+				`max_lateness_ms`. Events are buffered until the watermark of their group
+				passes them, and events already behind it when they arrive are late.
+				Since the watermark only advances when the group receives events, the
+				events of a group that received none for `max_lateness_ms` of wall clock
+				time are flushed.
+				"""
+		}
+
+		forced_flushes: {
+			title: "Forced Flushes"
+			body: """
+				The events flushed because their group went quiet, or because
+				`max_buffered_events` was reached, are output before the watermark
+				passes them, and the ordering guarantee no longer holds for them.
+				Flushing doesn't advance the watermark, as the late output tracks
+				watermarks from event timestamps alone. Events of their group that
+				arrive afterwards within `max_lateness_ms` are therefore not late,
+				but are output after the flushed events even if they are older.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		late_events_total:          components.sources.internal_metrics.output.metrics.late_events_total
+		stale_events_flushed_total: components.sources.internal_metrics.output.metrics.stale_events_flushed_total
+	}
+}
@@ -35,6 +35,31 @@ components: transforms: sample: {
 	}
 
 	configuration: {
+		dynamic: {
+			common: false
+			description: """
+				Adapts the rate of each value of `key_field` to its volume, so that the events kept stay within a
+				budget. Keys that are rare enough to fit in their share of the budget are always kept, while hot
+				keys are sampled harder, up to `rate`.
+				"""
+			required: false
+			warnings: []
+			type: object: options: {
+				target_events_per_sec: {
+					description: "The number of events per second to keep, across all keys."
+					required:    true
+					warnings: []
+					type: float: examples: [100.0]
+				}
+				window_secs: {
+					common:      false
+					description: "How long the volume of each key is measured for before its rate is adjusted."
+					required:    false
+					warnings: []
+					type: float: default: 30.0
+				}
+			}
+		}
 		key_field: {
 			common: false
 			description: """
@@ -70,7 +95,8 @@ components: transforms: sample: {
 		rate: {
 			description: """
 				The rate at which events will be forwarded, expressed as 1/N. For example,
-				`rate = 10` means 1 out of every 10 events will be forwarded and the rest will be dropped.
+				`rate = 10` means 1 out of every 10 events will be forwarded and the rest will be dropped. With
+				`dynamic` sampling, this is the highest rate of any key.
 				"""
 			required: true
 			warnings: []
@@ -86,6 +112,17 @@ components: transforms: sample: {
 		metrics: null
 	}
 
+	how_it_works: {
+		sample_rate: {
+			title: "Sample rate"
+			body: """
+				The rate each event was sampled at is written to its `sample_rate` field, so that counts can be
+				weighted by it downstream. With `dynamic` sampling, each key has its own rate, which changes
+				from one window to the next.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
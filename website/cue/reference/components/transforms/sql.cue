@@ -0,0 +1,162 @@
+package metadata
+
+components: transforms: sql: {
+	title: "SQL"
+
+	description: """
+		Aggregates log events over tumbling or hopping time windows with a SQL `SELECT` statement, and
+		emits the result rows as log or metric events when each window closes.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		aggregate: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		output: {
+			common:      false
+			description: "The type of the events the result rows are emitted as."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "log"
+				enum: {
+					log:    "Each result row is a log event with a field per column."
+					metric: "Each aggregated column of a result row is a metric named after the column, and tagged with the other columns of the row."
+				}
+				syntax: "literal"
+			}
+		}
+		query: {
+			description: "A `SELECT` statement aggregating the events, with `GROUP BY` or aggregate functions. The events are the rows of whatever table it selects from, and their fields are its columns. The query is validated when the configuration is loaded."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: [
+					"SELECT host, COUNT(*) AS errors FROM events WHERE status >= 500 GROUP BY host HAVING COUNT(*) > 10",
+					"SELECT window_start, request.method, AVG(duration_ms) AS duration_ms FROM events GROUP BY request.method",
+				]
+				syntax: "literal"
+			}
+		}
+		window: {
+			description: "The time windows the events are aggregated over, based on the time they are processed at."
+			required:    true
+			warnings: []
+			type: object: {
+				examples: [{type: "tumbling", size_secs: 60}, {type: "hopping", size_secs: 300, hop_secs: 60}]
+				options: {
+					hop_secs: {
+						description:   "The time between the starts of consecutive windows. The size of the windows must be a multiple of it."
+						relevant_when: "type = \"hopping\""
+						required:      true
+						warnings: []
+						type: uint: {
+							examples: [60]
+							unit: "seconds"
+						}
+					}
+					size_secs: {
+						description: "The size of the windows."
+						required:    true
+						warnings: []
+						type: uint: {
+							examples: [60, 300]
+							unit: "seconds"
+						}
+					}
+					type: {
+						description: "The type of the windows."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								tumbling: "Consecutive windows that don't overlap."
+								hopping:  "Windows that start every `hop_secs`, and so overlap if that is less than their size."
+							}
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Count errors by host"
+			configuration: {
+				query: "SELECT host, COUNT(*) AS errors FROM events WHERE status >= 500 GROUP BY host"
+				window: {type: "tumbling", size_secs: 60}
+			}
+			input: [
+				{log: {host: "web-1", status: 500}},
+				{log: {host: "web-1", status: 503}},
+				{log: {host: "web-2", status: 200}},
+			]
+			output: [
+				{log: {host: "web-1", errors: 2, timestamp: "2021-10-01T00:01:00Z"}},
+			]
+		},
+	]
+
+	how_it_works: {
+		metric_kinds: {
+			title: "Metric Kinds"
+			body: """
+				With `output` set to `metric`, `COUNT` and `SUM` columns are emitted as
+				incremental counters for tumbling windows. Hopping windows overlap and
+				count the same events more than once, so their counts and sums are
+				emitted as absolute gauges, as are all other aggregated columns.
+				"""
+		}
+
+		supported_sql: {
+			title: "Supported SQL"
+			body: """
+				The query is a single `SELECT` statement from a single table, with
+				optional `WHERE`, `GROUP BY` and `HAVING` clauses. The aggregate
+				functions `COUNT`, `SUM`, `AVG`, `MIN` and `MAX` are supported, along
+				with arithmetic, comparison and logical operators and `IS NULL`. As in
+				SQL, null values, including missing fields, are ignored by aggregate
+				functions.
+
+				The `window_start` and `window_end` columns hold the bounds of the
+				window a result row is for. Log events get a `timestamp` field set to
+				the end of the window unless the query selects one.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		windows_flushed_total: components.sources.internal_metrics.output.metrics.windows_flushed_total
+	}
+}
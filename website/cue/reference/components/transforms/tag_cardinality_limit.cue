@@ -74,6 +74,44 @@ components: transforms: tag_cardinality_limit: {
 				syntax: "literal"
 			}
 		}
+		per_metric_limits: {
+			common:      false
+			description: "Value limits for metrics whose name matches a glob pattern, used instead of `value_limit`. The first matching pattern applies, and the tags of matching metrics are always tracked per metric name."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"http_request_duration_seconds": 5000, "kafka_*": 1000}]
+				options: {
+					"*": {
+						description: "The value limit for the metrics whose name matches the pattern."
+						required:    true
+						warnings: []
+						type: uint: {
+							examples: [5000]
+							unit: null
+						}
+					}
+				}
+			}
+		}
+		report_interval_secs: {
+			common:      false
+			description: "How often to emit a `vector.tag_value_limit_exceeded` counter, tagged with `metric_name` and `tag_key`, for each metric and tag whose values were rejected since the last report. The counters are emitted along with the transform's output. No reports are emitted when unset."
+			required:    false
+			warnings: ["Each limited metric adds a series to the output."]
+			type: uint: {
+				default: null
+				examples: [60]
+				unit: "seconds"
+			}
+		}
+		track_per_metric: {
+			common:      false
+			description: "Tracks the values of each tag separately for every metric name, rather than across all metrics."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		value_limit: {
 			common:      true
 			description: "How many distinct values to accept for any given key."
@@ -218,6 +256,31 @@ components: transforms: tag_cardinality_limit: {
 				"""
 		}
 
+		per_metric_limits: {
+			title: "Per-metric Limits"
+			body: """
+				By default, the values of a tag are counted across all metrics, so
+				a single metric with a legitimately high-cardinality tag forces the
+				limit to be raised for every metric. Setting `track_per_metric`
+				counts the values of each tag separately for every metric name, and
+				`per_metric_limits` overrides `value_limit` for the metrics whose
+				name matches a pattern. Tracking values per metric name multiplies
+				the memory used by the number of metric names.
+				"""
+		}
+
+		reports: {
+			title: "Reports"
+			body: """
+				The `tag_value_limit_exceeded_total` internal metric counts the
+				rejected tags by tag key. To also see which metrics are being
+				limited, set `report_interval_secs`: the transform then outputs a
+				`vector.tag_value_limit_exceeded` counter per metric name and tag
+				key that had values rejected since the last report, which can be
+				observed with `vector tap` or routed to a sink.
+				"""
+		}
+
 		restarts: {
 			title: "Restarts"
 			body: """
@@ -0,0 +1,158 @@
+package metadata
+
+components: transforms: throttle: {
+	title: "Throttle"
+
+	description: """
+		Rate limits events, allowing up to a threshold of events per time window for each key, and discarding or
+		rerouting the rest.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		exclude: {
+			common: true
+			description: """
+				The set of logical conditions to exclude events from throttling. Excluded events are always let
+				through, and aren't counted against the threshold.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: [
+					#".status_code != 200 && !includes(["info", "debug"], .severity)"#,
+				]
+				syntax: "remap_boolean_expression"
+			}
+		}
+		key_field: {
+			common: false
+			description: """
+				The template rendered to get the key of an event. Each key has its own threshold. Events are
+				throttled under a single key if unspecified, or if the template fails to render.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ message }}", "{{ hostname }}"]
+				syntax: "template"
+			}
+		}
+		reroute_dropped: {
+			common: false
+			description: """
+				Sends the throttled events to `<transform_name>.dropped`, rather than discarding them. The events
+				that are let through are then sent to `<transform_name>.allowed`.
+				"""
+			required: false
+			warnings: []
+			type: bool: default: false
+		}
+		threshold: {
+			description: "The number of events allowed for each key in each window."
+			required:    true
+			warnings: []
+			type: uint: {
+				examples: [100, 10000]
+				unit: null
+			}
+		}
+		window_secs: {
+			description: "The length of the time window, starting from the first event of a key."
+			required:    true
+			warnings: []
+			type: float: {
+				examples: [1.0, 60.0]
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Rate limit by service"
+			configuration: {
+				threshold:   1
+				window_secs: 60.0
+				key_field:   "{{ service }}"
+			}
+
+			input: [
+				{
+					log: {
+						service: "api"
+						message: "first"
+					}
+				},
+				{
+					log: {
+						service: "api"
+						message: "second"
+					}
+				},
+			]
+			output: [
+				{
+					log: {
+						service: "api"
+						message: "first"
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		windows: {
+			title: "Windows"
+			body: """
+				The window of a key starts with its first event, and the count of events of the key is reset once
+				the window has passed. Keys that haven't been seen for a whole window are forgotten.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+		events_throttled_total: components.sources.internal_metrics.output.metrics.events_throttled_total
+	}
+}
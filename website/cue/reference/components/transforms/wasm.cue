@@ -0,0 +1,146 @@
+package metadata
+
+components: transforms: wasm: {
+	title: "WebAssembly"
+
+	description: """
+		Process events with a compiled [WebAssembly](\(urls.wasm)) module, run by an embedded
+		interpreter with limits on the instructions it executes and the memory it uses.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		program: {
+			runtime: {
+				name:    "WebAssembly"
+				url:     urls.wasm
+				version: "1.0"
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: [
+			"""
+			Modules are interpreted rather than compiled to native code, so this transform is considerably
+			slower than the [`remap` transform](\(urls.vector_remap_transform)). We recommend that you use the
+			`remap` transform whenever possible.
+			""",
+		]
+		notices: []
+	}
+
+	configuration: {
+		fuel: {
+			common:      false
+			description: "The fuel given to each call into the module, roughly one unit per instruction executed. A call that runs out of fuel is aborted."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10000000
+				unit:    null
+			}
+		}
+		memory_limit_bytes: {
+			common:      false
+			description: "The size the memory of the module may grow to. The transform fails to build if the module's initial memory is larger."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 67108864
+				unit:    "bytes"
+			}
+		}
+		module: {
+			description: "The path to the compiled WebAssembly module, in the binary format."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["/etc/vector/modules/parser.wasm"]
+				syntax: "file_system_path"
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		abi: {
+			title: "Module Interface"
+			body:  """
+				Events are passed to and from the module encoded as `EventWrapper`
+				[Protocol Buffers](\(urls.protobuf)) messages, as defined in
+				[`event.proto`](\(urls.event_proto)). The module must:
+
+				* Export its linear memory as `memory`.
+				* Export `allocate(len: i32) -> i32`, which returns a pointer to `len`
+				  bytes of its memory. Vector writes each event it receives there.
+				* Export `process(ptr: i32, len: i32)`, which is called with the event
+				  written to the allocated bytes. The bytes belong to the module from
+				  then on.
+
+				The module may also:
+
+				* Export `init()`, which is called once after the module is instantiated.
+				* Import `vector.emit(ptr: i32, len: i32)` to emit the event encoded in
+				  the given bytes of its memory. `process` may emit any number of events,
+				  including none to drop the event.
+
+				No other imports are available, so modules can't reach the file system or
+				the network.
+				"""
+		}
+
+		limits: {
+			title: "Limits"
+			body:  """
+				Each call into the module is given `fuel`, and its memory can't grow past
+				`memory_limit_bytes`. When a call runs out of fuel or traps, the event being
+				processed is dropped, a `processing_errors_total` error is counted, and the
+				module is instantiated anew, as its state can't be relied upon.
+				"""
+		}
+
+		metadata: {
+			title: "Event Metadata"
+			body:  """
+				The metadata of events, such as the acknowledgements they are tracked by,
+				isn't passed to the module. Instead, the metadata of the event being
+				processed is attached to every event the module emits for it, so that the
+				event is only acknowledged once all of them are. If the module emits
+				nothing, the event is acknowledged as soon as `process` returns.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
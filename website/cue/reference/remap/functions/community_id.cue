@@ -0,0 +1,77 @@
+package metadata
+
+remap: functions: community_id: {
+	category: "IP"
+	description: """
+		Generates a [Community ID](\(urls.community_id)) (version 1) for a network flow. Flows observed by
+		different tools, or in either direction, produce the same ID, making it possible to correlate them.
+
+		For ICMP and ICMPv6 flows, pass the message type as the `source_port` and the message code as the
+		`destination_port`.
+		"""
+
+	arguments: [
+		{
+			name:        "source_ip"
+			description: "The source IP address."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "destination_ip"
+			description: "The destination IP address."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "protocol"
+			description: "The IANA protocol number, such as `6` for TCP or `17` for UDP."
+			required:    true
+			type: ["integer"]
+		},
+		{
+			name:        "source_port"
+			description: "The source port. Required for TCP, UDP, SCTP, ICMP and ICMPv6 flows, ignored otherwise."
+			required:    false
+			type: ["integer", "null"]
+		},
+		{
+			name:        "destination_port"
+			description: "The destination port. Required for TCP, UDP, SCTP, ICMP and ICMPv6 flows, ignored otherwise."
+			required:    false
+			type: ["integer", "null"]
+		},
+		{
+			name:        "seed"
+			description: "The seed used when hashing the flow."
+			required:    false
+			default:     0
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`source_ip` or `destination_ip` isn't a valid IP address",
+		"`source_ip` and `destination_ip` aren't of the same IP version",
+		"`protocol` isn't between 0 and 255",
+		"`source_port`, `destination_port` or `seed` isn't between 0 and 65535",
+		"`source_port` and `destination_port` aren't set for a protocol that requires them",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "TCP flow"
+			source: #"""
+				community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", source_port: 1122, destination_port: 3344, protocol: 6)
+				"""#
+			return: "1:wCb3OG7yAFWelaUydu0D+125CLM="
+		},
+		{
+			title: "ICMP echo request"
+			source: #"""
+				community_id!(source_ip: "192.168.0.89", destination_ip: "192.168.0.1", source_port: 8, destination_port: 0, protocol: 1)
+				"""#
+			return: "1:X0snYXpgwiv9TZtqg64sgzUn6Dk="
+		},
+	]
+}
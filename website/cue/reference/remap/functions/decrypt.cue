@@ -0,0 +1,81 @@
+package metadata
+
+remap: functions: decrypt: {
+	category:    "Codec"
+	description: """
+		Decrypts a string with a symmetric encryption [algorithm](\(urls.aes)).
+
+		Supported algorithms:
+
+		* AES-128-GCM (key = 16 bytes, iv = 12 bytes)
+		* AES-256-GCM (key = 32 bytes, iv = 12 bytes)
+		* AES-128-CBC-PKCS7 (key = 16 bytes, iv = 16 bytes)
+		* AES-192-CBC-PKCS7 (key = 24 bytes, iv = 16 bytes)
+		* AES-256-CBC-PKCS7 (key = 32 bytes, iv = 16 bytes)
+
+		The `key` and `iv` must be the same ones that were used to encrypt the value, see
+		[`encrypt`](#encrypt).
+		"""
+
+	arguments: [
+		{
+			name:        "ciphertext"
+			description: "The string in raw bytes (not encoded) to decrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The algorithm to use."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: """
+				The key in raw bytes (not encoded) for decryption. The length must match the algorithm requested.
+				Exactly one of `key` and `key_file` must be given. A key written in the program is logged as a
+				warning, as it ends up in the configuration in plain text.
+				"""
+			required:    false
+			type: ["string"]
+		},
+		{
+			name:        "iv"
+			description: "The IV in raw bytes (not encoded) for decryption. The length must match the algorithm requested."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key_file"
+			description: """
+				The path of a file holding the key in raw bytes (not encoded) for decryption. The file is read once,
+				when the program is compiled, and its whole content is used as the key, except for a single
+				trailing newline (`\\n` or `\\r\\n`), which is stripped. A key whose last byte is a newline must
+				therefore be followed by one more.
+				"""
+			required:    false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`algorithm` isn't a supported algorithm",
+		"`key` length doesn't match the key size required for the algorithm specified",
+		"`iv` length doesn't match the iv size required for the algorithm specified",
+		"`ciphertext` can't be decrypted with the given `key` and `iv`, or has been tampered with",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decrypt value"
+			source: #"""
+				ciphertext = decode_base64!("GBw8Mu00v0Kc38+/PvsVtGgWuUJ+ZNLgF8Opy8ohIYE=")
+				iv = "1234567890123456" # typically you would call random_bytes(16)
+				decrypt!(ciphertext, "AES-128-CBC-PKCS7", key_file: "/etc/vector/aes-128.key", iv: iv) # the file holds "16_byte_keyxxxxx"
+				"""#
+			return:    "super secret message"
+			skip_test: true
+		},
+	]
+}
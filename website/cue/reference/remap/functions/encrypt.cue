@@ -0,0 +1,86 @@
+package metadata
+
+remap: functions: encrypt: {
+	category:    "Codec"
+	description: """
+		Encrypts a string with a symmetric encryption [algorithm](\(urls.aes)).
+
+		Supported algorithms:
+
+		* AES-128-GCM (key = 16 bytes, iv = 12 bytes)
+		* AES-256-GCM (key = 32 bytes, iv = 12 bytes)
+		* AES-128-CBC-PKCS7 (key = 16 bytes, iv = 16 bytes)
+		* AES-192-CBC-PKCS7 (key = 24 bytes, iv = 16 bytes)
+		* AES-256-CBC-PKCS7 (key = 32 bytes, iv = 16 bytes)
+
+		The `iv` must never be reused with the same `key`. Generate a fresh one for every value with
+		[`random_bytes`](#random_bytes) and store it alongside the ciphertext, it's required to decrypt
+		the value. The key should be read from a file with `key_file`, or sourced from the environment with
+		[`get_env_var`](#get_env_var), rather than written in the program.
+		"""
+
+	arguments: [
+		{
+			name:        "plaintext"
+			description: "The string to encrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The algorithm to use."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: """
+				The key in raw bytes (not encoded) for encryption. The length must match the algorithm requested.
+				Exactly one of `key` and `key_file` must be given. A key written in the program is logged as a
+				warning, as it ends up in the configuration in plain text.
+				"""
+			required:    false
+			type: ["string"]
+		},
+		{
+			name:        "iv"
+			description: #"""
+				The IV in raw bytes (not encoded) for encryption. The length must match the algorithm requested.
+				A new IV should be generated for every message. You can use `random_bytes` to generate a cryptographically secure random value.
+				"""#
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key_file"
+			description: """
+				The path of a file holding the key in raw bytes (not encoded) for encryption. The file is read once,
+				when the program is compiled, and its whole content is used as the key, except for a single
+				trailing newline (`\\n` or `\\r\\n`), which is stripped. A key whose last byte is a newline must
+				therefore be followed by one more.
+				"""
+			required:    false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`algorithm` isn't a supported algorithm",
+		"`key` length doesn't match the key size required for the algorithm specified",
+		"`iv` length doesn't match the iv size required for the algorithm specified",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encrypt value"
+			source: #"""
+				plaintext = "super secret message"
+				iv = "1234567890123456" # typically you would call random_bytes(16)
+				encrypted_message = encrypt!(plaintext, "AES-128-CBC-PKCS7", key_file: "/etc/vector/aes-128.key", iv: iv) # the file holds "16_byte_keyxxxxx"
+				encode_base64(encrypted_message)
+				"""#
+			return:    "GBw8Mu00v0Kc38+/PvsVtGgWuUJ+ZNLgF8Opy8ohIYE="
+			skip_test: true
+		},
+	]
+}
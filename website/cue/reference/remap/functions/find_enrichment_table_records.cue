@@ -0,0 +1,63 @@
+package metadata
+
+remap: functions: find_enrichment_table_records: {
+	category:    "Enrichment"
+	description: """
+		Searches an [enrichment table](\(urls.vector_configuration)#enrichment_tables) for all the rows
+		matching the `condition`. Each field of the condition must equal the column of the same name.
+
+		The columns searched for equal values are indexed when the program is compiled, so lookups don't
+		scan the whole table.
+
+		Fields of the `condition` can also be objects, to search with other conditions:
+
+		Condition | Matches rows where
+		:---------|:------------------
+		`{ "from": <timestamp>, "to": <timestamp> }` | The timestamp in the column is between `from` and `to`, inclusive.
+		`{ "to_field": "<column>", "value": <number or timestamp> }` | The `value` is between the value in the column and the value in the `to_field` column, inclusive.
+		`{ "contains_ip": <string> }` | The network in the column, in CIDR notation, contains the IP address. Not supported by `sqlite` tables.
+		`{ "prefix_of": <string> }` | The value in the column is a prefix of the string, ignoring case.
+		"""
+
+	arguments: [
+		{
+			name:        "table"
+			description: "The name of the enrichment table to search."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "condition"
+			description: "The values the columns of the rows must equal, or the conditions they must match, by column name."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "select"
+			description: "The columns to return. All the columns of the rows are returned by default."
+			required:    false
+			type: ["array"]
+		},
+	]
+	internal_failure_reasons: [
+		"The `condition` isn't supported by the table",
+		"The IP address of a `contains_ip` condition isn't valid",
+	]
+	return: {
+		types: ["array"]
+		rules: [
+			"An empty array is returned if no row matches the `condition`.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Look up rows"
+			source: #"""
+				find_enrichment_table_records!("assets", { "team": .team }, select: ["host"])
+				"""#
+			return:    [{"host": "web-1"}, {"host": "web-2"}]
+			skip_test: true
+		},
+	]
+}
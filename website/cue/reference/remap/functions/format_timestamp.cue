@@ -19,8 +19,20 @@ remap: functions: format_timestamp: {
 			required:    true
 			type: ["string"]
 		},
+		{
+			name:        "timezone"
+			description: """
+				The [timezone](\(urls.tz_time_zones)) to format the timestamp in, such as `America/New_York`, or
+				`local` for the timezone of the machine Vector runs on. Timestamps are formatted in UTC by default.
+				"""
+			required: false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`format` isn't a valid format string",
+		"`timezone` isn't a valid timezone",
 	]
-	internal_failure_reasons: []
 	return: types: ["string"]
 
 	examples: [
@@ -38,5 +50,12 @@ remap: functions: format_timestamp: {
 				"""#
 			return: "21-Oct-2020 16:00"
 		},
+		{
+			title: "Format a timestamp in a timezone"
+			source: #"""
+				format_timestamp!(t'2020-10-21T16:00:00Z', format: "%v %R %:z", timezone: "America/New_York")
+				"""#
+			return: "21-Oct-2020 12:00 -04:00"
+		},
 	]
 }
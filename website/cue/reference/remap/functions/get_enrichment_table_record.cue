@@ -0,0 +1,71 @@
+package metadata
+
+remap: functions: get_enrichment_table_record: {
+	category:    "Enrichment"
+	description: """
+		Searches an [enrichment table](\(urls.vector_configuration)#enrichment_tables) for the single row
+		matching the `condition`. Each field of the condition must equal the column of the same name.
+
+		The columns searched for equal values are indexed when the program is compiled, so lookups don't
+		scan the whole table.
+
+		Fields of the `condition` can also be objects, to search with other conditions:
+
+		Condition | Matches rows where
+		:---------|:------------------
+		`{ "from": <timestamp>, "to": <timestamp> }` | The timestamp in the column is between `from` and `to`, inclusive.
+		`{ "to_field": "<column>", "value": <number or timestamp> }` | The `value` is between the value in the column and the value in the `to_field` column, inclusive.
+		`{ "contains_ip": <string> }` | The network in the column, in CIDR notation, contains the IP address. Not supported by `sqlite` tables.
+		`{ "prefix_of": <string> }` | The value in the column is a prefix of the string, ignoring case.
+
+		When the IP address of a `contains_ip` condition is in overlapping networks, such as `10.0.0.0/8` and
+		`10.1.0.0/16`, the row with the most specific network, the one with the longest prefix, is returned.
+		"""
+
+	arguments: [
+		{
+			name:        "table"
+			description: "The name of the enrichment table to search."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "condition"
+			description: "The values the columns of the row must equal, or the conditions they must match, by column name."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "select"
+			description: "The columns to return. All the columns of the row are returned by default."
+			required:    false
+			type: ["array"]
+		},
+	]
+	internal_failure_reasons: [
+		"No row matches the `condition`",
+		"More than one row matches the `condition`",
+		"The `condition` isn't supported by the table",
+		"The IP address of a `contains_ip` condition isn't valid",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Look up a row"
+			source: #"""
+				get_enrichment_table_record!("assets", { "host": .host }, select: ["owner", "team"])
+				"""#
+			return:    {"owner": "jane", "team": "platform"}
+			skip_test: true
+		},
+		{
+			title: "Look up the network of an IP address"
+			source: #"""
+				get_enrichment_table_record!("networks", { "cidr": { "contains_ip": .client_ip } }, select: ["site"])
+				"""#
+			return:    {"site": "eu-west"}
+			skip_test: true
+		},
+	]
+}
@@ -0,0 +1,73 @@
+package metadata
+
+remap: functions: hmac: {
+	category:    "Hash"
+	description: """
+		Calculates a [HMAC](\(urls.hmac)) of the `value` using the given `key`. The hashing `algorithm` used can
+		be optionally specified.
+
+		To avoid embedding secrets in your configuration, read the key from a file with `key_file`, or source
+		it from the environment, for example using [`get_env_var`](#get_env_var). A key written in the program
+		is logged as a warning.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the HMAC for."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The string to use as the cryptographic key. Exactly one of `key` and `key_file` must be given."
+			required:    false
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The hashing algorithm to use."
+			enum: {
+				"SHA1":    "SHA1 algorithm"
+				"SHA-224": "SHA-224 algorithm"
+				"SHA-256": "SHA-256 algorithm"
+				"SHA-384": "SHA-384 algorithm"
+				"SHA-512": "SHA-512 algorithm"
+			}
+			required: false
+			default:  "SHA-256"
+			type: ["string"]
+		},
+		{
+			name:        "key_file"
+			description: """
+				The path of a file holding the cryptographic key. The file is read once, when the program is
+				compiled, and its whole content is used as the key, except for a single trailing newline
+				(`\\n` or `\\r\\n`), which is stripped.
+				"""
+			required:    false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Calculate message HMAC (defaults: SHA-256)"
+			source: #"""
+				hmac("john.doe@example.com", key_file: "/etc/vector/hmac.key") # the file holds "my_secret_key"
+				"""#
+			return:    "219b6bb37e964bb8feb1519160c717db497906fbfa598e750af8ba89f9cfa11f"
+			skip_test: true
+		},
+		{
+			title: "Calculate message HMAC using SHA1"
+			source: #"""
+				hmac("foo", key_file: "/etc/vector/hmac.key", algorithm: "SHA1")
+				"""#
+			return:    "155ce81b88766ccd779d515af9ab6081586f077f"
+			skip_test: true
+		},
+	]
+}
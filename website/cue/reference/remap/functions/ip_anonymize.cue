@@ -0,0 +1,87 @@
+package metadata
+
+remap: functions: ip_anonymize: {
+	category: "IP"
+	description: """
+		Anonymizes the `value` IP address.
+
+		In `truncate` mode, every bit after the configured prefix length is set to zero.
+
+		In `prefix_preserving` mode, the address is anonymized with [Crypto-PAn](\(urls.crypto_pan)): addresses
+		sharing a prefix are anonymized to addresses that share a prefix of the same length, keeping the
+		network structure intact while hiding the real addresses. The same `key` always produces the same
+		result, so it should be kept secret, for example by sourcing it with
+		[`get_env_var`](#get_env_var).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address to anonymize."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "mode"
+			description: "The anonymization mode."
+			required:    false
+			enum: {
+				truncate:          "Zero the host bits of the address."
+				prefix_preserving: "Anonymize the address with Crypto-PAn, preserving shared prefixes."
+			}
+			default: "truncate"
+			type: ["string"]
+		},
+		{
+			name:        "ipv4_prefix"
+			description: "The number of leading bits of IPv4 addresses to keep in `truncate` mode."
+			required:    false
+			default:     24
+			type: ["integer"]
+		},
+		{
+			name:        "ipv6_prefix"
+			description: "The number of leading bits of IPv6 addresses to keep in `truncate` mode."
+			required:    false
+			default:     48
+			type: ["integer"]
+		},
+		{
+			name:        "key"
+			description: "The 32 byte key used in `prefix_preserving` mode. Required in that mode."
+			required:    false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+		"`ipv4_prefix` isn't between 0 and 32",
+		"`ipv6_prefix` isn't between 0 and 128",
+		"`key` isn't 32 bytes long",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Truncate an IPv4 address"
+			source: #"""
+				ip_anonymize!("192.168.10.23")
+				"""#
+			return: "192.168.10.0"
+		},
+		{
+			title: "Truncate an IPv6 address"
+			source: #"""
+				ip_anonymize!("2001:db8:85a3:8d3:1319:8a2e:370:7348", ipv6_prefix: 32)
+				"""#
+			return: "2001:db8::"
+		},
+		{
+			title: "Prefix-preserving anonymization"
+			source: #"""
+				ip_anonymize!("192.168.10.23", mode: "prefix_preserving", key: "0123456789abcdef0123456789abcdef")
+				"""#
+			return: "177.88.10.28"
+		},
+	]
+}
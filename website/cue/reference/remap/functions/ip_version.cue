@@ -0,0 +1,38 @@
+package metadata
+
+remap: functions: ip_version: {
+	category: "IP"
+	description: """
+		Returns the version of the `value` IP address, either `IPv4` or `IPv6`.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "IPv4 address"
+			source: #"""
+				ip_version!("192.168.0.1")
+				"""#
+			return: "IPv4"
+		},
+		{
+			title: "IPv6 address"
+			source: #"""
+				ip_version!("2001:db8::1")
+				"""#
+			return: "IPv6"
+		},
+	]
+}
@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: is_private_ip: {
+	category: "IP"
+	description: """
+		Determines whether the `value` IP address isn't publicly routable.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address to check."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+	]
+	return: {
+		types: ["boolean"]
+		rules: [
+			"Returns `true` for IPv4 private addresses ([RFC 1918](\(urls.rfc_1918))), loopback addresses (`127.0.0.0/8`) and link-local addresses (`169.254.0.0/16`).",
+			"Returns `true` for IPv6 unique local addresses ([RFC 4193](\(urls.rfc_4193))), the loopback address (`::1`) and link-local addresses (`fe80::/10`).",
+			"IPv4-mapped IPv6 addresses are classified by the IPv4 address they map.",
+			"Returns `false` otherwise.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Private IPv4 address"
+			source: #"""
+				is_private_ip!("192.168.0.1")
+				"""#
+			return: true
+		},
+		{
+			title: "Public IPv4 address"
+			source: #"""
+				is_private_ip!("8.8.8.8")
+				"""#
+			return: false
+		},
+		{
+			title: "Unique local IPv6 address"
+			source: #"""
+				is_private_ip!("fd12:3456:789a:1::1")
+				"""#
+			return: true
+		},
+	]
+}
@@ -0,0 +1,83 @@
+package metadata
+
+remap: functions: parse_cef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in [Common Event Format (CEF)](\(urls.cef)) format.
+
+		* The seven header fields are returned as `cefVersion`, `deviceVendor`, `deviceProduct`, `deviceVersion`,
+		  `deviceEventClassId`, `name` and `severity`.
+		* Extension key/value pairs are added to the returned object as-is.
+		* `\\|` in the header, and `\\=` in extension values, are unescaped.
+		"""
+	notices: [
+		"""
+			Anything preceding the `CEF:` marker, such as a Syslog header, is ignored.
+			""",
+		"""
+			All values are returned as strings. We recommend manually coercing values to desired types as you see fit.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "translate_custom_fields"
+			description: "Replaces custom extension fields, such as `cs1`, by the value of their label field, such as `cs1Label`."
+			required:    false
+			default:     false
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a properly formatted CEF string",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse CEF"
+			source: #"""
+				parse_cef!(
+					"CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232"
+				)
+				"""#
+			return: {
+				cefVersion:         "0"
+				deviceVendor:       "Security"
+				deviceProduct:      "threatmanager"
+				deviceVersion:      "1.0"
+				deviceEventClassId: "100"
+				name:               "worm successfully stopped"
+				severity:           "10"
+				src:                "10.0.0.1"
+				dst:                "2.1.2.2"
+				spt:                "1232"
+			}
+		},
+		{
+			title: "Parse CEF with custom fields"
+			source: #"""
+				parse_cef!(
+					"CEF:0|Dev|firewall|2.2|1|Connection denied|5|c6a1=2345:0425:2CA1:0000:0000:0567:5673:23b5 c6a1Label=Device IPv6 Address",
+					translate_custom_fields: true
+				)
+				"""#
+			return: {
+				cefVersion:            "0"
+				deviceVendor:          "Dev"
+				deviceProduct:         "firewall"
+				deviceVersion:         "2.2"
+				deviceEventClassId:    "1"
+				name:                  "Connection denied"
+				severity:              "5"
+				"Device IPv6 Address": "2345:0425:2CA1:0000:0000:0567:5673:23b5"
+			}
+		},
+	]
+}
@@ -0,0 +1,82 @@
+package metadata
+
+remap: functions: parse_leef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in [Log Event Extended Format (LEEF)](\(urls.leef)) version 1.0 or 2.0 format.
+
+		* The header fields are returned as `leefVersion`, `vendor`, `product`, `productVersion` and `eventId`.
+		* Attributes are added to the returned object as-is.
+		* LEEF 2.0 messages can define their own attribute delimiter in the header, either as a single character
+		  or as its hex code, such as `^` or `x5E`.
+		"""
+	notices: [
+		"""
+			Anything preceding the `LEEF:` marker, such as a Syslog header, is ignored.
+			""",
+		"""
+			All values are returned as strings. We recommend manually coercing values to desired types as you see fit.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "delimiter"
+			description: "The character that separates attributes, if the message doesn't define one in its header."
+			required:    false
+			default:     "\t"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a properly formatted LEEF string",
+		"`delimiter` isn't a single character",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse LEEF 1.0"
+			source: #"""
+				parse_leef!(
+					"LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tusrName=joe.black"
+				)
+				"""#
+			return: {
+				leefVersion:    "1.0"
+				vendor:         "Microsoft"
+				product:        "MSExchange"
+				productVersion: "4.0 SP1"
+				eventId:        "15345"
+				src:            "192.0.2.0"
+				dst:            "172.50.123.1"
+				sev:            "5"
+				usrName:        "joe.black"
+			}
+		},
+		{
+			title: "Parse LEEF 2.0"
+			source: #"""
+				parse_leef!(
+					"LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^sev=5"
+				)
+				"""#
+			return: {
+				leefVersion:    "2.0"
+				vendor:         "Lancope"
+				product:        "StealthWatch"
+				productVersion: "1.0"
+				eventId:        "41"
+				src:            "10.0.1.8"
+				dst:            "10.0.0.5"
+				sev:            "5"
+			}
+		},
+	]
+}
@@ -0,0 +1,33 @@
+package metadata
+
+remap: functions: random_bytes: {
+	category:    "Random"
+	description: """
+		Generates `length` cryptographically secure random bytes. This is useful for creating the
+		initialization vector passed to [`encrypt`](#encrypt).
+		"""
+
+	arguments: [
+		{
+			name:        "length"
+			description: "The number of bytes to generate. Must not be larger than 64k."
+			required:    true
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`length` is negative",
+		"`length` is larger than the maximum value (64k)",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Generate random base64 encoded bytes"
+			source: #"""
+				encode_base64(random_bytes!(16))
+				"""#
+			return: "LNu0BBgUbh7XAlXbjSOomQ=="
+		},
+	]
+}
@@ -0,0 +1,71 @@
+package metadata
+
+remap: functions: timestamp_add: {
+	category: "Timestamp"
+	description: """
+		Adds the `duration` to the `value` timestamp. Negative durations move the timestamp back in time.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The timestamp to add the duration to."
+			required:    true
+			type: ["timestamp"]
+		},
+		{
+			name:        "duration"
+			description: "The duration to add, expressed in `unit`. Fractional durations are rounded to the nearest nanosecond."
+			required:    true
+			type: ["integer", "float"]
+		},
+		{
+			name:        "unit"
+			description: "The unit of the `duration`, as understood by [`parse_duration`](#parse_duration)."
+			required:    false
+			enum: {
+				ns: "Nanoseconds (1 billion nanoseconds in a second)"
+				us: "Microseconds (1 million microseconds in a second)"
+				"µs": "Microseconds (1 million microseconds in a second)"
+				ms: "Milliseconds (1 thousand microseconds in a second)"
+				cs: "Centiseconds (100 centiseconds in a second)"
+				ds: "Deciseconds (10 deciseconds in a second)"
+				s:  "Seconds"
+				m:  "Minutes (60 seconds in a minute)"
+				h:  "Hours (60 minutes in an hour)"
+				d:  "Days (24 hours in a day)"
+			}
+			default: "s"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`duration` is too large to be represented in nanoseconds",
+		"The resulting timestamp is out of the supported range",
+	]
+	return: types: ["timestamp"]
+
+	examples: [
+		{
+			title: "Add seconds to a timestamp"
+			source: #"""
+				timestamp_add!(t'2021-02-10T23:32:00Z', 90)
+				"""#
+			return: "2021-02-10T23:33:30Z"
+		},
+		{
+			title: "Subtract hours from a timestamp"
+			source: #"""
+				timestamp_add!(t'2021-02-10T23:32:00Z', -1.5, unit: "h")
+				"""#
+			return: "2021-02-10T22:02:00Z"
+		},
+		{
+			title: "Add a parsed duration to a timestamp"
+			source: #"""
+				timestamp_add!(t'2021-02-10T23:32:00Z', parse_duration!("1005ms", unit: "ms"), unit: "ms")
+				"""#
+			return: "2021-02-10T23:32:01.005Z"
+		},
+	]
+}
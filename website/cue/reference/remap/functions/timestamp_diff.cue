@@ -0,0 +1,62 @@
+package metadata
+
+remap: functions: timestamp_diff: {
+	category: "Timestamp"
+	description: """
+		Returns the time elapsed between the `start` and `end` timestamps, expressed in `unit`. The result is
+		negative if `end` is before `start`.
+		"""
+
+	arguments: [
+		{
+			name:        "start"
+			description: "The timestamp the duration starts at."
+			required:    true
+			type: ["timestamp"]
+		},
+		{
+			name:        "end"
+			description: "The timestamp the duration ends at."
+			required:    true
+			type: ["timestamp"]
+		},
+		{
+			name:        "unit"
+			description: "The unit to express the duration in, as understood by [`parse_duration`](#parse_duration)."
+			required:    false
+			enum: {
+				ns: "Nanoseconds (1 billion nanoseconds in a second)"
+				us: "Microseconds (1 million microseconds in a second)"
+				"µs": "Microseconds (1 million microseconds in a second)"
+				ms: "Milliseconds (1 thousand microseconds in a second)"
+				cs: "Centiseconds (100 centiseconds in a second)"
+				ds: "Deciseconds (10 deciseconds in a second)"
+				s:  "Seconds"
+				m:  "Minutes (60 seconds in a minute)"
+				h:  "Hours (60 minutes in an hour)"
+				d:  "Days (24 hours in a day)"
+			}
+			default: "s"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["float"]
+
+	examples: [
+		{
+			title: "Compute a latency in seconds"
+			source: #"""
+				timestamp_diff(t'2021-02-10T23:32:00Z', t'2021-02-10T23:33:30.5Z')
+				"""#
+			return: 90.5
+		},
+		{
+			title: "Compute a negative duration in milliseconds"
+			source: #"""
+				timestamp_diff(t'2021-02-10T23:32:00Z', t'2021-02-10T23:31:00Z', unit: "ms")
+				"""#
+			return: -60000.0
+		},
+	]
+}
@@ -0,0 +1,76 @@
+package metadata
+
+remap: functions: timestamp_part: {
+	category: "Timestamp"
+	description: """
+		Extracts a calendar or clock `part` from the `value` timestamp.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The timestamp to extract the part from."
+			required:    true
+			type: ["timestamp"]
+		},
+		{
+			name:        "part"
+			description: "The part to extract."
+			required:    true
+			enum: {
+				year:        "The year."
+				iso_year:    "The [ISO 8601 week-numbering year](\(urls.iso_week_date)), which may differ from `year` in the first and last days of the year."
+				quarter:     "The quarter of the year, from 1 to 4."
+				month:       "The month, from 1 to 12."
+				week:        "The [ISO 8601 week number](\(urls.iso_week_date)), from 1 to 53."
+				day:         "The day of the month, from 1 to 31."
+				day_of_year: "The day of the year, from 1 to 366."
+				weekday:     "The ISO 8601 day of the week, from 1 (Monday) to 7 (Sunday)."
+				hour:        "The hour, from 0 to 23."
+				minute:      "The minute, from 0 to 59."
+				second:      "The second, from 0 to 59."
+				millisecond: "The millisecond of the second, from 0 to 999."
+				microsecond: "The microsecond of the second, from 0 to 999999."
+				nanosecond:  "The nanosecond of the second, from 0 to 999999999."
+			}
+			type: ["string"]
+		},
+		{
+			name:        "timezone"
+			description: """
+				The [timezone](\(urls.tz_time_zones)) the part is extracted in, such as `America/New_York`, or
+				`local` for the timezone of the machine Vector runs on. Defaults to UTC.
+				"""
+			required: false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`timezone` isn't a valid timezone",
+	]
+	return: types: ["integer"]
+
+	examples: [
+		{
+			title: "Extract the year"
+			source: #"""
+				timestamp_part(t'2021-02-10T23:32:00Z', "year")
+				"""#
+			return: 2021
+		},
+		{
+			title: "Extract the ISO week number"
+			source: #"""
+				timestamp_part(t'2021-01-03T23:32:00Z', "week")
+				"""#
+			return: 53
+		},
+		{
+			title: "Extract the day of the week in a timezone"
+			source: #"""
+				timestamp_part!(t'2021-02-10T23:32:00Z', "weekday", timezone: "Asia/Tokyo")
+				"""#
+			return: 4
+		},
+	]
+}
@@ -0,0 +1,74 @@
+package metadata
+
+remap: functions: truncate_timestamp: {
+	category: "Timestamp"
+	description: """
+		Truncates the `value` timestamp to the start of the `unit` it falls in, for example to bucket events by
+		hour or by day.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The timestamp to truncate."
+			required:    true
+			type: ["timestamp"]
+		},
+		{
+			name:        "unit"
+			description: "The unit to truncate the timestamp to."
+			required:    true
+			enum: {
+				year:        "The start of the year."
+				quarter:     "The start of the quarter."
+				month:       "The start of the month."
+				week:        "The start of the ISO 8601 week, on Monday."
+				day:         "The start of the day."
+				hour:        "The start of the hour."
+				minute:      "The start of the minute."
+				second:      "The start of the second."
+				millisecond: "The start of the millisecond."
+				microsecond: "The start of the microsecond."
+			}
+			type: ["string"]
+		},
+		{
+			name:        "timezone"
+			description: """
+				The [timezone](\(urls.tz_time_zones)) the calendar boundaries are computed in, such as
+				`America/New_York`, or `local` for the timezone of the machine Vector runs on. Defaults to UTC.
+				"""
+			required: false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`timezone` isn't a valid timezone",
+		"The truncated local time doesn't exist in `timezone`, for example because of a daylight saving time transition",
+	]
+	return: types: ["timestamp"]
+
+	examples: [
+		{
+			title: "Truncate to the hour"
+			source: #"""
+				truncate_timestamp(t'2021-02-10T23:32:10.123Z', "hour")
+				"""#
+			return: "2021-02-10T23:00:00Z"
+		},
+		{
+			title: "Truncate to the start of the week"
+			source: #"""
+				truncate_timestamp(t'2021-02-10T23:32:10.123Z', "week")
+				"""#
+			return: "2021-02-08T00:00:00Z"
+		},
+		{
+			title: "Truncate to the day in a timezone"
+			source: #"""
+				truncate_timestamp!(t'2021-02-10T23:32:10.123Z', "day", timezone: "Asia/Tokyo")
+				"""#
+			return: "2021-02-10T15:00:00Z"
+		},
+	]
+}
@@ -0,0 +1,75 @@
+package metadata
+
+remap: functions: validate_json_schema: {
+	category:    "Type"
+	description: """
+		Validates the `value` against the [JSON schema](\(urls.json_schema)) stored in `schema_file` and returns the
+		list of violations. An empty array is returned if the `value` is valid.
+
+		Each violation is an object with the following fields:
+
+		* `instance_path`: the [JSON pointer](\(urls.rfc_6901)) to the invalid part of the `value`. An empty
+		  pointer refers to the `value` itself.
+		* `schema_path`: the JSON pointer to the schema keyword the `value` violates.
+		* `message`: a human readable description of the violation.
+		"""
+	notices: [
+		"""
+			The schema file is read and compiled once, when the VRL program is compiled. Changes to the file take
+			effect the next time Vector loads the configuration.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The value to validate."
+			required:    true
+			type: ["any"]
+		},
+		{
+			name:        "schema_file"
+			description: "The path to the file containing the JSON schema. It must be a string literal."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["array"]
+
+	examples: [
+		{
+			title: "Validate an event against a JSON schema"
+			source: #"""
+				validate_json_schema({ "name": "Vector", "age": -1 }, "/etc/vector/schemas/person.json")
+				"""#
+			return: [{
+				instance_path: "/age"
+				message:       "-1 is less than the minimum of 0"
+				schema_path:   "/properties/age/minimum"
+			}]
+		},
+		{
+			title: "Annotate invalid events"
+			input: log: {
+				name: "Vector"
+				age:  -1
+			}
+			source: #"""
+				violations = validate_json_schema(., "/etc/vector/schemas/person.json")
+				if length(violations) > 0 {
+					.violations = violations
+				}
+				"""#
+			output: log: {
+				name: "Vector"
+				age:  -1
+				violations: [{
+					instance_path: "/age"
+					message:       "-1 is less than the minimum of 0"
+					schema_path:   "/properties/age/minimum"
+				}]
+			}
+		},
+	]
+}
@@ -4,6 +4,7 @@ urls: {
 	azure_blob_storage:                                       "https://azure.microsoft.com/en-us/services/storage/blobs/"
 	affine_type_system:                                       "\(wikipedia)/wiki/Substructural_type_system#Affine_type_systems"
 	adaptive_request_concurrency_post:                        "/blog/adaptive-request-concurrency/"
+	aes:                                                      "\(wikipedia)/wiki/Advanced_Encryption_Standard"
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
 	ansi_escape_codes:                                        "\(wikipedia)/wiki/ANSI_escape_code"
 	apache:                                                   "https://httpd.apache.org"
@@ -94,6 +95,7 @@ urls: {
 	bind_dnstap:                                              "https://kb.isc.org/docs/aa-01342"
 	b_tree_map:                                               "https://doc.rust-lang.org/std/collections/struct.BTreeMap.html"
 	cargo_audit:                                              "\(github)/RustSec/cargo-audit"
+	cef:                                                      "https://www.microfocus.com/documentation/arcsight/arcsight-smartconnectors/pdfdoc/common-event-format-v25/common-event-format-v25.pdf"
 	centos:                                                   "https://www.centos.org/"
 	chrono_time_formats:                                      "https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers"
 	cgroups_limit_resources:                                  "https://the.binbashtheory.com/control-resources-cgroups/"
@@ -102,10 +104,12 @@ urls: {
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
+	community_id:                                             "https://github.com/corelight/community-id-spec"
 	console:                                                  "\(wikipedia)/wiki/System_console"
 	conventional_commits:                                     "https://www.conventionalcommits.org"
 	contributing:                                             "\(vector_repo)/blob/master/CONTRIBUTING.md#setup"
 	crc:                                                      "\(wikipedia)/wiki/Cyclic_redundancy_check"
+	crypto_pan:                                               "https://www.cc.gatech.edu/computing/Networking/projects/cryptopan/"
 	ctime:                                                    "https://www.cplusplus.com/reference/ctime"
 	cue:                                                      "https://cuelang.org/"
 	dag:                                                      "\(wikipedia)/wiki/Directed_acyclic_graph"
@@ -220,6 +224,7 @@ urls: {
 	heroku:                                                   "https://www.heroku.com"
 	heroku_http_log_drain:                                    "https://devcenter.heroku.com/articles/log-drains#https-drains"
 	heroku_start:                                             "https://devcenter.heroku.com/start"
+	hmac:                                                     "\(wikipedia)/wiki/HMAC"
 	homebrew:                                                 "https://brew.sh/"
 	homebrew_services:                                        "\(github)/Homebrew/homebrew-services"
 	honeycomb:                                                "https://honeycomb.io"
@@ -247,11 +252,13 @@ urls: {
 	ip_aton:                                                  "https://linux.die.net/man/3/inet_aton"
 	ip_ntoa:                                                  "https://linux.die.net/man/3/inet_ntoa"
 	iso_8601:                                                 "\(wikipedia)/wiki/ISO_8601"
+	iso_week_date:                                            "\(wikipedia)/wiki/ISO_week_date"
 	iso3166_2:                                                "\(wikipedia)/wiki/ISO_3166-2"
 	issue_1694:                                               "\(vector_repo)/issues/1694"
 	journalctl:                                               "https://www.freedesktop.org/software/systemd/man/journalctl.html"
 	journald:                                                 "https://www.freedesktop.org/software/systemd/man/systemd-journald.service.html"
 	json:                                                     "\(wikipedia)/wiki/JSON"
+	json_schema:                                              "https://json-schema.org/"
 	json_types:                                               "\(wikipedia)/wiki/JSON#Data_types_and_syntax"
 	jsonnet:                                                  "https://jsonnet.org/"
 	kafka:                                                    "https://kafka.apache.org/"
@@ -272,6 +279,7 @@ urls: {
 	kubernetes_rbac:                                          "\(kubernetes)/docs/reference/access-authn-authz/rbac/"
 	kubernetes_request_verbs:                                 "\(kubernetes)/docs/reference/access-authn-authz/authorization/#determine-the-request-verb"
 	kubernetes_watch_api:                                     "\(kubernetes)/docs/reference/generated/kubernetes-api/v1.10/#watch-30"
+	leef:                                                     "https://www.ibm.com/docs/en/dsm?topic=overview-leef-event-components"
 	leveldb:                                                  "\(github)/google/leveldb"
 	leveldb_sys_2:                                            "https://crates.io/crates/leveldb-sys"
 	leveldb_sys_3:                                            "\(github)/timberio/leveldb-sys/tree/v3.0.0"
@@ -373,6 +381,7 @@ urls: {
 	prometheus_summary:                                       "https://prometheus.io/docs/concepts/metric_types/#summary"
 	prometheus_text_based_exposition_format:                  "\(github)/prometheus/docs/blob/master/content/docs/instrumenting/exposition_formats.md#text-based-format"
 	prometheus_metric_naming:                                 "https://prometheus.io/docs/practices/naming/#metric-names"
+	prometheus_relabel_config:                                "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#relabel_config"
 	prometheus_remote_integrations:                           "https://prometheus.io/docs/operating/integrations/#remote-endpoints-and-storage"
 	prometheus_remote_write:                                  "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#remote_write"
 	prometheus_remote_write_protocol:                         "https://docs.google.com/document/d/1LPhVRSFkGNSuU1fBd81ulhsCPR4hkSZyyBj1SZ8fWOM/edit#heading=h.n0d0vphea3fe"
@@ -389,12 +398,15 @@ urls: {
 	rfc_793:                                                  "https://tools.ietf.org/html/rfc793"
 	rfc_822:                                                  "https://tools.ietf.org/html/rfc822#section-5"
 	rfc_1035:                                                 "https://tools.ietf.org/html/rfc1035"
+	rfc_1918:                                                 "https://tools.ietf.org/html/rfc1918"
 	rfc_2064:                                                 "\(vector_repo)/blob/master/rfcs/2020-03-17-2064-event-driven-observability.md"
 	rfc_2136:                                                 "https://tools.ietf.org/html/rfc2136"
 	rfc_2460:                                                 "https://tools.ietf.org/html/rfc2460"
 	rfc_2822:                                                 "https://tools.ietf.org/html/rfc2822#section-3.3"
 	rfc_3339:                                                 "https://tools.ietf.org/html/rfc3339"
 	rfc_4180:                                                 "https://tools.ietf.org/html/rfc4180"
+	rfc_4193:                                                 "https://tools.ietf.org/html/rfc4193"
+	rfc_6901:                                                 "https://tools.ietf.org/html/rfc6901"
 	rfc_6891:                                                 "https://tools.ietf.org/html/rfc6891"
 	rhel:                                                     "https://www.redhat.com/en/technologies/linux-platforms/enterprise-linux"
 	rpm:                                                      "https://rpm.org/"
@@ -433,6 +445,7 @@ urls: {
 	splunk_hec_protocol:                                      "https://docs.splunk.com/Documentation/Splunk/8.0.0/Data/HECRESTendpoints"
 	splunk_hec_raw_endpoint:                                  "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fraw"
 	splunk_hec_setup:                                         "https://docs.splunk.com/Documentation/Splunk/latest/Data/UsetheHTTPEventCollector"
+	sqlite:                                                   "https://www.sqlite.org/"
 	standard_streams:                                         "\(wikipedia)/wiki/Standard_streams"
 	statsd:                                                   "\(github)/statsd/statsd"
 	statsd_multi:                                             "\(github)/statsd/statsd/blob/master/docs/metric_types.md#multi-metric-packets"